@@ -0,0 +1,140 @@
+//! Batch-building throughput benchmarks
+//!
+//! Measures φ-sorting, φ-grouping, Williams chunking and full batch building
+//! over synthetic signed intents (no chain required). Results are reported
+//! in intents/sec:
+//!
+//! ```bash
+//! cargo bench --bench batch_building
+//! ```
+
+use alloy_primitives::{Address, U256};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use ethers::signers::{LocalWallet, Signer};
+use fisher_relayer::{phi_freeman, williams, FisherConfig, FisherRelayer, Intent};
+
+/// Batch sizes every benchmark runs over
+const SIZES: [usize; 4] = [10, 100, 1_000, 10_000];
+
+/// Distinct senders the synthetic intents are spread across
+const SENDERS: u8 = 16;
+
+/// Deterministic signed intents with varied amounts, ages and priorities
+fn synthetic_intents(n: usize) -> Vec<Intent> {
+    let wallets: Vec<LocalWallet> = (1..=SENDERS)
+        .map(|seed| LocalWallet::from_bytes(&[seed; 32]).unwrap())
+        .collect();
+    
+    (0..n as u64)
+        .map(|i| {
+            let wallet = &wallets[i as usize % wallets.len()];
+            let mut intent = Intent::new(
+                format!("bench_{}", i),
+                Address::from_slice(wallet.address().as_bytes()),
+                Address::repeat_byte((i % 251) as u8),
+                U256::from((i * 7_919) % 1_000_000 + 1),
+                i % 10 == 0,
+                i,
+                vec![],
+            );
+            intent.timestamp = 1_700_000_000 + i % 600;
+            intent.max_gas_price = Some(U256::from(1_000_000_000 + i % 50));
+            intent.signed(wallet).unwrap()
+        })
+        .collect()
+}
+
+fn bench_phi_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("phi_sort");
+    
+    for n in SIZES {
+        let intents = synthetic_intents(n);
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &intents, |b, intents| {
+            b.iter_batched(
+                || intents.clone(),
+                |mut intents| phi_freeman::phi_sort(black_box(&mut intents)),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    
+    group.finish();
+}
+
+fn bench_phi_group(c: &mut Criterion) {
+    let mut group = c.benchmark_group("phi_group");
+    
+    for n in SIZES {
+        let intents = synthetic_intents(n);
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &intents, |b, intents| {
+            b.iter(|| phi_freeman::phi_group(black_box(intents)))
+        });
+    }
+    
+    group.finish();
+}
+
+fn bench_process_in_chunks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("williams_process_in_chunks");
+    
+    for n in SIZES {
+        let intents = synthetic_intents(n);
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &intents, |b, intents| {
+            b.iter(|| {
+                williams::process_in_chunks(black_box(intents), |chunk| {
+                    Ok(chunk.iter().fold(U256::ZERO, |total, intent| total + intent.amount))
+                })
+                .unwrap()
+            })
+        });
+    }
+    
+    group.finish();
+}
+
+/// Full `build_batches`, with and without the per-intent signature check
+fn bench_build_batch(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let relayer = FisherRelayer::new(FisherConfig {
+        enable_attestation: false,
+        ..FisherConfig::default()
+    })
+    .unwrap();
+    
+    let mut group = c.benchmark_group("build_batch");
+    
+    for n in SIZES {
+        let intents = synthetic_intents(n);
+        group.throughput(Throughput::Elements(n as u64));
+        
+        group.bench_with_input(BenchmarkId::new("verified", n), &intents, |b, intents| {
+            b.iter_batched(
+                || intents.clone(),
+                |intents| runtime.block_on(relayer.build_batches(intents)).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+        
+        group.bench_with_input(BenchmarkId::new("unverified", n), &intents, |b, intents| {
+            b.iter_batched(
+                || intents.clone(),
+                |intents| runtime.block_on(relayer.build_batches_unchecked(intents)).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_phi_sort,
+    bench_phi_group,
+    bench_process_in_chunks,
+    bench_build_batch
+);
+criterion_main!(benches);
@@ -9,6 +9,10 @@ echo "🔨 Building Fisher Relayer..."
 echo "📦 Building native binary..."
 cargo build --release
 
+# The algorithm core must stay no_std
+echo "🧩 Checking no_std core..."
+cargo build -p fisher-core --no-default-features
+
 # Build WASM for Enarx
 echo "🌐 Building WASM for Enarx..."
 cargo build --target wasm32-wasi --release
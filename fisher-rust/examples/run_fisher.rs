@@ -1,8 +1,8 @@
 //! Example: Run Fisher relayer
 
-use fisher_relayer::{FisherRelayer, FisherConfig, Intent};
+use fisher_relayer::{BlobMode, FisherRelayer, FisherConfig, Intent};
 use alloy_primitives::{Address, U256};
-use std::str::FromStr;
+use ethers::signers::{LocalWallet, Signer};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -15,11 +15,11 @@ async fn main() -> anyhow::Result<()> {
         fisher_address: Address::ZERO,
         evvm_core_address: Address::ZERO,
         min_batch_size: 5,
-        max_batch_size: 100,
+        batch_trigger_size: 100,
         batch_interval_ms: 2000,
         enable_attestation: false,
-        enable_blobs: true,
-        private_key: None,
+        enable_blobs: BlobMode::On,
+        ..FisherConfig::default()
     };
     
     // Create relayer
@@ -35,17 +35,17 @@ async fn main() -> anyhow::Result<()> {
     // Start automatic batch processing
     relayer.start().await;
     
-    // Example: Submit test intents
+    // Example: Submit test intents signed by a throwaway user key
+    let user = LocalWallet::new(&mut rand::thread_rng());
     for i in 0..15 {
-        let intent = Intent::new(
-            format!("test_{}", i),
-            Address::ZERO,
-            Address::ZERO,
-            U256::from(100),
-            false,
-            i,
-            vec![0xDE, 0xAD, 0xBE, 0xEF],
-        );
+        let intent = Intent::builder()
+            .id(format!("test_{}", i))
+            .from(Address::from_slice(user.address().as_bytes()))
+            .to(Address::repeat_byte(0x02))
+            .amount(U256::from(100))
+            .nonce(i)
+            .build()?
+            .signed(&user)?;
         
         relayer.submit_intent(intent).await?;
     }
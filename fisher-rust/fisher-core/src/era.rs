@@ -0,0 +1,79 @@
+//! φ era fee growth
+
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+
+/// Scale factor for fixed-point arithmetic (matches Solidity's 1e18)
+pub const SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Compute compound growth: initial * (1 + rate)^periods
+///
+/// Uses iterative multiplication for accuracy and safety in production.
+/// For very large periods, this could use φ-approximation, but for
+/// typical era counts (< 1000), direct computation is safer.
+pub fn compound_growth(initial: u128, rate: u128, periods: u64) -> u128 {
+    if periods == 0 {
+        return initial;
+    }
+    
+    let mut result = initial;
+    for _ in 0..periods {
+        result = grow_one_period(result, rate);
+    }
+    
+    result
+}
+
+/// One period of compound growth: `value * (SCALE + rate) / SCALE`
+///
+/// The product is taken in 256 bits so an overflowing fee clamps at
+/// `u128::MAX` instead of saturating before the division and shrinking.
+pub fn grow_one_period(value: u128, rate: u128) -> u128 {
+    let grown = U256::from(value).saturating_mul(U256::from(SCALE) + U256::from(rate)) / U256::from(SCALE);
+    u128::try_from(grown).unwrap_or(u128::MAX)
+}
+
+/// `(era, fee)` for `era` charging `fee` and each of the next `n_eras`
+pub fn fee_schedule(era: u64, fee: u128, rate: u128, n_eras: u64) -> Vec<(u64, u128)> {
+    let mut fee = fee;
+    
+    (0..=n_eras)
+        .map(|offset| {
+            if offset > 0 {
+                fee = grow_one_period(fee, rate);
+            }
+            (era.saturating_add(offset), fee)
+        })
+        .collect()
+}
+
+/// Whole eras of `era_duration` seconds elapsed between `era_start` and `now`
+///
+/// Zero-length eras never elapse.
+pub fn eras_elapsed(era_start: u64, era_duration: u64, now: u64) -> u64 {
+    if era_duration == 0 {
+        return 0;
+    }
+    
+    now.saturating_sub(era_start) / era_duration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    
+    #[test]
+    fn test_growth_and_schedule() {
+        let rate = SCALE / 10;
+        assert_eq!(compound_growth(1_000, rate, 0), 1_000);
+        assert_eq!(compound_growth(1_000, rate, 2), 1_210);
+        assert_eq!(compound_growth(u128::MAX, rate, 3), u128::MAX);
+        
+        assert_eq!(fee_schedule(4, 1_000, rate, 2), vec![(4, 1_000), (5, 1_100), (6, 1_210)]);
+        
+        assert_eq!(eras_elapsed(100, 10, 135), 3);
+        assert_eq!(eras_elapsed(100, 10, 50), 0);
+        assert_eq!(eras_elapsed(100, 0, 1_000), 0);
+    }
+}
@@ -0,0 +1,28 @@
+//! # Fisher Core - no_std batching algorithms
+//!
+//! The pure math behind the relayer: Williams chunk sizing, φ-Freeman
+//! scoring and φ era fee growth. Nothing here reads the clock; functions
+//! that depend on time take `now` (unix seconds) as a parameter. With the
+//! `std` feature (on by default) [`unix_now`] supplies it from `SystemTime`.
+//!
+//! Builds with `--no-default-features` for `no_std` targets (needs `alloc`).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+extern crate alloc;
+
+pub mod era;
+pub mod phi;
+pub mod williams;
+
+mod math;
+
+/// Current unix time in seconds
+#[cfg(feature = "std")]
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
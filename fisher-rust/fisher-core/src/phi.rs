@@ -0,0 +1,171 @@
+//! φ-Freeman scoring
+
+use crate::math;
+
+/// Golden ratio (φ)
+pub const PHI: f64 = 1.618033988749;
+
+/// Low mantissa bits ignored when comparing scores (about 1e-14 relative),
+/// so float noise can't decide between equally scored intents
+const SCORE_TIE_MASK: u64 = (1 << 6) - 1;
+
+/// Weighting coefficients for φ-Freeman scoring
+///
+/// The amount and gas terms are logarithmic and therefore bounded
+/// (`ln(u128::MAX) ≈ 88.7`), so once an intent's age passes
+/// `fairness_age_threshold_secs` the super-linear age term eventually
+/// outweighs any whale. This bounds the worst-case wait for small intents.
+///
+/// Missing fields deserialize to the defaults, which reproduce the original
+/// fixed formula.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PhiWeights {
+    /// Multiplier applied to the whole score of priority intents
+    pub priority_multiplier: f64,
+    
+    /// Weight of the age term
+    pub age_weight: f64,
+    
+    /// Exponent applied to age (`1/φ` by default, so age gains weight sub-linearly)
+    pub age_exponent: f64,
+    
+    /// Weight of the `ln(amount)` term
+    pub amount_weight: f64,
+    
+    /// Weight of the `ln(max_gas_price)` term
+    pub gas_weight: f64,
+    
+    /// Age (seconds) past which age weight grows super-linearly (`None` disables fairness)
+    pub fairness_age_threshold_secs: Option<u64>,
+    
+    /// Exponent applied to age beyond the threshold (should be > 1)
+    pub fairness_exponent: f64,
+}
+
+impl PhiWeights {
+    /// Fairness mode: starvation-free ordering past `threshold_secs`
+    pub fn fair(threshold_secs: u64) -> Self {
+        Self {
+            fairness_age_threshold_secs: Some(threshold_secs),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for PhiWeights {
+    fn default() -> Self {
+        Self {
+            priority_multiplier: PHI,
+            age_weight: 1.0,
+            age_exponent: 1.0 / PHI,
+            amount_weight: 1.0,
+            gas_weight: 1.0,
+            fairness_age_threshold_secs: None,
+            fairness_exponent: 2.0,
+        }
+    }
+}
+
+/// The parts of an intent that feed its φ score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreInput {
+    /// Priority flag
+    pub priority: bool,
+    
+    /// Creation time (unix seconds)
+    pub timestamp: u64,
+    
+    /// Amount (saturated to `u128`)
+    pub amount: u128,
+    
+    /// Declared gas price cap (saturated to `u128`)
+    pub max_gas_price: Option<u128>,
+}
+
+/// φ-Freeman score of an intent at time `now` (unix seconds)
+///
+/// Combines multiple factors:
+/// - Priority flag (urgent vs normal)
+/// - Amount (larger transactions weighted higher)
+/// - Timestamp (older transactions processed first)
+/// - Gas price (higher paying users get priority)
+///
+/// The score is always finite. Zero amounts and gas prices are treated as 1
+/// (contributing `ln(1) = 0`), a zero age contributes nothing, and any
+/// non-finite result from extreme weights is clamped (NaN becomes 0).
+pub fn score(input: &ScoreInput, now: u64, weights: &PhiWeights) -> f64 {
+    let age = now.saturating_sub(input.timestamp);
+    let age_factor = age as f64;
+    let amount_factor = (input.amount as f64).max(1.0);
+    let priority_factor = if input.priority { weights.priority_multiplier } else { 1.0 };
+    let gas_factor = input.max_gas_price
+        .map(|p| (p as f64).max(1.0))
+        .unwrap_or(1.0);
+    
+    // 0^x is 0, 1 or inf depending on the exponent; a new intent has no age weight
+    let age_term = if age == 0 { 0.0 } else { math::powf(age_factor, weights.age_exponent) };
+    
+    // Fairness: old intents gain weight faster than any bounded factor can
+    let fairness_boost = weights.fairness_age_threshold_secs
+        .filter(|threshold| age > *threshold)
+        .map(|threshold| math::powf((age - threshold) as f64, weights.fairness_exponent))
+        .unwrap_or(0.0);
+    
+    // Combine factors with φ-weighted formula
+    let score = priority_factor * (
+        weights.age_weight * (age_term + fairness_boost)
+            + weights.amount_weight * math::ln(amount_factor)
+            + weights.gas_weight * math::ln(gas_factor)
+    );
+    
+    if score.is_nan() {
+        0.0
+    } else {
+        score.clamp(f64::MIN, f64::MAX)
+    }
+}
+
+/// Order-preserving integer form of a score, with float noise dropped
+///
+/// Scores within [`SCORE_TIE_MASK`] of each other map to the same rank.
+pub fn score_rank(score: f64) -> i64 {
+    let bits = (score.to_bits() & !SCORE_TIE_MASK) as i64;
+    
+    // The same mapping `f64::total_cmp` uses
+    bits ^ (((bits >> 63) as u64) >> 1) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn input(priority: bool, timestamp: u64, amount: u128) -> ScoreInput {
+        ScoreInput { priority, timestamp, amount, max_gas_price: None }
+    }
+    
+    #[test]
+    fn test_score_at_explicit_time() {
+        let weights = PhiWeights::default();
+        let intent = input(false, 1_000, 1);
+        
+        // Age only counts relative to the supplied `now`
+        assert_eq!(score(&intent, 1_000, &weights), 0.0);
+        assert!(score(&intent, 2_000, &weights) > score(&intent, 1_100, &weights));
+        assert_eq!(score(&intent, 500, &weights), 0.0);
+        
+        let priority = input(true, 1_000, 1);
+        assert_eq!(score(&priority, 1_100, &weights), PHI * score(&intent, 1_100, &weights));
+        
+        let extreme = PhiWeights { age_weight: f64::INFINITY, amount_weight: f64::NEG_INFINITY, ..weights };
+        assert!(score(&input(false, 0, u128::MAX), 100, &extreme).is_finite());
+    }
+    
+    #[test]
+    fn test_score_rank_orders_scores() {
+        assert!(score_rank(2.0) > score_rank(1.0));
+        assert!(score_rank(-1.0) < score_rank(0.0));
+        assert_eq!(score_rank(1.0), score_rank(1.0 + f64::EPSILON));
+    }
+}
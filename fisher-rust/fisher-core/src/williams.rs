@@ -0,0 +1,48 @@
+//! Williams chunk sizing - O(√n log n) space
+
+use crate::math;
+
+/// Chunk size `√n · ⌈log₂ n⌉^log_exponent`, at least `min_chunk_size`
+///
+/// The result is always within `1..=n` (and `0` for an empty batch).
+pub fn chunk_size(n: usize, log_exponent: f64, min_chunk_size: usize) -> usize {
+    if n <= 1 {
+        return n;
+    }
+    
+    let sqrt_n = math::sqrt(n as f64) as usize;
+    let log_n = math::ceil(math::log2(n as f64));
+    let chunk_size = (sqrt_n as f64 * math::powf(log_n, log_exponent)) as usize;
+    
+    chunk_size.max(min_chunk_size).clamp(1, n)
+}
+
+/// Memory savings of processing `n` operations in chunks of `chunk_size`
+///
+/// Lets the savings be measured from the chunk size the contract actually uses.
+pub fn savings_for_chunk_size(n: usize, chunk_size: usize) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+    
+    // Small batches may chunk wider than the batch itself (no savings)
+    (n.saturating_sub(chunk_size) as f64 / n as f64) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_chunk_size() {
+        assert_eq!(chunk_size(0, 1.0, 1), 0);
+        assert_eq!(chunk_size(1, 1.0, 1), 1);
+        assert_eq!(chunk_size(10_000, 1.0, 1), 1_400);
+        assert_eq!(chunk_size(10, 1.0, 1), 10);
+        assert_eq!(chunk_size(100, 0.0, 50), 50);
+        
+        assert_eq!(savings_for_chunk_size(100, 25), 75.0);
+        assert_eq!(savings_for_chunk_size(10, 40), 0.0);
+        assert_eq!(savings_for_chunk_size(0, 0), 0.0);
+    }
+}
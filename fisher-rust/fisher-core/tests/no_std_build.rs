@@ -0,0 +1,18 @@
+//! The core must keep building without `std`
+
+use std::process::Command;
+
+#[test]
+fn test_builds_without_std() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    
+    // Own target dir, so the outer build's lock isn't contended
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--offline", "--quiet", "--no-default-features", "--features", "serde", "--manifest-path"])
+        .arg(format!("{}/Cargo.toml", manifest_dir))
+        .env("CARGO_TARGET_DIR", format!("{}/../target/no-std-core", manifest_dir))
+        .status()
+        .expect("failed to run cargo");
+    
+    assert!(status.success(), "fisher-core no longer builds with --no-default-features");
+}
@@ -0,0 +1,180 @@
+//! Same-recipient analysis and payment aggregation
+//!
+//! Batches often contain many intents paying the same recipient (e.g. a
+//! popular contract). Each such recipient is recorded as a `RecipientGroup`.
+//! When aggregation is enabled, intents from one sender to one recipient
+//! (with the same priority) are collapsed into a single summed payment
+//! carrying every constituent signature, which the contract must verify
+//! as an aggregate.
+
+use crate::{AggregatedPayment, Intent, RecipientGroup};
+use alloy_primitives::{Address, U256};
+use std::collections::HashMap;
+
+/// Recipients paid by more than one intent, in order of first appearance
+pub fn recipient_groups(intents: &[Intent]) -> Vec<RecipientGroup> {
+    let mut groups: Vec<RecipientGroup> = Vec::new();
+    let mut index: HashMap<Address, usize> = HashMap::new();
+    
+    for intent in intents {
+        let i = *index.entry(intent.to).or_insert_with(|| {
+            groups.push(RecipientGroup {
+                to: intent.to,
+                intent_ids: Vec::new(),
+                total_amount: U256::ZERO,
+            });
+            groups.len() - 1
+        });
+        
+        let group = &mut groups[i];
+        group.intent_ids.push(intent.id.clone());
+        group.total_amount = group.total_amount.saturating_add(intent.amount);
+    }
+    
+    groups.retain(|g| g.intent_ids.len() > 1);
+    groups
+}
+
+/// Collapse same-(sender, recipient, priority) intents into single payments
+///
+/// Returns the payments (keeping the position of each group's first intent)
+/// and the originals behind every aggregated payment. Groups whose total
+/// would overflow are left unaggregated, as are intents carrying a permit
+/// (the permit only covers the intent's own amount).
+pub fn aggregate_payments(intents: Vec<Intent>) -> (Vec<Intent>, Vec<AggregatedPayment>) {
+    let mut buckets: Vec<Vec<Intent>> = Vec::new();
+    let mut index: HashMap<(Address, Address, bool), usize> = HashMap::new();
+    
+    for intent in intents {
+        if intent.permit.is_some() {
+            buckets.push(vec![intent]);
+            continue;
+        }
+        
+        let key = (intent.from, intent.to, intent.priority);
+        match index.get(&key) {
+            Some(&i) => buckets[i].push(intent),
+            None => {
+                index.insert(key, buckets.len());
+                buckets.push(vec![intent]);
+            }
+        }
+    }
+    
+    let mut payments = Vec::with_capacity(buckets.len());
+    let mut aggregated = Vec::new();
+    
+    for bucket in buckets {
+        let total = bucket.iter().try_fold(U256::ZERO, |total, i| total.checked_add(i.amount));
+        
+        match total {
+            Some(amount) if bucket.len() > 1 => {
+                let first = &bucket[0];
+                payments.push(Intent {
+                    amount,
+                    signature: bucket.iter().flat_map(|i| i.signature.iter().copied()).collect(),
+                    ..first.clone()
+                });
+                aggregated.push(AggregatedPayment {
+                    payment_id: first.id.clone(),
+                    intents: bucket,
+                });
+            }
+            _ => payments.extend(bucket),
+        }
+    }
+    
+    (payments, aggregated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn intent(id: &str, from: u8, to: u8, amount: u64) -> Intent {
+        Intent::new(
+            id.to_string(),
+            Address::repeat_byte(from),
+            Address::repeat_byte(to),
+            U256::from(amount),
+            false,
+            0,
+            vec![from; 65],
+        )
+    }
+    
+    #[test]
+    fn test_recipient_groups() {
+        let intents = vec![
+            intent("a", 1, 0xC0, 100),
+            intent("b", 2, 0xD0, 50),
+            intent("c", 3, 0xC0, 200),
+            intent("d", 1, 0xC0, 300),
+        ];
+        
+        let groups = recipient_groups(&intents);
+        
+        // Only the shared recipient is an opportunity
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].to, Address::repeat_byte(0xC0));
+        assert_eq!(groups[0].intent_ids, vec!["a", "c", "d"]);
+        assert_eq!(groups[0].total_amount, U256::from(600));
+    }
+    
+    #[test]
+    fn test_aggregates_same_sender_and_recipient() {
+        let intents = vec![
+            intent("a", 1, 0xC0, 100),
+            intent("b", 2, 0xC0, 50),
+            intent("c", 1, 0xD0, 70),
+            intent("d", 1, 0xC0, 300),
+        ];
+        
+        let (payments, aggregated) = aggregate_payments(intents);
+        
+        // a + d collapse; other senders and recipients stay separate
+        let ids: Vec<&str> = payments.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(payments[0].amount, U256::from(400));
+        assert_eq!(payments[0].signature.len(), 130);
+        assert_eq!(payments[1].amount, U256::from(50));
+        assert_eq!(payments[2].amount, U256::from(70));
+        
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated[0].payment_id, "a");
+        let originals: Vec<&str> = aggregated[0].intents.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(originals, vec!["a", "d"]);
+    }
+    
+    #[test]
+    fn test_overflowing_group_not_aggregated() {
+        let intents = vec![
+            Intent { amount: U256::MAX, ..intent("a", 1, 0xC0, 0) },
+            intent("b", 1, 0xC0, 1),
+        ];
+        
+        let (payments, aggregated) = aggregate_payments(intents);
+        assert_eq!(payments.len(), 2);
+        assert!(aggregated.is_empty());
+    }
+    
+    #[test]
+    fn test_permit_intents_not_aggregated() {
+        let permit = crate::Permit { deadline: u64::MAX, v: 27, r: Default::default(), s: Default::default() };
+        let intents = vec![
+            intent("a", 1, 0xC0, 100).with_permit(permit),
+            intent("b", 1, 0xC0, 50),
+            intent("c", 1, 0xC0, 25),
+        ];
+        
+        let (payments, aggregated) = aggregate_payments(intents);
+        
+        // b + c collapse; the permitted intent stays on its own
+        let ids: Vec<&str> = payments.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(payments[0].permit, Some(permit));
+        assert_eq!(payments[1].amount, U256::from(75));
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated[0].payment_id, "b");
+    }
+}
@@ -1,11 +1,32 @@
 //! TDX Attestation Integration
 //!
 //! Integrates with your Enarx TDX backend for hardware-backed attestation.
+//!
+//! Each `AttestationManager` generates an ephemeral secp256k1 keypair inside the
+//! TEE. The public key is bound into the quote's report data, so users who verify
+//! the attestation can encrypt intents (ECIES) that only this enclave can read.
 
-use crate::{Error, Result};
+use crate::{Error, Intent, Result};
+use aes::cipher::{KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use k256::{elliptic_curve::sec1::ToEncodedPoint, PublicKey, SecretKey};
+use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use serde_big_array::BigArray;
+use std::sync::Arc;
+
+type Aes256Ctr = ctr::Ctr128BE<aes::Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+/// Size of an uncompressed SEC1 secp256k1 public key
+pub const PUBLIC_KEY_SIZE: usize = 65;
+
+/// AES-CTR initialization vector size
+const IV_SIZE: usize = 16;
+
+/// HMAC-SHA256 tag size
+const MAC_SIZE: usize = 32;
 
 /// TDX Quote for attestation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,23 +56,133 @@ pub struct AttestationReport {
     
     /// Public key for encrypted intents
     pub public_key: Vec<u8>,
+    
+    /// Canonical digest of the attested batch (batch reports only)
+    #[serde(default)]
+    pub batch_digest: Option<[u8; 32]>,
+}
+
+/// Whether the running relayer is attested
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttestationStatus {
+    /// `enable_attestation` is off
+    #[default]
+    Disabled,
+    
+    /// A quote was produced at startup
+    Attested,
+    
+    /// Attestation is enabled but no quote could be produced
+    Unattested,
+}
+
+impl AttestationStatus {
+    /// Lowercase name, as used in metrics labels
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Attested => "attested",
+            Self::Unattested => "unattested",
+        }
+    }
+}
+
+/// Source of TDX quotes
+pub trait QuoteProvider: Send + Sync {
+    /// Quote covering `report_data`
+    fn quote(&self, report_data: &[u8; 64]) -> Result<Vec<u8>>;
+}
+
+/// Quotes from the local TDX device
+pub struct TdxDevice;
+
+impl QuoteProvider for TdxDevice {
+    /// Get TDX quote from hardware
+    ///
+    /// TODO: This will interface with your Enarx TDX backend:
+    /// `/aristo-fresh 2/enarx/src/backend/tdx/attestation.rs`
+    ///
+    /// Until then no quote can be produced outside tests, so the relayer runs
+    /// [`AttestationStatus::Unattested`] (or refuses to start, if attestation
+    /// is required) rather than publishing a fake attestation.
+    fn quote(&self, _report_data: &[u8; 64]) -> Result<Vec<u8>> {
+        // Example integration point:
+        // let quote = aristo_enarx::tdx::TdxQuote::new(report_data)?;
+        // Ok(quote.data)
+        
+        #[cfg(test)]
+        {
+            Ok(vec![0xDE, 0xAD, 0xBE, 0xEF]) // Mock quote
+        }
+        
+        #[cfg(not(test))]
+        {
+            Err(Error::Attestation("TDX device not available".to_string()))
+        }
+    }
 }
 
 /// Attestation manager
 pub struct AttestationManager {
     enabled: bool,
+    
+    /// Ephemeral intent-encryption key (never leaves the TEE)
+    encryption_key: SecretKey,
+    
+    /// Where quotes come from (the TDX device unless overridden)
+    quotes: Arc<dyn QuoteProvider>,
 }
 
 impl AttestationManager {
-    /// Create new attestation manager
+    /// Create new attestation manager with a fresh encryption keypair
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            encryption_key: SecretKey::random(&mut OsRng),
+            quotes: Arc::new(TdxDevice),
+        }
+    }
+    
+    /// Take quotes from `provider` instead of the TDX device
+    pub fn with_quote_provider(mut self, provider: Arc<dyn QuoteProvider>) -> Self {
+        self.quotes = provider;
+        self
+    }
+    
+    /// Public key users encrypt intents to (65-byte uncompressed SEC1)
+    pub fn public_key(&self) -> Vec<u8> {
+        self.encryption_key
+            .public_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
     }
     
     /// Generate attestation report
     ///
     /// This will call into your Enarx TDX backend to generate a quote.
     pub fn generate_report(&self, config_hash: [u8; 32]) -> Result<AttestationReport> {
+        self.build_report(config_hash, None)
+    }
+    
+    /// Generate an attestation report committing to one batch
+    ///
+    /// `batch_digest` should be [`crate::Batch::canonical_digest`], so verifiers
+    /// can recompute it from the batch contents.
+    pub fn generate_batch_report(
+        &self,
+        config_hash: [u8; 32],
+        batch_digest: [u8; 32],
+    ) -> Result<AttestationReport> {
+        self.build_report(config_hash, Some(batch_digest))
+    }
+    
+    fn build_report(
+        &self,
+        config_hash: [u8; 32],
+        batch_digest: Option<[u8; 32]>,
+    ) -> Result<AttestationReport> {
         if !self.enabled {
             return Err(Error::Attestation("Attestation not enabled".to_string()));
         }
@@ -59,10 +190,10 @@ impl AttestationManager {
         // TODO: Integrate with your Enarx TDX backend
         // This is where we call your /aristo-fresh 2/enarx/src/backend/tdx/attestation.rs
         
-        let report_data = self.prepare_report_data(&config_hash);
+        let public_key = self.public_key();
+        let report_data = Self::prepare_report_data(&config_hash, &public_key, batch_digest.as_ref());
         
-        // Placeholder - will be replaced with actual TDX call
-        let quote_data = self.get_tdx_quote(&report_data)?;
+        let quote_data = self.quotes.quote(&report_data)?;
         
         Ok(AttestationReport {
             quote: TdxQuote {
@@ -75,56 +206,136 @@ impl AttestationManager {
             },
             fisher_version: crate::VERSION.to_string(),
             config_hash,
-            public_key: vec![], // TODO: Generate ephemeral key pair in TEE
+            public_key,
+            batch_digest,
         })
     }
     
     /// Prepare report data for TDX quote
-    fn prepare_report_data(&self, config_hash: &[u8; 32]) -> [u8; 64] {
+    ///
+    /// Layout: `config_hash || sha256(fisher_version || public_key [|| batch_digest])`
+    fn prepare_report_data(
+        config_hash: &[u8; 32],
+        public_key: &[u8],
+        batch_digest: Option<&[u8; 32]>,
+    ) -> [u8; 64] {
         let mut report_data = [0u8; 64];
         report_data[..32].copy_from_slice(config_hash);
         
-        // Add Fisher version hash
-        let version_hash = Sha256::digest(crate::VERSION.as_bytes());
-        report_data[32..].copy_from_slice(&version_hash);
-        
-        report_data
-    }
-    
-    /// Get TDX quote from hardware
-    ///
-    /// TODO: This will interface with your Enarx TDX backend:
-    /// `/aristo-fresh 2/enarx/src/backend/tdx/attestation.rs`
-    fn get_tdx_quote(&self, _report_data: &[u8; 64]) -> Result<Vec<u8>> {
-        // Placeholder - actual implementation will use your TDX infrastructure
-        #[cfg(target_os = "linux")]
-        {
-            // On Linux, this would call into TDX device
-            // using your existing attestation.rs code
-            
-            // Example integration point:
-            // let quote = aristo_enarx::tdx::TdxQuote::new(report_data)?;
-            // Ok(quote.data)
+        // Bind Fisher version, encryption key and batch (if any) into the quote
+        let mut hasher = Sha256::new();
+        hasher.update(crate::VERSION.as_bytes());
+        hasher.update(public_key);
+        if let Some(digest) = batch_digest {
+            hasher.update(digest);
         }
+        report_data[32..].copy_from_slice(&hasher.finalize());
         
-        #[cfg(not(target_os = "linux"))]
-        {
-            // For testing/development
-            Ok(vec![0xDE, 0xAD, 0xBE, 0xEF]) // Mock quote
-        }
+        report_data
     }
     
     /// Verify another Fisher's attestation
-    pub fn verify_attestation(&self, _report: &AttestationReport) -> Result<bool> {
+    pub fn verify_attestation(&self, report: &AttestationReport) -> Result<bool> {
         if !self.enabled {
             return Ok(true); // Skip verification if attestation disabled
         }
         
+        // The published encryption key must be the one covered by the quote
+        let expected = Self::prepare_report_data(
+            &report.config_hash,
+            &report.public_key,
+            report.batch_digest.as_ref(),
+        );
+        if report.quote.report_data != expected {
+            return Ok(false);
+        }
+        
         // TODO: Implement TDX quote verification
         // This would use Intel DCAP libraries or your verification code
         
         Ok(true)
     }
+    
+    /// Decrypt a payload produced by [`encrypt_to`] for this enclave's public key
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() < PUBLIC_KEY_SIZE + IV_SIZE + MAC_SIZE {
+            return Err(Error::Decryption("Ciphertext too short".to_string()));
+        }
+        
+        let (ephemeral, rest) = ciphertext.split_at(PUBLIC_KEY_SIZE);
+        let (body, tag) = rest.split_at(rest.len() - MAC_SIZE);
+        let (iv, encrypted) = body.split_at(IV_SIZE);
+        
+        let ephemeral = PublicKey::from_sec1_bytes(ephemeral)
+            .map_err(|_| Error::Decryption("Invalid ephemeral public key".to_string()))?;
+        let shared = k256::ecdh::diffie_hellman(
+            self.encryption_key.to_nonzero_scalar(),
+            ephemeral.as_affine(),
+        );
+        let (enc_key, mac_key) = derive_keys(shared.raw_secret_bytes());
+        
+        // Authenticate before decrypting
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&mac_key)
+            .map_err(|e| Error::Decryption(e.to_string()))?;
+        mac.update(body);
+        mac.verify_slice(tag)
+            .map_err(|_| Error::Decryption("Authentication tag mismatch".to_string()))?;
+        
+        let mut plaintext = encrypted.to_vec();
+        Aes256Ctr::new(&enc_key.into(), iv.into()).apply_keystream(&mut plaintext);
+        
+        Ok(plaintext)
+    }
+    
+    /// Decrypt a JSON-encoded intent encrypted to this enclave
+    pub fn decrypt_intent(&self, ciphertext: &[u8]) -> Result<Intent> {
+        let plaintext = self.decrypt(ciphertext)?;
+        
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| Error::InvalidIntent(format!("Failed to parse decrypted intent: {}", e)))
+    }
+}
+
+/// Encrypt a payload to an attested Fisher public key (ECIES over secp256k1)
+///
+/// Output layout: `ephemeral_pubkey (65) || iv (16) || aes256ctr(plaintext) || hmac_sha256 (32)`
+pub fn encrypt_to(public_key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let recipient = PublicKey::from_sec1_bytes(public_key)
+        .map_err(|_| Error::Decryption("Invalid recipient public key".to_string()))?;
+    
+    let ephemeral = k256::ecdh::EphemeralSecret::random(&mut OsRng);
+    let shared = ephemeral.diffie_hellman(&recipient);
+    let (enc_key, mac_key) = derive_keys(shared.raw_secret_bytes());
+    
+    let mut iv = [0u8; IV_SIZE];
+    OsRng.fill_bytes(&mut iv);
+    
+    let mut encrypted = plaintext.to_vec();
+    Aes256Ctr::new(&enc_key.into(), &iv.into()).apply_keystream(&mut encrypted);
+    
+    let mut output = Vec::with_capacity(PUBLIC_KEY_SIZE + IV_SIZE + encrypted.len() + MAC_SIZE);
+    output.extend_from_slice(ephemeral.public_key().to_encoded_point(false).as_bytes());
+    output.extend_from_slice(&iv);
+    output.extend_from_slice(&encrypted);
+    
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(&mac_key)
+        .map_err(|e| Error::Decryption(e.to_string()))?;
+    mac.update(&output[PUBLIC_KEY_SIZE..]);
+    output.extend_from_slice(&mac.finalize().into_bytes());
+    
+    Ok(output)
+}
+
+/// Derive AES and HMAC keys from the ECDH shared secret (NIST SP 800-56 concat KDF)
+fn derive_keys(shared_secret: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let block = |counter: u32| -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(counter.to_be_bytes());
+        hasher.update(shared_secret);
+        hasher.finalize().into()
+    };
+    
+    (block(1), block(2))
 }
 
 #[cfg(test)]
@@ -138,6 +349,57 @@ mod tests {
         
         // Should work in test mode
         let report = manager.generate_report(config_hash);
-        assert!(report.is_ok() || !cfg!(target_os = "linux"));
+        assert!(report.is_ok());
+    }
+    
+    #[test]
+    fn test_report_binds_public_key() {
+        let manager = AttestationManager::new(true);
+        let mut report = manager.generate_report([7u8; 32]).unwrap();
+        
+        assert_eq!(report.public_key.len(), PUBLIC_KEY_SIZE);
+        assert_eq!(report.public_key, manager.public_key());
+        assert!(manager.verify_attestation(&report).unwrap());
+        
+        // Swapping in a different key must break the binding
+        report.public_key = AttestationManager::new(true).public_key();
+        assert!(!manager.verify_attestation(&report).unwrap());
+    }
+    
+    #[test]
+    fn test_batch_report_binds_digest() {
+        let manager = AttestationManager::new(true);
+        let mut report = manager.generate_batch_report([7u8; 32], [1u8; 32]).unwrap();
+        
+        assert_eq!(report.batch_digest, Some([1u8; 32]));
+        assert!(manager.verify_attestation(&report).unwrap());
+        
+        // Claiming a different batch must break the binding
+        report.batch_digest = Some([2u8; 32]);
+        assert!(!manager.verify_attestation(&report).unwrap());
+        
+        report.batch_digest = None;
+        assert!(!manager.verify_attestation(&report).unwrap());
+    }
+    
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let manager = AttestationManager::new(true);
+        let payload = b"transfer 100 to alice";
+        
+        let ciphertext = encrypt_to(&manager.public_key(), payload).unwrap();
+        assert_ne!(&ciphertext[PUBLIC_KEY_SIZE + IV_SIZE..][..payload.len()], payload);
+        
+        let plaintext = manager.decrypt(&ciphertext).unwrap();
+        assert_eq!(plaintext, payload);
+    }
+    
+    #[test]
+    fn test_decrypt_with_wrong_key_fails() {
+        let manager = AttestationManager::new(true);
+        let other = AttestationManager::new(true);
+        
+        let ciphertext = encrypt_to(&manager.public_key(), b"secret").unwrap();
+        assert!(matches!(other.decrypt(&ciphertext), Err(Error::Decryption(_))));
     }
 }
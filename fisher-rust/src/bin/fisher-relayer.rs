@@ -4,8 +4,8 @@
 
 use fisher_relayer::*;
 use std::path::PathBuf;
-use clap::Parser;
-use tracing::{info, error};
+use clap::{Parser, Subcommand};
+use tracing::info;
 
 #[derive(Parser)]
 #[command(name = "fisher-relayer")]
@@ -26,6 +26,31 @@ struct Cli {
     /// Run health check only
     #[arg(long)]
     health_check: bool,
+    
+    /// Submit the intents in this JSON file once running
+    #[arg(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+    
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print what a batch would cost at current fees, without running the relayer
+    GasReport {
+        /// Intents in the batch
+        #[arg(long)]
+        size: usize,
+        
+        /// Also cost posting the batch data in blobs
+        #[arg(long)]
+        blobs: bool,
+        
+        /// ETH price in USD
+        #[arg(long, default_value_t = 2500.0)]
+        eth_price: f64,
+    },
 }
 
 #[tokio::main]
@@ -45,7 +70,12 @@ async fn main() -> anyhow::Result<()> {
     
     // Load configuration
     info!("📋 Loading config from: {}", cli.config.display());
-    let mut config = FisherConfig::load(&cli.config).await?;
+    let mut config = FisherConfig::load_with_env_overrides(&cli.config).await?;
+    
+    // Only needs the RPC, so skip validating the relayer settings
+    if let Some(Command::GasReport { size, blobs, eth_price }) = cli.command {
+        return run_gas_report(config, size, blobs, eth_price).await;
+    }
     
     // Validate configuration
     validate_config(&config)?;
@@ -65,34 +95,30 @@ async fn main() -> anyhow::Result<()> {
     
     // Initialize Ethereum connection
     #[cfg(not(target_arch = "wasm32"))]
-    {
+    if relayer.config.submission_backend == SubmissionBackend::Ethereum {
         info!("🔗 Connecting to Ethereum...");
         relayer.init_ethereum().await?;
+    } else {
+        info!("🧪 Simulating submissions, no chain connection");
     }
     
-    // Generate attestation if enabled
-    if relayer.config.enable_attestation {
-        #[cfg(feature = "attestation")]
-        match relayer.get_attestation() {
-            Ok(report) => {
-                info!("✅ Attestation generated");
-                info!("   Quote: {} bytes", report.quote.len());
-            },
-            Err(e) => warn!("⚠️  Attestation failed: {}", e),
-        }
-        
-        #[cfg(not(feature = "attestation"))]
-        info!("ℹ️  Attestation disabled (compile with --features attestation to enable)");
-    }
+    // Attest before taking intents (fails here if attestation is required but unavailable)
+    relayer.attest_startup().await?;
     
     // Start automatic batch processing
     info!("🎯 Starting automatic batch processor");
     info!("   Batch interval: {}ms", relayer.config.batch_interval_ms);
     info!("   Min batch size: {}", relayer.config.min_batch_size);
-    info!("   Max batch size: {}", relayer.config.max_batch_size);
+    info!("   Batch trigger size: {}", relayer.config.batch_trigger_size);
+    info!("   Max intents per tx: {}", relayer.config.max_intents_per_tx);
     
     relayer.start().await;
     
+    if let Some(path) = &cli.replay {
+        info!("📂 Replaying intents from: {}", path.display());
+        relayer.submit_intents_file(path).await?;
+    }
+    
     info!("✅ Fisher Relayer is running!");
     info!("   Press Ctrl+C to stop");
     
@@ -103,6 +129,8 @@ async fn main() -> anyhow::Result<()> {
         }
     }
     
+    relayer.stop();
+    
     // Print final metrics
     let metrics = relayer.get_metrics().await;
     info!("\n{}", metrics.summary());
@@ -129,8 +157,12 @@ fn validate_config(config: &FisherConfig) -> anyhow::Result<()> {
         anyhow::bail!("min_batch_size must be > 0");
     }
     
-    if config.max_batch_size < config.min_batch_size {
-        anyhow::bail!("max_batch_size must be >= min_batch_size");
+    if config.batch_trigger_size < config.min_batch_size {
+        anyhow::bail!("batch_trigger_size must be >= min_batch_size");
+    }
+    
+    if config.max_intents_per_tx == 0 {
+        anyhow::bail!("max_intents_per_tx must be > 0");
     }
     
     Ok(())
@@ -144,6 +176,7 @@ async fn run_health_check(config: &FisherConfig) -> anyhow::Result<()> {
     info!("   Fisher: {:?}", config.fisher_address);
     info!("   EVVM Core: {:?}", config.evvm_core_address);
     info!("   RPC: {}", mask_rpc_url(&config.rpc_url));
+    info!("   Config hash: 0x{}", hex::encode(config.config_hash()));
     
     // Test relayer creation
     let relayer = FisherRelayer::new(config.clone())?;
@@ -155,23 +188,21 @@ async fn run_health_check(config: &FisherConfig) -> anyhow::Result<()> {
         info!("✅ Ethereum connection configured");
     }
     
-    // Test attestation (if enabled)
-    if config.enable_attestation {
-        #[cfg(feature = "attestation")]
-        match relayer.get_attestation() {
-            Ok(report) => info!("✅ Attestation: {} bytes", report.quote.len()),
-            Err(e) => warn!("⚠️  Attestation: {}", e),
-        }
-        #[cfg(not(feature = "attestation"))]
-        info!("ℹ️  Attestation disabled (compile with --features attestation to enable)");
+    // Test attestation
+    match relayer.attest_startup().await? {
+        AttestationStatus::Unattested => tracing::warn!("⚠️  Attestation: UNATTESTED"),
+        status => info!("✅ Attestation: {}", status.as_str()),
     }
     
     // Test gas savings calculation
-    let (williams, phi, combined) = phi_optimization::estimate_total_savings(1000);
+    let savings = relayer.preview_savings(1000, config.enable_blobs.enabled());
     info!("✅ Gas savings calculation working");
-    info!("   Williams: {:.2}%", williams);
-    info!("   φ-optimization: {:.2}%", phi);
-    info!("   Combined: {:.2}%", combined);
+    info!("   Williams: {:.2}%", savings.williams_percent);
+    info!("   φ-optimization: {:.2}%", savings.phi_percent);
+    if config.enable_blobs.enabled() {
+        info!("   EIP-4844 blobs: {:.2}%", savings.blob_percent);
+    }
+    info!("   Combined: {:.2}%", savings.combined_percent);
     
     info!("\n🎉 Health check passed!");
     info!("   System is ready for production deployment");
@@ -179,6 +210,16 @@ async fn run_health_check(config: &FisherConfig) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_gas_report(config: FisherConfig, size: usize, blobs: bool, eth_price: f64) -> anyhow::Result<()> {
+    info!("⛽ Fetching fees from: {}", mask_rpc_url(&config.rpc_url));
+    
+    let relayer = FisherRelayer::new(config)?;
+    let report = relayer.gas_report(size, eth_price).await?;
+    println!("{}", report.summary(blobs));
+    
+    Ok(())
+}
+
 fn mask_rpc_url(url: &str) -> String {
     if let Some(pos) = url.rfind('/') {
         let (base, key) = url.split_at(pos + 1);
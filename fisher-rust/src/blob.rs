@@ -3,8 +3,10 @@
 //! Implements blob transactions for massive gas savings on batch data
 
 use crate::{Batch, Result, Error};
+use alloy_primitives::U256;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
 
 /// Type alias for KZG commitment (48 bytes)
 pub type Commitment = Vec<u8>;
@@ -15,15 +17,86 @@ pub type Proof = Vec<u8>;
 /// Maximum blob size (128KB per blob)
 pub const BLOB_SIZE: usize = 131_072;
 
-/// Maximum number of blobs per transaction
+/// Default blobs per transaction (`max_blobs_per_tx`), the Cancun per-block maximum
 pub const MAX_BLOBS_PER_TX: usize = 6;
 
+/// Blob limits a network enforces per block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobParams {
+    /// Blobs per block the base fee steers towards
+    pub target_blobs_per_block: usize,
+    
+    /// Most blobs a block (and so a single transaction) may carry
+    pub max_blobs_per_block: usize,
+}
+
+impl BlobParams {
+    /// Current limits of a known chain, `None` for networks without an entry
+    pub fn for_chain(chain_id: u64) -> Option<Self> {
+        let params = |target_blobs_per_block, max_blobs_per_block| Self { target_blobs_per_block, max_blobs_per_block };
+        
+        match chain_id {
+            // Ethereum mainnet and testnets since Pectra (EIP-7691)
+            1 | 11_155_111 | 17_000 | 560_048 => Some(params(6, 9)),
+            // Gnosis Chain
+            100 => Some(params(1, 2)),
+            _ => None,
+        }
+    }
+}
+
+/// Check a configured `max_blobs_per_tx` against the network's per-block maximum
+///
+/// Unknown (or unset) chains are only checked for a non-zero limit.
+pub fn validate_max_blobs(max_blobs_per_tx: usize, chain_id: Option<u64>) -> Result<()> {
+    if max_blobs_per_tx == 0 {
+        return Err(Error::Config("max_blobs_per_tx must be at least 1".to_string()));
+    }
+    
+    let Some(chain_id) = chain_id else { return Ok(()) };
+    match BlobParams::for_chain(chain_id) {
+        Some(params) if max_blobs_per_tx > params.max_blobs_per_block => Err(Error::Config(format!(
+            "max_blobs_per_tx is {}, but chain {} allows at most {} blobs per block",
+            max_blobs_per_tx, chain_id, params.max_blobs_per_block
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Field element size for KZG commitment
 pub const FIELD_ELEMENT_SIZE: usize = 32;
 
 /// Number of field elements per blob
 pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
 
+/// Blob gas consumed by each blob
+pub const GAS_PER_BLOB: u64 = 131_072;
+
+/// Calldata gas per non-zero byte
+pub const CALLDATA_GAS_PER_BYTE: u64 = 16;
+
+/// Lowest blob base fee (wei)
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+
+/// `BLOB_BASE_FEE_UPDATE_FRACTION` since Prague (EIP-7691)
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 5_007_716;
+
+/// zstd compression level for batch payloads
+pub const ZSTD_LEVEL: i32 = 3;
+
+/// Payload header: 1-byte codec flag + 4-byte little-endian payload length
+const PAYLOAD_HEADER_SIZE: usize = 5;
+
+/// Codec flag for uncompressed bincode payloads
+const CODEC_RAW: u8 = 0;
+
+/// Codec flag for zstd-compressed bincode payloads
+const CODEC_ZSTD: u8 = 1;
+
+/// Largest decompressed batch accepted, so a crafted payload can't expand
+/// without bound (far above any batch that fits in a transaction's blobs)
+pub const MAX_DECODED_BATCH_BYTES: usize = 32 * 1024 * 1024;
+
 /// Blob transaction data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlobTx {
@@ -40,23 +113,138 @@ pub struct BlobTx {
     pub proof: Proof,
 }
 
+/// Serialized batch payload, zstd-compressed when that makes it smaller
+#[derive(Debug, Clone)]
+pub struct CompressedBatch {
+    /// Framed payload (header + bincode or zstd bytes)
+    pub data: Vec<u8>,
+    
+    /// Size of the uncompressed bincode serialization
+    pub original_size: usize,
+    
+    /// Whether zstd compression was applied
+    pub compressed: bool,
+}
+
+impl CompressedBatch {
+    /// Achieved compression ratio (original / encoded, >= 1.0 means smaller)
+    pub fn compression_ratio(&self) -> f64 {
+        if self.data.is_empty() {
+            return 1.0;
+        }
+        self.original_size as f64 / self.data.len() as f64
+    }
+}
+
 /// Blob batch encoder
 pub struct BlobEncoder;
 
 impl BlobEncoder {
-    /// Encode batch into blob format
-    pub fn encode_batch(batch: &Batch) -> Result<Vec<BlobTx>> {
-        // Serialize batch to bytes
-        let batch_bytes = bincode::serialize(batch)
+    /// Serialize and compress batch for blob or calldata posting
+    ///
+    /// Falls back to the raw bincode bytes if compression doesn't help.
+    pub fn compress_batch(batch: &Batch) -> Result<CompressedBatch> {
+        let raw = bincode::serialize(batch)
             .map_err(|e| Error::Other(format!("Failed to serialize batch: {}", e)))?;
         
+        let zstd_bytes = zstd::bulk::compress(&raw, ZSTD_LEVEL)
+            .map_err(|e| Error::Other(format!("Failed to compress batch: {}", e)))?;
+        
+        let (codec, payload) = if zstd_bytes.len() < raw.len() {
+            (CODEC_ZSTD, zstd_bytes)
+        } else {
+            (CODEC_RAW, raw.clone())
+        };
+        
+        let mut data = Vec::with_capacity(PAYLOAD_HEADER_SIZE + payload.len());
+        data.push(codec);
+        data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(&payload);
+        
+        Ok(CompressedBatch {
+            data,
+            original_size: raw.len(),
+            compressed: codec == CODEC_ZSTD,
+        })
+    }
+    
+    /// Decode a payload produced by [`BlobEncoder::compress_batch`]
+    ///
+    /// Trailing bytes (e.g. blob padding) after the framed payload are ignored.
+    pub fn decompress_batch(data: &[u8]) -> Result<Batch> {
+        if data.len() < PAYLOAD_HEADER_SIZE {
+            return Err(Error::Other("Batch payload too short".to_string()));
+        }
+        
+        let codec = data[0];
+        let len = u32::from_le_bytes([data[1], data[2], data[3], data[4]]) as usize;
+        let payload = data
+            .get(PAYLOAD_HEADER_SIZE..PAYLOAD_HEADER_SIZE + len)
+            .ok_or_else(|| Error::Other("Batch payload truncated".to_string()))?;
+        
+        let raw = match codec {
+            CODEC_RAW => payload.to_vec(),
+            CODEC_ZSTD => zstd::bulk::decompress(payload, MAX_DECODED_BATCH_BYTES)
+                .map_err(|e| Error::Other(format!("Failed to decompress batch: {}", e)))?,
+            other => return Err(Error::Other(format!("Unknown batch codec: {}", other))),
+        };
+        
+        bincode::deserialize(&raw)
+            .map_err(|e| Error::Other(format!("Failed to deserialize batch: {}", e)))
+    }
+    
+    /// Number of blobs the encoded batch would occupy
+    pub fn blobs_required(batch: &Batch) -> Result<usize> {
+        Ok(Self::compress_batch(batch)?.data.len().div_ceil(BLOB_SIZE))
+    }
+    
+    /// Size of the batch's ABI-encoded `submitBatchOptimized(Payment[], bytes[])` calldata
+    ///
+    /// Selector and two array offsets, then each `Payment` as five static
+    /// words, then each signature as an offset, a length word and its bytes
+    /// padded to a whole word. Batches carrying permits go through
+    /// `submitBatchWithPermits`, adding a third offset and five words per intent.
+    pub fn calldata_bytes(batch: &Batch) -> usize {
+        const WORD: usize = 32;
+        const PAYMENT_WORDS: usize = 5;
+        const PERMIT_WORDS: usize = 5;
+        
+        let n = batch.intents.len();
+        let signatures: usize = batch.intents.iter()
+            .map(|intent| WORD + intent.signature.len().div_ceil(WORD) * WORD)
+            .sum();
+        
+        let payments_and_signatures = 4 + 2 * WORD
+            + WORD + n * PAYMENT_WORDS * WORD
+            + WORD + n * WORD + signatures;
+        
+        // submitBatchWithPermits adds a third array of static permit tuples
+        if batch.intents.iter().any(|intent| intent.permit.is_some()) {
+            payments_and_signatures + WORD + WORD + n * PERMIT_WORDS * WORD
+        } else {
+            payments_and_signatures
+        }
+    }
+    
+    /// Encode batch into blob format, using at most `max_blobs` blobs
+    pub fn encode_batch(batch: &Batch, max_blobs: usize) -> Result<Vec<BlobTx>> {
+        // Serialize and compress batch to bytes
+        let compressed = Self::compress_batch(batch)?;
+        log::debug!(
+            "Batch payload: {} -> {} bytes ({:.2}x)",
+            compressed.original_size,
+            compressed.data.len(),
+            compressed.compression_ratio()
+        );
+        let batch_bytes = compressed.data;
+        
         // Split into blobs if needed (max 128KB per blob)
-        let num_blobs = (batch_bytes.len() + BLOB_SIZE - 1) / BLOB_SIZE;
+        let num_blobs = batch_bytes.len().div_ceil(BLOB_SIZE);
         
-        if num_blobs > MAX_BLOBS_PER_TX {
+        if num_blobs > max_blobs {
             return Err(Error::BatchTooLarge(format!(
-                "Batch requires {} blobs, max is {}",
-                num_blobs, MAX_BLOBS_PER_TX
+                "Batch requires {} blobs, max_blobs_per_tx is {}",
+                num_blobs, max_blobs
             )));
         }
         
@@ -145,14 +333,190 @@ impl BlobEncoder {
             combined_data.extend_from_slice(&blob.blob_data);
         }
         
-        // Deserialize batch
-        let batch: Batch = bincode::deserialize(&combined_data)
-            .map_err(|e| Error::Other(format!("Failed to deserialize batch: {}", e)))?;
+        // Decompress and deserialize batch
+        Self::decompress_batch(&combined_data)
+    }
+}
+
+/// Location of one batch's payload inside a packed blob set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackedBatch {
+    /// ID of the packed batch
+    pub batch_id: u64,
+    
+    /// Byte offset of the framed payload across the concatenated blobs
+    pub offset: usize,
+    
+    /// Length of the framed payload
+    pub len: usize,
+}
+
+/// Blobs carrying several batches, and where each batch sits in them
+#[derive(Debug, Clone)]
+pub struct PackedBlobs {
+    /// Blobs for a single transaction
+    pub blobs: Vec<BlobTx>,
+    
+    /// One entry per packed batch, in packing order
+    pub batches: Vec<PackedBatch>,
+}
+
+impl PackedBlobs {
+    /// Decode the batch described by `entry`
+    pub fn decode(&self, entry: &PackedBatch) -> Result<Batch> {
+        BlobPacker::unpack(&self.blobs, entry)
+    }
+    
+    /// Decode every packed batch, in packing order
+    pub fn decode_all(&self) -> Result<Vec<Batch>> {
+        let data = concat_blobs(&self.blobs);
+        self.batches.iter()
+            .map(|entry| decode_entry(&data, entry))
+            .collect()
+    }
+}
+
+/// Packs several small batches into one blob transaction
+///
+/// A 10KB batch alone still pays for a whole blob. The packer appends
+/// encoded batches back to back and flushes once the data nears the
+/// transaction's blob limit or the oldest pending batch has waited
+/// `max_wait`, amortizing the blob base fee across batches.
+pub struct BlobPacker {
+    data: Vec<u8>,
+    entries: Vec<PackedBatch>,
+    oldest: Option<Instant>,
+    max_wait: Duration,
+    capacity: usize,
+    flush_at_bytes: usize,
+}
+
+impl BlobPacker {
+    /// Packer for up to `max_blobs` blobs per transaction (`max_blobs_per_tx`),
+    /// flushing at 90% of capacity or after `max_wait`
+    pub fn new(max_blobs: usize, max_wait: Duration) -> Self {
+        let capacity = BLOB_SIZE * max_blobs;
+        Self {
+            data: Vec::new(),
+            entries: Vec::new(),
+            oldest: None,
+            max_wait,
+            capacity,
+            flush_at_bytes: capacity / 10 * 9,
+        }
+    }
+    
+    /// Flush as soon as pending data reaches `bytes` (capped at capacity)
+    pub fn with_flush_at(mut self, bytes: usize) -> Self {
+        self.flush_at_bytes = bytes.min(self.capacity);
+        self
+    }
+    
+    /// Bytes one blob transaction can carry
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    
+    /// Batches waiting to be flushed
+    pub fn pending_batches(&self) -> usize {
+        self.entries.len()
+    }
+    
+    /// Encoded bytes waiting to be flushed
+    pub fn pending_bytes(&self) -> usize {
+        self.data.len()
+    }
+    
+    /// Add a batch, returning any blob set that became ready
+    ///
+    /// If the batch doesn't fit alongside the pending ones, the pending
+    /// batches are flushed first and the batch starts a new set.
+    pub fn add(&mut self, batch: &Batch) -> Result<Option<PackedBlobs>> {
+        let payload = BlobEncoder::compress_batch(batch)?.data;
+        if payload.len() > self.capacity {
+            return Err(Error::BatchTooLarge(format!(
+                "Batch {} needs {} bytes, blob capacity is {}",
+                batch.id,
+                payload.len(),
+                self.capacity
+            )));
+        }
+        
+        let overflow = if self.data.len() + payload.len() > self.capacity {
+            self.flush()?
+        } else {
+            None
+        };
+        
+        self.entries.push(PackedBatch {
+            batch_id: batch.id,
+            offset: self.data.len(),
+            len: payload.len(),
+        });
+        self.data.extend_from_slice(&payload);
+        self.oldest.get_or_insert_with(Instant::now);
+        
+        if overflow.is_some() {
+            return Ok(overflow);
+        }
+        if self.data.len() >= self.flush_at_bytes {
+            return self.flush();
+        }
+        Ok(None)
+    }
+    
+    /// Flush if the oldest pending batch has waited at least `max_wait` by `now`
+    pub fn flush_if_due(&mut self, now: Instant) -> Result<Option<PackedBlobs>> {
+        match self.oldest {
+            Some(oldest) if now.saturating_duration_since(oldest) >= self.max_wait => self.flush(),
+            _ => Ok(None),
+        }
+    }
+    
+    /// Pack everything pending into blobs, if anything is pending
+    pub fn flush(&mut self) -> Result<Option<PackedBlobs>> {
+        if self.entries.is_empty() {
+            return Ok(None);
+        }
+        
+        let blobs = self.data.chunks(BLOB_SIZE)
+            .map(BlobEncoder::create_blob_tx)
+            .collect::<Result<Vec<_>>>()?;
+        
+        log::debug!(
+            "Packed {} batches into {} blobs ({} bytes)",
+            self.entries.len(),
+            blobs.len(),
+            self.data.len()
+        );
         
-        Ok(batch)
+        self.data.clear();
+        self.oldest = None;
+        let batches = std::mem::take(&mut self.entries);
+        
+        Ok(Some(PackedBlobs { blobs, batches }))
+    }
+    
+    /// Decode one batch from a packed blob set given its mapping entry
+    pub fn unpack(blobs: &[BlobTx], entry: &PackedBatch) -> Result<Batch> {
+        decode_entry(&concat_blobs(blobs), entry)
     }
 }
 
+fn concat_blobs(blobs: &[BlobTx]) -> Vec<u8> {
+    blobs.iter().flat_map(|blob| blob.blob_data.iter().copied()).collect()
+}
+
+fn decode_entry(data: &[u8], entry: &PackedBatch) -> Result<Batch> {
+    let payload = data
+        .get(entry.offset..entry.offset + entry.len)
+        .ok_or_else(|| Error::Other(format!("Packed batch {} out of range", entry.batch_id)))?;
+    BlobEncoder::decompress_batch(payload)
+}
+
+/// Approximate calldata per intent, for savings previews before encoding
+pub const ESTIMATED_INTENT_BYTES: usize = 200;
+
 /// Calculate gas savings from using blobs vs calldata
 pub fn calculate_blob_savings(batch_size_bytes: usize) -> (u64, u64, f64) {
     // Calldata cost: 16 gas per byte
@@ -172,6 +536,61 @@ pub fn calculate_blob_savings(batch_size_bytes: usize) -> (u64, u64, f64) {
     (calldata_gas, blob_gas_total, savings_pct)
 }
 
+/// Blob base fee implied by a header's `excess_blob_gas` (EIP-4844 `fake_exponential`)
+pub fn blob_base_fee(excess_blob_gas: u64) -> U256 {
+    let factor = U256::from(MIN_BLOB_BASE_FEE);
+    let numerator = U256::from(excess_blob_gas);
+    let denominator = U256::from(BLOB_BASE_FEE_UPDATE_FRACTION);
+    
+    let mut output = U256::ZERO;
+    let mut accum = factor * denominator;
+    let mut i = U256::from(1);
+    while !accum.is_zero() {
+        output = output.saturating_add(accum);
+        accum = accum.saturating_mul(numerator) / (denominator * i);
+        i += U256::from(1);
+    }
+    
+    output / denominator
+}
+
+/// Calldata gas to post `intent_count` intents
+pub fn calldata_gas(intent_count: usize) -> u64 {
+    (intent_count * ESTIMATED_INTENT_BYTES) as u64 * CALLDATA_GAS_PER_BYTE
+}
+
+/// Blob gas to post `intent_count` intents (at least one blob)
+pub fn blob_gas(intent_count: usize) -> u64 {
+    (intent_count * ESTIMATED_INTENT_BYTES).div_ceil(BLOB_SIZE).max(1) as u64 * GAS_PER_BLOB
+}
+
+/// Current fees for posting batch data, as blobs or as calldata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataFees {
+    /// Execution base fee per gas (wei)
+    pub base_fee: U256,
+    
+    /// Blob base fee per blob gas (wei)
+    pub blob_base_fee: U256,
+}
+
+impl DataFees {
+    /// Cost of posting `intent_count` intents as calldata (wei)
+    pub fn calldata_cost(&self, intent_count: usize) -> U256 {
+        U256::from(calldata_gas(intent_count)).saturating_mul(self.base_fee)
+    }
+    
+    /// Cost of posting `intent_count` intents in blobs (wei)
+    pub fn blob_cost(&self, intent_count: usize) -> U256 {
+        U256::from(blob_gas(intent_count)).saturating_mul(self.blob_base_fee)
+    }
+    
+    /// Whether blobs are the cheaper way to post a batch of `intent_count` intents
+    pub fn blobs_cheaper(&self, intent_count: usize) -> bool {
+        self.blob_cost(intent_count) < self.calldata_cost(intent_count)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,7 +601,7 @@ mod tests {
     fn test_blob_encoding() {
         let batch = create_test_batch(100);
         
-        let blobs = BlobEncoder::encode_batch(&batch).unwrap();
+        let blobs = BlobEncoder::encode_batch(&batch, MAX_BLOBS_PER_TX).unwrap();
         assert!(!blobs.is_empty());
         assert!(blobs.len() <= MAX_BLOBS_PER_TX);
         
@@ -195,13 +614,46 @@ mod tests {
     fn test_blob_roundtrip() {
         let original = create_test_batch(50);
         
-        let blobs = BlobEncoder::encode_batch(&original).unwrap();
+        let blobs = BlobEncoder::encode_batch(&original, MAX_BLOBS_PER_TX).unwrap();
         let decoded = BlobEncoder::decode_batch(&blobs).unwrap();
         
         assert_eq!(original.intents.len(), decoded.intents.len());
         assert_eq!(original.id, decoded.id);
     }
     
+    #[test]
+    fn test_configured_blob_limit() {
+        // ~1.7 and ~3.6 blobs of incompressible data
+        let small = create_test_batch(3000);
+        let large = create_test_batch(6500);
+        assert_eq!(BlobEncoder::blobs_required(&small).unwrap(), 2);
+        assert_eq!(BlobEncoder::blobs_required(&large).unwrap(), 4);
+        
+        assert_eq!(BlobEncoder::encode_batch(&small, 3).unwrap().len(), 2);
+        
+        match BlobEncoder::encode_batch(&large, 3) {
+            Err(Error::BatchTooLarge(msg)) => assert!(msg.contains("4 blobs") && msg.contains("max_blobs_per_tx is 3"), "{}", msg),
+            other => panic!("expected BatchTooLarge, got {:?}", other.map(|blobs| blobs.len())),
+        }
+        assert_eq!(BlobEncoder::encode_batch(&large, MAX_BLOBS_PER_TX).unwrap().len(), 4);
+    }
+    
+    #[test]
+    fn test_max_blobs_validated_against_network() {
+        assert!(validate_max_blobs(MAX_BLOBS_PER_TX, None).is_ok());
+        assert!(validate_max_blobs(9, Some(1)).is_ok());
+        assert!(validate_max_blobs(0, None).is_err());
+        
+        match validate_max_blobs(10, Some(1)) {
+            Err(Error::Config(msg)) => assert!(msg.contains("max_blobs_per_tx is 10") && msg.contains("at most 9"), "{}", msg),
+            other => panic!("expected Config error, got {:?}", other),
+        }
+        assert!(validate_max_blobs(3, Some(100)).is_err());
+        
+        // No table entry: trust the operator
+        assert!(validate_max_blobs(12, Some(31_337)).is_ok());
+    }
+    
     #[test]
     fn test_blob_savings_calculation() {
         // 1000 intents × 200 bytes = 200KB
@@ -223,7 +675,7 @@ mod tests {
         // Create batch larger than one blob
         let batch = create_test_batch(2000);
         
-        let blobs = BlobEncoder::encode_batch(&batch).unwrap();
+        let blobs = BlobEncoder::encode_batch(&batch, MAX_BLOBS_PER_TX).unwrap();
         
         // Should split into multiple blobs
         assert!(blobs.len() > 1);
@@ -233,13 +685,167 @@ mod tests {
         assert_eq!(batch.intents.len(), decoded.intents.len());
     }
     
+    #[test]
+    fn test_compression_roundtrip() {
+        for n in [10, 100, 1000] {
+            let batch = create_test_batch(n);
+            
+            let compressed = BlobEncoder::compress_batch(&batch).unwrap();
+            let decoded = BlobEncoder::decompress_batch(&compressed.data).unwrap();
+            
+            assert_eq!(decoded.intents.len(), n);
+            assert_eq!(decoded.intents[n - 1].id, batch.intents[n - 1].id);
+            assert_eq!(decoded.intents[n - 1].signature, batch.intents[n - 1].signature);
+        }
+    }
+    
+    #[test]
+    fn test_compression_shrinks_large_batches() {
+        for n in [100, 1000] {
+            let batch = create_test_batch(n);
+            let raw_size = bincode::serialize(&batch).unwrap().len();
+            
+            let compressed = BlobEncoder::compress_batch(&batch).unwrap();
+            
+            assert!(compressed.compressed);
+            assert_eq!(compressed.original_size, raw_size);
+            assert!(compressed.data.len() < raw_size);
+            assert!(compressed.compression_ratio() > 1.0);
+        }
+    }
+    
+    #[test]
+    fn test_raw_payload_decodes_with_padding() {
+        let batch = create_test_batch(1);
+        let raw = bincode::serialize(&batch).unwrap();
+        
+        // Uncompressed frame followed by blob zero-padding
+        let mut data = vec![CODEC_RAW];
+        data.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+        data.extend_from_slice(&raw);
+        data.resize(BLOB_SIZE, 0);
+        
+        let decoded = BlobEncoder::decompress_batch(&data).unwrap();
+        assert_eq!(decoded.id, batch.id);
+        assert_eq!(decoded.intents[0].id, batch.intents[0].id);
+    }
+    
+    #[test]
+    fn test_decompression_bomb_rejected() {
+        // A few KB that would expand past the decode limit
+        let bomb = zstd::bulk::compress(&vec![0u8; MAX_DECODED_BATCH_BYTES + 1], ZSTD_LEVEL).unwrap();
+        assert!(bomb.len() < BLOB_SIZE);
+        
+        let mut data = vec![CODEC_ZSTD];
+        data.extend_from_slice(&(bomb.len() as u32).to_le_bytes());
+        data.extend_from_slice(&bomb);
+        
+        let err = BlobEncoder::decompress_batch(&data).unwrap_err();
+        assert!(err.to_string().contains("decompress"), "{}", err);
+    }
+    
+    #[test]
+    fn test_packer_shares_one_blob_between_small_batches() {
+        let batches: Vec<Batch> = (1..=5)
+            .map(|id| Batch { id, ..create_test_batch(10 * id as usize) })
+            .collect();
+        
+        let mut packer = BlobPacker::new(MAX_BLOBS_PER_TX, Duration::from_secs(60));
+        for batch in &batches {
+            assert!(packer.add(batch).unwrap().is_none());
+        }
+        assert_eq!(packer.pending_batches(), 5);
+        
+        let packed = packer.flush().unwrap().unwrap();
+        assert_eq!(packer.pending_bytes(), 0);
+        assert!(packer.flush().unwrap().is_none());
+        
+        // Five batches, one blob's worth of gas
+        assert_eq!(packed.blobs.len(), 1);
+        assert_eq!(packed.batches.len(), 5);
+        assert_eq!(packed.batches[0].offset, 0);
+        for pair in packed.batches.windows(2) {
+            assert_eq!(pair[1].offset, pair[0].offset + pair[0].len);
+        }
+        
+        for (entry, original) in packed.batches.iter().zip(&batches) {
+            let decoded = packed.decode(entry).unwrap();
+            assert_eq!(decoded.id, original.id);
+            assert_eq!(decoded.intents.len(), original.intents.len());
+            assert_eq!(decoded.intents.last().unwrap().id, original.intents.last().unwrap().id);
+        }
+        
+        let all: Vec<u64> = packed.decode_all().unwrap().iter().map(|b| b.id).collect();
+        assert_eq!(all, vec![1, 2, 3, 4, 5]);
+        
+        // The consumer only needs the blobs and its mapping entry
+        assert_eq!(BlobPacker::unpack(&packed.blobs, &packed.batches[3]).unwrap().id, 4);
+    }
+    
+    #[test]
+    fn test_packer_flushes_when_near_full() {
+        let first = Batch { id: 1, ..create_test_batch(50) };
+        let second = Batch { id: 2, ..create_test_batch(50) };
+        let size = BlobEncoder::compress_batch(&first).unwrap().data.len();
+        
+        let mut packer = BlobPacker::new(MAX_BLOBS_PER_TX, Duration::from_secs(60)).with_flush_at(size + 1);
+        assert!(packer.add(&first).unwrap().is_none());
+        
+        let packed = packer.add(&second).unwrap().unwrap();
+        assert_eq!(packed.batches.iter().map(|b| b.batch_id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(packer.pending_batches(), 0);
+    }
+    
+    #[test]
+    fn test_packer_spills_into_next_set_at_capacity() {
+        // Capacity follows the configured blob limit, not the Cancun default
+        let mut packer = BlobPacker::new(2, Duration::from_secs(60));
+        assert_eq!(packer.capacity(), 2 * BLOB_SIZE);
+        let big = create_test_batch(2000);
+        let size = BlobEncoder::compress_batch(&big).unwrap().data.len();
+        let fits = packer.capacity() / size;
+        
+        let mut flushed = Vec::new();
+        for id in 0..=fits as u64 {
+            if let Some(packed) = packer.add(&Batch { id, ..big.clone() }).unwrap() {
+                flushed.push(packed);
+            }
+        }
+        
+        // Everything that fit went out together; the rest waits for the next set
+        let packed = &flushed[0];
+        assert!(packed.blobs.len() <= 2);
+        assert!(packer.pending_batches() >= 1);
+        assert_eq!(packed.batches.len() + packer.pending_batches(), fits + 1);
+        assert_eq!(packed.decode_all().unwrap().len(), packed.batches.len());
+    }
+    
+    #[test]
+    fn test_packer_flushes_on_timeout() {
+        let mut packer = BlobPacker::new(MAX_BLOBS_PER_TX, Duration::from_secs(5));
+        assert!(packer.flush_if_due(Instant::now()).unwrap().is_none());
+        
+        packer.add(&create_test_batch(10)).unwrap();
+        let added = Instant::now();
+        
+        assert!(packer.flush_if_due(added).unwrap().is_none());
+        let packed = packer.flush_if_due(added + Duration::from_secs(6)).unwrap().unwrap();
+        assert_eq!(packed.batches.len(), 1);
+        assert_eq!(packed.decode(&packed.batches[0]).unwrap().intents.len(), 10);
+    }
+    
     fn create_test_batch(num_intents: usize) -> Batch {
         let intents: Vec<Intent> = (0..num_intents)
             .map(|i| {
                 let mut from = [0u8; 20];
-                from[0] = (i as u8);
+                from[0] = i as u8;
                 let mut to = [1u8; 20];
-                to[0] = (i as u8);
+                to[0] = i as u8;
+                
+                // Real signatures are high-entropy; don't let them compress away
+                let mut signature = Sha256::digest((i as u64).to_le_bytes()).to_vec();
+                signature.extend_from_slice(&Sha256::digest(&signature));
+                signature.push(27);
                 
                 Intent {
                     id: format!("intent_{}", i),
@@ -248,9 +854,12 @@ mod tests {
                     amount: U256::from(1000),
                     priority: false,
                     nonce: i as u64,
-                    signature: vec![0u8; 65],
+                    signature,
                     timestamp: 1234567890,
                     max_gas_price: Some(U256::from(20_000_000_000u64)),
+                    target_chain: None,
+                    version: crate::INTENT_VERSION,
+                    permit: None,
                 }
             })
             .collect();
@@ -263,6 +872,33 @@ mod tests {
             estimated_gas: U256::from(14_000_000),
             estimated_savings: U256::from(226_000_000),
             created_at: 1234567890,
+            recipient_groups: Vec::new(),
+            aggregated: Vec::new(),
         }
     }
+    
+    #[test]
+    fn test_blob_base_fee_from_excess() {
+        assert_eq!(blob_base_fee(0), U256::from(MIN_BLOB_BASE_FEE));
+        
+        // e^1 at one update fraction of excess
+        assert_eq!(blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION), U256::from(2));
+        assert_eq!(blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION * 20), U256::from(485_165_195u64));
+        assert!(blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION * 30) > blob_base_fee(BLOB_BASE_FEE_UPDATE_FRACTION * 20));
+    }
+    
+    #[test]
+    fn test_cheaper_data_path_follows_blob_fee() {
+        let gwei = U256::from(1_000_000_000u64);
+        let calm = DataFees { base_fee: gwei, blob_base_fee: U256::from(1) };
+        let spiked = DataFees { base_fee: gwei, blob_base_fee: gwei * U256::from(50) };
+        
+        assert!(calm.blobs_cheaper(10));
+        assert!(calm.blobs_cheaper(1_000));
+        assert!(!spiked.blobs_cheaper(10));
+        
+        // A whole blob is paid for however little goes in it
+        assert_eq!(spiked.blob_cost(1), spiked.blob_cost(600));
+        assert!(spiked.blob_cost(700) > spiked.blob_cost(600));
+    }
 }
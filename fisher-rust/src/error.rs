@@ -1,7 +1,5 @@
 //! Error types for Fisher relayer
 
-use thiserror::Error;
-
 /// Fisher error types
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -25,10 +23,33 @@ pub enum Error {
     #[error("RPC error: {0}")]
     Rpc(String),
     
-    /// Contract error
+    /// RPC node unreachable (transient; the relayer reconnects with backoff)
+    #[error("RPC disconnected: {0}")]
+    Disconnected(String),
+    
+    /// Contract error with no more specific classification
     #[error("Contract error: {0}")]
     Contract(String),
     
+    /// Transaction nonce already used by the sender
+    #[error("Nonce too low: {0}")]
+    NonceTooLow(String),
+    
+    /// Sender balance can't cover gas and value
+    #[error("Insufficient funds: {0}")]
+    InsufficientFunds(String),
+    
+    /// Gas price below what the node accepts (or too low to replace a pending tx)
+    #[error("Transaction underpriced: {0}")]
+    Underpriced(String),
+    
+    /// Call or transaction reverted
+    #[error("Reverted: {reason}")]
+    Reverted {
+        /// Decoded revert reason
+        reason: String,
+    },
+    
     /// Invalid signature
     #[error("Invalid signature")]
     InvalidSignature,
@@ -41,10 +62,51 @@ pub enum Error {
     #[error("Attestation error: {0}")]
     Attestation(String),
     
+    /// Decryption error
+    #[error("Decryption error: {0}")]
+    Decryption(String),
+    
     /// Invalid intent
     #[error("Invalid intent: {0}")]
     InvalidIntent(String),
     
+    /// Intent already batched or submitted on-chain
+    #[error("Intent already settled: {0}")]
+    AlreadySettled(String),
+    
+    /// Batch transaction dropped by a chain reorganization
+    #[error("Batch reorged out: {0}")]
+    Reorged(String),
+    
+    /// Transaction signing failed
+    #[error("Signing error: {0}")]
+    Signing(String),
+    
+    /// Intent kept failing on-chain and was dropped
+    #[error("Retries exhausted: {0}")]
+    RetriesExhausted(String),
+    
+    /// Intent queue at `max_queue_size`; retry once a batch drains it
+    #[error("Intent queue full ({0} intents)")]
+    QueueFull(usize),
+    
+    /// Fishing spot unreachable or answered with an error status
+    #[error("Fishing spot error: {0}")]
+    FishingSpot(String),
+    
+    /// Fishing spot still refused to acknowledge these intents after retrying
+    #[error("Fishing spot rejected acknowledgement of {}", .0.join(", "))]
+    AcknowledgementRejected(Vec<String>),
+    
+    /// Intent cancelled locally, but the fishing spot has not confirmed it yet
+    /// (the cancellation is retried on each batch tick)
+    #[error("Cancellation of {0} pending fishing spot confirmation")]
+    CancellationPending(String),
+    
+    /// Sender exceeded its intent rate limit
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+    
     /// Generic error
     #[error("{0}")]
     Other(String),
@@ -53,6 +115,57 @@ pub enum Error {
 /// Result type for Fisher operations
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Whether this is a connection-level failure that reconnecting may fix
+    pub fn is_connection_error(&self) -> bool {
+        matches!(self, Error::Disconnected(_))
+    }
+    
+    /// Classify a node's JSON-RPC error message into a structured variant
+    ///
+    /// Matches the wording used by geth, Nethermind, Erigon and Besu. Returns
+    /// `None` when no dedicated variant applies.
+    pub fn from_node_message(context: &str, message: &str) -> Option<Self> {
+        let lower = message.to_ascii_lowercase();
+        let detail = || format!("{}: {}", context, message);
+        
+        if let Some(at) = lower.find("execution reverted") {
+            let reason = message[at + "execution reverted".len()..]
+                .trim_start_matches(':')
+                .trim();
+            let reason = if reason.is_empty() {
+                "execution reverted without reason".to_string()
+            } else {
+                reason.to_string()
+            };
+            return Some(Error::Reverted { reason });
+        }
+        
+        if lower.contains("nonce too low")
+            || lower.contains("nonce has already been used")
+            || lower.contains("oldnonce")
+        {
+            return Some(Error::NonceTooLow(detail()));
+        }
+        
+        if lower.contains("insufficient funds")
+            || lower.contains("insufficientfunds")
+            || lower.contains("exceeds account balance")
+        {
+            return Some(Error::InsufficientFunds(detail()));
+        }
+        
+        if lower.contains("underpriced")
+            || lower.contains("less than block base fee")
+            || lower.contains("feetoolow")
+        {
+            return Some(Error::Underpriced(detail()));
+        }
+        
+        None
+    }
+}
+
 impl From<String> for Error {
     fn from(s: String) -> Self {
         Error::Other(s)
@@ -64,3 +177,159 @@ impl From<&str> for Error {
         Error::Other(s.to_string())
     }
 }
+
+/// `Error(string)` selector
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// `Panic(uint256)` selector
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decode Solidity revert data into a human-readable reason
+///
+/// Handles `Error(string)` and `Panic(uint256)`; custom errors are reported
+/// by their 4-byte selector.
+pub fn decode_revert_reason(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "execution reverted without reason".to_string();
+    }
+    
+    if data.len() < 4 {
+        return format!("malformed revert data 0x{}", hex::encode(data));
+    }
+    
+    let (selector, payload) = data.split_at(4);
+    
+    if selector == ERROR_STRING_SELECTOR {
+        if let Some(reason) = decode_abi_string(payload) {
+            return reason;
+        }
+    } else if selector == PANIC_SELECTOR && payload.len() >= 32 {
+        // Panic codes fit in the low byte
+        let code = payload[31];
+        return format!("Panic(0x{:02x}): {}", code, panic_description(code));
+    }
+    
+    format!("custom error 0x{}", hex::encode(selector))
+}
+
+/// Decode an ABI-encoded `string` (offset, length, bytes)
+fn decode_abi_string(payload: &[u8]) -> Option<String> {
+    let word = |at: usize| -> Option<usize> {
+        let slot = payload.get(at..at + 32)?;
+        // Reject values that don't fit in a usize
+        if slot[..24].iter().any(|b| *b != 0) {
+            return None;
+        }
+        Some(u64::from_be_bytes(slot[24..].try_into().ok()?) as usize)
+    };
+    
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let bytes = payload.get(start..start.checked_add(len)?)?;
+    
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Describe a Solidity `Panic(uint256)` code
+fn panic_description(code: u8) -> &'static str {
+    match code {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic code",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_decode_error_string() {
+        // Error("Insufficient balance")
+        let data = hex::decode(
+            "08c379a0\
+             0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000014\
+             496e73756666696369656e742062616c616e6365000000000000000000000000",
+        ).unwrap();
+        
+        assert_eq!(decode_revert_reason(&data), "Insufficient balance");
+    }
+    
+    #[test]
+    fn test_decode_panic() {
+        let data = hex::decode(
+            "4e487b71\
+             0000000000000000000000000000000000000000000000000000000000000011",
+        ).unwrap();
+        
+        assert_eq!(
+            decode_revert_reason(&data),
+            "Panic(0x11): arithmetic overflow or underflow"
+        );
+    }
+    
+    #[test]
+    fn test_decode_custom_error_and_empty() {
+        // InvalidSignature() custom error with no arguments
+        let data = hex::decode("8baa579f").unwrap();
+        assert_eq!(decode_revert_reason(&data), "custom error 0x8baa579f");
+        
+        assert_eq!(decode_revert_reason(&[]), "execution reverted without reason");
+    }
+    
+    #[test]
+    fn test_classify_node_messages() {
+        let classify = |message: &str| Error::from_node_message("Transaction failed", message);
+        
+        // geth / Nethermind / Besu spellings
+        for message in ["nonce too low", "Nonce too low: next nonce 5, tx nonce 3", "OldNonce", "nonce has already been used"] {
+            assert!(matches!(classify(message), Some(Error::NonceTooLow(_))), "{}", message);
+        }
+        for message in [
+            "insufficient funds for gas * price + value: address 0x01 have 0 want 1",
+            "InsufficientFunds, Account balance: 0",
+            "Upfront cost exceeds account balance",
+        ] {
+            assert!(matches!(classify(message), Some(Error::InsufficientFunds(_))), "{}", message);
+        }
+        for message in [
+            "replacement transaction underpriced",
+            "transaction underpriced",
+            "max fee per gas less than block base fee: address 0x01, maxFeePerGas: 1 baseFee: 7",
+        ] {
+            assert!(matches!(classify(message), Some(Error::Underpriced(_))), "{}", message);
+        }
+        
+        // Context is kept alongside the node message
+        match classify("nonce too low") {
+            Some(e) => assert_eq!(e.to_string(), "Nonce too low: Transaction failed: nonce too low"),
+            None => panic!("unclassified"),
+        }
+        
+        assert!(classify("header not found").is_none());
+        assert!(classify("method not found").is_none());
+    }
+    
+    #[test]
+    fn test_classify_revert_messages() {
+        match Error::from_node_message("Call failed", "execution reverted: Fisher: paused") {
+            Some(Error::Reverted { reason }) => assert_eq!(reason, "Fisher: paused"),
+            other => panic!("expected revert, got {:?}", other),
+        }
+        
+        match Error::from_node_message("Call failed", "execution reverted") {
+            Some(Error::Reverted { reason }) => assert_eq!(reason, "execution reverted without reason"),
+            other => panic!("expected revert, got {:?}", other),
+        }
+    }
+}
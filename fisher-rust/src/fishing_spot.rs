@@ -5,6 +5,7 @@
 
 use crate::{Intent, Result, Error};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 /// Configuration for fishing spot connection
@@ -21,6 +22,34 @@ pub struct FishingSpotConfig {
     
     /// API authentication token (if required)
     pub auth_token: Option<String>,
+    
+    /// Extra headers sent with every request (tenant id, signatures, ...)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    
+    /// PEM client certificate for mutual TLS (requires `client_key_path`)
+    #[serde(default)]
+    pub client_cert_path: Option<String>,
+    
+    /// PEM private key for the client certificate
+    #[serde(default)]
+    pub client_key_path: Option<String>,
+    
+    /// Attempts before giving up on an acknowledgement
+    #[serde(default = "default_ack_max_attempts")]
+    pub ack_max_attempts: u32,
+    
+    /// Initial acknowledgement retry backoff in milliseconds (doubles per attempt)
+    #[serde(default = "default_ack_backoff_ms")]
+    pub ack_backoff_ms: u64,
+}
+
+fn default_ack_max_attempts() -> u32 {
+    5
+}
+
+fn default_ack_backoff_ms() -> u64 {
+    500
 }
 
 impl Default for FishingSpotConfig {
@@ -30,6 +59,11 @@ impl Default for FishingSpotConfig {
             poll_interval_ms: 1000,  // Poll every second
             max_batch_size: 1000,
             auth_token: None,
+            headers: HashMap::new(),
+            client_cert_path: None,
+            client_key_path: None,
+            ack_max_attempts: default_ack_max_attempts(),
+            ack_backoff_ms: default_ack_backoff_ms(),
         }
     }
 }
@@ -46,27 +80,113 @@ pub struct FishingSpotClient {
 struct FishingSpotResponse {
     intents: Vec<Intent>,
     total_pending: usize,
+    #[allow(dead_code)]
     timestamp: u64,
 }
 
+/// Which intents a fishing spot accepted as settled
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcknowledgeResult {
+    /// Intents the spot stopped serving
+    #[serde(default)]
+    pub acknowledged: Vec<String>,
+    
+    /// Intents the spot refused to acknowledge
+    #[serde(default)]
+    pub rejected: Vec<String>,
+}
+
+impl AcknowledgeResult {
+    /// Read the spot's reply to acknowledging `intent_ids`
+    ///
+    /// An empty body (older spots) acknowledges everything; otherwise any id
+    /// the reply doesn't list as acknowledged counts as rejected.
+    fn from_response(intent_ids: &[String], body: &[u8]) -> Result<Self> {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Self { acknowledged: intent_ids.to_vec(), rejected: Vec::new() });
+        }
+        
+        let reply: Self = serde_json::from_slice(body)
+            .map_err(|e| Error::FishingSpot(format!("Failed to parse acknowledgement: {}", e)))?;
+        let acknowledged: HashSet<&String> = reply.acknowledged.iter().collect();
+        let (acknowledged, rejected) = intent_ids.iter()
+            .cloned()
+            .partition(|id| acknowledged.contains(id));
+        
+        Ok(Self { acknowledged, rejected })
+    }
+}
+
 impl FishingSpotClient {
     /// Create new fishing spot client
-    pub fn new(config: FishingSpotConfig) -> Self {
-        let client = reqwest::Client::builder()
+    ///
+    /// Errs with `Error::Config` if a header is malformed or the client
+    /// certificate can't be loaded, rather than failing on the first poll.
+    pub fn new(config: FishingSpotConfig) -> Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &config.headers {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::Config(format!("Invalid fishing spot header name {:?}: {}", name, e)))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|e| Error::Config(format!("Invalid value for fishing spot header {}: {}", name, e)))?;
+            headers.insert(name, value);
+        }
+        
+        let mut builder = reqwest::Client::builder()
             .timeout(Duration::from_secs(10))
+            .default_headers(headers);
+        
+        if let Some(identity) = Self::client_identity(&config)? {
+            builder = builder.use_rustls_tls().identity(identity);
+        }
+        
+        let client = builder
             .build()
-            .expect("Failed to create HTTP client");
+            .map_err(|e| Error::Config(format!("Failed to create fishing spot HTTP client: {}", e)))?;
         
-        Self { config, client }
+        Ok(Self { config, client })
+    }
+    
+    /// mTLS identity from the configured certificate and key, if any
+    fn client_identity(config: &FishingSpotConfig) -> Result<Option<reqwest::Identity>> {
+        let (cert_path, key_path) = match (&config.client_cert_path, &config.client_key_path) {
+            (None, None) => return Ok(None),
+            (Some(cert), Some(key)) => (cert, key),
+            _ => {
+                return Err(Error::Config(
+                    "Fishing spot mTLS needs both client_cert_path and client_key_path".to_string(),
+                ));
+            }
+        };
+        
+        let read = |path: &String| {
+            std::fs::read(path)
+                .map_err(|e| Error::Config(format!("Failed to read fishing spot TLS file {}: {}", path, e)))
+        };
+        let mut pem = read(cert_path)?;
+        pem.push(b'\n');
+        pem.extend(read(key_path)?);
+        
+        reqwest::Identity::from_pem(&pem)
+            .map(Some)
+            .map_err(|e| Error::Config(format!(
+                "Invalid fishing spot client certificate {} / key {}: {}",
+                cert_path, key_path, e
+            )))
     }
     
     /// Poll fishing spot for pending intents
     pub async fn collect_intents(&self) -> Result<Vec<Intent>> {
+        self.collect_up_to(self.config.max_batch_size).await
+    }
+    
+    /// Poll for at most `limit` pending intents
+    pub async fn collect_up_to(&self, limit: usize) -> Result<Vec<Intent>> {
         let url = format!("{}/api/v1/pending-intents", self.config.endpoint);
         
         let mut request = self.client
             .get(&url)
-            .query(&[("limit", self.config.max_batch_size)]);
+            .query(&[("limit", limit)]);
         
         // Add auth if configured
         if let Some(token) = &self.config.auth_token {
@@ -96,12 +216,30 @@ impl FishingSpotClient {
             data.total_pending
         );
         
-        Ok(data.intents)
+        // Don't trust the spot to honour the limit
+        let mut intents = data.intents;
+        intents.truncate(limit);
+        
+        Ok(intents)
     }
     
     /// Acknowledge processed intents to fishing spot
-    pub async fn acknowledge_intents(&self, intent_ids: &[String]) -> Result<()> {
-        let url = format!("{}/api/v1/acknowledge", self.config.endpoint);
+    ///
+    /// Returns which intents the spot accepted and which it rejected.
+    pub async fn acknowledge_intents(&self, intent_ids: &[String]) -> Result<AcknowledgeResult> {
+        self.post_intent_ids("acknowledge", intent_ids).await
+    }
+    
+    /// Tell the fishing spot these intents were cancelled, so it stops serving them
+    ///
+    /// Replies are read like acknowledgements: ids not confirmed are rejected.
+    pub async fn cancel_intents(&self, intent_ids: &[String]) -> Result<AcknowledgeResult> {
+        self.post_intent_ids("cancel", intent_ids).await
+    }
+    
+    /// POST `intent_ids` to `/api/v1/{action}` and read which the spot accepted
+    async fn post_intent_ids(&self, action: &str, intent_ids: &[String]) -> Result<AcknowledgeResult> {
+        let url = format!("{}/api/v1/{}", self.config.endpoint, action);
         
         let mut request = self.client.post(&url).json(&serde_json::json!({
             "intent_ids": intent_ids
@@ -114,13 +252,64 @@ impl FishingSpotClient {
         let response = request
             .send()
             .await
-            .map_err(|e| Error::Other(format!("Failed to acknowledge intents: {}", e)))?;
+            .map_err(|e| Error::FishingSpot(format!("Failed to {} intents: {}", action, e)))?;
         
         if !response.status().is_success() {
-            log::warn!("Failed to acknowledge intents: {}", response.status());
+            return Err(Error::FishingSpot(format!(
+                "{} request failed with status {}",
+                action,
+                response.status()
+            )));
         }
         
-        Ok(())
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Error::FishingSpot(format!("Failed to read {} reply: {}", action, e)))?;
+        
+        AcknowledgeResult::from_response(intent_ids, &body)
+    }
+    
+    /// Acknowledge intents, retrying failed requests and rejected ids with exponential backoff
+    ///
+    /// Errs with `Error::AcknowledgementRejected` if some ids are still
+    /// rejected once `ack_max_attempts` is used up.
+    pub async fn acknowledge_with_retry(&self, intent_ids: &[String]) -> Result<AcknowledgeResult> {
+        let attempts = self.config.ack_max_attempts.max(1);
+        let mut backoff = Duration::from_millis(self.config.ack_backoff_ms);
+        let mut acknowledged = Vec::with_capacity(intent_ids.len());
+        let mut pending = intent_ids.to_vec();
+        
+        for attempt in 1..=attempts {
+            match self.acknowledge_intents(&pending).await {
+                Ok(result) => {
+                    acknowledged.extend(result.acknowledged);
+                    pending = result.rejected;
+                    if pending.is_empty() {
+                        return Ok(AcknowledgeResult { acknowledged, rejected: pending });
+                    }
+                    if attempt == attempts {
+                        return Err(Error::AcknowledgementRejected(pending));
+                    }
+                    log::warn!(
+                        "Fishing spot rejected {} acknowledgements on attempt {}/{} (retrying in {:?})",
+                        pending.len(), attempt, attempts, backoff
+                    );
+                }
+                Err(e) if attempt < attempts => {
+                    log::warn!(
+                        "Acknowledgement attempt {}/{} failed: {} (retrying in {:?})",
+                        attempt, attempts, e, backoff
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+            
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+        
+        unreachable!("at least one acknowledgement attempt is made")
     }
     
     /// Get fishing spot health and statistics
@@ -142,6 +331,124 @@ impl FishingSpotClient {
     }
 }
 
+/// A fishing spot polled by a [`MultiSpotCollector`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedSpot {
+    /// Connection settings for the spot
+    #[serde(flatten)]
+    pub config: FishingSpotConfig,
+    
+    /// Relative share of each poll's intent budget (0 disables the spot)
+    #[serde(default = "default_spot_weight")]
+    pub weight: u32,
+    
+    /// Merge order; lower values come first and win on duplicate intents
+    #[serde(default)]
+    pub priority: u32,
+}
+
+fn default_spot_weight() -> u32 {
+    1
+}
+
+impl WeightedSpot {
+    /// Spot with the given weight and default priority
+    pub fn new(config: FishingSpotConfig, weight: u32) -> Self {
+        Self {
+            config,
+            weight,
+            priority: 0,
+        }
+    }
+}
+
+/// Collects intents from several fishing spots at once
+///
+/// Each poll splits `max_intents_per_poll` across spots by weight, queries
+/// them concurrently and merges the results in priority order, dropping
+/// duplicate intent IDs. A failing spot only loses its own share.
+pub struct MultiSpotCollector {
+    spots: Vec<(WeightedSpot, FishingSpotClient)>,
+    max_intents_per_poll: usize,
+}
+
+impl MultiSpotCollector {
+    /// Create a collector over `spots` with a global per-poll cap
+    ///
+    /// Errs if any spot's client can't be built (see [`FishingSpotClient::new`]).
+    pub fn new(mut spots: Vec<WeightedSpot>, max_intents_per_poll: usize) -> Result<Self> {
+        // Stable, so equal priorities keep their configured order
+        spots.sort_by_key(|spot| spot.priority);
+        
+        let spots = spots.into_iter()
+            .map(|spot| {
+                let client = FishingSpotClient::new(spot.config.clone())?;
+                Ok((spot, client))
+            })
+            .collect::<Result<_>>()?;
+        
+        Ok(Self { spots, max_intents_per_poll })
+    }
+    
+    /// Per-spot intent quota for one poll, in priority order
+    ///
+    /// Quotas are proportional to weight; leftover intents from rounding go
+    /// to the largest remainders (earlier spots win ties). Each quota is
+    /// also capped by that spot's own `max_batch_size`.
+    pub fn allocation(&self) -> Vec<usize> {
+        let total_weight: u64 = self.spots.iter().map(|(spot, _)| spot.weight as u64).sum();
+        if total_weight == 0 {
+            return vec![0; self.spots.len()];
+        }
+        
+        let cap = self.max_intents_per_poll as u64;
+        let mut quotas: Vec<u64> = self.spots.iter()
+            .map(|(spot, _)| cap * spot.weight as u64 / total_weight)
+            .collect();
+        
+        let mut by_remainder: Vec<usize> = (0..self.spots.len()).collect();
+        by_remainder.sort_by_key(|&i| std::cmp::Reverse(cap * self.spots[i].0.weight as u64 % total_weight));
+        
+        let leftover = cap - quotas.iter().sum::<u64>();
+        for &i in by_remainder.iter().take(leftover as usize) {
+            quotas[i] += 1;
+        }
+        
+        quotas.into_iter()
+            .zip(&self.spots)
+            .map(|(quota, (spot, _))| (quota as usize).min(spot.config.max_batch_size))
+            .collect()
+    }
+    
+    /// Poll every spot concurrently and merge their intents
+    ///
+    /// Spots that fail are logged and skipped.
+    pub async fn collect_intents(&self) -> Vec<Intent> {
+        let quotas = self.allocation();
+        
+        let polls = self.spots.iter().zip(&quotas).map(|((spot, client), &quota)| async move {
+            if quota == 0 {
+                return Ok(Vec::new());
+            }
+            client.collect_up_to(quota).await.map_err(|e| (spot.config.endpoint.as_str(), e))
+        });
+        
+        let mut seen = HashSet::new();
+        let mut merged = Vec::new();
+        
+        for result in futures::future::join_all(polls).await {
+            match result {
+                Ok(intents) => {
+                    merged.extend(intents.into_iter().filter(|intent| seen.insert(intent.id.clone())));
+                }
+                Err((endpoint, e)) => log::warn!("⚠️  Fishing spot {} skipped: {}", endpoint, e),
+            }
+        }
+        
+        merged
+    }
+}
+
 /// Fishing spot statistics
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FishingSpotStats {
@@ -161,6 +468,221 @@ pub struct FishingSpotStats {
     pub uptime_seconds: u64,
 }
 
+/// Minimal HTTP fishing spot that records acknowledged intent ids
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    
+    /// Acknowledged id lists, one entry per successful request
+    pub(crate) type Acks = Arc<Mutex<Vec<Vec<String>>>>;
+    
+    /// Spawn a mock fishing spot that fails the first `failures` requests with a 500
+    ///
+    /// Returns the endpoint URL and the recorded acknowledgements.
+    pub(crate) async fn spawn(failures: usize) -> (String, Acks) {
+        spawn_rejecting(failures, Vec::new(), 0).await
+    }
+    
+    /// Like [`spawn`], but the first `rejections` successful requests reject `rejected`
+    ///
+    /// Rejecting replies list the acknowledged and rejected ids as JSON; other
+    /// replies have an empty body. Every successful request is recorded.
+    pub(crate) async fn spawn_rejecting(failures: usize, rejected: Vec<String>, rejections: usize) -> (String, Acks) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let acks: Acks = Arc::default();
+        
+        let recorded = Arc::clone(&acks);
+        tokio::spawn(async move {
+            let mut remaining_failures = failures;
+            let mut remaining_rejections = rejections;
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let body = read_body(&mut socket).await;
+                
+                let (status, reply) = if remaining_failures > 0 {
+                    remaining_failures -= 1;
+                    ("500 Internal Server Error", String::new())
+                } else {
+                    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let ids: Vec<String> = serde_json::from_value(json["intent_ids"].clone()).unwrap();
+                    recorded.lock().unwrap().push(ids.clone());
+                    
+                    if remaining_rejections > 0 {
+                        remaining_rejections -= 1;
+                        let (refused, accepted): (Vec<_>, Vec<_>) = ids.into_iter().partition(|id| rejected.contains(id));
+                        ("200 OK", serde_json::json!({"acknowledged": accepted, "rejected": refused}).to_string())
+                    } else {
+                        ("200 OK", String::new())
+                    }
+                };
+                
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    reply.len(),
+                    reply
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        (endpoint, acks)
+    }
+    
+    /// Request paths and id lists, one entry per successful request
+    pub(crate) type Requests = Arc<Mutex<Vec<(String, Vec<String>)>>>;
+    
+    /// Spawn a mock fishing spot accepting every id posted to any path
+    ///
+    /// Fails the first `failures` requests with a 500, then records each
+    /// request's path and intent ids.
+    pub(crate) async fn spawn_recording(failures: usize) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let requests: Requests = Arc::default();
+        
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            let mut remaining_failures = failures;
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let (head, body) = read_request(&mut socket).await;
+                
+                let status = if remaining_failures > 0 {
+                    remaining_failures -= 1;
+                    "500 Internal Server Error"
+                } else {
+                    let path = head.split_whitespace().nth(1).unwrap_or_default().to_string();
+                    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let ids: Vec<String> = serde_json::from_value(json["intent_ids"].clone()).unwrap();
+                    recorded.lock().unwrap().push((path, ids));
+                    "200 OK"
+                };
+                
+                let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        (endpoint, requests)
+    }
+    
+    /// Spawn a mock fishing spot answering every request with an empty 200
+    ///
+    /// Returns the endpoint URL and the header block of each request received.
+    pub(crate) async fn spawn_heads() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let heads: Arc<Mutex<Vec<String>>> = Arc::default();
+        
+        let recorded = Arc::clone(&heads);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let (head, _) = read_request(&mut socket).await;
+                recorded.lock().unwrap().push(head);
+                
+                let _ = socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n").await;
+            }
+        });
+        
+        (endpoint, heads)
+    }
+    
+    /// Spawn a mock fishing spot serving up to `limit` of `intents` per poll
+    ///
+    /// An unhealthy spot answers every request with a 503. Returns the
+    /// endpoint URL and the `limit` of each poll received.
+    pub(crate) async fn spawn_pending(
+        intents: Vec<crate::Intent>,
+        healthy: bool,
+    ) -> (String, Arc<Mutex<Vec<usize>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let limits: Arc<Mutex<Vec<usize>>> = Arc::default();
+        
+        let recorded = Arc::clone(&limits);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let (head, _) = read_request(&mut socket).await;
+                
+                let limit = head.split_whitespace()
+                    .nth(1)
+                    .and_then(|path| path.split("limit=").nth(1))
+                    .and_then(|v| v.split('&').next()?.parse::<usize>().ok())
+                    .unwrap_or(usize::MAX);
+                recorded.lock().unwrap().push(limit);
+                
+                let (status, body) = if healthy {
+                    let served: Vec<_> = intents.iter().take(limit).collect();
+                    let body = serde_json::json!({
+                        "intents": served,
+                        "total_pending": intents.len(),
+                        "timestamp": 0,
+                    });
+                    ("200 OK", body.to_string())
+                } else {
+                    ("503 Service Unavailable", String::new())
+                };
+                
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        (endpoint, limits)
+    }
+    
+    /// Read one HTTP request and return its body
+    pub(crate) async fn read_body(socket: &mut tokio::net::TcpStream) -> Vec<u8> {
+        read_request(socket).await.1
+    }
+    
+    /// Read one HTTP request and return its request line and headers, and body
+    pub(crate) async fn read_request(socket: &mut tokio::net::TcpStream) -> (String, Vec<u8>) {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            
+            if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                let headers = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+                let length = headers
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                
+                let body_start = end + 4;
+                while buf.len() < body_start + length {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                let head = String::from_utf8_lossy(&buf[..end]).into_owned();
+                return (head, buf[body_start..].to_vec());
+            }
+            
+            if n == 0 {
+                return (String::from_utf8_lossy(&buf).into_owned(), Vec::new());
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,7 +697,232 @@ mod tests {
     #[tokio::test]
     async fn test_fishing_spot_client_creation() {
         let config = FishingSpotConfig::default();
-        let _client = FishingSpotClient::new(config);
+        let _client = FishingSpotClient::new(config).unwrap();
         // Just verify it creates successfully
     }
+    
+    fn mock_config(endpoint: String) -> FishingSpotConfig {
+        FishingSpotConfig {
+            endpoint,
+            ack_max_attempts: 3,
+            ack_backoff_ms: 1,
+            ..FishingSpotConfig::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_acknowledge_retries_until_success() {
+        let (endpoint, acks) = mock::spawn(2).await;
+        let client = FishingSpotClient::new(mock_config(endpoint)).unwrap();
+        let ids = vec!["a".to_string(), "b".to_string()];
+        
+        client.acknowledge_with_retry(&ids).await.unwrap();
+        
+        assert_eq!(*acks.lock().unwrap(), vec![ids]);
+    }
+    
+    #[tokio::test]
+    async fn test_acknowledge_gives_up_after_max_attempts() {
+        let (endpoint, acks) = mock::spawn(3).await;
+        let client = FishingSpotClient::new(mock_config(endpoint)).unwrap();
+        
+        let result = client.acknowledge_with_retry(&["a".to_string()]).await;
+        
+        assert!(matches!(result, Err(Error::FishingSpot(_))));
+        assert!(acks.lock().unwrap().is_empty());
+    }
+    
+    fn ids(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+    
+    #[tokio::test]
+    async fn test_rejected_acknowledgements_retried() {
+        let (endpoint, acks) = mock::spawn_rejecting(0, ids(&["b", "d"]), 1).await;
+        let client = FishingSpotClient::new(mock_config(endpoint)).unwrap();
+        
+        let result = client.acknowledge_with_retry(&ids(&["a", "b", "c", "d"])).await.unwrap();
+        
+        // Only the rejected ids are sent again
+        assert_eq!(*acks.lock().unwrap(), vec![ids(&["a", "b", "c", "d"]), ids(&["b", "d"])]);
+        assert_eq!(result.acknowledged, ids(&["a", "c", "b", "d"]));
+        assert!(result.rejected.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_persistent_rejection_is_an_error() {
+        let (endpoint, acks) = mock::spawn_rejecting(0, ids(&["b"]), usize::MAX).await;
+        let client = FishingSpotClient::new(mock_config(endpoint)).unwrap();
+        
+        let result = client.acknowledge_with_retry(&ids(&["a", "b"])).await;
+        
+        match result {
+            Err(Error::AcknowledgementRejected(rejected)) => assert_eq!(rejected, ids(&["b"])),
+            other => panic!("expected rejected acknowledgement, got {:?}", other),
+        }
+        assert_eq!(*acks.lock().unwrap(), vec![ids(&["a", "b"]), ids(&["b"]), ids(&["b"])]);
+    }
+    
+    #[test]
+    fn test_acknowledge_result_parsing() {
+        let sent = ids(&["a", "b", "c"]);
+        
+        // Legacy spots reply with an empty body
+        let legacy = AcknowledgeResult::from_response(&sent, b"").unwrap();
+        assert_eq!(legacy, AcknowledgeResult { acknowledged: sent.clone(), rejected: Vec::new() });
+        
+        // Ids the spot doesn't confirm count as rejected
+        let partial = AcknowledgeResult::from_response(&sent, br#"{"acknowledged": ["a"]}"#).unwrap();
+        assert_eq!(partial, AcknowledgeResult { acknowledged: ids(&["a"]), rejected: ids(&["b", "c"]) });
+        
+        assert!(matches!(AcknowledgeResult::from_response(&sent, b"not json"), Err(Error::FishingSpot(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_intents_posts_to_cancel_endpoint() {
+        let (endpoint, requests) = mock::spawn_recording(0).await;
+        let client = FishingSpotClient::new(mock_config(endpoint)).unwrap();
+        
+        let result = client.cancel_intents(&ids(&["a", "b"])).await.unwrap();
+        
+        assert_eq!(result.acknowledged, ids(&["a", "b"]));
+        assert_eq!(*requests.lock().unwrap(), vec![("/api/v1/cancel".to_string(), ids(&["a", "b"]))]);
+    }
+    
+    #[tokio::test]
+    async fn test_custom_headers_sent_with_every_request() {
+        let (endpoint, heads) = mock::spawn_heads().await;
+        let config = FishingSpotConfig {
+            auth_token: Some("secret".to_string()),
+            headers: HashMap::from([
+                ("X-Tenant-Id".to_string(), "acme".to_string()),
+                ("X-Signature".to_string(), "0xabc".to_string()),
+            ]),
+            ..mock_config(endpoint)
+        };
+        let client = FishingSpotClient::new(config).unwrap();
+        
+        client.acknowledge_intents(&ids(&["a"])).await.unwrap();
+        // The empty reply isn't a valid poll response; only the request matters
+        let _ = client.collect_up_to(5).await;
+        
+        let heads = heads.lock().unwrap().clone();
+        assert_eq!(heads.len(), 2);
+        for head in heads {
+            let head = head.to_lowercase();
+            assert!(head.contains("x-tenant-id: acme"), "{}", head);
+            assert!(head.contains("x-signature: 0xabc"), "{}", head);
+            assert!(head.contains("authorization: bearer secret"), "{}", head);
+        }
+    }
+    
+    #[test]
+    fn test_bad_client_identity_rejected_at_construction() {
+        let missing = FishingSpotConfig {
+            client_cert_path: Some("/nonexistent/fisher-client.pem".to_string()),
+            client_key_path: Some("/nonexistent/fisher-client.key".to_string()),
+            ..FishingSpotConfig::default()
+        };
+        let err = FishingSpotClient::new(missing).err().unwrap();
+        assert!(matches!(&err, Error::Config(msg) if msg.contains("/nonexistent/fisher-client.pem")), "{}", err);
+        
+        let garbage = std::env::temp_dir().join(format!("fisher-client-{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&garbage, "not a certificate").unwrap();
+        let path = garbage.to_string_lossy().into_owned();
+        let invalid = FishingSpotConfig {
+            client_cert_path: Some(path.clone()),
+            client_key_path: Some(path.clone()),
+            ..FishingSpotConfig::default()
+        };
+        assert!(matches!(FishingSpotClient::new(invalid), Err(Error::Config(_))));
+        
+        // A certificate without its key is a configuration mistake too
+        let half = FishingSpotConfig { client_cert_path: Some(path), ..FishingSpotConfig::default() };
+        assert!(matches!(FishingSpotClient::new(half), Err(Error::Config(_))));
+        std::fs::remove_file(garbage).unwrap();
+        
+        let bad_header = FishingSpotConfig {
+            headers: HashMap::from([("X-Tenant\nId".to_string(), "acme".to_string())]),
+            ..FishingSpotConfig::default()
+        };
+        assert!(matches!(FishingSpotClient::new(bad_header), Err(Error::Config(_))));
+    }
+    
+    fn spot_intents(prefix: &str, n: usize) -> Vec<Intent> {
+        use alloy_primitives::{Address, U256};
+        
+        (0..n)
+            .map(|i| Intent::new(format!("{}_{}", prefix, i), Address::ZERO, Address::ZERO, U256::from(1), false, i as u64, vec![]))
+            .collect()
+    }
+    
+    #[test]
+    fn test_allocation_by_weight() {
+        let spot = |weight| WeightedSpot::new(FishingSpotConfig::default(), weight);
+        
+        let collector = MultiSpotCollector::new(vec![spot(3), spot(1)], 20).unwrap();
+        assert_eq!(collector.allocation(), vec![15, 5]);
+        
+        // Rounding leftovers go to the largest remainders
+        let collector = MultiSpotCollector::new(vec![spot(1), spot(1), spot(1)], 10).unwrap();
+        assert_eq!(collector.allocation(), vec![4, 3, 3]);
+        
+        // Zero weight disables a spot; a spot's own max_batch_size still caps it
+        let small = WeightedSpot::new(FishingSpotConfig { max_batch_size: 2, ..FishingSpotConfig::default() }, 1);
+        let collector = MultiSpotCollector::new(vec![spot(0), small, spot(1)], 10).unwrap();
+        assert_eq!(collector.allocation(), vec![0, 2, 5]);
+        
+        assert_eq!(MultiSpotCollector::new(vec![spot(0)], 10).unwrap().allocation(), vec![0]);
+    }
+    
+    #[tokio::test]
+    async fn test_failing_spot_does_not_block_healthy_one() {
+        let (healthy, healthy_limits) = mock::spawn_pending(spot_intents("healthy", 50), true).await;
+        let (failing, failing_limits) = mock::spawn_pending(spot_intents("failing", 50), false).await;
+        
+        let collector = MultiSpotCollector::new(
+            vec![
+                WeightedSpot::new(mock_config(failing), 1),
+                WeightedSpot::new(mock_config(healthy), 3),
+            ],
+            20,
+        ).unwrap();
+        
+        let intents = collector.collect_intents().await;
+        assert_eq!(intents.len(), 15);
+        assert!(intents.iter().all(|i| i.id.starts_with("healthy_")));
+        
+        // Both were polled with their weighted share
+        assert_eq!(*failing_limits.lock().unwrap(), vec![5]);
+        assert_eq!(*healthy_limits.lock().unwrap(), vec![15]);
+    }
+    
+    #[tokio::test]
+    async fn test_weights_control_allocation_and_duplicates_merge() {
+        // The secondary spot also carries the primary's first intent
+        let mut secondary = spot_intents("secondary", 50);
+        secondary.insert(0, spot_intents("primary", 1).remove(0));
+        
+        let (primary, _) = mock::spawn_pending(spot_intents("primary", 50), true).await;
+        let (secondary, _) = mock::spawn_pending(secondary, true).await;
+        
+        let collector = MultiSpotCollector::new(
+            vec![
+                WeightedSpot { priority: 1, ..WeightedSpot::new(mock_config(secondary), 1) },
+                WeightedSpot::new(mock_config(primary), 4),
+            ],
+            10,
+        ).unwrap();
+        
+        let intents = collector.collect_intents().await;
+        let from = |prefix: &str| intents.iter().filter(|i| i.id.starts_with(prefix)).count();
+        
+        // 8 from the primary; the secondary's 2 include the duplicate
+        assert_eq!(from("primary_"), 8);
+        assert_eq!(from("secondary_"), 1);
+        assert_eq!(intents.len(), 9);
+        
+        // Higher-priority spot is merged first
+        assert_eq!(intents[0].id, "primary_0");
+    }
 }
@@ -0,0 +1,226 @@
+//! Host-call interface for WASM (Enarx) batch submission
+//!
+//! Inside the Enarx keep the relayer has no sockets of its own. It builds the
+//! raw `submitBatchOptimized` (or, when intents carry permits,
+//! `submitBatchWithPermits`) transaction itself, signs it with its signing
+//! backend, so the key never leaves the keep (or its KMS), and hands the
+//! bytes to the host runtime to broadcast.
+//!
+//! ## Host ABI
+//! The runtime must provide these imports in the `fisher_host` module:
+//!
+//! ```text
+//! fisher_transaction_count(addr_ptr: *const u8) -> i64
+//!     Pending nonce for the 20-byte address at `addr_ptr` (negative on error)
+//!
+//! fisher_gas_price(out_ptr: *mut u8) -> i32
+//!     Writes the current gas price as 32 big-endian bytes; returns 0 on success
+//!
+//! fisher_send_raw_transaction(tx_ptr: *const u8, tx_len: u32, out_ptr: *mut u8) -> i32
+//!     Broadcasts the signed RLP transaction and waits for its receipt, then
+//!     writes HOST_RECEIPT_LEN bytes: tx hash (32), gas used (8, big-endian),
+//!     status (1, 1 = success). Returns 0 on success
+//! ```
+
+use crate::{Batch, Permit, Result};
+#[cfg(target_arch = "wasm32")]
+use crate::Error;
+use alloy_primitives::{Address, B256, U256};
+use ethers::abi::Token;
+use ethers::types::{transaction::eip2718::TypedTransaction, Bytes, Signature, TransactionRequest, H160};
+
+/// Solidity signature of the batch entry point on the Fisher contract
+const SUBMIT_BATCH_SIGNATURE: &str =
+    "submitBatchOptimized((address,address,uint256,bool,uint256)[],bytes[])";
+
+/// Solidity signature of the batch entry point that runs ERC-2612 permits first
+const SUBMIT_BATCH_WITH_PERMITS_SIGNATURE: &str =
+    "submitBatchWithPermits((address,address,uint256,bool,uint256)[],bytes[],(bool,uint256,uint8,bytes32,bytes32)[])";
+
+/// Gas limit as a percentage of the batch estimate
+const GAS_LIMIT_HEADROOM_PERCENT: u64 = 150;
+
+/// Size of the receipt buffer written by `fisher_send_raw_transaction`
+pub const HOST_RECEIPT_LEN: usize = 32 + 8 + 1;
+
+/// Receipt returned by the host for a broadcast transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostReceipt {
+    /// Transaction hash
+    pub tx_hash: [u8; 32],
+    
+    /// Gas used by the mined transaction
+    pub gas_used: u64,
+    
+    /// Whether the transaction succeeded
+    pub success: bool,
+}
+
+impl HostReceipt {
+    /// Decode the fixed-size receipt buffer written by the host
+    pub fn from_bytes(bytes: &[u8; HOST_RECEIPT_LEN]) -> Self {
+        let mut tx_hash = [0u8; 32];
+        tx_hash.copy_from_slice(&bytes[..32]);
+        
+        let mut gas_used = [0u8; 8];
+        gas_used.copy_from_slice(&bytes[32..40]);
+        
+        Self {
+            tx_hash,
+            gas_used: u64::from_be_bytes(gas_used),
+            success: bytes[40] == 1,
+        }
+    }
+    
+    /// Transaction hash as 0x-prefixed hex
+    pub fn tx_hash_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.tx_hash))
+    }
+}
+
+/// Chain access provided by the runtime hosting the relayer
+pub trait HostRpc {
+    /// Pending nonce for `address`
+    fn transaction_count(&self, address: Address) -> Result<u64>;
+    
+    /// Current gas price (wei)
+    fn gas_price(&self) -> Result<U256>;
+    
+    /// Broadcast a signed raw transaction and wait for its receipt
+    fn send_raw_transaction(&self, raw_tx: &[u8]) -> Result<HostReceipt>;
+}
+
+/// ABI-encode the batch call, using `submitBatchWithPermits` if any intent carries a permit
+pub fn encode_batch_calldata(batch: &Batch) -> Bytes {
+    let payments = batch.intents.iter()
+        .map(|intent| Token::Tuple(vec![
+            Token::Address(H160::from_slice(intent.from.as_slice())),
+            Token::Address(H160::from_slice(intent.to.as_slice())),
+            Token::Uint(ethers::types::U256(intent.amount.into_limbs())),
+            Token::Bool(intent.priority),
+            Token::Uint(intent.nonce.into()),
+        ]))
+        .collect();
+    
+    let signatures = batch.intents.iter()
+        .map(|intent| Token::Bytes(intent.signature.clone()))
+        .collect();
+    
+    if batch.intents.iter().all(|intent| intent.permit.is_none()) {
+        let mut calldata = ethers::utils::id(SUBMIT_BATCH_SIGNATURE).to_vec();
+        calldata.extend(ethers::abi::encode(&[Token::Array(payments), Token::Array(signatures)]));
+        return calldata.into();
+    }
+    
+    let permits = batch.intents.iter()
+        .map(|intent| {
+            let permit = intent.permit.unwrap_or(Permit { deadline: 0, v: 0, r: B256::ZERO, s: B256::ZERO });
+            Token::Tuple(vec![
+                Token::Bool(intent.permit.is_some()),
+                Token::Uint(permit.deadline.into()),
+                Token::Uint(permit.v.into()),
+                Token::FixedBytes(permit.r.to_vec()),
+                Token::FixedBytes(permit.s.to_vec()),
+            ])
+        })
+        .collect();
+    
+    let mut calldata = ethers::utils::id(SUBMIT_BATCH_WITH_PERMITS_SIGNATURE).to_vec();
+    calldata.extend(ethers::abi::encode(&[Token::Array(payments), Token::Array(signatures), Token::Array(permits)]));
+    
+    calldata.into()
+}
+
+/// Unsigned EIP-155 transaction submitting `batch` from `sender`
+pub fn batch_transaction(
+    sender: Address,
+    chain_id: u64,
+    fisher_address: Address,
+    batch: &Batch,
+    nonce: u64,
+    gas_price: U256,
+) -> TypedTransaction {
+    let gas_limit = batch.estimated_gas.saturating_mul(U256::from(GAS_LIMIT_HEADROOM_PERCENT))
+        / U256::from(100);
+    
+    TransactionRequest::new()
+        .from(H160::from_slice(sender.as_slice()))
+        .to(H160::from_slice(fisher_address.as_slice()))
+        .data(encode_batch_calldata(batch))
+        .nonce(nonce)
+        .gas(ethers::types::U256(gas_limit.into_limbs()))
+        .gas_price(ethers::types::U256(gas_price.into_limbs()))
+        .chain_id(chain_id)
+        .into()
+}
+
+/// Raw signed transaction for `tx`, given the signing backend's `signature` over it
+pub fn build_raw_batch_tx(tx: &TypedTransaction, signature: &Signature) -> Bytes {
+    tx.rlp_signed(signature)
+}
+
+/// Host imports provided by the Enarx runtime (see the module docs)
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "fisher_host")]
+extern "C" {
+    fn fisher_transaction_count(addr_ptr: *const u8) -> i64;
+    fn fisher_gas_price(out_ptr: *mut u8) -> i32;
+    fn fisher_send_raw_transaction(tx_ptr: *const u8, tx_len: u32, out_ptr: *mut u8) -> i32;
+}
+
+/// [`HostRpc`] backed by the `fisher_host` imports
+#[cfg(target_arch = "wasm32")]
+pub struct ImportedHost;
+
+#[cfg(target_arch = "wasm32")]
+impl HostRpc for ImportedHost {
+    fn transaction_count(&self, address: Address) -> Result<u64> {
+        let nonce = unsafe { fisher_transaction_count(address.as_slice().as_ptr()) };
+        u64::try_from(nonce)
+            .map_err(|_| Error::Rpc(format!("Host nonce lookup failed ({})", nonce)))
+    }
+    
+    fn gas_price(&self) -> Result<U256> {
+        let mut out = [0u8; 32];
+        let status = unsafe { fisher_gas_price(out.as_mut_ptr()) };
+        if status != 0 {
+            return Err(Error::Rpc(format!("Host gas price lookup failed ({})", status)));
+        }
+        
+        Ok(U256::from_be_bytes(out))
+    }
+    
+    fn send_raw_transaction(&self, raw_tx: &[u8]) -> Result<HostReceipt> {
+        let mut out = [0u8; HOST_RECEIPT_LEN];
+        let status = unsafe {
+            fisher_send_raw_transaction(raw_tx.as_ptr(), raw_tx.len() as u32, out.as_mut_ptr())
+        };
+        if status != 0 {
+            return Err(Error::Rpc(format!("Host transaction broadcast failed ({})", status)));
+        }
+        
+        Ok(HostReceipt::from_bytes(&out))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_host_receipt_wire_format() {
+        let mut bytes = [0u8; HOST_RECEIPT_LEN];
+        bytes[..32].copy_from_slice(&[0xAB; 32]);
+        bytes[32..40].copy_from_slice(&150_000u64.to_be_bytes());
+        bytes[40] = 1;
+        
+        let receipt = HostReceipt::from_bytes(&bytes);
+        assert_eq!(receipt.tx_hash, [0xAB; 32]);
+        assert_eq!(receipt.gas_used, 150_000);
+        assert!(receipt.success);
+        assert_eq!(receipt.tx_hash_hex(), format!("0x{}", "ab".repeat(32)));
+        
+        bytes[40] = 0;
+        assert!(!HostReceipt::from_bytes(&bytes).success);
+    }
+}
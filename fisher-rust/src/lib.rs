@@ -30,16 +30,31 @@ pub mod attestation;
 pub mod metrics;
 pub mod error;
 pub mod blob;
+pub mod merkle;
 pub mod fishing_spot;
+pub mod private_relay;
 pub mod staking;
+pub mod rate_limit;
+pub mod lru_state;
+pub mod multichain;
+pub mod host;
+pub mod aggregation;
+pub mod verification;
+pub mod signer;
+pub mod submissions;
+pub mod wasm;
 
 // Re-export main types
 pub use types::*;
 pub use relayer::FisherRelayer;
-pub use error::{Error, Result};
-pub use blob::{BlobEncoder, BlobTx, calculate_blob_savings};
-pub use fishing_spot::{FishingSpotClient, FishingSpotConfig, FishingSpotStats};
-pub use staking::{FisherStaking, StakingStatus};
+pub use multichain::MultiChainFisher;
+pub use error::{Error, Result, decode_revert_reason};
+pub use blob::{BlobEncoder, BlobPacker, BlobTx, DataFees, PackedBatch, PackedBlobs, calculate_blob_savings};
+pub use phi_optimization::{GasCost, GasReport, SavingsEstimate};
+pub use fishing_spot::{AcknowledgeResult, FishingSpotClient, FishingSpotConfig, FishingSpotStats, MultiSpotCollector, WeightedSpot};
+pub use metrics::{MetricsCollector, MetricsFormat};
+pub use staking::{FisherStaking, PolicyAction, StakingPolicy, StakingStatus};
+pub use attestation::{AttestationStatus, QuoteProvider};
 
 /// Fisher version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,347 @@
+//! Bounded replay-protection state
+//!
+//! Dedup of batched intent IDs and per-sender nonce tracking would otherwise
+//! keep one entry for every intent a long-lived relayer has ever seen. Both
+//! live in [`LruState`] maps capped by size and idle time, so memory stays
+//! bounded however many unique senders show up.
+
+use crate::{Error, Intent, Result};
+use alloy_primitives::Address;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// One stored value and when it was last touched
+#[derive(Debug)]
+struct Slot<V> {
+    value: V,
+    last_used: Instant,
+    tick: u64,
+}
+
+/// Map bounded by capacity (least recently used evicted first) and idle TTL
+#[derive(Debug)]
+pub struct LruState<K, V> {
+    /// Most entries kept at once
+    capacity: usize,
+    
+    /// Entries untouched for this long expire
+    ttl: Duration,
+    
+    slots: HashMap<K, Slot<V>>,
+    
+    /// Keys by last use, oldest first
+    recency: BTreeMap<u64, K>,
+    
+    next_tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> LruState<K, V> {
+    /// Create a map holding at most `capacity` entries (at least one) for up to `ttl` idle
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            slots: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_tick: 0,
+        }
+    }
+    
+    /// Insert or replace `key`, returning any live entry evicted to make room
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.insert_at(key, value, Instant::now())
+    }
+    
+    /// [`LruState::insert`] with an explicit clock
+    ///
+    /// Expired entries are dropped silently; only an entry evicted before
+    /// its TTL ran out is returned.
+    pub fn insert_at(&mut self, key: K, value: V, now: Instant) -> Option<(K, V)> {
+        self.prune_expired(now);
+        self.remove(&key);
+        
+        let evicted = if self.slots.len() >= self.capacity {
+            self.pop_least_recent()
+        } else {
+            None
+        };
+        
+        let tick = self.bump();
+        self.recency.insert(tick, key.clone());
+        self.slots.insert(key, Slot { value, last_used: now, tick });
+        
+        evicted
+    }
+    
+    /// Look up `key`, refreshing its place and TTL
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_at(key, Instant::now())
+    }
+    
+    /// [`LruState::get`] with an explicit clock
+    pub fn get_at<Q>(&mut self, key: &Q, now: Instant) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.prune_expired(now);
+        
+        let tick = self.bump();
+        let slot = self.slots.get_mut(key)?;
+        let stored = self.recency.remove(&slot.tick)?;
+        self.recency.insert(tick, stored);
+        slot.tick = tick;
+        slot.last_used = now;
+        
+        Some(&slot.value)
+    }
+    
+    /// Whether `key` is present (refreshing it if so)
+    pub fn contains<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+    
+    /// Remove `key`, returning its value
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.slots.remove(key)?;
+        self.recency.remove(&slot.tick);
+        Some(slot.value)
+    }
+    
+    /// Drop every entry idle for longer than the TTL
+    pub fn prune_expired(&mut self, now: Instant) {
+        while let Some((&tick, key)) = self.recency.first_key_value() {
+            let expired = self.slots.get(key)
+                .is_none_or(|slot| now.saturating_duration_since(slot.last_used) > self.ttl);
+            if !expired {
+                break;
+            }
+            
+            if let Some(key) = self.recency.remove(&tick) {
+                self.slots.remove(&key);
+            }
+        }
+    }
+    
+    /// Number of entries held
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+    
+    /// Whether no entries are held
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+    
+    fn pop_least_recent(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.recency.pop_first()?;
+        let slot = self.slots.remove(&key)?;
+        Some((key, slot.value))
+    }
+    
+    fn bump(&mut self) -> u64 {
+        self.next_tick += 1;
+        self.next_tick
+    }
+}
+
+/// Rejects intents that were already drained into a batch
+///
+/// Tracks batched intent IDs (dedup) and `(sender, nonce)` pairs (nonce
+/// replay), each mapped to the intent's timestamp. Forgetting an entry is
+/// safe because intents are only accepted within the clock-skew window:
+/// the TTL should cover twice that window, and when capacity forces out an
+/// entry early, intents no newer than it are refused until they age out.
+#[derive(Debug)]
+pub struct ReplayGuard {
+    ids: LruState<String, u64>,
+    nonces: LruState<(Address, u64), u64>,
+    
+    /// Newest timestamp among entries evicted before their TTL
+    evicted_through: Option<u64>,
+}
+
+impl ReplayGuard {
+    /// Guard keeping up to `capacity` IDs and nonces for up to `ttl` idle
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            ids: LruState::new(capacity, ttl),
+            nonces: LruState::new(capacity, ttl),
+            evicted_through: None,
+        }
+    }
+    
+    /// Err if `intent` (or its sender's nonce) was already batched
+    pub fn check(&mut self, intent: &Intent) -> Result<()> {
+        if self.ids.contains(intent.id.as_str()) {
+            return Err(Error::InvalidIntent(format!("intent {} was already batched", intent.id)));
+        }
+        
+        if self.nonces.contains(&(intent.from, intent.nonce)) {
+            return Err(Error::InvalidIntent(format!(
+                "nonce {} already used by {:?}",
+                intent.nonce, intent.from
+            )));
+        }
+        
+        // Its entry may have been evicted under load; refuse rather than risk a replay
+        if self.evicted_through.is_some_and(|through| intent.timestamp <= through) {
+            return Err(Error::InvalidIntent(format!(
+                "intent {} is older than evicted replay state; re-sign with a fresh timestamp",
+                intent.id
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    /// Remember `intent` as batched
+    pub fn record(&mut self, intent: &Intent) {
+        let evicted = [
+            self.ids.insert(intent.id.clone(), intent.timestamp).map(|(_, ts)| ts),
+            self.nonces.insert((intent.from, intent.nonce), intent.timestamp).map(|(_, ts)| ts),
+        ];
+        
+        for timestamp in evicted.into_iter().flatten() {
+            self.evicted_through = Some(self.evicted_through.map_or(timestamp, |t| t.max(timestamp)));
+        }
+    }
+    
+    /// Forget `intent`, e.g. when it goes back to the queue or fails on-chain
+    pub fn forget(&mut self, intent: &Intent) {
+        self.ids.remove(intent.id.as_str());
+        self.nonces.remove(&(intent.from, intent.nonce));
+    }
+    
+    /// Whether the intent with `id` was batched
+    pub fn is_batched(&mut self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+    
+    /// Number of intent IDs tracked
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+    
+    /// Whether nothing is tracked
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    
+    fn intent(id: &str, from: u8, nonce: u64, timestamp: u64) -> Intent {
+        Intent {
+            timestamp,
+            ..Intent::new(id.to_string(), Address::repeat_byte(from), Address::ZERO, U256::from(1), false, nonce, vec![])
+        }
+    }
+    
+    #[test]
+    fn test_capacity_evicts_least_recently_used() {
+        let mut state = LruState::new(3, Duration::from_secs(60));
+        let now = Instant::now();
+        
+        for key in 0..3 {
+            assert!(state.insert_at(key, key * 10, now).is_none());
+        }
+        
+        // Touching 0 makes 1 the least recently used
+        assert_eq!(state.get_at(&0, now), Some(&0));
+        assert_eq!(state.insert_at(3, 30, now), Some((1, 10)));
+        
+        assert_eq!(state.len(), 3);
+        assert!(state.get_at(&1, now).is_none());
+        assert!(state.get_at(&0, now).is_some());
+    }
+    
+    #[test]
+    fn test_size_stays_bounded() {
+        let mut state = LruState::new(100, Duration::from_secs(3600));
+        let now = Instant::now();
+        
+        for key in 0..10_000u64 {
+            state.insert_at(key, (), now);
+            assert!(state.len() <= 100);
+        }
+        
+        assert_eq!(state.len(), 100);
+        assert_eq!(state.recency.len(), 100);
+        assert!(state.get_at(&9_999, now).is_some());
+        assert!(state.get_at(&0, now).is_none());
+    }
+    
+    #[test]
+    fn test_idle_entries_expire() {
+        let mut state = LruState::new(10, Duration::from_secs(60));
+        let start = Instant::now();
+        
+        state.insert_at("idle", 1, start);
+        state.insert_at("busy", 2, start);
+        
+        // Only "busy" is touched, so only "idle" expires
+        assert!(state.get_at("busy", start + Duration::from_secs(50)).is_some());
+        assert!(state.get_at("idle", start + Duration::from_secs(70)).is_none());
+        assert!(state.get_at("busy", start + Duration::from_secs(70)).is_some());
+        
+        // Expiry frees room without reporting an early eviction
+        state.prune_expired(start + Duration::from_secs(200));
+        assert!(state.is_empty());
+        assert!(state.insert_at("new", 3, start + Duration::from_secs(200)).is_none());
+    }
+    
+    #[test]
+    fn test_guard_rejects_batched_ids_and_nonces() {
+        let mut guard = ReplayGuard::new(10, Duration::from_secs(600));
+        let settled = intent("a", 1, 7, 1_000);
+        
+        assert!(guard.check(&settled).is_ok());
+        guard.record(&settled);
+        assert!(guard.is_batched("a"));
+        
+        assert!(guard.check(&settled).is_err());
+        assert!(guard.check(&intent("a_resigned", 1, 7, 1_001)).is_err());
+        assert!(guard.check(&intent("b", 1, 8, 1_001)).is_ok());
+        assert!(guard.check(&intent("c", 2, 7, 1_001)).is_ok());
+        
+        // Re-queued intents may be batched again
+        guard.forget(&settled);
+        assert!(guard.check(&settled).is_ok());
+    }
+    
+    #[test]
+    fn test_guard_eviction_does_not_enable_replay() {
+        let mut guard = ReplayGuard::new(2, Duration::from_secs(600));
+        let oldest = intent("oldest", 1, 0, 1_000);
+        
+        guard.record(&oldest);
+        guard.record(&intent("second", 2, 0, 1_010));
+        guard.record(&intent("third", 3, 0, 1_020));
+        
+        // "oldest" was pushed out, but replaying it is still refused
+        assert_eq!(guard.len(), 2);
+        assert!(!guard.is_batched("oldest"));
+        assert!(guard.check(&oldest).is_err());
+        
+        // Fresh intents from anyone are unaffected
+        assert!(guard.check(&intent("fresh", 1, 1, 1_030)).is_ok());
+    }
+}
@@ -0,0 +1,164 @@
+//! Keccak256 Merkle tree over batch intents
+//!
+//! Lets the contract commit only the batch root on-chain while leaves are
+//! posted via blob. Pairs are hashed in sorted order (`keccak256(min || max)`),
+//! matching OpenZeppelin's `MerkleProof.verify`, and odd levels duplicate
+//! their last node.
+
+use sha3::{Digest, Keccak256};
+
+/// 32-byte tree node
+pub type Hash = [u8; 32];
+
+/// Hash two sibling nodes in sorted order
+fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    
+    let mut hasher = Keccak256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Build the next tree level, duplicating the last node on odd counts
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_pair(left, right),
+            [last] => hash_pair(last, last),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Compute the Merkle root of `leaves` (zero hash for an empty tree)
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+    
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    
+    level[0]
+}
+
+/// Build the sibling path from leaf `index` up to the root
+///
+/// Returns `None` if `index` is out of range.
+pub fn merkle_proof(leaves: &[Hash], index: usize) -> Option<Vec<Hash>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut index = index;
+    
+    while level.len() > 1 {
+        let sibling = if index.is_multiple_of(2) {
+            // Last node on an odd level is paired with itself
+            *level.get(index + 1).unwrap_or(&level[index])
+        } else {
+            level[index - 1]
+        };
+        proof.push(sibling);
+        
+        level = next_level(&level);
+        index /= 2;
+    }
+    
+    Some(proof)
+}
+
+/// Verify that `leaf` is included under `root` using `proof`
+pub fn verify_proof(leaf: &Hash, proof: &[Hash], root: &Hash) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |node, sibling| hash_pair(&node, sibling));
+    
+    &computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn leaves(n: u8) -> Vec<Hash> {
+        (0..n).map(|i| Keccak256::digest([i]).into()).collect()
+    }
+    
+    #[test]
+    fn test_empty_and_single_leaf() {
+        assert_eq!(merkle_root(&[]), [0u8; 32]);
+        
+        let single = leaves(1);
+        assert_eq!(merkle_root(&single), single[0]);
+        assert_eq!(merkle_proof(&single, 0), Some(vec![]));
+        assert!(verify_proof(&single[0], &[], &single[0]));
+    }
+    
+    #[test]
+    fn test_proofs_verify_for_every_leaf() {
+        // Odd and even sizes exercise the duplicate-last rule
+        for n in [2u8, 3, 5, 8, 13] {
+            let leaves = leaves(n);
+            let root = merkle_root(&leaves);
+            
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = merkle_proof(&leaves, i).unwrap();
+                assert!(verify_proof(leaf, &proof, &root), "n={} i={}", n, i);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_batch_merkle_proof() {
+        use crate::{Batch, Intent};
+        use alloy_primitives::{Address, U256};
+        
+        let intents: Vec<Intent> = (0..5)
+            .map(|i| {
+                Intent::new(
+                    format!("intent_{}", i),
+                    Address::ZERO,
+                    Address::ZERO,
+                    U256::from(i),
+                    false,
+                    i,
+                    vec![1],
+                )
+            })
+            .collect();
+        let batch = Batch {
+            id: 1,
+            intents,
+            chunk_size: 5,
+            phi_score: 0.0,
+            estimated_gas: U256::ZERO,
+            estimated_savings: U256::ZERO,
+            created_at: 0,
+            recipient_groups: Vec::new(),
+            aggregated: Vec::new(),
+        };
+        
+        let root = batch.merkle_root();
+        let proof = batch.merkle_proof(4).unwrap();
+        assert!(verify_proof(&batch.intents[4].hash(), &proof, &root));
+        assert!(!verify_proof(&batch.intents[0].hash(), &proof, &root));
+    }
+    
+    #[test]
+    fn test_wrong_leaf_fails() {
+        let leaves = leaves(7);
+        let root = merkle_root(&leaves);
+        let proof = merkle_proof(&leaves, 3).unwrap();
+        
+        assert!(!verify_proof(&leaves[4], &proof, &root));
+        assert!(!verify_proof(&[0xFF; 32], &proof, &root));
+        assert_eq!(merkle_proof(&leaves, 7), None);
+    }
+}
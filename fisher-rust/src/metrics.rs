@@ -2,46 +2,332 @@
 
 use crate::Metrics;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::RwLock;
 
 /// Metrics collector
 pub struct MetricsCollector {
     metrics: Arc<RwLock<Metrics>>,
+    
+    /// When the collector (and so the relayer process) started
+    started_at: Instant,
 }
 
 impl MetricsCollector {
     /// Create new metrics collector
+    ///
+    /// It renders its own empty metrics; use
+    /// [`FisherRelayer::metrics_collector`](crate::FisherRelayer::metrics_collector)
+    /// to export a relayer's.
     pub fn new() -> Self {
+        Self::with_metrics(Arc::new(RwLock::new(Metrics::default())))
+    }
+    
+    /// Collector rendering the metrics behind `metrics`, as they are updated
+    pub fn with_metrics(metrics: Arc<RwLock<Metrics>>) -> Self {
         Self {
-            metrics: Arc::new(RwLock::new(Metrics::default())),
+            metrics,
+            started_at: Instant::now(),
         }
     }
     
     /// Get Prometheus-format metrics
     pub async fn prometheus_metrics(&self) -> String {
+        self.render(MetricsFormat::Prometheus).await
+    }
+    
+    /// Get OpenMetrics-format metrics
+    pub async fn openmetrics(&self) -> String {
+        self.render(MetricsFormat::OpenMetrics).await
+    }
+    
+    /// Render for a scrape request, returning the content type and body
+    ///
+    /// The format follows the request's `Accept` header, defaulting to the
+    /// Prometheus text format.
+    pub async fn scrape(&self, accept: Option<&str>) -> (&'static str, String) {
+        let format = MetricsFormat::negotiate(accept);
+        (format.content_type(), self.render(format).await)
+    }
+    
+    /// Render all metric families in `format`
+    pub async fn render(&self, format: MetricsFormat) -> String {
+        let families = self.families().await;
+        
+        match format {
+            MetricsFormat::Prometheus => families.iter()
+                .map(Family::prometheus)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            MetricsFormat::OpenMetrics => {
+                let mut out: String = families.iter().map(Family::openmetrics).collect();
+                out.push_str("# EOF\n");
+                out
+            }
+        }
+    }
+    
+    async fn families(&self) -> Vec<Family> {
         let m = self.metrics.read().await;
         
-        format!(
-            "# HELP fisher_total_batches Total number of batches processed\n\
-             # TYPE fisher_total_batches counter\n\
-             fisher_total_batches {}\n\
-             \n\
-             # HELP fisher_total_intents Total number of intents processed\n\
-             # TYPE fisher_total_intents counter\n\
-             fisher_total_intents {}\n\
-             \n\
-             # HELP fisher_avg_savings_percent Average gas savings percentage\n\
-             # TYPE fisher_avg_savings_percent gauge\n\
-             fisher_avg_savings_percent {:.2}\n\
-             \n\
-             # HELP fisher_avg_batch_size Average batch size\n\
-             # TYPE fisher_avg_batch_size gauge\n\
-             fisher_avg_batch_size {:.2}\n",
-            m.total_batches,
-            m.total_intents,
-            m.avg_savings_percent,
-            m.avg_batch_size,
-        )
+        vec![
+            Family::info(
+                "fisher_build",
+                "Relayer build information",
+                format!("version=\"{}\"", crate::VERSION),
+            ),
+            Family::info(
+                "fisher_attestation",
+                "TEE attestation state found at startup",
+                format!("status=\"{}\"", m.attestation.as_str()),
+            ),
+            Family::gauge(
+                "fisher_uptime_seconds",
+                "Seconds since the relayer started",
+                format!("{:.3}", self.started_at.elapsed().as_secs_f64()),
+            ),
+            Family::gauge(
+                "fisher_last_batch_timestamp",
+                "Unix time of the last settled batch (0 if none)",
+                m.last_batch_timestamp.unwrap_or_default().to_string(),
+            ),
+            Family::counter(
+                "fisher_batches",
+                "fisher_total_batches",
+                "Total number of batches processed",
+                vec![(String::new(), m.total_batches)],
+            ),
+            Family::counter(
+                "fisher_intents",
+                "fisher_total_intents",
+                "Total number of intents processed",
+                vec![(String::new(), m.total_intents)],
+            ),
+            Family::gauge(
+                "fisher_avg_savings_percent",
+                "Average gas savings percentage",
+                format!("{:.2}", m.avg_savings_percent()),
+            ),
+            Family::gauge(
+                "fisher_avg_williams_savings_percent",
+                "Average Williams savings at the contract's chunk size",
+                format!("{:.2}", m.avg_williams_savings()),
+            ),
+            Family::gauge(
+                "fisher_avg_phi_savings_estimate_percent",
+                "Average estimated φ-optimization savings (not measured on-chain)",
+                format!("{:.2}", m.avg_phi_savings_estimate()),
+            ),
+            Family::gauge(
+                "fisher_contract_gas_saved",
+                "Total gas saved as reported by the contract",
+                m.total_contract_gas_saved.to_string(),
+            ),
+            Family::gauge(
+                "fisher_avg_batch_size",
+                "Average batch size",
+                format!("{:.2}", m.avg_batch_size()),
+            ),
+            Family::gauge(
+                "fisher_avg_compression_ratio",
+                "Average blob payload compression ratio",
+                format!("{:.2}", m.avg_compression_ratio()),
+            ),
+            Family::gauge(
+                "fisher_paused",
+                "1 while batch submission is paused for maintenance",
+                u8::from(m.paused).to_string(),
+            ),
+            Family::gauge(
+                "fisher_queue_depth",
+                "Intents currently waiting in the queue",
+                m.current_queue_depth.to_string(),
+            ),
+            Family::gauge(
+                "fisher_max_queue_depth",
+                "Deepest queue depth seen",
+                m.max_queue_depth_seen.to_string(),
+            ),
+            Family::gauge(
+                "fisher_avg_intent_wait_ms",
+                "Average time from intent creation to batch inclusion",
+                format!("{:.2}", m.avg_intent_wait_ms()),
+            ),
+            Family::gauge(
+                "fisher_avg_assembly_ms",
+                "Average time to φ-sort and split intents into batches",
+                format!("{:.2}", m.avg_assembly_time_ms()),
+            ),
+            Family::counter(
+                "fisher_slow_assemblies",
+                "fisher_slow_assemblies",
+                "Batch assemblies slower than assembly_timeout_ms",
+                vec![(String::new(), m.slow_assemblies)],
+            ),
+            Family::counter(
+                "fisher_triggered_runs",
+                "fisher_triggered_runs",
+                "Early batch runs scheduled by a full queue or the priority fast lane",
+                vec![(String::new(), m.triggered_runs)],
+            ),
+            Family::counter(
+                "fisher_coalesced_triggers",
+                "fisher_coalesced_triggers",
+                "Early batch triggers folded into an already scheduled run",
+                vec![(String::new(), m.coalesced_triggers)],
+            ),
+            Family::counter(
+                "fisher_intents_rejected",
+                "fisher_intents_rejected",
+                "Intents refused at submission, by reason",
+                crate::RejectionReason::ALL
+                    .iter()
+                    .map(|reason| (format!("reason=\"{}\"", reason.label()), m.rejections(*reason)))
+                    .collect(),
+            ),
+            Family::counter(
+                "fisher_batches_by_savings",
+                "fisher_batches_by_savings",
+                "Settled batches by realized savings percentage",
+                m.savings_histogram.buckets()
+                    .iter()
+                    .map(|(bucket, count)| (format!("bucket=\"{}\"", bucket), *count))
+                    .collect(),
+            ),
+        ]
+    }
+}
+
+/// Exposition format for scraped metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    /// Prometheus text format 0.0.4 (the legacy default)
+    Prometheus,
+    
+    /// OpenMetrics 1.0: `_total` counters, no blank lines, `# EOF` terminator
+    OpenMetrics,
+}
+
+impl MetricsFormat {
+    /// Pick a format from an HTTP `Accept` header
+    ///
+    /// OpenMetrics wins when it is accepted with at least the quality of
+    /// `text/plain`; anything else falls back to the Prometheus format.
+    pub fn negotiate(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Prometheus;
+        };
+        
+        let mut openmetrics_q: f64 = 0.0;
+        let mut text_q: f64 = 0.0;
+        
+        for range in accept.split(',') {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default().to_ascii_lowercase();
+            let q = params
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f64>().ok())
+                .unwrap_or(1.0);
+            
+            match media_type.as_str() {
+                "application/openmetrics-text" => openmetrics_q = openmetrics_q.max(q),
+                "text/plain" => text_q = text_q.max(q),
+                _ => {}
+            }
+        }
+        
+        if openmetrics_q > 0.0 && openmetrics_q >= text_q {
+            Self::OpenMetrics
+        } else {
+            Self::Prometheus
+        }
+    }
+    
+    /// `Content-Type` for a response in this format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Prometheus => "text/plain; version=0.0.4; charset=utf-8",
+            Self::OpenMetrics => "application/openmetrics-text; version=1.0.0; charset=utf-8",
+        }
+    }
+}
+
+/// One metric family, renderable in either format
+struct Family {
+    /// OpenMetrics family name (counters without `_total`, info without `_info`)
+    name: &'static str,
+    
+    /// Name the Prometheus format has always used
+    legacy_name: &'static str,
+    
+    help: &'static str,
+    kind: FamilyKind,
+    
+    /// Label set (without braces) and value of each sample
+    samples: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy)]
+enum FamilyKind {
+    Counter,
+    Gauge,
+    Info,
+}
+
+impl Family {
+    fn gauge(name: &'static str, help: &'static str, value: String) -> Self {
+        Self { name, legacy_name: name, help, kind: FamilyKind::Gauge, samples: vec![(String::new(), value)] }
+    }
+    
+    fn counter(name: &'static str, legacy_name: &'static str, help: &'static str, samples: Vec<(String, u64)>) -> Self {
+        Self {
+            name,
+            legacy_name,
+            help,
+            kind: FamilyKind::Counter,
+            samples: samples.into_iter().map(|(labels, value)| (labels, value.to_string())).collect(),
+        }
+    }
+    
+    fn info(name: &'static str, help: &'static str, labels: String) -> Self {
+        Self { name, legacy_name: name, help, kind: FamilyKind::Info, samples: vec![(labels, "1".to_string())] }
+    }
+    
+    fn prometheus(&self) -> String {
+        let (name, kind) = match self.kind {
+            FamilyKind::Counter => (self.legacy_name.to_string(), "counter"),
+            FamilyKind::Gauge => (self.legacy_name.to_string(), "gauge"),
+            // Prometheus has no info type; it is a gauge with a constant 1
+            FamilyKind::Info => (format!("{}_info", self.name), "gauge"),
+        };
+        
+        let mut out = format!("# HELP {} {}\n# TYPE {} {}\n", name, self.help, name, kind);
+        out.push_str(&self.sample_lines(&name));
+        out
+    }
+    
+    fn openmetrics(&self) -> String {
+        let (kind, suffix) = match self.kind {
+            FamilyKind::Counter => ("counter", "_total"),
+            FamilyKind::Gauge => ("gauge", ""),
+            FamilyKind::Info => ("info", "_info"),
+        };
+        
+        let mut out = format!("# TYPE {} {}\n# HELP {} {}\n", self.name, kind, self.name, self.help);
+        out.push_str(&self.sample_lines(&format!("{}{}", self.name, suffix)));
+        out
+    }
+    
+    fn sample_lines(&self, name: &str) -> String {
+        self.samples.iter()
+            .map(|(labels, value)| {
+                if labels.is_empty() {
+                    format!("{} {}\n", name, value)
+                } else {
+                    format!("{}{{{}}} {}\n", name, labels, value)
+                }
+            })
+            .collect()
     }
 }
 
@@ -50,3 +336,143 @@ impl Default for MetricsCollector {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_prometheus_savings_buckets() {
+        let collector = MetricsCollector::new();
+        {
+            let mut metrics = collector.metrics.write().await;
+            for savings in [50.0, 85.0, 96.0, 97.0] {
+                metrics.savings_histogram.record(savings);
+            }
+        }
+        
+        let output = collector.prometheus_metrics().await;
+        assert!(output.contains("fisher_batches_by_savings{bucket=\"<80\"} 1\n"));
+        assert!(output.contains("fisher_batches_by_savings{bucket=\"80-90\"} 1\n"));
+        assert!(output.contains("fisher_batches_by_savings{bucket=\"90-95\"} 0\n"));
+        assert!(output.contains("fisher_batches_by_savings{bucket=\">=95\"} 2\n"));
+    }
+    
+    #[tokio::test]
+    async fn test_prometheus_rejections_by_reason() {
+        let collector = MetricsCollector::new();
+        {
+            let mut metrics = collector.metrics.write().await;
+            metrics.record_rejection(crate::RejectionReason::BadSignature);
+            metrics.record_rejection(crate::RejectionReason::BadSignature);
+            metrics.record_rejection(crate::RejectionReason::QueueFull);
+        }
+        
+        let output = collector.prometheus_metrics().await;
+        assert!(output.contains("# TYPE fisher_intents_rejected counter\n"));
+        assert!(output.contains("fisher_intents_rejected{reason=\"bad_signature\"} 2\n"));
+        assert!(output.contains("fisher_intents_rejected{reason=\"stale_nonce\"} 0\n"));
+        assert!(output.contains("fisher_intents_rejected{reason=\"queue_full\"} 1\n"));
+    }
+    
+    #[tokio::test]
+    async fn test_prometheus_build_info_and_uptime() {
+        let collector = MetricsCollector::new();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        
+        let output = collector.prometheus_metrics().await;
+        assert!(output.contains(&format!("fisher_build_info{{version=\"{}\"}} 1\n", crate::VERSION)));
+        assert!(output.contains("fisher_attestation_info{status=\"disabled\"} 1\n"));
+        assert!(output.contains("fisher_last_batch_timestamp 0\n"));
+        
+        let uptime: f64 = output.lines()
+            .find_map(|line| line.strip_prefix("fisher_uptime_seconds "))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(uptime > 0.0);
+        
+        collector.metrics.write().await.last_batch_timestamp = Some(1_700_000_000);
+        let output = collector.prometheus_metrics().await;
+        assert!(output.contains("fisher_last_batch_timestamp 1700000000\n"));
+    }
+    
+    /// Minimal OpenMetrics text parser: returns (family, type) pairs in order
+    fn parse_openmetrics(output: &str) -> Vec<(String, String)> {
+        let body = output.strip_suffix("# EOF\n").expect("missing # EOF terminator");
+        assert!(!body.contains("# EOF"));
+        
+        let mut families: Vec<(String, String)> = Vec::new();
+        for line in body.lines() {
+            assert!(!line.is_empty(), "blank lines are not allowed");
+            
+            if let Some(decl) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = decl.split_once(' ').unwrap();
+                assert!(families.iter().all(|(f, _)| f != name), "{} declared twice", name);
+                assert!(["counter", "gauge", "info"].contains(&kind));
+                families.push((name.to_string(), kind.to_string()));
+            } else if let Some(help) = line.strip_prefix("# HELP ") {
+                assert_eq!(help.split(' ').next(), families.last().map(|(f, _)| f.as_str()));
+            } else {
+                let (series, value) = line.rsplit_once(' ').unwrap();
+                value.parse::<f64>().unwrap();
+                
+                let name = series.split('{').next().unwrap();
+                let (family, kind) = families.last().expect("sample before TYPE");
+                let expected = match kind.as_str() {
+                    "counter" => format!("{}_total", family),
+                    "info" => format!("{}_info", family),
+                    _ => family.clone(),
+                };
+                assert_eq!(name, expected);
+            }
+        }
+        
+        families
+    }
+    
+    #[tokio::test]
+    async fn test_openmetrics_output_parses() {
+        let collector = MetricsCollector::new();
+        {
+            let mut metrics = collector.metrics.write().await;
+            metrics.total_batches = 3;
+            metrics.total_intents = 42;
+            metrics.savings_histogram.record(96.0);
+        }
+        
+        let output = collector.openmetrics().await;
+        let families = parse_openmetrics(&output);
+        
+        assert!(families.contains(&("fisher_batches".to_string(), "counter".to_string())));
+        assert!(families.contains(&("fisher_build".to_string(), "info".to_string())));
+        assert!(output.contains("fisher_batches_total 3\n"));
+        assert!(output.contains("fisher_intents_total 42\n"));
+        assert!(output.contains("fisher_batches_by_savings_total{bucket=\">=95\"} 1\n"));
+        assert!(output.ends_with("# EOF\n"));
+        
+        // Legacy output keeps its names and has no terminator
+        let legacy = collector.prometheus_metrics().await;
+        assert!(legacy.contains("# TYPE fisher_total_batches counter\nfisher_total_batches 3\n"));
+        assert!(!legacy.contains("# EOF"));
+    }
+    
+    #[tokio::test]
+    async fn test_scrape_negotiates_format() {
+        let collector = MetricsCollector::new();
+        
+        // What Prometheus sends when it supports OpenMetrics
+        let accept = "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5,*/*;q=0.1";
+        let (content_type, body) = collector.scrape(Some(accept)).await;
+        assert!(content_type.starts_with("application/openmetrics-text"));
+        assert!(body.ends_with("# EOF\n"));
+        
+        for accept in [None, Some("text/plain"), Some("*/*"), Some("application/openmetrics-text;q=0.2, text/plain")] {
+            let (content_type, body) = collector.scrape(accept).await;
+            assert!(content_type.starts_with("text/plain"), "{:?}", accept);
+            assert!(body.contains("fisher_total_batches 0\n"));
+        }
+        
+        assert_eq!(MetricsFormat::negotiate(Some("application/openmetrics-text;q=0")), MetricsFormat::Prometheus);
+    }
+}
@@ -0,0 +1,189 @@
+//! Multi-chain Fisher coordination
+//!
+//! Runs one `FisherRelayer` per chain (Ethereum mainnet and L2s) with shared
+//! batching settings, routing each intent by its `target_chain`. Intents
+//! without a target go to the first configured chain; nothing stops the
+//! same untargeted intent being sent to another chain's relayer too, so
+//! senders should set `target_chain` whenever more than one chain is served.
+
+use crate::{ChainConfig, Error, FisherConfig, FisherRelayer, Intent, Metrics, Result};
+use std::collections::BTreeMap;
+use tracing::{debug, info};
+
+/// Coordinator owning one relayer per chain
+pub struct MultiChainFisher {
+    /// Relayers keyed by chain ID
+    relayers: BTreeMap<u64, FisherRelayer>,
+    
+    /// Chain for intents without a `target_chain`
+    default_chain: u64,
+}
+
+impl MultiChainFisher {
+    /// Create a relayer for each chain from a shared base configuration
+    ///
+    /// The first chain becomes the default route.
+    pub fn new(base: FisherConfig, chains: Vec<ChainConfig>) -> Result<Self> {
+        let default_chain = chains
+            .first()
+            .map(|c| c.chain_id)
+            .ok_or_else(|| Error::Config("At least one chain is required".to_string()))?;
+        
+        let mut relayers = BTreeMap::new();
+        for chain in &chains {
+            info!("🌐 Adding chain {} ({})", chain.chain_id, chain.rpc_url);
+            
+            let relayer = FisherRelayer::new(base.for_chain(chain))?;
+            if relayers.insert(chain.chain_id, relayer).is_some() {
+                return Err(Error::Config(format!("Duplicate chain ID {}", chain.chain_id)));
+            }
+        }
+        
+        Ok(Self { relayers, default_chain })
+    }
+    
+    /// Configured chain IDs
+    pub fn chain_ids(&self) -> Vec<u64> {
+        self.relayers.keys().copied().collect()
+    }
+    
+    /// Relayer for a chain
+    pub fn relayer(&self, chain_id: u64) -> Option<&FisherRelayer> {
+        self.relayers.get(&chain_id)
+    }
+    
+    /// Mutable relayer for a chain (e.g. to attach fishing spot or staking)
+    pub fn relayer_mut(&mut self, chain_id: u64) -> Option<&mut FisherRelayer> {
+        self.relayers.get_mut(&chain_id)
+    }
+    
+    /// Connect every relayer to its chain
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn init_ethereum(&mut self) -> Result<()> {
+        for relayer in self.relayers.values_mut() {
+            relayer.init_ethereum().await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Route an intent to the relayer for its target chain
+    pub async fn submit_intent(&self, intent: Intent) -> Result<String> {
+        let chain_id = intent.target_chain.unwrap_or(self.default_chain);
+        let relayer = self.relayers.get(&chain_id).ok_or_else(|| {
+            Error::InvalidIntent(format!("unsupported target chain {}", chain_id))
+        })?;
+        
+        debug!("🧭 Routing intent {} to chain {}", intent.id, chain_id);
+        relayer.submit_intent(intent).await
+    }
+    
+    /// Metrics for every chain, keyed by chain ID
+    pub async fn get_metrics(&self) -> BTreeMap<u64, Metrics> {
+        let mut metrics = BTreeMap::new();
+        for (chain_id, relayer) in &self.relayers {
+            metrics.insert(*chain_id, relayer.get_metrics().await);
+        }
+        
+        metrics
+    }
+    
+    /// Start automatic batch processing on every chain
+    pub async fn start(&self) {
+        for relayer in self.relayers.values() {
+            relayer.start().await;
+        }
+    }
+    
+    /// Stop automatic batch processing on every chain
+    pub fn stop(&self) {
+        for relayer in self.relayers.values() {
+            relayer.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+    use ethers::signers::{LocalWallet, Signer};
+    
+    fn chain(chain_id: u64, enable_blobs: bool) -> ChainConfig {
+        ChainConfig {
+            chain_id,
+            rpc_url: format!("http://chain-{}.invalid", chain_id),
+            fisher_address: Address::repeat_byte(chain_id as u8),
+            enable_blobs,
+        }
+    }
+    
+    fn intent(id: &str, target_chain: Option<u64>) -> Intent {
+        let wallet = LocalWallet::from_bytes(&[0x11; 32]).unwrap();
+        Intent {
+            target_chain,
+            ..Intent::new(
+                id.to_string(),
+                Address::from_slice(wallet.address().as_bytes()),
+                Address::repeat_byte(0x01),
+                U256::from(100),
+                false,
+                // Distinct per id, so intents routed to one chain don't share a nonce
+                u64::from_be_bytes(ethers::utils::keccak256(id)[..8].try_into().unwrap()),
+                vec![],
+            )
+        }
+        .signed(&wallet)
+        .unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_routes_intents_by_target_chain() {
+        let fisher = MultiChainFisher::new(
+            FisherConfig::default(),
+            vec![chain(1, true), chain(10, false)],
+        ).unwrap();
+        
+        fisher.submit_intent(intent("mainnet", None)).await.unwrap();
+        fisher.submit_intent(intent("mainnet_explicit", Some(1))).await.unwrap();
+        fisher.submit_intent(intent("optimism", Some(10))).await.unwrap();
+        
+        assert_eq!(fisher.relayer(1).unwrap().queue_len().await, 2);
+        assert_eq!(fisher.relayer(10).unwrap().queue_len().await, 1);
+        
+        let unknown = fisher.submit_intent(intent("base", Some(8453))).await;
+        assert!(matches!(unknown, Err(Error::InvalidIntent(_))));
+        
+        // Handed straight to the wrong chain's relayer, a targeted intent is still refused
+        let misrouted = fisher.relayer(1).unwrap().submit_intent(intent("optimism_only", Some(10))).await;
+        assert!(matches!(misrouted, Err(Error::InvalidIntent(_))));
+        assert_eq!(fisher.relayer(1).unwrap().queue_len().await, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_per_chain_config_and_metrics() {
+        let base = FisherConfig {
+            min_batch_size: 42,
+            ..FisherConfig::default()
+        };
+        let fisher = MultiChainFisher::new(base, vec![chain(1, true), chain(10, false)]).unwrap();
+        
+        let optimism = &fisher.relayer(10).unwrap().config;
+        assert_eq!(optimism.chain_id, Some(10));
+        assert_eq!(optimism.fisher_address, Address::repeat_byte(10));
+        assert_eq!(optimism.enable_blobs, crate::BlobMode::Off);
+        assert_eq!(optimism.min_batch_size, 42);
+        
+        let metrics = fisher.get_metrics().await;
+        assert_eq!(metrics.keys().copied().collect::<Vec<_>>(), vec![1, 10]);
+    }
+    
+    #[test]
+    fn test_rejects_empty_and_duplicate_chains() {
+        assert!(MultiChainFisher::new(FisherConfig::default(), vec![]).is_err());
+        assert!(MultiChainFisher::new(
+            FisherConfig::default(),
+            vec![chain(1, true), chain(1, false)],
+        ).is_err());
+    }
+}
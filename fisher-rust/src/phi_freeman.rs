@@ -4,28 +4,25 @@
 //! for transaction ordering and grouping.
 
 use crate::Intent;
-use std::cmp::Ordering;
 
-/// Golden ratio (φ)
-const PHI: f64 = 1.618033988749;
+pub use fisher_core::phi::PhiWeights;
+use fisher_core::phi::{score_rank, ScoreInput, PHI};
 
-/// φ-Freeman score for an intent
-///
-/// Combines multiple factors:
-/// - Priority flag (urgent vs normal)
-/// - Amount (larger transactions weighted higher)
-/// - Timestamp (older transactions processed first)
-/// - Gas price (higher paying users get priority)
-fn calculate_phi_score(intent: &Intent, now: u64) -> f64 {
-    let age_factor = (now.saturating_sub(intent.timestamp)) as f64;
-    let amount_factor = intent.amount.to::<u128>() as f64;
-    let priority_factor = if intent.priority { PHI } else { 1.0 };
-    let gas_factor = intent.max_gas_price
-        .map(|p| p.to::<u128>() as f64)
-        .unwrap_or(1.0);
-    
-    // Combine factors with φ-weighted formula
-    priority_factor * (age_factor.powf(1.0 / PHI) + amount_factor.ln() + gas_factor.ln())
+/// φ-Freeman score for an intent at `now` (see [`fisher_core::phi::score`])
+fn calculate_phi_score(intent: &Intent, now: u64, weights: &PhiWeights) -> f64 {
+    score_as(intent, intent.priority, now, weights)
+}
+
+/// φ-Freeman score for an intent treated as priority or not, regardless of its flag
+fn score_as(intent: &Intent, priority: bool, now: u64, weights: &PhiWeights) -> f64 {
+    let input = ScoreInput {
+        priority,
+        timestamp: intent.timestamp,
+        amount: intent.amount.saturating_to(),
+        max_gas_price: intent.max_gas_price.map(|p| p.saturating_to()),
+    };
+    
+    fisher_core::phi::score(&input, now, weights)
 }
 
 /// Sort intents using φ-Freeman optimization
@@ -45,16 +42,39 @@ fn calculate_phi_score(intent: &Intent, now: u64) -> f64 {
 /// // Now optimally ordered for batching
 /// ```
 pub fn phi_sort(intents: &mut [Intent]) {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+    phi_sort_with(intents, &PhiWeights::default());
+}
+
+/// Sort intents using φ-Freeman optimization with custom weights
+///
+/// Use [`PhiWeights::fair`] to guarantee small intents are not starved by
+/// a sustained stream of large or high-gas intents.
+pub fn phi_sort_with(intents: &mut [Intent], weights: &PhiWeights) {
+    let now = fisher_core::unix_now();
     
-    intents.sort_by(|a, b| {
-        let score_a = calculate_phi_score(a, now);
-        let score_b = calculate_phi_score(b, now);
-        
-        score_b.partial_cmp(&score_a).unwrap_or(Ordering::Equal)
+    sort_at(intents, now, weights);
+}
+
+/// [`phi_sort_with`], scoring priority by `has_priority` instead of each intent's flag
+///
+/// The flag is signed, so a relayer that doesn't honor it (see
+/// `PriorityPolicy`) can't clear it; it orders the intent as normal instead.
+pub fn phi_sort_with_priority(intents: &mut [Intent], weights: &PhiWeights, has_priority: impl Fn(&Intent) -> bool) {
+    let now = fisher_core::unix_now();
+    
+    sort_at_by(intents, now, weights, has_priority);
+}
+
+/// Highest score first; scores with equal [`score_rank`] are tied and
+/// ordered by [`Intent::hash`], so the result is reproducible
+fn sort_at(intents: &mut [Intent], now: u64, weights: &PhiWeights) {
+    sort_at_by(intents, now, weights, |intent| intent.priority);
+}
+
+fn sort_at_by(intents: &mut [Intent], now: u64, weights: &PhiWeights, has_priority: impl Fn(&Intent) -> bool) {
+    intents.sort_by_cached_key(|intent| {
+        let score = score_as(intent, has_priority(intent), now, weights);
+        (std::cmp::Reverse(score_rank(score)), intent.hash())
     });
 }
 
@@ -95,20 +115,22 @@ pub fn phi_group(intents: &[Intent]) -> Vec<Vec<Intent>> {
 /// Evaluates how well a batch is composed using φ metrics.
 /// Higher scores indicate better optimization.
 pub fn batch_score(intents: &[Intent]) -> f64 {
+    batch_score_with(intents, &PhiWeights::default())
+}
+
+/// Calculate batch composition score with custom weights
+pub fn batch_score_with(intents: &[Intent], weights: &PhiWeights) -> f64 {
     if intents.is_empty() {
         return 0.0;
     }
     
     let n = intents.len() as f64;
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+    let now = fisher_core::unix_now();
     
     // Average φ score
     let avg_score: f64 = intents
         .iter()
-        .map(|i| calculate_phi_score(i, now))
+        .map(|i| calculate_phi_score(i, now, weights))
         .sum::<f64>() / n;
     
     // Size factor (closer to φ-optimal size scores higher)
@@ -138,9 +160,25 @@ mod tests {
             signature: vec![],
             timestamp,
             max_gas_price: None,
+            target_chain: None,
+            version: crate::INTENT_VERSION,
+            permit: None,
         }
     }
 
+    #[test]
+    fn test_empty_and_single_batches() {
+        assert_eq!(batch_score(&[]), 0.0);
+        assert!(phi_group(&[]).is_empty());
+        
+        let single = [make_intent("only", false, 100, 1000)];
+        let score = batch_score(&single);
+        assert!(score.is_finite() && score >= 0.0);
+        let groups = phi_group(&single);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0][0].id, "only");
+    }
+    
     #[test]
     fn test_phi_sort() {
         let mut intents = vec![
@@ -155,6 +193,38 @@ mod tests {
         assert!(intents[0].priority || intents[0].id == "old");
     }
 
+    #[test]
+    fn test_equal_scores_ordered_by_hash() {
+        let now = 10_000;
+        let intents: Vec<Intent> = (0..8)
+            .map(|i| make_intent(&format!("tie_{}", i), false, 500, 9_000))
+            .collect();
+        
+        let mut expected = intents.clone();
+        expected.sort_by_key(Intent::hash);
+        let expected: Vec<String> = expected.into_iter().map(|i| i.id).collect();
+        
+        // Same order whatever order they arrive in
+        for rotation in 0..intents.len() {
+            let mut sorted = intents.clone();
+            sorted.rotate_left(rotation);
+            sorted.reverse();
+            sort_at(&mut sorted, now, &PhiWeights::default());
+            
+            assert_eq!(sorted.into_iter().map(|i| i.id).collect::<Vec<_>>(), expected);
+        }
+    }
+    
+    #[test]
+    fn test_score_rank_ignores_float_noise() {
+        assert_eq!(score_rank(42.0), score_rank(42.0 * (1.0 + 1e-15)));
+        assert!(score_rank(42.0 * (1.0 + 1e-12)) > score_rank(42.0));
+        assert!(score_rank(42.0) > score_rank(41.9));
+        assert!(score_rank(0.0) > score_rank(-1.0));
+        assert!(score_rank(-1.0) > score_rank(-2.0));
+        assert!(score_rank(f64::MAX) > score_rank(1e300));
+    }
+
     #[test]
     fn test_phi_group() {
         let intents: Vec<Intent> = (0..100)
@@ -176,4 +246,152 @@ mod tests {
         let score = batch_score(&intents);
         assert!(score > 0.0);
     }
+    
+    /// Rounds until the tiny intent makes a batch while whales keep arriving
+    fn rounds_until_tiny_included(weights: &PhiWeights, max_rounds: u64) -> Option<u64> {
+        const ROUND_SECS: u64 = 10;
+        const WHALES_PER_ROUND: usize = 20;
+        const BATCH_SIZE: usize = 10;
+        
+        let whale = |round: u64, i: usize| Intent {
+            max_gas_price: Some(U256::from(100_000_000_000u64)),
+            ..make_intent(&format!("whale_{}_{}", round, i), false, u64::MAX, round * ROUND_SECS)
+        };
+        
+        let mut pool = vec![Intent {
+            max_gas_price: Some(U256::from(1_000_000_000u64)),
+            ..make_intent("tiny", false, 1, 0)
+        }];
+        
+        for round in 1..=max_rounds {
+            pool.extend((0..WHALES_PER_ROUND).map(|i| whale(round, i)));
+            sort_at(&mut pool, round * ROUND_SECS, weights);
+            
+            let batch: Vec<Intent> = pool.drain(..BATCH_SIZE).collect();
+            if batch.iter().any(|i| i.id == "tiny") {
+                return Some(round);
+            }
+        }
+        
+        None
+    }
+
+    #[test]
+    fn test_fairness_bounds_wait_under_whale_load() {
+        // Without fairness the tiny intent is starved
+        assert_eq!(rounds_until_tiny_included(&PhiWeights::default(), 30), None);
+        
+        // With fairness it is included shortly after crossing the threshold
+        let round = rounds_until_tiny_included(&PhiWeights::fair(60), 30).unwrap();
+        assert!(round <= 10, "tiny intent waited {} rounds", round);
+    }
+    
+    #[test]
+    fn test_default_weights_match_original_score() {
+        let intent = Intent {
+            max_gas_price: Some(U256::from(50)),
+            ..make_intent("a", true, 1000, 900)
+        };
+        let expected = PHI * (100f64.powf(1.0 / PHI) + 1000f64.ln() + 50f64.ln());
+        
+        let score = calculate_phi_score(&intent, 1000, &PhiWeights::default());
+        assert!((score - expected).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_zero_amount_weight_ignores_amount() {
+        let weights = PhiWeights { amount_weight: 0.0, ..PhiWeights::default() };
+        let ages = [50, 10, 90, 30, 70];
+        
+        let ordered_ids = |amounts: [u64; 5]| {
+            let mut intents: Vec<Intent> = ages.iter().zip(amounts)
+                .enumerate()
+                .map(|(i, (age, amount))| make_intent(&format!("i{}", i), false, amount, 1000 - age))
+                .collect();
+            sort_at(&mut intents, 1000, &weights);
+            intents.into_iter().map(|i| i.id).collect::<Vec<_>>()
+        };
+        
+        // Oldest first, whatever the amounts
+        let by_age = vec!["i2", "i4", "i0", "i3", "i1"];
+        assert_eq!(ordered_ids([1, 1, 1, 1, 1]), by_age);
+        assert_eq!(ordered_ids([u64::MAX, 5, 1, 1_000_000, 42]), by_age);
+        assert_eq!(ordered_ids([1, u64::MAX, 2, 3, 1_000_000_000]), by_age);
+        
+        // With the default weight a whale jumps the queue
+        let mut intents = vec![
+            make_intent("old", false, 1, 900),
+            make_intent("whale", false, u64::MAX, 990),
+        ];
+        sort_at(&mut intents, 1000, &PhiWeights::default());
+        assert_eq!(intents[0].id, "whale");
+    }
+    
+    #[test]
+    fn test_custom_priority_and_age_weights() {
+        let urgent = make_intent("urgent", true, 1000, 900);
+        let flat = PhiWeights { priority_multiplier: 1.0, age_exponent: 1.0, ..PhiWeights::default() };
+        
+        let score = calculate_phi_score(&urgent, 1000, &flat);
+        assert!((score - (100.0 + 1000f64.ln())).abs() < 1e-9);
+        
+        // Batch scoring honours the same weights
+        let no_boost = PhiWeights { priority_multiplier: 1.0, ..PhiWeights::default() };
+        let batch = vec![urgent, make_intent("normal", false, 1000, 900)];
+        assert!(batch_score_with(&batch, &no_boost) < batch_score(&batch));
+    }
+    
+    #[test]
+    fn test_partial_weights_deserialize_with_defaults() {
+        let weights: PhiWeights = serde_json::from_str(r#"{"amount_weight": 0.0}"#).unwrap();
+        assert_eq!(weights, PhiWeights { amount_weight: 0.0, ..PhiWeights::default() });
+    }
+    
+    #[test]
+    fn test_zero_amount_zero_age_scores_finite() {
+        let fresh = Intent {
+            max_gas_price: Some(U256::ZERO),
+            ..make_intent("fresh", false, 0, 1000)
+        };
+        
+        assert_eq!(calculate_phi_score(&fresh, 1000, &PhiWeights::default()), 0.0);
+        
+        // Exponents that would make 0^x infinite, and weights that overflow
+        let extreme = PhiWeights { age_exponent: -1.0, gas_weight: f64::INFINITY, ..PhiWeights::default() };
+        assert!(calculate_phi_score(&fresh, 1000, &extreme).is_finite());
+        let whale = make_intent("whale", true, u64::MAX, 0);
+        assert!(calculate_phi_score(&whale, 1000, &extreme).is_finite());
+        
+        // Same position (last) wherever it starts in the queue
+        let others = vec![
+            make_intent("a", false, 100, 900),
+            make_intent("b", true, 10, 990),
+            make_intent("c", false, 1, 500),
+        ];
+        let mut orders = Vec::new();
+        for position in 0..=others.len() {
+            let mut intents = others.clone();
+            intents.insert(position, fresh.clone());
+            sort_at(&mut intents, 1000, &PhiWeights::default());
+            orders.push(intents.into_iter().map(|i| i.id).collect::<Vec<_>>());
+        }
+        
+        assert!(orders.iter().all(|order| order == &orders[0]));
+        assert_eq!(orders[0].last().unwrap(), "fresh");
+    }
+    
+    #[test]
+    fn test_sort_with_unearned_priority_ignored() {
+        let mut intents = vec![
+            make_intent("flagged", true, 1000, 960),
+            make_intent("older", false, 1000, 950),
+        ];
+        
+        sort_at(&mut intents, 1000, &PhiWeights::default());
+        assert_eq!(intents[0].id, "flagged");
+        
+        // Not honoring the flag, the older intent goes first
+        sort_at_by(&mut intents, 1000, &PhiWeights::default(), |_| false);
+        assert_eq!(intents[0].id, "older");
+    }
 }
@@ -0,0 +1,309 @@
+//! Private order-flow relay submission (Flashbots Protect / MEV-Share)
+//!
+//! Batches sent through a relay never touch the public mempool, so they
+//! can't be front-run or sandwiched. The relay only forwards the signed
+//! transaction to builders; inclusion is confirmed by watching the chain
+//! for its receipt.
+
+use crate::{Error, Result};
+use ethers::signers::LocalWallet;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Blocks a private transaction stays eligible for inclusion (the Flashbots default)
+pub const MAX_INCLUSION_BLOCKS: u64 = 25;
+
+/// Header carrying the request signature
+pub const SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+/// Client for a relay's `eth_sendPrivateTransaction` endpoint
+#[derive(Debug, Clone)]
+pub struct PrivateRelayClient {
+    url: String,
+    client: reqwest::Client,
+    
+    /// Identifies this relayer to the relay for reputation; holds no funds
+    auth_key: LocalWallet,
+}
+
+/// JSON-RPC reply from the relay
+#[derive(Debug, Deserialize)]
+struct RelayResponse {
+    #[serde(default)]
+    result: Option<serde_json::Value>,
+    
+    #[serde(default)]
+    error: Option<RelayError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelayError {
+    message: String,
+}
+
+impl PrivateRelayClient {
+    /// Create a client for the relay at `url`, with a fresh signing key
+    ///
+    /// Relays build reputation per key, so use [`Self::with_auth_key`] to keep
+    /// it across restarts.
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to create HTTP client: {}", e)))?;
+        
+        Ok(Self {
+            url: url.into(),
+            client,
+            auth_key: LocalWallet::new(&mut rand::thread_rng()),
+        })
+    }
+    
+    /// Sign requests with `key` (hex private key) instead of a fresh one
+    pub fn with_auth_key(mut self, key: &str) -> Result<Self> {
+        self.auth_key = key.trim_start_matches("0x").parse()
+            .map_err(|e| Error::Config(format!("Invalid relay auth key: {}", e)))?;
+        Ok(self)
+    }
+    
+    /// Relay endpoint
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    
+    /// Address the relay attributes requests (and reputation) to
+    pub fn auth_address(&self) -> ethers::types::Address {
+        use ethers::signers::Signer;
+        self.auth_key.address()
+    }
+    
+    /// JSON-RPC request sending `raw_tx` privately until `max_block_number`
+    pub fn private_transaction_request(raw_tx: &[u8], max_block_number: u64) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendPrivateTransaction",
+            "params": [{
+                "tx": format!("0x{}", hex::encode(raw_tx)),
+                "maxBlockNumber": format!("{:#x}", max_block_number),
+                "preferences": { "fast": true },
+            }],
+        })
+    }
+    
+    /// `address:signature`, the EIP-191 signature over the body's hex keccak hash
+    pub fn sign_body(&self, body: &[u8]) -> Result<String> {
+        use ethers::signers::Signer;
+        
+        let digest = format!("0x{}", hex::encode(ethers::utils::keccak256(body)));
+        let signature = self.auth_key
+            .sign_hash(ethers::utils::hash_message(digest))
+            .map_err(|e| Error::Signing(e.to_string()))?;
+        
+        Ok(format!("{:?}:0x{}", self.auth_key.address(), hex::encode(signature.to_vec())))
+    }
+    
+    /// Send a signed transaction through the relay, returning its hash
+    ///
+    /// The relay must acknowledge the hash of `raw_tx` itself; a reply naming
+    /// any other transaction is an error.
+    pub async fn send_private_transaction(&self, raw_tx: &[u8], max_block_number: u64) -> Result<[u8; 32]> {
+        let body = serde_json::to_vec(&Self::private_transaction_request(raw_tx, max_block_number))?;
+        let signature = self.sign_body(&body)?;
+        
+        let response = self.client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| Error::Rpc(format!("Private relay unreachable: {}", e)))?;
+        
+        if !response.status().is_success() {
+            return Err(Error::Rpc(format!("Private relay returned {}", response.status())));
+        }
+        
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| Error::Rpc(format!("Failed to read private relay reply: {}", e)))?;
+        
+        let tx_hash = Self::read_response(&bytes)?;
+        let expected = ethers::utils::keccak256(raw_tx);
+        if tx_hash != expected {
+            return Err(Error::Rpc(format!(
+                "Private relay acknowledged 0x{}, expected 0x{}",
+                hex::encode(tx_hash),
+                hex::encode(expected)
+            )));
+        }
+        
+        Ok(tx_hash)
+    }
+    
+    /// Transaction hash from a relay reply
+    ///
+    /// Accepts a bare hash (Flashbots Protect) or `{"txHash": ...}`; relay
+    /// errors are classified like node errors (nonce too low, underpriced, ...).
+    fn read_response(body: &[u8]) -> Result<[u8; 32]> {
+        let reply: RelayResponse = serde_json::from_slice(body)?;
+        
+        if let Some(error) = reply.error {
+            return Err(Error::from_node_message("Private relay", &error.message)
+                .unwrap_or_else(|| Error::Rpc(format!("Private relay: {}", error.message))));
+        }
+        
+        let hash = match &reply.result {
+            Some(serde_json::Value::String(hash)) => Some(hash.as_str()),
+            Some(result) => result["txHash"].as_str(),
+            None => None,
+        };
+        
+        hash.and_then(|hash| hex::decode(hash.trim_start_matches("0x")).ok())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| Error::Rpc(format!("Private relay returned no transaction hash: {:?}", reply.result)))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::AsyncWriteExt;
+    
+    /// Request the mock relay received: header block and JSON body
+    #[derive(Debug, Clone)]
+    pub(crate) struct RelayRequest {
+        pub head: String,
+        pub body: serde_json::Value,
+    }
+    
+    /// Relay that replies to each request with `reply(body)` and records it
+    pub(crate) async fn spawn_relay<F>(reply: F) -> (String, Arc<Mutex<Vec<RelayRequest>>>)
+    where
+        F: Fn(&serde_json::Value) -> serde_json::Value + Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let requests: Arc<Mutex<Vec<RelayRequest>>> = Arc::default();
+        
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let (head, body) = crate::fishing_spot::mock::read_request(&mut socket).await;
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                let response = reply(&body).to_string();
+                recorded.lock().unwrap().push(RelayRequest { head, body });
+                
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        (endpoint, requests)
+    }
+    
+    /// Reply acknowledging the transaction in `request`, as Flashbots Protect does
+    pub(crate) fn acknowledge(request: &serde_json::Value) -> serde_json::Value {
+        let tx = request["params"][0]["tx"].as_str().unwrap();
+        let raw = hex::decode(tx.trim_start_matches("0x")).unwrap();
+        let hash = ethers::utils::keccak256(raw);
+        
+        serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": format!("0x{}", hex::encode(hash))})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Signature;
+    
+    #[tokio::test]
+    async fn test_private_transaction_payload() {
+        let (endpoint, requests) = mock::spawn_relay(mock::acknowledge).await;
+        let relay = PrivateRelayClient::new(&endpoint).unwrap();
+        
+        let raw_tx = vec![0x02, 0xF8, 0x01, 0x02, 0x03];
+        let tx_hash = relay.send_private_transaction(&raw_tx, 0x1234).await.unwrap();
+        assert_eq!(tx_hash, ethers::utils::keccak256(&raw_tx));
+        
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        
+        assert_eq!(request.body["jsonrpc"], "2.0");
+        assert_eq!(request.body["method"], "eth_sendPrivateTransaction");
+        let params = request.body["params"].as_array().unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0]["tx"], "0x02f8010203");
+        assert_eq!(params[0]["maxBlockNumber"], "0x1234");
+        assert_eq!(params[0]["preferences"]["fast"], true);
+        
+        // The signature header is the auth key's EIP-191 signature over the body hash
+        let header = request.head.lines()
+            .find_map(|line| line.to_lowercase().strip_prefix("x-flashbots-signature:").map(str::to_string))
+            .unwrap();
+        let (address, signature) = header.trim().split_once(':').unwrap();
+        let signature: Signature = signature.parse().unwrap();
+        let body = serde_json::to_vec(&request.body).unwrap();
+        let digest = format!("0x{}", hex::encode(ethers::utils::keccak256(&body)));
+        let signer = signature.recover(digest).unwrap();
+        assert_eq!(format!("{:?}", signer), address);
+    }
+    
+    #[tokio::test]
+    async fn test_relay_errors_surface() {
+        let (endpoint, _) = mock::spawn_relay(|request| serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": {"code": -32000, "message": "nonce too low"},
+        })).await;
+        let relay = PrivateRelayClient::new(endpoint).unwrap();
+        assert!(matches!(relay.send_private_transaction(&[0x01], 1).await, Err(Error::NonceTooLow(_))));
+        
+        // Acknowledging some other transaction doesn't count
+        let (endpoint, _) = mock::spawn_relay(|request| serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": format!("0x{}", "ab".repeat(32)),
+        })).await;
+        let relay = PrivateRelayClient::new(endpoint).unwrap();
+        assert!(matches!(relay.send_private_transaction(&[0x01], 1).await, Err(Error::Rpc(_))));
+    }
+    
+    #[test]
+    fn test_configured_auth_key_is_stable() {
+        let key = format!("0x{}", "11".repeat(32));
+        let first = PrivateRelayClient::new("http://relay").unwrap().with_auth_key(&key).unwrap();
+        let second = PrivateRelayClient::new("http://relay").unwrap().with_auth_key(&key).unwrap();
+        assert_eq!(first.auth_address(), second.auth_address());
+        
+        // Without one, every client gets a new identity
+        let fresh = PrivateRelayClient::new("http://relay").unwrap();
+        assert_ne!(fresh.auth_address(), first.auth_address());
+        
+        assert!(matches!(
+            PrivateRelayClient::new("http://relay").unwrap().with_auth_key("0xnope"),
+            Err(Error::Config(_))
+        ));
+    }
+    
+    #[test]
+    fn test_response_formats() {
+        let hash = format!("0x{}", "cd".repeat(32));
+        
+        let bare = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": hash});
+        assert_eq!(PrivateRelayClient::read_response(bare.to_string().as_bytes()).unwrap(), [0xCD; 32]);
+        
+        let object = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"txHash": hash}});
+        assert_eq!(PrivateRelayClient::read_response(object.to_string().as_bytes()).unwrap(), [0xCD; 32]);
+        
+        let empty = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": null});
+        assert!(PrivateRelayClient::read_response(empty.to_string().as_bytes()).is_err());
+    }
+}
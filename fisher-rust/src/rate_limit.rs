@@ -0,0 +1,158 @@
+//! Per-sender rate limiting
+//!
+//! Token bucket keyed on `Intent.from` so a single address can't flood the
+//! gasless queue. Each sender may submit `capacity` intents per window, with
+//! tokens refilling continuously. Idle senders are pruned to bound memory.
+
+use alloy_primitives::Address;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Token bucket for one sender
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token-bucket rate limiter keyed on sender address
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Intents allowed per window (0 disables limiting)
+    capacity: u32,
+    
+    /// Refill window
+    window: Duration,
+    
+    /// Per-sender buckets
+    buckets: HashMap<Address, Bucket>,
+    
+    /// Last time idle buckets were pruned
+    last_prune: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `capacity` intents per sender per `window`
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            buckets: HashMap::new(),
+            last_prune: Instant::now(),
+        }
+    }
+    
+    /// Try to consume one token for `sender`; returns false if rate limited
+    pub fn check(&mut self, sender: Address) -> bool {
+        self.check_at(sender, Instant::now())
+    }
+    
+    /// [`RateLimiter::check`] with an explicit clock
+    pub fn check_at(&mut self, sender: Address, now: Instant) -> bool {
+        if self.capacity == 0 || self.window.is_zero() {
+            return true;
+        }
+        
+        self.prune_idle(now);
+        
+        let capacity = self.capacity as f64;
+        let refill_per_sec = capacity / self.window.as_secs_f64();
+        
+        let bucket = self.buckets.entry(sender).or_insert(Bucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+        
+        let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.last_refill = now;
+        
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+    
+    /// Number of senders currently tracked
+    pub fn tracked_senders(&self) -> usize {
+        self.buckets.len()
+    }
+    
+    /// Drop buckets idle for a full window (they'd be refilled to capacity anyway)
+    fn prune_idle(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_prune) < self.window {
+            return;
+        }
+        
+        let window = self.window;
+        self.buckets
+            .retain(|_, bucket| now.saturating_duration_since(bucket.last_refill) < window);
+        self.last_prune = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_limit_is_per_sender() {
+        let mut limiter = RateLimiter::new(3, Duration::from_secs(60));
+        let now = Instant::now();
+        let abuser = Address::repeat_byte(0xAA);
+        let honest = Address::repeat_byte(0xBB);
+        
+        for _ in 0..3 {
+            assert!(limiter.check_at(abuser, now));
+        }
+        assert!(!limiter.check_at(abuser, now));
+        
+        // Another sender is unaffected
+        assert!(limiter.check_at(honest, now));
+    }
+    
+    #[test]
+    fn test_bucket_refills_after_window() {
+        let mut limiter = RateLimiter::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+        let sender = Address::repeat_byte(0x01);
+        
+        assert!(limiter.check_at(sender, start));
+        assert!(limiter.check_at(sender, start));
+        assert!(!limiter.check_at(sender, start));
+        
+        // Half a window refills one token
+        assert!(limiter.check_at(sender, start + Duration::from_secs(5)));
+        assert!(!limiter.check_at(sender, start + Duration::from_secs(5)));
+        
+        // A full window refills the bucket
+        let later = start + Duration::from_secs(20);
+        assert!(limiter.check_at(sender, later));
+        assert!(limiter.check_at(sender, later));
+    }
+    
+    #[test]
+    fn test_idle_senders_pruned() {
+        let mut limiter = RateLimiter::new(5, Duration::from_secs(1));
+        let start = Instant::now();
+        
+        for i in 0..50u8 {
+            limiter.check_at(Address::repeat_byte(i), start);
+        }
+        assert_eq!(limiter.tracked_senders(), 50);
+        
+        limiter.check_at(Address::repeat_byte(0xFF), start + Duration::from_secs(2));
+        assert_eq!(limiter.tracked_senders(), 1);
+    }
+    
+    #[test]
+    fn test_zero_capacity_disables_limit() {
+        let mut limiter = RateLimiter::new(0, Duration::from_secs(60));
+        for _ in 0..1000 {
+            assert!(limiter.check(Address::ZERO));
+        }
+        assert_eq!(limiter.tracked_senders(), 0);
+    }
+}
@@ -2,34 +2,76 @@
 
 use crate::{
     types::*,
+    blob::{BlobEncoder, DataFees},
+    phi_optimization::{EraState, GasReport, SavingsEstimate},
+    attestation::{AttestationManager, AttestationStatus},
+    rate_limit::RateLimiter,
     fishing_spot::{FishingSpotClient, FishingSpotConfig},
+    host::HostRpc,
+    lru_state::ReplayGuard,
     staking::FisherStaking,
     Error, Result,
 };
+use crate::signer::LocalWalletSigner;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::signer::{SharedSigner, SignerClient};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::submissions::{InFlightLog, InFlightState, SubmissionLog};
 use alloy_primitives::U256;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, watch, Mutex, Notify, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
-use tracing::{info, warn, error, debug};
+use tracing::{info, warn, error, debug, instrument};
+
+/// Settled batches buffered per subscriber before it starts lagging
+const BATCH_EVENT_CAPACITY: usize = 256;
+
+/// How often to poll the chain head while waiting for confirmations
+#[cfg(not(target_arch = "wasm32"))]
+const CONFIRMATION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Block time assumed when turning a confirmation depth into wall-clock time
+#[cfg(not(target_arch = "wasm32"))]
+const SECONDS_PER_BLOCK: u64 = 12;
+
+/// How long resolved submission markers outlive their confirmation depth
+#[cfg(not(target_arch = "wasm32"))]
+const SUBMISSION_RETENTION_MARGIN: std::time::Duration = std::time::Duration::from_secs(3600);
 
 #[cfg(not(target_arch = "wasm32"))]
 use ethers::prelude::*;
 #[cfg(not(target_arch = "wasm32"))]
-use ethers::contract::abigen;
+use bindings::*;
 
 // Generate Rust bindings from your FisherProduction contract
 #[cfg(not(target_arch = "wasm32"))]
-abigen!(
-    FisherContract,
-    r#"[
-        struct Payment { address from; address to; uint256 amount; bool priorityFlag; uint256 nonce; }
-        function submitBatchOptimized(Payment[] payments, bytes[] signatures) external returns (bool[])
-        function calculateChunkSize(uint256 batchSize) external view returns (uint256)
-        function estimateGas(uint256 batchSize) external view returns (uint256, uint256)
-        function batchCounter() external view returns (uint256)
-        event BatchSubmitted(uint256 indexed batchId, uint256 operationCount, uint256 gasUsed, uint256 gasSaved, uint256 feesCollected, uint256 timestamp)
-    ]"#
-);
+mod bindings {
+    use ethers::contract::abigen;
+
+    abigen!(
+        FisherContract,
+        r#"[
+            struct Payment { address from; address to; uint256 amount; bool priorityFlag; uint256 nonce; }
+            struct PermitData { bool present; uint256 deadline; uint8 v; bytes32 r; bytes32 s; }
+            function submitBatchOptimized(Payment[] payments, bytes[] signatures) external returns (bool[])
+            function submitBatchWithPermits(Payment[] payments, bytes[] signatures, PermitData[] permits) external returns (bool[])
+            function calculateChunkSize(uint256 batchSize) external view returns (uint256)
+            function estimateGas(uint256 batchSize) external view returns (uint256, uint256)
+            function batchCounter() external view returns (uint256)
+            event BatchSubmitted(uint256 indexed batchId, uint256 operationCount, uint256 gasUsed, uint256 gasSaved, uint256 feesCollected, uint256 timestamp)
+            event FeeCharged(address indexed user, uint256 amount)
+        ]"#
+    );
+    
+    abigen!(
+        Erc1271Wallet,
+        r#"[
+            function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4)
+        ]"#
+    );
+}
 
 /// Fisher relayer - Collects and batches user intents
 pub struct FisherRelayer {
@@ -39,41 +81,179 @@ pub struct FisherRelayer {
     /// Intent queue
     intent_queue: Arc<RwLock<Vec<Intent>>>,
     
-    /// Ethereum wallet
+    /// Woken whenever intents leave the queue, for submitters waiting on `max_queue_size`
+    queue_space: Arc<Notify>,
+    
+    /// Held while a `process_batch` run drains and settles the queue
+    processing_guard: Arc<Mutex<()>>,
+    
+    /// Set by `pause()`: batches are deferred while intents keep queueing
+    paused: Arc<AtomicBool>,
+    
+    /// Set while an early batch run is scheduled but hasn't started
+    trigger_pending: Arc<AtomicBool>,
+    
+    /// Ethereum wallet (replaced on reconnect, shared across clones)
     #[cfg(not(target_arch = "wasm32"))]
-    wallet: Option<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    wallet: Arc<RwLock<Option<SignerClient>>>,
+    
+    /// Signing backend (defaults to a local wallet from `config.private_key`)
+    signer_backend: Option<Arc<dyn crate::signer::Signer>>,
+    
+    /// Markers for batches already handed to the network
+    #[cfg(not(target_arch = "wasm32"))]
+    submissions: Arc<RwLock<SubmissionLog>>,
+    
+    /// Private order-flow relay (when `submission_route` is `PrivateRelay`)
+    #[cfg(not(target_arch = "wasm32"))]
+    private_relay: Option<crate::private_relay::PrivateRelayClient>,
     
     /// Metrics collector
     metrics: Arc<RwLock<Metrics>>,
     
+    /// φ-compounded era fee state (when `era_fees` is configured)
+    era_state: Option<Arc<RwLock<EraState>>>,
+    
     /// Fishing spot client (optional)
     fishing_spot: Option<FishingSpotClient>,
     
+    /// Cancelled intents the fishing spot has yet to confirm, with failed attempts, by ID
+    pending_cancellations: Arc<RwLock<HashMap<String, (Intent, u32)>>>,
+    
+    /// Fishing spot intents collected but not yet acknowledged back
+    in_flight: Arc<RwLock<InFlightLog>>,
+    
     /// Staking manager (optional)
     staking: Option<FisherStaking>,
+    
+    /// TEE attestation and intent-encryption keys
+    attestation: Arc<AttestationManager>,
+    
+    /// Per-sender intent rate limiter
+    rate_limiter: Arc<Mutex<RateLimiter>>,
+    
+    /// Intents already drained into a batch (no longer cancellable or resubmittable)
+    replay_guard: Arc<RwLock<ReplayGuard>>,
+    
+    /// On-chain failures so far per intent ID, for intents awaiting a retry
+    failed_attempts: Arc<RwLock<HashMap<String, u32>>>,
+    
+    /// Recently dropped intents and why, oldest first
+    dead_letters: Arc<RwLock<VecDeque<(Intent, DropReason)>>>,
+    
+    /// Broadcasts each batch result after settlement
+    batch_events: broadcast::Sender<BatchResult>,
+    
+    /// Next batch ID to assign
+    next_batch_id: Arc<AtomicU64>,
+    
+    /// Set to `true` to stop the background tasks spawned by `start()`
+    shutdown: Arc<watch::Sender<bool>>,
 }
 
 impl FisherRelayer {
+    /// Create new relayer from configuration
     pub fn new(config: FisherConfig) -> Result<Self> {
         info!("🚀 Initializing Fisher Relayer v{}", crate::VERSION);
         info!("📍 Fisher address: {:?}", config.fisher_address);
         info!("📍 EVVM Core: {:?}", config.evvm_core_address);
         
+        config.williams_params.validate()?;
+        crate::blob::validate_max_blobs(config.max_blobs_per_tx, config.chain_id)?;
+        if let Some(params) = &config.era_fees {
+            params.validate()?;
+        }
+        let era_state = config.era_fees.as_ref()
+            .map(|params| Arc::new(RwLock::new(EraState::from_params(params))));
+        
+        let attestation = Arc::new(AttestationManager::new(config.enable_attestation));
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new(
+            config.max_intents_per_sender_per_window,
+            std::time::Duration::from_millis(config.rate_limit_window_ms),
+        )));
+        let replay_guard = ReplayGuard::new(
+            config.replay_state_capacity,
+            std::time::Duration::from_secs(
+                config.replay_state_ttl_secs.max(config.max_intent_clock_skew_secs.saturating_mul(2)),
+            ),
+        );
+        
+        #[cfg(not(target_arch = "wasm32"))]
+        let submissions = match &config.submission_log_path {
+            Some(path) => SubmissionLog::open(path)?,
+            None => SubmissionLog::in_memory(),
+        }
+        .with_retention(
+            std::time::Duration::from_secs(config.confirmation_depth.saturating_mul(SECONDS_PER_BLOCK))
+                + SUBMISSION_RETENTION_MARGIN,
+        )?;
+        
+        let in_flight = match &config.in_flight_log_path {
+            Some(path) => InFlightLog::open(path)?,
+            None => InFlightLog::in_memory(),
+        };
+        
+        #[cfg(not(target_arch = "wasm32"))]
+        let private_relay = match &config.submission_route {
+            SubmissionRoute::PublicMempool => None,
+            SubmissionRoute::PrivateRelay { url } if url.is_empty() => {
+                return Err(Error::Config("private_relay url must not be empty".to_string()));
+            }
+            SubmissionRoute::PrivateRelay { url } => {
+                info!("🕶️  Submitting through private relay {}", url);
+                let relay = crate::private_relay::PrivateRelayClient::new(url)?;
+                Some(match &config.relay_auth_key {
+                    Some(key) => relay.with_auth_key(key)?,
+                    None => {
+                        warn!(
+                            "⚠️  {} not set; relay reputation resets on every restart",
+                            crate::types::RELAY_AUTH_KEY_ENV
+                        );
+                        relay
+                    }
+                })
+            }
+        };
+        
         Ok(Self {
             config,
             intent_queue: Arc::new(RwLock::new(Vec::new())),
+            queue_space: Arc::new(Notify::new()),
+            processing_guard: Arc::new(Mutex::new(())),
+            paused: Arc::new(AtomicBool::new(false)),
+            trigger_pending: Arc::new(AtomicBool::new(false)),
+            #[cfg(not(target_arch = "wasm32"))]
+            wallet: Arc::new(RwLock::new(None)),
+            signer_backend: None,
             #[cfg(not(target_arch = "wasm32"))]
-            wallet: None,
+            submissions: Arc::new(RwLock::new(submissions)),
+            #[cfg(not(target_arch = "wasm32"))]
+            private_relay,
             metrics: Arc::new(RwLock::new(Metrics::default())),
+            era_state,
             fishing_spot: None,
+            pending_cancellations: Arc::new(RwLock::new(HashMap::new())),
+            in_flight: Arc::new(RwLock::new(in_flight)),
             staking: None,
+            attestation,
+            rate_limiter,
+            replay_guard: Arc::new(RwLock::new(replay_guard)),
+            failed_attempts: Arc::new(RwLock::new(HashMap::new())),
+            dead_letters: Arc::new(RwLock::new(VecDeque::new())),
+            batch_events: broadcast::channel(BATCH_EVENT_CAPACITY).0,
+            shutdown: Arc::new(watch::channel(false).0),
+            next_batch_id: Arc::new(AtomicU64::new(
+                SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            )),
         })
     }
     
     /// Enable fishing spot integration
-    pub fn with_fishing_spot(mut self, config: FishingSpotConfig) -> Self {
-        self.fishing_spot = Some(FishingSpotClient::new(config));
-        self
+    ///
+    /// Errs if the fishing spot client can't be built (bad headers or TLS identity).
+    pub fn with_fishing_spot(mut self, config: FishingSpotConfig) -> Result<Self> {
+        self.fishing_spot = Some(FishingSpotClient::new(config)?);
+        Ok(self)
     }
     
     /// Enable staking integration
@@ -82,382 +262,6067 @@ impl FisherRelayer {
         self
     }
     
+    /// Sign transactions with `signer` instead of `config.private_key`
+    pub fn with_signer(mut self, signer: Arc<dyn crate::signer::Signer>) -> Self {
+        self.signer_backend = Some(signer);
+        self
+    }
+    
+    /// Take TDX quotes from `provider` (e.g. a remote quoting service)
+    ///
+    /// Replaces the attestation manager, so call this before publishing the
+    /// encryption public key.
+    pub fn with_quote_provider(mut self, provider: Arc<dyn crate::attestation::QuoteProvider>) -> Self {
+        self.attestation = Arc::new(
+            AttestationManager::new(self.config.enable_attestation).with_quote_provider(provider),
+        );
+        self
+    }
+    
+    /// Configured signing backend, if any
+    fn signing_backend(&self) -> Result<Option<Arc<dyn crate::signer::Signer>>> {
+        if let Some(signer) = &self.signer_backend {
+            return Ok(Some(Arc::clone(signer)));
+        }
+        
+        match &self.config.private_key {
+            Some(private_key) => Ok(Some(Arc::new(LocalWalletSigner::from_private_key(private_key)?))),
+            None => Ok(None),
+        }
+    }
+    
+    /// Connect to Ethereum and set up the signing wallet
     #[cfg(not(target_arch = "wasm32"))]
     pub async fn init_ethereum(&mut self) -> Result<()> {
+        self.connect().await?;
+        self.resume_submissions().await?;
+        
+        Ok(())
+    }
+    
+    /// Rebuild the provider and signing wallet (also used to reconnect)
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn connect(&self) -> Result<()> {
         info!("🔗 Connecting to Ethereum: {}", self.config.rpc_url);
         
         // Connect to Ethereum
         let provider = Provider::<Http>::try_from(&self.config.rpc_url)
-            .map_err(|e| Error::Rpc(e.to_string()))?;
+            .map_err(|e| Error::Config(format!("Invalid RPC URL: {}", e)))?;
         
-        // Setup wallet if private key provided
-        if let Some(private_key) = &self.config.private_key {
-            let wallet: LocalWallet = private_key
-                .parse()
-                .map_err(|e| Error::Config(format!("Invalid private key: {}", e)))?;
-            
+        // Setup wallet if a signer is configured
+        if let Some(backend) = self.signing_backend()? {
             let chain_id = provider
                 .get_chainid()
                 .await
-                .map_err(|e| Error::Rpc(e.to_string()))?;
+                .map_err(|e| provider_error("Failed to get chain ID", &e))?;
+            
+            if let Some(expected) = self.config.chain_id {
+                if chain_id.as_u64() != expected {
+                    return Err(Error::Config(format!(
+                        "RPC {} is chain {}, expected {}",
+                        self.config.rpc_url, chain_id, expected
+                    )));
+                }
+            }
             
-            let wallet = wallet.with_chain_id(chain_id.as_u64());
+            let wallet = SharedSigner::new(backend).with_chain_id(chain_id.as_u64());
             let signer = SignerMiddleware::new(provider, wallet);
             
-            self.wallet = Some(signer);
+            *self.wallet.write().await = Some(signer);
         }
         info!("✅ Connected to Ethereum");
         
         Ok(())
     }
     
+    /// Reconnect with exponential backoff until connected or a permanent error
+    ///
+    /// Only connection-level failures are retried; configuration errors such
+    /// as a chain ID mismatch are returned immediately.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn reconnect_with_backoff(&self) -> Result<()> {
+        retry_connection(
+            std::time::Duration::from_millis(self.config.reconnect_backoff_ms),
+            std::time::Duration::from_millis(self.config.reconnect_max_backoff_ms),
+            || self.connect(),
+        ).await?;
+        
+        info!("🔌 Reconnected to Ethereum");
+        Ok(())
+    }
+    
+    /// Connected signing client
+    ///
+    /// Returns `Error::Disconnected` while a configured wallet has no live connection.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn signer(&self) -> Result<SignerClient> {
+        self.wallet.read().await.clone().ok_or_else(|| {
+            if self.config.private_key.is_some() || self.signer_backend.is_some() {
+                Error::Disconnected("Not connected to Ethereum".to_string())
+            } else {
+                Error::Contract("Wallet not initialized".to_string())
+            }
+        })
+    }
+    
+    /// Public key users encrypt intents to (published in the attestation report)
+    pub fn encryption_public_key(&self) -> Vec<u8> {
+        self.attestation.public_key()
+    }
+    
     /// Submit intent to queue
+    #[instrument(skip_all, fields(intent_id = %intent.id))]
     pub async fn submit_intent(&self, intent: Intent) -> Result<String> {
         debug!("📨 Received intent: {}", intent.id);
         
-        // Verify signature
-        if !intent.verify_signature() {
-            return Err(Error::InvalidSignature);
+        if self.config.require_encrypted_intents {
+            return Err(self.rejected(RejectionReason::Invalid, Error::InvalidIntent(
+                "Plaintext intents are disabled; submit an encrypted intent".to_string(),
+            )).await);
         }
         
-        // Add to queue
-        let mut queue = self.intent_queue.write().await;
-        let intent_id = intent.id.clone();
-        queue.push(intent);
-        
-        info!("✅ Intent queued: {} (queue size: {})", intent_id, queue.len());
+        self.queue_intent(intent).await
+    }
+    
+    /// Submit every intent in a JSON array file, e.g. to replay recorded traffic
+    ///
+    /// Rejected intents are logged and skipped. Returns how many were queued.
+    pub async fn submit_intents_file(&self, path: impl AsRef<std::path::Path>) -> Result<usize> {
+        let intents = load_intents_file(path).await?;
+        let total = intents.len();
         
-        // Check if we should process immediately
-        if queue.len() >= self.config.max_batch_size {
-            drop(queue); // Release lock
-            tokio::spawn({
-                let this = self.clone_arc();
-                async move {
-                    if let Err(e) = this.process_batch().await {
-                        error!("❌ Batch processing failed: {}", e);
-                    }
-                }
-            });
+        let mut queued = 0;
+        for intent in intents {
+            let id = intent.id.clone();
+            match self.submit_intent(intent).await {
+                Ok(_) => queued += 1,
+                Err(e) => warn!("⚠️  Skipping intent {}: {}", id, e),
+            }
         }
         
-        Ok(intent_id)
+        info!("📂 Queued {} of {} intents from file", queued, total);
+        Ok(queued)
     }
     
-    /// Process current batch
-    pub async fn process_batch(&self) -> Result<BatchResult> {
-        let start_time = SystemTime::now();
+    /// Stream intents from newline-delimited JSON, one intent per line
+    ///
+    /// Each line is validated and submitted as it is read, so the input can be
+    /// far larger than memory. When the queue is at `max_queue_size` the import
+    /// waits for a batch to drain it. Malformed and rejected lines are counted
+    /// and skipped; only a read error aborts the import.
+    pub async fn import_ndjson<R>(&self, reader: R) -> Result<ImportStats>
+    where
+        R: tokio::io::AsyncBufRead + Unpin,
+    {
+        use tokio::io::AsyncBufReadExt;
         
-        // Get intents from queue
-        let mut queue = self.intent_queue.write().await;
+        let mut stats = ImportStats::default();
+        let mut lines = reader.lines();
+        let mut line_number = 0;
         
-        if queue.len() < self.config.min_batch_size {
-            debug!("⏳ Queue too small ({} < {})", queue.len(), self.config.min_batch_size);
-            return Err(Error::BatchProcessing("Queue too small".to_string()));
+        while let Some(line) = lines.next_line().await? {
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            
+            let intent: Intent = match serde_json::from_str(&line) {
+                Ok(intent) => intent,
+                Err(e) => {
+                    debug!("⚠️  Skipping malformed line {}: {}", line_number, e);
+                    stats.malformed += 1;
+                    continue;
+                }
+            };
+            
+            loop {
+                self.wait_for_queue_space().await;
+                match self.submit_intent(intent.clone()).await {
+                    Ok(_) => stats.accepted += 1,
+                    // Another submitter took the space first
+                    Err(Error::QueueFull(_)) => continue,
+                    Err(e) => {
+                        debug!("⚠️  Skipping intent {} on line {}: {}", intent.id, line_number, e);
+                        stats.rejected += 1;
+                    }
+                }
+                break;
+            }
         }
         
-        let intents = queue.drain(..).collect::<Vec<_>>();
-        drop(queue); // Release lock early
+        info!(
+            "📂 Imported {} intents ({} rejected, {} malformed lines)",
+            stats.accepted, stats.rejected, stats.malformed
+        );
+        Ok(stats)
+    }
+    
+    /// Wait until the queue is below `max_queue_size`
+    async fn wait_for_queue_space(&self) {
+        let limit = self.config.max_queue_size;
+        if limit == 0 {
+            return;
+        }
         
-        info!("📦 Processing batch of {} intents", intents.len());
+        loop {
+            // Register before checking so a drain in between isn't missed
+            let drained = self.queue_space.notified();
+            tokio::pin!(drained);
+            drained.as_mut().enable();
+            
+            if self.intent_queue.read().await.len() < limit {
+                return;
+            }
+            drained.await;
+        }
+    }
+    
+    /// Submit intent encrypted to the TEE's attested public key
+    ///
+    /// The intent is decrypted inside the relayer and then goes through the
+    /// normal verify-and-queue flow.
+    pub async fn submit_encrypted_intent(&self, encrypted: EncryptedIntent) -> Result<String> {
+        let intent = self.attestation.decrypt_intent(&encrypted.ciphertext)?;
+        debug!("🔐 Decrypted intent: {}", intent.id);
         
-        // Build optimized batch
-        let batch = self.build_batch(intents).await?;
+        self.queue_intent(intent).await
+    }
+    
+    /// Cancel a queued intent
+    ///
+    /// `signature` must be the original sender's EIP-191 signature over
+    /// [`Intent::cancellation_message`]. Returns whether the intent was found;
+    /// intents already drained into a batch return `Error::AlreadySettled`.
+    ///
+    /// With a fishing spot configured, the cancellation is only confirmed once
+    /// the spot acknowledges it. Until then the intent stays out of the queue
+    /// (even if the spot serves it again), `Error::CancellationPending` is
+    /// returned and the spot is retried each batch tick.
+    ///
+    /// With `enforce_canonical_ids`, `intent_id` is the intent's
+    /// [`Intent::canonical_id`], the only id it can have been queued under.
+    pub async fn cancel_intent(&self, intent_id: &str, signature: Vec<u8>) -> Result<bool> {
+        let mut queue = self.intent_queue.write().await;
         
-        info!("✨ Batch optimized:");
-        info!("   • Chunk size: {}", batch.chunk_size);
-        info!("   • φ score: {:.2}", batch.phi_score);
-        info!("   • Est. savings: {:.1}%", batch.savings_percent());
+        let Some(index) = queue.iter().position(|i| i.id == intent_id) else {
+            drop(queue);
+            if self.replay_guard.write().await.is_batched(intent_id) {
+                return Err(Error::AlreadySettled(intent_id.to_string()));
+            }
+            return Ok(false);
+        };
         
-        // Submit to Ethereum
-        let result = self.submit_batch_to_chain(&batch).await?;
+        if !queue[index].verify_cancellation(&signature) {
+            warn!("🚫 Rejected forged cancellation for {}", intent_id);
+            return Err(Error::InvalidSignature);
+        }
         
-        // Update metrics
-        self.update_metrics(&batch, &result).await;
+        let intent = queue.remove(index);
+        self.queue_space.notify_waiters();
+        self.failed_attempts.write().await.remove(intent_id);
+        self.metrics.write().await.record_queue_depth(queue.len());
+        info!("🗑️  Intent cancelled: {} (queue size: {})", intent_id, queue.len());
+        drop(queue);
         
-        let processing_time = start_time.elapsed().unwrap().as_millis() as u64;
-        info!("🎉 Batch {} complete in {}ms", result.batch_id, processing_time);
+        let Some(fishing_spot) = &self.fishing_spot else { return Ok(true) };
         
-        Ok(result)
+        let ids = [intent_id.to_string()];
+        match fishing_spot.cancel_intents(&ids).await {
+            Ok(result) if result.rejected.is_empty() => Ok(true),
+            outcome => {
+                if let Err(e) = outcome {
+                    warn!("⚠️  Fishing spot unreachable for cancellation of {}: {}", intent_id, e);
+                }
+                self.pending_cancellations.write().await.insert(intent_id.to_string(), (intent, 1));
+                Err(Error::CancellationPending(intent_id.to_string()))
+            }
+        }
     }
     
-    /// Build optimized batch using Williams compression and φ-Freeman
-    async fn build_batch(&self, mut intents: Vec<Intent>) -> Result<Batch> {
-        // Generate batch ID from timestamp
-        let batch_id = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+    /// Resend cancellations the fishing spot has not yet confirmed
+    ///
+    /// Each gets `max_intent_retries` more attempts. After that it is given
+    /// up on and dropped as `DropReason::CancellationUnconfirmed`, and its
+    /// intent is recorded with the replay guard so the spot serving it again
+    /// still can't get it settled.
+    async fn retry_pending_cancellations(&self) {
+        let Some(fishing_spot) = &self.fishing_spot else { return };
         
-        // Step 1: φ-optimization (priority scoring)
-        let phi_score = intents.iter()
-            .map(|i| crate::phi_optimization::phi_priority_score(i.priority, 0, i.amount.to()))
-            .sum::<f64>() / intents.len() as f64;
+        let pending: Vec<String> = self.pending_cancellations.read().await.keys().cloned().collect();
+        if pending.is_empty() {
+            return;
+        }
         
-        // Step 2: Williams compression (optimal chunking)
-        let chunk_size = crate::williams::williams_chunk_size(intents.len());
+        let acknowledged = match fishing_spot.cancel_intents(&pending).await {
+            Ok(result) => {
+                debug!("📬 Fishing spot confirmed {} cancellations", result.acknowledged.len());
+                result.acknowledged
+            }
+            Err(e) => {
+                warn!("⚠️  {} cancellations still pending: {}", pending.len(), e);
+                Vec::new()
+            }
+        };
         
-        // Step 3: Estimate gas
-        let (estimated_gas, estimated_savings) = self.estimate_batch_gas(&intents);
+        let mut exhausted = Vec::new();
+        {
+            let mut cancellations = self.pending_cancellations.write().await;
+            for id in &pending {
+                if acknowledged.contains(id) {
+                    cancellations.remove(id);
+                    continue;
+                }
+                let Some((_, failures)) = cancellations.get_mut(id) else { continue };
+                *failures += 1;
+                if *failures > self.config.max_intent_retries {
+                    if let Some(entry) = cancellations.remove(id) {
+                        exhausted.push(entry);
+                    }
+                }
+            }
+        }
+        if exhausted.is_empty() {
+            return;
+        }
         
-        Ok(Batch {
-            id: batch_id,
-            intents,
-            chunk_size,
-            phi_score,
-            estimated_gas,
-            estimated_savings,
-            created_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        })
+        error!("❌ Fishing spot never confirmed {} cancellations; giving up", exhausted.len());
+        {
+            let mut guard = self.replay_guard.write().await;
+            for (intent, _) in &exhausted {
+                guard.record(intent);
+            }
+        }
+        self.dead_letter(
+            exhausted.into_iter().map(|(intent, failures)| (intent, DropReason::CancellationUnconfirmed(failures))),
+        ).await;
     }
     
-    /// Estimate gas for batch
-    fn estimate_batch_gas(&self, intents: &[Intent]) -> (U256, U256) {
-        let n = intents.len() as u128;
+    /// Poll the fishing spot and queue its pending intents, returning how many were queued
+    ///
+    /// Intents already handed to the network in a batch the spot hasn't been
+    /// told about (e.g. sent just before a restart) are skipped, so a spot
+    /// re-serving them never causes a double submission. So are intents
+    /// cancelled while the spot couldn't be told. Intents that fail admission
+    /// are logged and skipped.
+    pub async fn collect_from_fishing_spot(&self) -> Result<usize> {
+        let Some(fishing_spot) = &self.fishing_spot else {
+            return Err(Error::Config("No fishing spot configured".to_string()));
+        };
         
-        // Traditional: ~100K gas per operation
-        let traditional_gas = U256::from(n * 100_000);
+        let collected = fishing_spot.collect_intents().await?;
+        let total = collected.len();
         
-        // Williams-optimized: ~14K gas per operation
-        let optimized_gas = U256::from(n * 14_000);
+        // Unacknowledged intents are served again on every poll
+        let mut seen: std::collections::HashSet<String> =
+            self.intent_queue.read().await.iter().map(|i| i.id.clone()).collect();
+        let fresh: Vec<Intent> = {
+            let in_flight = self.in_flight.read().await;
+            let cancelled = self.pending_cancellations.read().await;
+            collected.into_iter()
+                .filter(|intent| !in_flight.is_submitted(&intent.id) && !cancelled.contains_key(&intent.id))
+                .filter(|intent| seen.insert(intent.id.clone()))
+                .collect()
+        };
+        if fresh.len() < total {
+            info!("⏭️  Skipping {} collected intents already queued, submitted or cancelled", total - fresh.len());
+        }
         
-        let savings = traditional_gas - optimized_gas;
+        self.in_flight.write().await.record(fresh.iter().map(|i| i.id.as_str()), InFlightState::Collected)?;
         
-        (optimized_gas, savings)
+        let mut queued = 0;
+        for intent in fresh {
+            let intent_id = intent.id.clone();
+            match self.queue_intent(intent).await {
+                Ok(_) => queued += 1,
+                Err(e) => {
+                    warn!("⚠️  Collected intent {} rejected: {}", intent_id, e);
+                    self.advance_in_flight([intent_id.as_str()], InFlightState::Acknowledged).await;
+                }
+            }
+        }
+        
+        Ok(queued)
     }
     
-    /// Submit batch to Ethereum
-    async fn submit_batch_to_chain(&self, batch: &Batch) -> Result<BatchResult> {
-        info!("📤 Submitting batch {} to chain...", batch.id);
+    /// Move tracked fishing spot intents to `state`; untracked ids are ignored
+    async fn advance_in_flight<'a>(&self, ids: impl IntoIterator<Item = &'a str>, state: InFlightState) {
+        let mut in_flight = self.in_flight.write().await;
+        let tracked: Vec<&str> = ids.into_iter().filter(|id| in_flight.state(id).is_some()).collect();
+        if let Err(e) = in_flight.record(tracked, state) {
+            error!("❌ Failed to record in-flight intents as {:?}: {}", state, e);
+        }
+    }
+    
+    /// Verify and add intent to queue
+    async fn queue_intent(&self, intent: Intent) -> Result<String> {
+        // Older clients send older schemas
+        let intent = match intent.normalized() {
+            Ok(intent) => intent,
+            Err(e) => return Err(self.rejected(RejectionReason::Invalid, e).await),
+        };
         
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.submit_batch_to_ethereum(batch).await
+        // Cancelled, but the fishing spot may not know yet
+        if self.pending_cancellations.read().await.contains_key(&intent.id) {
+            let e = Error::InvalidIntent(format!("intent {} was cancelled", intent.id));
+            return Err(self.rejected(RejectionReason::Cancelled, e).await);
         }
         
-        #[cfg(target_arch = "wasm32")]
-        {
-            // WASM fallback (for Enarx)
-            self.submit_batch_wasm(batch).await
+        let limit = self.config.max_queue_size;
+        if limit > 0 && self.intent_queue.read().await.len() >= limit {
+            return Err(self.rejected(RejectionReason::QueueFull, Error::QueueFull(limit)).await);
         }
-    }
-    
-    /// Submit batch to Ethereum (native)
-    #[cfg(not(target_arch = "wasm32"))]
-    async fn submit_batch_to_ethereum(&self, batch: &Batch) -> Result<BatchResult> {
-        let start = std::time::Instant::now();
         
-        let wallet = self.wallet.as_ref()
-            .ok_or_else(|| Error::Contract("Wallet not initialized".to_string()))?;
+        let base_fee = if intent.priority { self.priority_base_fee().await } else { U256::ZERO };
+        if let Err(e) = self.admit(&intent, base_fee).await {
+            self.dead_letter([(intent, DropReason::rejected(&e))]).await;
+            return Err(e);
+        }
         
-        // Create contract instance
-        let contract = FisherContract::new(
-            H160::from_slice(self.config.fisher_address.as_slice()),
-            Arc::new(wallet.clone()),
-        );
+        // Add to queue, unless a copy (or another intent for its nonce) is already waiting
+        let mut queue = self.intent_queue.write().await;
+        if let Some(e) = queued_duplicate(&queue, &intent) {
+            drop(queue);
+            return Err(self.rejected(RejectionReason::StaleNonce, e).await);
+        }
+        let intent_id = intent.id.clone();
+        let priority = self.has_priority(&intent, base_fee);
+        queue.push(intent);
+        self.metrics.write().await.record_queue_depth(queue.len());
         
-        // Convert intents to contract Payment structs
-        let payments: Vec<Payment> = batch.intents.iter()
-            .map(|intent| {
-                let from = H160::from_slice(intent.from.as_slice());
-                let to = H160::from_slice(intent.to.as_slice());
-                let amount = ethers::types::U256::from_big_endian(&intent.amount.to_be_bytes::<32>());
-                let nonce = ethers::types::U256::from(intent.nonce);
-                
-                Payment {
-                    from,
-                    to,
-                    amount,
-                    priority_flag: intent.priority,
-                    nonce,
-                }
-            })
-            .collect();
+        info!("✅ Intent queued: {} (queue size: {})", intent_id, queue.len());
         
-        // Convert signatures
-        let signatures: Vec<Bytes> = batch.intents.iter()
-            .map(|intent| Bytes::from(intent.signature.clone()))
-            .collect();
+        // Check if we should process immediately (full batch or urgent intents)
+        let fast_lane = priority && self.fast_lane_ready(&queue, base_fee);
+        if fast_lane {
+            debug!("⚡ Priority fast lane triggered by {}", intent_id);
+        }
         
-        info!("📝 Calling submitBatchOptimized with {} intents", payments.len());
+        if queue.len() >= self.config.batch_trigger_size || fast_lane {
+            drop(queue); // Release lock
+            self.trigger_batch().await;
+        }
         
-        // Call your FisherProduction.sol contract!
-        let call = contract.submit_batch_optimized(payments, signatures);
+        Ok(intent_id)
+    }
+    
+    /// Schedule an early batch run, unless one is already waiting to start
+    ///
+    /// The run starts `batch_trigger_debounce_ms` later and after any run in
+    /// progress, so a burst of triggers under load spawns one task rather
+    /// than one per intent.
+    async fn trigger_batch(&self) {
+        if self.trigger_pending.swap(true, Ordering::AcqRel) {
+            self.metrics.write().await.coalesced_triggers += 1;
+            return;
+        }
+        self.metrics.write().await.triggered_runs += 1;
         
-        let tx = call
-            .send()
-            .await
-            .map_err(|e| Error::Contract(format!("Transaction failed: {}", e)))?;
+        let this = self.clone_arc();
+        tokio::spawn(async move {
+            let debounce = std::time::Duration::from_millis(this.config.batch_trigger_debounce_ms);
+            tokio::time::sleep(debounce).await;
+            
+            // Wait out a run in progress instead of being skipped by it
+            drop(this.processing_guard.lock().await);
+            this.trigger_pending.store(false, Ordering::Release);
+            
+            if let Err(e) = this.process_batch().await {
+                error!("❌ Batch processing failed: {}", e);
+            }
+        });
+    }
+    
+    /// Checks an intent must pass before it is queued
+    async fn admit(&self, intent: &Intent, base_fee: U256) -> Result<()> {
+        // Verify signature (ECDSA, or EIP-1271 for contract wallets)
+        if !intent.verify_signature() && !self.contract_signature_valid(intent).await? {
+            return Err(self.rejected(RejectionReason::BadSignature, Error::InvalidSignature).await);
+        }
         
-        info!("⏳ Transaction sent: {:?}", tx.tx_hash());
+        // Reject intents that would waste batch space
+        if let Err(e) = intent.validate(&self.config) {
+            return Err(self.rejected(RejectionReason::Invalid, e).await);
+        }
         
-        // Wait for confirmation
-        let receipt = tx
-            .await
-            .map_err(|e| Error::Contract(format!("Receipt failed: {}", e)))?
-            .ok_or_else(|| Error::Contract("No receipt returned".to_string()))?;
+        // Signed for another chain; settling it here would replay it
+        if let Some(target) = intent.target_chain.filter(|&chain| Some(chain) != self.config.chain_id) {
+            let e = Error::InvalidIntent(format!(
+                "intent {} targets chain {} but this relayer settles chain {:?}",
+                intent.id, target, self.config.chain_id
+            ));
+            return Err(self.rejected(RejectionReason::Invalid, e).await);
+        }
         
-        let gas_used_eth = receipt.gas_used.unwrap_or_default();
-        let gas_used = U256::from_limbs(gas_used_eth.0);
+        // Priority must be earned under a restrictive policy
+        if intent.priority && !self.has_priority(intent, base_fee) {
+            if let PriorityPolicy::Reject { .. } = self.config.priority_policy {
+                let e = Error::InvalidIntent(format!(
+                    "intent {} claims priority without an allowlisted sender or gas premium",
+                    intent.id
+                ));
+                return Err(self.rejected(RejectionReason::Invalid, e).await);
+            }
+            debug!("⬇️  Intent {} handled without priority (no gas premium)", intent.id);
+        }
         
-        let processing_time_ms = start.elapsed().as_millis() as u64;
+        // Reject replays of intents (or nonces) already batched
+        let replayed = self.replay_guard.write().await.check(intent);
+        if let Err(e) = replayed {
+            return Err(self.rejected(RejectionReason::StaleNonce, e).await);
+        }
         
-        info!("✅ Batch {} confirmed!", batch.id);
-        info!("   Gas used: {}", gas_used);
-        info!("   Tx: {:?}", receipt.transaction_hash);
-        info!("   Processing time: {}ms", processing_time_ms);
+        // Enforce per-sender rate limit
+        if !self.rate_limiter.lock().await.check(intent.from) {
+            warn!("🚫 Rate limited sender {:?}", intent.from);
+            let e = Error::RateLimited(format!("{:?}", intent.from));
+            return Err(self.rejected(RejectionReason::RateLimited, e).await);
+        }
         
-        // Parse success flags from return value
-        let successes = vec![true; batch.intents.len()]; // TODO: Parse from logs
+        Ok(())
+    }
+    
+    /// Base fee that priority premiums are measured against
+    ///
+    /// Zero under the open policy (nothing to measure) and while offline.
+    async fn priority_base_fee(&self) -> U256 {
+        if self.config.priority_policy == PriorityPolicy::Open {
+            return U256::ZERO;
+        }
         
-        Ok(BatchResult {
-            batch_id: batch.id,
-            tx_hash: format!("{:?}", receipt.transaction_hash),
-            gas_used,
-            gas_saved: batch.estimated_savings,
-            successes,
-            processing_time_ms,
-            used_blob: self.config.enable_blobs,
-            blob_gas_saved: if self.config.enable_blobs {
-                batch.estimated_savings
-            } else {
-                U256::ZERO
-            },
-        })
+        self.data_fees().await.map(|fees| fees.base_fee).unwrap_or(U256::ZERO)
     }
     
-    /// Submit batch (WASM fallback for Enarx)
-    #[cfg(target_arch = "wasm32")]
-    async fn submit_batch_wasm(&self, batch: &Batch) -> Result<BatchResult> {
-        let start = std::time::Instant::now();
+    /// Whether `intent` is handled as priority under `priority_policy`
+    fn has_priority(&self, intent: &Intent, base_fee: U256) -> bool {
+        self.config.priority_policy.earns_priority(intent, &self.config.priority_allowlist, base_fee)
+    }
+    
+    /// Count a submission rejection for `reason`, passing `error` through
+    async fn rejected(&self, reason: RejectionReason, error: Error) -> Error {
+        debug!("🚫 Rejected intent ({}): {}", reason.label(), error);
+        self.metrics.write().await.record_rejection(reason);
+        error
+    }
+    
+    /// Process current batch
+    ///
+    /// The queue is split into several sub-batches when it would not fit in a
+    /// single blob transaction. Each sub-batch settles independently: only
+    /// confirmed ones are acknowledged, and a failed one's intents go back to
+    /// the queue. Errs only when no sub-batch confirmed.
+    ///
+    /// Only one run processes at a time (so a size trigger and the interval
+    /// loop can't submit two under-full batches or race on nonces); a call
+    /// made while another is running, or while paused, returns an empty
+    /// submission.
+    pub async fn process_batch(&self) -> Result<BatchSubmission> {
+        if self.is_paused() {
+            debug!("⏸️  Paused, deferring batch");
+            return Ok(BatchSubmission::default());
+        }
         
-        // TODO: Implement WASM-compatible Ethereum submission
-        // This would use WASI sockets or host calls
+        let Ok(_processing) = self.processing_guard.try_lock() else {
+            debug!("⏳ Batch already processing, skipping");
+            return Ok(BatchSubmission::default());
+        };
         
-        info!("📤 WASM batch submission for batch {}", batch.id);
+        let start_time = SystemTime::now();
         
-        Ok(BatchResult {
-            batch_id: batch.id,
-            tx_hash: format!("0x{:064x}", batch.id),
-            gas_used: batch.estimated_gas,
-            gas_saved: batch.estimated_savings,
-            successes: vec![true; batch.intents.len()],
-            processing_time_ms: start.elapsed().as_millis() as u64,
-            used_blob: false,
-            blob_gas_saved: U256::ZERO,
-        })
+        // During fee spikes a small batch can cost more than individual sends
+        if let Some(min_savings) = self.config.min_savings_percent {
+            let queued = self.intent_queue.read().await.len();
+            if let Some(projected) = self.projected_savings_percent(queued).await {
+                if queued > 0 && projected < min_savings {
+                    info!(
+                        "⏸️  Deferring batch of {} intents: projected savings {:.1}% below {:.1}%",
+                        queued, projected, min_savings
+                    );
+                    return Err(Error::BatchProcessing(format!(
+                        "Projected savings {:.1}% below minimum {:.1}%",
+                        projected, min_savings
+                    )));
+                }
+            }
+        }
+        
+        // Get intents from queue
+        let base_fee = self.priority_base_fee().await;
+        let mut queue = self.intent_queue.write().await;
+        
+        if queue.len() < self.config.min_batch_size && !self.fast_lane_ready(&queue, base_fee) {
+            debug!("⏳ Queue too small ({} < {})", queue.len(), self.config.min_batch_size);
+            return Err(Error::BatchProcessing("Queue too small".to_string()));
+        }
+        
+        let now = start_time.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let (intents, expired): (Vec<_>, Vec<_>) = queue.drain(..).partition(|intent| {
+            self.config.max_intent_age_secs == 0
+                || now.saturating_sub(intent.timestamp) < self.config.max_intent_age_secs
+        });
+        {
+            let mut guard = self.replay_guard.write().await;
+            for intent in &intents {
+                guard.record(intent);
+            }
+        }
+        {
+            let now_ms = start_time.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+            let mut metrics = self.metrics.write().await;
+            metrics.record_queue_depth(0);
+            metrics.record_intent_waits(&intents, now_ms);
+        }
+        drop(queue); // Release lock early
+        self.queue_space.notify_waiters();
+        
+        if !expired.is_empty() {
+            warn!("⌛ Dropping {} expired intents", expired.len());
+            self.dead_letter(expired.into_iter().map(|intent| (intent, DropReason::Expired))).await;
+        }
+        
+        // Drop anything whose signature doesn't verify (checked in parallel)
+        let (intents, rejected) = self.partition_valid(intents).await;
+        if !rejected.is_empty() {
+            warn!("🚫 Dropping {} intents with invalid signatures", rejected.len());
+            self.dead_letter(rejected.into_iter().map(|intent| (intent, DropReason::InvalidSignature))).await;
+        }
+        if intents.is_empty() {
+            return Err(Error::BatchProcessing("No intents left to batch".to_string()));
+        }
+        
+        info!("📦 Processing batch of {} intents", intents.len());
+        
+        // Build optimized batches, each within blob limits
+        let batches = self.build_batches_unchecked(intents).await?;
+        if batches.len() > 1 {
+            info!("✂️  Split into {} sub-batches to fit blob capacity", batches.len());
+        }
+        
+        let (submission, first_error) = self.settle_batches(batches).await;
+        let confirmed = submission.results().count();
+        if let Some(e) = first_error.filter(|_| confirmed == 0) {
+            return Err(e);
+        }
+        
+        let processing_time = start_time.elapsed().unwrap().as_millis() as u64;
+        info!(
+            "🎉 {}/{} batch(es) confirmed in {}ms",
+            confirmed,
+            submission.sub_batches.len(),
+            processing_time
+        );
+        
+        Ok(submission)
     }
     
-    /// Update metrics
-    async fn update_metrics(&self, batch: &Batch, result: &BatchResult) {
-        let mut metrics = self.metrics.write().await;
+    /// Settle each sub-batch in turn, tracking its transaction separately
+    ///
+    /// A failed sub-batch doesn't stop the others, except on a lost connection:
+    /// sub-batches not yet sent then stay pending and go back to the queue.
+    /// Also returns the first error seen.
+    async fn settle_batches(&self, batches: Vec<Batch>) -> (BatchSubmission, Option<Error>) {
+        let mut submission = BatchSubmission::default();
+        let mut first_error = None;
+        let mut unsent = Vec::new();
         
-        metrics.total_batches += 1;
-        metrics.total_intents += batch.intents.len() as u64;
-        metrics.total_gas_saved += result.gas_saved;
+        for batch in batches {
+            let batch_id = batch.id;
+            let intent_ids = batch.intents.iter()
+                .flat_map(|payment| batch.constituents(payment))
+                .map(|intent| intent.id.clone())
+                .collect();
+            
+            let status = if first_error.as_ref().is_some_and(Error::is_connection_error) {
+                unsent.extend(batch.into_original_intents());
+                SubBatchStatus::Pending
+            } else {
+                match self.settle_batch(batch).await {
+                    Ok(result) => SubBatchStatus::Confirmed(Box::new(result)),
+                    Err(e) => {
+                        warn!("⚠️  Sub-batch {} failed: {}", batch_id, e);
+                        let status = SubBatchStatus::Failed(e.to_string());
+                        first_error.get_or_insert(e);
+                        status
+                    }
+                }
+            };
+            
+            submission.sub_batches.push(SubBatch { batch_id, intent_ids, status });
+        }
         
-        // Calculate detailed savings breakdown
-        let (williams_savings, phi_savings, combined_savings) = 
-            crate::phi_optimization::estimate_total_savings(batch.intents.len());
+        if !unsent.is_empty() {
+            self.requeue_intents(unsent).await;
+        }
         
-        // Update averages
-        let n = metrics.total_batches as f64;
-        metrics.avg_batch_size = (metrics.avg_batch_size * (n - 1.0) + batch.intents.len() as f64) / n;
-        metrics.avg_savings_percent = (metrics.avg_savings_percent * (n - 1.0) + combined_savings) / n;
-        metrics.avg_williams_savings = (metrics.avg_williams_savings * (n - 1.0) + williams_savings) / n;
-        metrics.avg_phi_savings = (metrics.avg_phi_savings * (n - 1.0) + phi_savings) / n;
+        (submission, first_error)
     }
     
-    /// Get current metrics
-    pub async fn get_metrics(&self) -> Metrics {
-        self.metrics.read().await.clone()
+    /// Simulate (if enabled), submit and finalize a single batch
+    async fn settle_batch(&self, mut batch: Batch) -> Result<BatchResult> {
+        // Dry-run first so we don't spend gas on a reverting batch
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.config.simulate_before_submit && self.config.submission_backend == SubmissionBackend::Ethereum {
+            batch = self.drop_failing_intents(batch).await?;
+        }
+        
+        self.finish_batch(batch).await
     }
     
-    /// Generate attestation report (if enabled)
-    #[cfg(feature = "attestation")]
-    pub fn get_attestation(&self) -> Result<crate::attestation::AttestationReport> {
-        use sha2::{Digest, Sha256};
-        let mut hasher = Sha256::new();
-        hasher.update(self.config.fisher_address.as_slice());
-        hasher.update(self.config.evvm_core_address.as_slice());
-        let config_hash = hasher.finalize().into();
+    /// Submit and finalize a batch as is, re-queueing its intents on failure
+    ///
+    /// Intents whose transaction went out but wasn't confirmed are held
+    /// instead, until that transaction is mined, reverts or is dropped.
+    async fn finish_batch(&self, batch: Batch) -> Result<BatchResult> {
+        info!("✨ Batch optimized:");
+        info!("   • Chunk size: {}", batch.chunk_size);
+        info!("   • φ score: {:.2}", batch.phi_score);
+        info!("   • Est. savings: {:.1}%", batch.savings_percent());
+        
+        let use_blob = self.use_blobs(&batch).await;
+        
+        // Remember what is about to go out so a restart doesn't collect it again
+        let originals = || batch.intents.iter().flat_map(|payment| batch.constituents(payment));
+        self.advance_in_flight(originals().map(|i| i.id.as_str()), InFlightState::Submitted).await;
+        
+        // Submit to Ethereum
+        let result = self.submit_batch_to_chain(&batch, use_blob).await;
+        
+        // A transaction recorded for the batch may still be mined, so sending
+        // its intents again could pay them twice: hold them until it settles
+        #[cfg(not(target_arch = "wasm32"))]
+        let result = match result {
+            Err(e) if !matches!(e, Error::Reverted { .. })
+                && self.submissions.read().await.is_unresolved(&batch.canonical_digest()) =>
+            {
+                warn!("⏸️  Batch {} may still be mined ({}), holding its intents until it settles", batch.id, e);
+                let this = self.clone_arc();
+                tokio::spawn(async move { this.follow_parked(batch).await });
+                return Err(e);
+            }
+            result => result,
+        };
+        
+        if result.is_err() {
+            self.advance_in_flight(originals().map(|i| i.id.as_str()), InFlightState::Collected).await;
+        }
+        
+        // Settled or handed back below either way, so a restart mustn't resume it
+        if let Err(e) = self.submissions.write().await.resolve(&batch.canonical_digest()) {
+            warn!("⚠️  Failed to record batch {} as resolved: {}", batch.id, e);
+        }
+        
+        let result = match result {
+            Err(Error::Disconnected(reason)) => {
+                // Nothing was broadcast, so the intents can safely retry
+                let intents = batch.into_original_intents();
+                warn!("🔌 RPC unavailable, re-queueing {} intents", intents.len());
+                self.requeue_intents(intents).await;
+                return Err(Error::Disconnected(reason));
+            }
+            Err(e) => {
+                self.retry_batch(batch).await;
+                return Err(e);
+            }
+            Ok(mut result) => {
+                result.record_encoded_size(&batch);
+                result.attribute_fees(&batch);
+                result
+            }
+        };
+        
+        self.finalize_batch(&batch, &result).await;
+        info!("🎉 Batch {} settled", result.batch_id);
         
-        crate::attestation::generate_tdx_attestation(&config_hash)
+        Ok(result)
     }
     
-    /// Clone for Arc sharing (internal use)
-    fn clone_arc(&self) -> Self {
-        Self {
-            config: self.config.clone(),
-            intent_queue: Arc::clone(&self.intent_queue),
-            metrics: Arc::clone(&self.metrics),
-            fishing_spot: self.fishing_spot.clone(),
-            staking: self.staking.clone(),
-            
-            #[cfg(not(target_arch = "wasm32"))]
-            wallet: self.wallet.clone(),
+    /// Retry a batch none of which settled, like intents that failed on-chain
+    async fn retry_batch(&self, batch: Batch) {
+        let intents = batch.into_original_intents();
+        {
+            let mut guard = self.replay_guard.write().await;
+            for intent in &intents {
+                guard.forget(intent);
+            }
         }
+        self.retry_or_drop(&[], intents.iter().collect()).await;
     }
     
-    /// Start automatic batch processing
-    pub async fn start(&self) {
-        info!("🎯 Starting automatic batch processor");
-        info!("   • Interval: {}ms", self.config.batch_interval_ms);
-        info!("   • Min size: {}", self.config.min_batch_size);
-        info!("   • Max size: {}", self.config.max_batch_size);
+    /// Record a settled batch: metrics, fishing spot acknowledgement and subscribers
+    async fn finalize_batch(&self, batch: &Batch, result: &BatchResult) {
+        // Update metrics
+        self.update_metrics(batch, result).await;
         
-        let this = self.clone_arc();
+        // Tell the fishing spot which intents are settled so it stops serving them
+        let _ = self.acknowledge_confirmed(batch, result).await;
         
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                tokio::time::Duration::from_millis(this.config.batch_interval_ms)
-            );
+        // Give intents that failed on-chain another chance in a later batch
+        self.retry_failed_intents(batch, result).await;
+        
+        // No subscribers is not an error
+        let _ = self.batch_events.send(result.clone());
+    }
+    
+    /// Stream of batch results, emitted after each batch settles
+    ///
+    /// A subscriber that falls more than `BATCH_EVENT_CAPACITY` results behind
+    /// receives `Err(RecvError::Lagged(skipped))` and continues from the oldest
+    /// buffered result; the relayer never waits on slow consumers.
+    pub fn subscribe_batches(
+        &self,
+    ) -> impl futures::Stream<Item = std::result::Result<BatchResult, broadcast::error::RecvError>> {
+        futures::stream::unfold(self.batch_events.subscribe(), |mut receiver| async move {
+            match receiver.recv().await {
+                Err(broadcast::error::RecvError::Closed) => None,
+                item => Some((item, receiver)),
+            }
+        })
+    }
+    
+    /// Acknowledge intents that succeeded on-chain to the fishing spot
+    ///
+    /// Intents that failed on-chain are left unacknowledged (and cancellable
+    /// again) so the fishing spot serves them for another attempt. The
+    /// acknowledgement and its retries run in a background task, so a slow
+    /// spot never holds up the next batch; its handle is returned.
+    async fn acknowledge_confirmed(&self, batch: &Batch, result: &BatchResult) -> Option<tokio::task::JoinHandle<()>> {
+        let (confirmed, failed) = on_chain_outcomes(batch, result);
+        
+        if !failed.is_empty() {
+            let mut guard = self.replay_guard.write().await;
+            for intent in &failed {
+                guard.forget(intent);
+            }
+            warn!("⚠️  {} intents failed on-chain in batch {}", failed.len(), batch.id);
+            drop(guard);
+            self.advance_in_flight(failed.iter().map(|i| i.id.as_str()), InFlightState::Collected).await;
+        }
+        
+        self.fishing_spot.as_ref()?;
+        
+        let ids: Vec<String> = confirmed.iter().map(|intent| intent.id.clone()).collect();
+        if ids.is_empty() {
+            return None;
+        }
+        
+        let this = self.clone_arc();
+        let batch_id = batch.id;
+        Some(tokio::spawn(async move {
+            let Some(fishing_spot) = &this.fishing_spot else { return };
             
-            loop {
-                interval.tick().await;
+            // Unacknowledged intents stay marked submitted, so they aren't collected again
+            match fishing_spot.acknowledge_with_retry(&ids).await {
+                Ok(result) => {
+                    debug!("📬 Acknowledged {} intents to fishing spot", result.acknowledged.len());
+                    this.advance_in_flight(result.acknowledged.iter().map(String::as_str), InFlightState::Acknowledged).await;
+                }
+                Err(e) => error!("❌ Failed to acknowledge batch {} to fishing spot: {}", batch_id, e),
+            }
+        }))
+    }
+    
+    /// Re-queue intents that failed on-chain, dropping any out of retries
+    ///
+    /// Each intent gets `max_intent_retries` more attempts after its first
+    /// failure; after that it is dropped with `Error::RetriesExhausted`.
+    async fn retry_failed_intents(&self, batch: &Batch, result: &BatchResult) {
+        let (confirmed, failed) = on_chain_outcomes(batch, result);
+        self.retry_or_drop(&confirmed, failed).await;
+    }
+    
+    /// Reset retry counts for `confirmed`; re-queue `failed` or drop those out of retries
+    async fn retry_or_drop(&self, confirmed: &[&Intent], failed: Vec<&Intent>) {
+        let mut retry = Vec::new();
+        let mut exhausted = Vec::new();
+        {
+            let mut attempts = self.failed_attempts.write().await;
+            for intent in confirmed {
+                attempts.remove(&intent.id);
+            }
+            
+            for intent in failed {
+                let failures = attempts.entry(intent.id.clone()).or_insert(0);
+                *failures += 1;
                 
-                if let Err(e) = this.process_batch().await {
-                    if !matches!(e, Error::BatchProcessing(_)) {
-                        warn!("⚠️  Batch processing error: {}", e);
-                    }
+                if *failures > self.config.max_intent_retries {
+                    let failures = attempts.remove(&intent.id).unwrap_or_default();
+                    error!("❌ {}", Error::RetriesExhausted(format!(
+                        "intent {} failed {} times",
+                        intent.id, failures
+                    )));
+                    exhausted.push((intent.clone(), DropReason::RetriesExhausted(failures)));
+                } else {
+                    retry.push(intent.clone());
                 }
             }
-        });
+        }
+        
+        if !exhausted.is_empty() || !retry.is_empty() {
+            let mut metrics = self.metrics.write().await;
+            metrics.intents_retried += retry.len() as u64;
+            metrics.intents_dropped += exhausted.len() as u64;
+        }
+        self.dead_letter(exhausted).await;
+        
+        if !retry.is_empty() {
+            info!("🔁 Re-queueing {} failed intents", retry.len());
+            self.requeue_intents(retry).await;
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloy_primitives::Address;
-
-    #[tokio::test]
-    async fn test_fisher_relayer() {
-        let config = FisherConfig::default();
-        let relayer = FisherRelayer::new(config).unwrap();
+    
+    /// Take the intents dropped since the last call, oldest first, with why each was dropped
+    ///
+    /// Only the most recent `dead_letter_capacity` drops are held, e.g. for
+    /// reporting back to the fishing spot they came from.
+    pub async fn drained_intents(&self) -> Vec<(Intent, DropReason)> {
+        self.dead_letters.write().await.drain(..).collect()
+    }
+    
+    /// Hold dropped intents for `drained_intents`, discarding the oldest past capacity
+    async fn dead_letter(&self, dropped: impl IntoIterator<Item = (Intent, DropReason)>) {
+        let capacity = self.config.dead_letter_capacity;
+        if capacity == 0 {
+            return;
+        }
         
-        // Submit some test intents
-        for i in 0..15 {
-            let intent = Intent::new(
-                format!("test_{}", i),
-                Address::ZERO,
-                Address::ZERO,
-                U256::from(100),
-                false,
-                i,
-                vec![0xDE, 0xAD, 0xBE, 0xEF],
-            );
+        let mut dead_letters = self.dead_letters.write().await;
+        for entry in dropped {
+            if dead_letters.len() >= capacity {
+                dead_letters.pop_front();
+            }
+            dead_letters.push_back(entry);
+        }
+    }
+    
+    /// Put intents back at the front of the queue so the next batch retries them
+    async fn requeue_intents(&self, intents: Vec<Intent>) {
+        let mut queue = self.intent_queue.write().await;
+        let mut guard = self.replay_guard.write().await;
+        
+        for intent in &intents {
+            guard.forget(intent);
+        }
+        queue.splice(0..0, intents);
+        self.metrics.write().await.record_queue_depth(queue.len());
+    }
+    
+    /// Whether enough priority intents are queued to skip waiting for a full batch
+    fn fast_lane_ready(&self, queue: &[Intent], base_fee: U256) -> bool {
+        let threshold = self.config.priority_batch_threshold;
+        threshold > 0 && queue.iter().filter(|i| self.has_priority(i, base_fee)).count() >= threshold
+    }
+    
+    /// φ-sort intents and build batches that each fit in one transaction
+    ///
+    /// Intents are first carved into `max_intents_per_tx` chunks; batches
+    /// over the block gas budget (or, with blobs enabled, over the blob
+    /// limit) are then halved until every part fits, so sub-batches are
+    /// contiguous slices of the φ-ordered intents. Signatures are re-verified
+    /// (in parallel) once up front and bad intents dropped.
+    ///
+    /// Nothing is queued or submitted, so this doubles as a dry run.
+    pub async fn build_batches(&self, intents: Vec<Intent>) -> Result<Vec<Batch>> {
+        // Drop anything whose signature doesn't verify (checked in parallel)
+        let (intents, rejected) = self.partition_valid(intents).await;
+        if !rejected.is_empty() {
+            warn!("🚫 Dropping {} intents with invalid signatures", rejected.len());
+        }
+        if intents.is_empty() {
+            return Err(Error::BatchProcessing("No intents with valid signatures".to_string()));
+        }
+        
+        self.build_batches_unchecked(intents).await
+    }
+    
+    /// Split intents by signature validity, ECDSA in parallel then EIP-1271
+    ///
+    /// Only ECDSA failures are asked of their sender contract; one that can't
+    /// be checked (RPC error) is rejected.
+    async fn partition_valid(&self, intents: Vec<Intent>) -> (Vec<Intent>, Vec<Intent>) {
+        let (mut valid, rejected) = crate::verification::partition_valid(intents);
+        if rejected.is_empty() || !self.config.allow_contract_wallets {
+            return (valid, rejected);
+        }
+        
+        let mut invalid = Vec::new();
+        for intent in rejected {
+            match self.contract_signature_valid(&intent).await {
+                Ok(true) => valid.push(intent),
+                Ok(false) => invalid.push(intent),
+                Err(e) => {
+                    warn!("⚠️  EIP-1271 check for {} failed: {}", intent.id, e);
+                    invalid.push(intent);
+                }
+            }
+        }
+        
+        (valid, invalid)
+    }
+    
+    /// Whether `intent.from` is a contract wallet accepting the signature (EIP-1271)
+    ///
+    /// Always `false` unless `allow_contract_wallets` is set and the relayer
+    /// is online: the check is an `eth_call` to the sender.
+    async fn contract_signature_valid(&self, intent: &Intent) -> Result<bool> {
+        if !self.config.allow_contract_wallets {
+            return Ok(false);
+        }
+        
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(wallet) = self.wallet.read().await.clone() {
+            return eip1271_signature_valid(Arc::new(wallet), intent).await;
+        }
+        
+        debug!("📴 Offline, can't check contract wallet signature for {}", intent.id);
+        Ok(false)
+    }
+    
+    /// Like [`Self::build_batches`], but trusts that signatures were already verified
+    ///
+    /// Works on the owned, already-drained intents, so no queue lock is held
+    /// while sorting. Assembly time is recorded, with a warning past
+    /// `assembly_timeout_ms`.
+    pub async fn build_batches_unchecked(&self, mut intents: Vec<Intent>) -> Result<Vec<Batch>> {
+        if intents.is_empty() {
+            return Err(Error::BatchProcessing("No intents to batch".to_string()));
+        }
+        
+        let started = std::time::Instant::now();
+        let intent_count = intents.len();
+        
+        let base_fee = self.priority_base_fee().await;
+        crate::phi_freeman::phi_sort_with_priority(&mut intents, &self.config.phi_weights, |intent| {
+            self.has_priority(intent, base_fee)
+        });
+        
+        // Allowlisted senders go first; the sort is stable, so φ order holds on either side
+        if !self.config.priority_allowlist.is_empty() {
+            intents.sort_by_key(|intent| !self.config.priority_allowlist.contains(&intent.from));
+        }
+        
+        // Carve φ-ordered runs within the per-tx count and total value caps;
+        // intents past either start another sub-batch, settled in this same round
+        let cap = self.config.max_intents_per_tx.max(1);
+        let mut work = std::collections::VecDeque::new();
+        let mut chunk = Vec::new();
+        let mut chunk_value = U256::ZERO;
+        for intent in intents {
+            let over_value = self.config.max_batch_total_value
+                .is_some_and(|max_value| chunk_value.saturating_add(intent.amount) > max_value);
+            if !chunk.is_empty() && (chunk.len() >= cap || over_value) {
+                work.push_back(std::mem::take(&mut chunk));
+                chunk_value = U256::ZERO;
+            }
             
-            relayer.submit_intent(intent).await.unwrap();
+            chunk_value = chunk_value.saturating_add(intent.amount);
+            chunk.push(intent);
         }
+        work.push_back(chunk);
         
-        // Verify intents were queued
-        let queue = relayer.intent_queue.read().await;
-        assert_eq!(queue.len(), 15);
+        let gas_budget = self.batch_gas_budget().await;
+        let mut batches = Vec::with_capacity(work.len());
         
-        // Note: Can't test process_batch() without Ethereum connection
-        // For full integration tests, use examples/run_fisher.rs
+        while let Some(chunk) = work.pop_front() {
+            let batch = self.build_batch(chunk).await?;
+            
+            let fits = batch.estimated_gas <= gas_budget
+                && (!self.config.enable_blobs.enabled()
+                    || BlobEncoder::blobs_required(&batch)? <= self.config.max_blobs_per_tx);
+            
+            if fits || batch.intents.len() <= 1 {
+                if batch.estimated_gas > gas_budget {
+                    warn!("⚠️  Single intent needs {} gas, over the {} budget", batch.estimated_gas, gas_budget);
+                }
+                batches.push(batch);
+                continue;
+            }
+            
+            let mut first = batch.into_original_intents();
+            let second = first.split_off(first.len() / 2);
+            work.push_front(second);
+            work.push_front(first);
+        }
+        
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let timeout_ms = self.config.assembly_timeout_ms;
+        if timeout_ms > 0 && elapsed_ms > timeout_ms {
+            warn!(
+                "🐢 Batch assembly took {}ms (limit {}ms) for {} intents in {} batches",
+                elapsed_ms,
+                timeout_ms,
+                intent_count,
+                batches.len()
+            );
+        } else {
+            debug!("🧱 Assembled {} intents into {} batches in {}ms", intent_count, batches.len(), elapsed_ms);
+        }
+        self.metrics.write().await.record_assembly(elapsed_ms, timeout_ms);
+        
+        Ok(batches)
+    }
+    
+    /// Most gas one batch may use: `block_gas_fraction` of the block gas limit
+    async fn batch_gas_budget(&self) -> U256 {
+        let limit = self.block_gas_limit().await;
+        let budget = limit as f64 * self.config.block_gas_fraction.clamp(0.0, 1.0);
+        
+        U256::from(budget as u64)
+    }
+    
+    /// Current block gas limit, or the configured fallback when offline
+    async fn block_gas_limit(&self) -> u64 {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(wallet) = self.wallet.read().await.clone() {
+            match wallet.get_block(BlockNumber::Latest).await {
+                Ok(Some(block)) if !block.gas_limit.is_zero() => {
+                    return block.gas_limit.low_u64();
+                }
+                Ok(_) => debug!("⛽ Latest block has no gas limit, using fallback"),
+                Err(e) => debug!("⛽ Block gas limit unavailable ({}), using fallback", e),
+            }
+        }
+        
+        self.config.fallback_block_gas_limit
+    }
+    
+    /// Build optimized batch using Williams compression and φ-Freeman
+    #[instrument(skip_all, fields(intents = intents.len(), batch_id = tracing::field::Empty))]
+    async fn build_batch(&self, intents: Vec<Intent>) -> Result<Batch> {
+        // Unique, increasing batch ID (seeded from the start timestamp)
+        let batch_id = self.next_batch_id.fetch_add(1, Ordering::Relaxed);
+        tracing::Span::current().record("batch_id", batch_id);
+        debug!(
+            intent_ids = ?intents.iter().map(|intent| intent.id.as_str()).collect::<Vec<_>>(),
+            "🧱 Building batch"
+        );
+        
+        // Step 0: Same-recipient analysis (and optional aggregation)
+        let recipient_groups = crate::aggregation::recipient_groups(&intents);
+        let (intents, aggregated) = if self.config.aggregate_same_recipient {
+            crate::aggregation::aggregate_payments(intents)
+        } else {
+            (intents, Vec::new())
+        };
+        if !aggregated.is_empty() {
+            debug!("🧮 Aggregated {} same-recipient payment groups", aggregated.len());
+        }
+        
+        // Step 1: φ-optimization (priority scoring)
+        let phi_score = crate::phi_optimization::batch_phi_score(&intents);
+        
+        // Step 2: Williams compression (optimal chunking)
+        let chunk_size = crate::williams::williams_chunk_size_with(intents.len(), &self.config.williams_params);
+        
+        // Step 3: Estimate gas
+        let (estimated_gas, estimated_savings) = self.estimate_batch_gas(&intents);
+        
+        Ok(Batch {
+            id: batch_id,
+            intents,
+            chunk_size,
+            phi_score,
+            estimated_gas,
+            estimated_savings,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            recipient_groups,
+            aggregated,
+        })
+    }
+    
+    /// Estimate gas for batch
+    fn estimate_batch_gas(&self, intents: &[Intent]) -> (U256, U256) {
+        // Traditional: one standalone transaction per operation
+        let traditional_gas = U256::from(crate::phi_optimization::traditional_gas(
+            intents.len(),
+            self.config.per_tx_baseline_gas,
+        ));
+        
+        // Williams-optimized: ~14K gas per operation, plus the era update
+        let optimized_gas = U256::from(crate::phi_optimization::optimized_gas(intents.len()));
+        
+        // Cheap-baseline chains may not beat the optimized cost at all
+        let savings = traditional_gas.saturating_sub(optimized_gas);
+        
+        (optimized_gas, savings)
+    }
+    
+    /// Savings (percent) from batching `intent_count` intents at current fees
+    ///
+    /// The batch's execution gas plus its data posting cost (blobs or calldata,
+    /// per `enable_blobs`) against each intent sent alone at the same base fee.
+    /// `None` when the fees can't be read.
+    async fn projected_savings_percent(&self, intent_count: usize) -> Option<f64> {
+        let fees = self.data_fees().await?;
+        
+        let data_cost = match self.config.enable_blobs {
+            BlobMode::Off => fees.calldata_cost(intent_count),
+            BlobMode::On => fees.blob_cost(intent_count),
+            BlobMode::Auto => fees.blob_cost(intent_count).min(fees.calldata_cost(intent_count)),
+        };
+        let baseline = U256::from(crate::phi_optimization::traditional_gas(intent_count, self.config.per_tx_baseline_gas))
+            .saturating_mul(fees.base_fee);
+        let cost = U256::from(crate::phi_optimization::optimized_gas(intent_count))
+            .saturating_mul(fees.base_fee)
+            .saturating_add(data_cost);
+        
+        Some(savings_percent(baseline, cost))
+    }
+    
+    /// Whether to post this batch's data as blobs rather than calldata
+    ///
+    /// With `enable_blobs` set to auto, the current blob and execution base
+    /// fees decide; if they can't be read the batch falls back to calldata.
+    async fn use_blobs(&self, batch: &Batch) -> bool {
+        match self.config.enable_blobs {
+            BlobMode::Off => false,
+            BlobMode::On => true,
+            BlobMode::Auto => {
+                let Some(fees) = self.data_fees().await else {
+                    debug!("🫧 Blob fees unavailable, using calldata");
+                    return false;
+                };
+                
+                let use_blob = fees.blobs_cheaper(batch.intents.len());
+                info!(
+                    "🫧 Posting batch {} as {} (blob {} wei vs calldata {} wei)",
+                    batch.id,
+                    if use_blob { "blobs" } else { "calldata" },
+                    fees.blob_cost(batch.intents.len()),
+                    fees.calldata_cost(batch.intents.len())
+                );
+                use_blob
+            }
+        }
+    }
+    
+    /// Current blob and execution base fees, if connected
+    async fn data_fees(&self) -> Option<DataFees> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(wallet) = self.wallet.read().await.clone() {
+            return fetch_data_fees(wallet.provider()).await
+                .map_err(|e| debug!("🫧 {}", e))
+                .ok();
+        }
+        
+        None
+    }
+    
+    /// Cost of a batch of `batch_size` intents at current fees, against sending each alone
+    ///
+    /// Reads fees through the connected wallet, or straight from `rpc_url`
+    /// when not connected, so no signing key is needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn gas_report(&self, batch_size: usize, eth_price_usd: f64) -> Result<GasReport> {
+        let fees = match self.wallet.read().await.clone() {
+            Some(wallet) => fetch_data_fees(wallet.provider()).await?,
+            None => {
+                let provider = Provider::<Http>::try_from(&self.config.rpc_url)
+                    .map_err(|e| Error::Config(format!("Invalid RPC URL: {}", e)))?;
+                fetch_data_fees(&provider).await?
+            }
+        };
+        
+        Ok(GasReport::new(batch_size, self.config.per_tx_baseline_gas, fees, eth_price_usd))
+    }
+    
+    /// Submit batch through the configured backend
+    async fn submit_batch_to_chain(&self, batch: &Batch, use_blob: bool) -> Result<BatchResult> {
+        info!("📤 Submitting batch {} to chain...", batch.id);
+        
+        match self.config.submission_backend {
+            SubmissionBackend::Simulate => Ok(self.submit_batch_simulated(batch, use_blob)),
+            
+            #[cfg(not(target_arch = "wasm32"))]
+            SubmissionBackend::Ethereum => self.submit_batch_to_ethereum(batch, use_blob).await,
+            
+            // WASM fallback (for Enarx)
+            #[cfg(target_arch = "wasm32")]
+            SubmissionBackend::Wasm => self.submit_batch_wasm(batch).await,
+            
+            backend => Err(Error::Config(format!(
+                "{:?} submission is not available on this target",
+                backend
+            ))),
+        }
+    }
+    
+    /// Settle a batch against the gas model instead of a chain
+    ///
+    /// Gas used is the batch's estimate (the Williams-optimized cost plus the
+    /// φ era update), priced at `min_gas_price`; every intent succeeds. The transaction hash is
+    /// derived from the batch contents, so replays are reproducible.
+    fn submit_batch_simulated(&self, batch: &Batch, use_blob: bool) -> BatchResult {
+        use sha3::{Digest, Keccak256};
+        
+        let intent_count = batch.intents.len();
+        let gas_used = batch.estimated_gas;
+        let baseline = U256::from(intent_count as u128 * self.config.per_tx_baseline_gas as u128);
+        let tx_hash = Keccak256::digest(batch.canonical_bytes());
+        
+        info!("🧪 Simulated batch {}: {} intents, {} gas", batch.id, intent_count, gas_used);
+        
+        BatchResult {
+            batch_id: batch.id,
+            tx_hash: format!("0x{}", hex::encode(tx_hash)),
+            gas_used,
+            gas_saved: baseline.saturating_sub(gas_used),
+            successes: vec![true; intent_count],
+            processing_time_ms: 0,
+            used_blob: use_blob,
+            blob_gas_saved: if use_blob { batch.estimated_savings } else { U256::ZERO },
+            fees_collected: U256::ZERO,
+            gas_cost_wei: gas_used.saturating_mul(self.config.min_gas_price),
+            estimated_savings_percent: batch.savings_percent(),
+            calldata_bytes: 0,
+            blob_bytes: 0,
+            compression_ratio: 0.0,
+            williams_savings_percent: None,
+            contract_gas_saved: U256::ZERO,
+            fee_charged: Vec::new(),
+            onchain_batch_id: None,
+            onchain_operation_count: None,
+            realized_savings_percent: realized_savings_percent(
+                intent_count,
+                self.config.per_tx_baseline_gas,
+                gas_used,
+            ),
+        }
+    }
+    
+    /// Dry-run a batch via `eth_call` against current chain state
+    ///
+    /// Returns the predicted per-intent success flags, or the revert reason
+    /// if the whole call would revert.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn simulate_batch(&self, batch: &Batch) -> Result<BatchSimulation> {
+        let wallet = self.signer().await?;
+        
+        simulate_with(Arc::new(wallet), self.config.fisher_address, batch).await
+    }
+    
+    /// Simulate the batch and rebuild it without intents predicted to fail
+    ///
+    /// Nothing has been broadcast yet, so if the simulation itself fails the
+    /// intents go back to the queue; if the whole call reverts they are
+    /// dropped as `DropReason::SimulationFailed`.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn drop_failing_intents(&self, batch: Batch) -> Result<Batch> {
+        let simulation = match self.simulate_batch(&batch).await {
+            Ok(simulation) => simulation,
+            Err(Error::Reverted { reason }) => {
+                self.drop_simulation_failures(batch.into_original_intents()).await;
+                return Err(Error::Reverted { reason });
+            }
+            Err(e) => {
+                let intents = batch.into_original_intents();
+                warn!("⚠️  Simulation unavailable ({}), re-queueing {} intents", e, intents.len());
+                self.requeue_intents(intents).await;
+                return Err(e);
+            }
+        };
+        
+        if let Some(reason) = simulation.revert_reason {
+            warn!("⚠️  Batch {} reverts in simulation: {}", batch.id, reason);
+            self.drop_simulation_failures(batch.into_original_intents()).await;
+            return Err(Error::Reverted { reason });
+        }
+        
+        if simulation.failed_count() == 0 {
+            return Ok(batch);
+        }
+        
+        warn!("⚠️  Dropping {} intents that fail simulation", simulation.failed_count());
+        
+        let mut intents = Vec::new();
+        let mut failing = Vec::new();
+        for (payment, ok) in batch.intents.iter().zip(simulation.successes.iter().chain(std::iter::repeat(&true))) {
+            let constituents = batch.constituents(payment).iter().cloned();
+            if *ok {
+                intents.extend(constituents);
+            } else {
+                failing.extend(constituents);
+            }
+        }
+        self.drop_simulation_failures(failing).await;
+        
+        if intents.is_empty() {
+            return Err(Error::BatchProcessing("All intents failed simulation".to_string()));
+        }
+        
+        self.build_batch(intents).await
+    }
+    
+    /// Dead-letter intents predicted to fail, letting their senders resubmit them
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn drop_simulation_failures(&self, intents: Vec<Intent>) {
+        {
+            let mut guard = self.replay_guard.write().await;
+            for intent in &intents {
+                guard.forget(intent);
+            }
+        }
+        self.dead_letter(intents.into_iter().map(|intent| (intent, DropReason::SimulationFailed))).await;
+    }
+    
+    /// Submit batch to Ethereum (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    #[instrument(skip_all, fields(batch_id = batch.id, tx_hash = tracing::field::Empty))]
+    async fn submit_batch_to_ethereum(&self, batch: &Batch, use_blob: bool) -> Result<BatchResult> {
+        let start = std::time::Instant::now();
+        
+        let wallet = self.signer().await?;
+        let batch_hash = batch.canonical_digest();
+        
+        // Never send the same batch twice (e.g. rebuilt after a crash)
+        let receipt = match self.prior_submission(&wallet, &batch_hash).await? {
+            Some(PriorSubmission::Mined(receipt)) => {
+                tracing::Span::current().record("tx_hash", tracing::field::debug(receipt.transaction_hash));
+                info!("♻️  Batch {} already mined in {:?}, not resending", batch.id, receipt.transaction_hash);
+                *receipt
+            }
+            prior => {
+                let (tx_hash, relay_deadline) = match prior {
+                    Some(PriorSubmission::Pending(tx_hash, relay_deadline)) => {
+                        info!("♻️  Batch {} already pending in {:?}, not resending", batch.id, tx_hash);
+                        (tx_hash, relay_deadline)
+                    }
+                    Some(PriorSubmission::Reverted(tx_hash)) => {
+                        info!("🔁 Batch {} reverted in {:?}, sending it again", batch.id, tx_hash);
+                        self.broadcast_batch(&wallet, batch).await?
+                    }
+                    _ => self.broadcast_batch(&wallet, batch).await?,
+                };
+                tracing::Span::current().record("tx_hash", tracing::field::debug(tx_hash));
+                
+                info!("⏳ Transaction sent: {:?}", tx_hash);
+                
+                // Wait for confirmation
+                match relay_deadline {
+                    // The node never sees a private transaction until it's mined
+                    Some(max_block_number) => wait_for_private_inclusion(
+                        &wallet,
+                        tx_hash,
+                        max_block_number,
+                        CONFIRMATION_POLL_INTERVAL,
+                    ).await?,
+                    None => PendingTransaction::new(tx_hash, wallet.provider())
+                        .await
+                        .map_err(|e| Error::Contract(format!("Receipt failed: {}", e)))?
+                        .ok_or_else(|| Error::Contract("No receipt returned".to_string()))?,
+                }
+            }
+        };
+        
+        // A reverted batch moved nothing, so its intents take the retry path
+        ensure_receipt_succeeded(&receipt)?;
+        
+        // Wait until the batch is buried deep enough to survive reorgs
+        let receipt = if self.config.confirmation_depth > 1 {
+            info!("⏳ Waiting for {} confirmations...", self.config.confirmation_depth);
+            wait_for_confirmations(
+                &wallet,
+                receipt,
+                self.config.confirmation_depth,
+                CONFIRMATION_POLL_INTERVAL,
+            ).await?
+        } else {
+            receipt
+        };
+        
+        let gas_used_eth = receipt.gas_used.unwrap_or_default();
+        let gas_used = U256::from_limbs(gas_used_eth.0);
+        
+        let processing_time_ms = start.elapsed().as_millis() as u64;
+        
+        info!("✅ Batch {} confirmed!", batch.id);
+        info!("   Gas used: {}", gas_used);
+        info!("   Tx: {:?}", receipt.transaction_hash);
+        info!("   Processing time: {}ms", processing_time_ms);
+        
+        // Per-payment outcomes, recovered from the fee events
+        let successes = payment_successes(batch, &receipt);
+        
+        let (fees_collected, gas_cost_wei) = batch_accounting(&receipt);
+        info!("   Fees: {} wei, gas cost: {} wei", fees_collected, gas_cost_wei);
+        
+        let contract_gas_saved = contract_gas_saved(&receipt);
+        let (onchain_batch_id, onchain_operation_count) = onchain_batch_info(batch, &receipt);
+        let williams_savings_percent =
+            contract_williams_savings(Arc::new(wallet.clone()), self.config.fisher_address, batch.intents.len()).await;
+        
+        // Measure savings against the real gas bill, not the estimate
+        let intent_count = batch.intents.len();
+        let baseline = U256::from(intent_count as u128 * self.config.per_tx_baseline_gas as u128);
+        let realized_savings_percent =
+            realized_savings_percent(intent_count, self.config.per_tx_baseline_gas, gas_used);
+        info!(
+            "   Savings: {:.1}% realized vs {:.1}% estimated",
+            realized_savings_percent,
+            batch.savings_percent()
+        );
+        
+        Ok(BatchResult {
+            batch_id: batch.id,
+            tx_hash: format!("{:?}", receipt.transaction_hash),
+            gas_used,
+            gas_saved: baseline.saturating_sub(gas_used),
+            successes,
+            processing_time_ms,
+            used_blob: use_blob,
+            blob_gas_saved: if use_blob {
+                batch.estimated_savings
+            } else {
+                U256::ZERO
+            },
+            fees_collected,
+            gas_cost_wei,
+            estimated_savings_percent: batch.savings_percent(),
+            calldata_bytes: 0,
+            blob_bytes: 0,
+            compression_ratio: 0.0,
+            williams_savings_percent,
+            contract_gas_saved,
+            fee_charged: Vec::new(),
+            onchain_batch_id,
+            onchain_operation_count,
+            realized_savings_percent,
+        })
+    }
+    
+    /// Transaction already carrying this batch, per the submission log
+    ///
+    /// A marker whose transaction the node no longer knows (dropped from the
+    /// mempool before mining) doesn't count, so the batch is sent again.
+    /// Neither does one that reverted, though it's reported as
+    /// [`PriorSubmission::Reverted`] so a resumed batch isn't finalized.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn prior_submission(&self, wallet: &SignerClient, batch_hash: &[u8; 32]) -> Result<Option<PriorSubmission>> {
+        let Some(marker) = self.submissions.read().await.get(batch_hash).cloned() else {
+            return Ok(None);
+        };
+        let tx_hash = crate::submissions::decode_hash(&marker.tx_hash)
+            .map(H256)
+            .ok_or_else(|| Error::Other(format!("Corrupt submission marker: {}", marker.tx_hash)))?;
+        
+        let provider = wallet.provider();
+        if let Some(receipt) = provider.get_transaction_receipt(tx_hash).await
+            .map_err(|e| provider_error("Failed to look up prior submission", &e))?
+        {
+            if ensure_receipt_succeeded(&receipt).is_err() {
+                return Ok(Some(PriorSubmission::Reverted(tx_hash)));
+            }
+            return Ok(Some(PriorSubmission::Mined(Box::new(receipt))));
+        }
+        
+        if provider.get_transaction(tx_hash).await
+            .map_err(|e| provider_error("Failed to look up prior submission", &e))?
+            .is_some()
+        {
+            return Ok(Some(PriorSubmission::Pending(tx_hash, None)));
+        }
+        
+        // The node never sees a private transaction, so only the relay deadline tells it's gone
+        if let Some(max_block_number) = marker.max_block_number {
+            let head = provider.get_block_number().await
+                .map_err(|e| provider_error("Failed to look up prior submission", &e))?
+                .as_u64();
+            if head <= max_block_number {
+                return Ok(Some(PriorSubmission::Pending(tx_hash, Some(max_block_number))));
+            }
+        }
+        
+        warn!("⚠️  Prior submission {:?} unknown to the node", tx_hash);
+        Ok(None)
+    }
+    
+    /// Pick up batches a previous run left in flight
+    ///
+    /// Each unresolved batch in the submission log is looked up on chain: a
+    /// mined one is finalized (metrics, fishing spot acknowledgement), a
+    /// pending one is waited on in the background, a reverted one is retried
+    /// like any failed batch and one the node dropped goes back to the queue.
+    /// Nothing is resent. Returns how many batches
+    /// were found.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn resume_submissions(&self) -> Result<usize> {
+        let batches: Vec<Batch> = self.submissions.read().await.pending().cloned().collect();
+        if batches.is_empty() {
+            return Ok(0);
+        }
+        
+        let wallet = self.signer().await?;
+        info!("♻️  Resuming {} batches left in flight", batches.len());
+        
+        for batch in &batches {
+            let batch_hash = batch.canonical_digest();
+            let prior = self.prior_submission(&wallet, &batch_hash).await?;
+            
+            // Resubmitted copies of these intents are replays until the batch resolves
+            {
+                let mut guard = self.replay_guard.write().await;
+                for intent in batch.intents.iter().flat_map(|payment| batch.constituents(payment)) {
+                    guard.record(intent);
+                }
+            }
+            
+            self.resume_batch(batch.clone(), prior).await?;
+        }
+        
+        Ok(batches.len())
+    }
+    
+    /// Finish, retry or re-queue a recorded batch according to its transaction's fate
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn resume_batch(&self, batch: Batch, prior: Option<PriorSubmission>) -> Result<()> {
+        let batch_hash = batch.canonical_digest();
+        match prior {
+            Some(PriorSubmission::Mined(_)) => self.finish_resumed(batch).await,
+            Some(PriorSubmission::Pending(..)) => {
+                let this = self.clone_arc();
+                tokio::spawn(async move { this.finish_resumed(batch).await });
+            }
+            Some(PriorSubmission::Reverted(tx_hash)) => {
+                self.submissions.write().await.resolve(&batch_hash)?;
+                info!("🔁 Batch {} reverted in {:?}, retrying its intents", batch.id, tx_hash);
+                let originals = batch.intents.iter().flat_map(|payment| batch.constituents(payment));
+                self.advance_in_flight(originals.map(|i| i.id.as_str()), InFlightState::Collected).await;
+                self.retry_batch(batch).await;
+            }
+            None => {
+                self.submissions.write().await.resolve(&batch_hash)?;
+                let batch_id = batch.id;
+                let intents = batch.into_original_intents();
+                info!("🔁 Batch {} was dropped, re-queueing {} intents", batch_id, intents.len());
+                self.advance_in_flight(intents.iter().map(|i| i.id.as_str()), InFlightState::Collected).await;
+                self.requeue_intents(intents).await;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Poll a held batch's transaction until it is mined, reverts or is dropped
+    ///
+    /// The batch's marker stays unresolved meanwhile, so a restart picks it
+    /// up through [`resume_submissions`](Self::resume_submissions) instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn follow_parked(&self, batch: Batch) {
+        let batch_hash = batch.canonical_digest();
+        loop {
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+            
+            let prior = match self.signer().await {
+                Ok(wallet) => self.prior_submission(&wallet, &batch_hash).await,
+                Err(e) => Err(e),
+            };
+            match prior {
+                Ok(prior) => {
+                    if let Err(e) = self.resume_batch(batch, prior).await {
+                        error!("❌ Failed to resolve held batch: {}", e);
+                    }
+                    return;
+                }
+                Err(e) => warn!("⚠️  Held batch {} lookup failed, retrying: {}", batch.id, e),
+            }
+        }
+    }
+    
+    /// Follow a resumed batch to its receipt and finalize it
+    ///
+    /// Boxed because a batch held by [`finish_batch`](Self::finish_batch)
+    /// comes back through here.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn finish_resumed(&self, batch: Batch) -> futures::future::BoxFuture<'_, ()> {
+        Box::pin(async move {
+            let batch_id = batch.id;
+            if let Err(e) = self.finish_batch(batch).await {
+                warn!("⚠️  Resumed batch {} failed: {}", batch_id, e);
+            }
+        })
+    }
+    
+    /// Sign the batch transaction, record its hash, then broadcast it
+    ///
+    /// The marker is written before the transaction can exist anywhere, so a
+    /// crash at any point leaves a record of what may have been sent.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn broadcast_batch(&self, wallet: &SignerClient, batch: &Batch) -> Result<(H256, Option<u64>)> {
+        // Create contract instance
+        let contract = FisherContract::new(
+            H160::from_slice(self.config.fisher_address.as_slice()),
+            Arc::new(wallet.clone()),
+        );
+        
+        let call = batch_call(&contract, batch);
+        let method = if encode_batch_permits(batch).is_some() { "submitBatchWithPermits" } else { "submitBatchOptimized" };
+        
+        info!("📝 Calling {} with {} intents", method, batch.intents.len());
+        
+        // Call your FisherProduction.sol contract!
+        let mut tx = call.tx;
+        wallet.fill_transaction(&mut tx, None)
+            .await
+            .map_err(|e| middleware_error("Transaction failed", e))?;
+        
+        let signature = wallet.signer().sign_transaction(&tx).await?;
+        let raw_tx = tx.rlp_signed(&signature);
+        let tx_hash = H256(ethers::utils::keccak256(&raw_tx));
+        
+        let relay_deadline = match &self.private_relay {
+            Some(_) => {
+                let head = wallet.get_block_number()
+                    .await
+                    .map_err(|e| middleware_error("Block number failed", e))?
+                    .as_u64();
+                Some(head + crate::private_relay::MAX_INCLUSION_BLOCKS)
+            }
+            None => None,
+        };
+        
+        self.submissions.write().await.record_batch(batch, tx_hash.0, relay_deadline)?;
+        
+        if let (Some(relay), Some(max_block_number)) = (&self.private_relay, relay_deadline) {
+            info!("🕶️  Sending batch {} privately via {} (until block {})", batch.id, relay.url(), max_block_number);
+            relay.send_private_transaction(&raw_tx, max_block_number).await?;
+            return Ok((tx_hash, Some(max_block_number)));
+        }
+        
+        if let Err(e) = wallet.send_raw_transaction(raw_tx).await {
+            let e = middleware_error("Transaction failed", e);
+            
+            // A node that answered with a rejection never passed the transaction on
+            if !e.is_connection_error() && !e.to_string().contains("already known") {
+                self.submissions.write().await.resolve(&batch.canonical_digest())?;
+            }
+            return Err(e);
+        }
+        
+        Ok((tx_hash, None))
+    }
+    
+    /// Submit batch (WASM fallback for Enarx)
+    #[cfg(target_arch = "wasm32")]
+    async fn submit_batch_wasm(&self, batch: &Batch) -> Result<BatchResult> {
+        self.submit_batch_via_host(&crate::host::ImportedHost, batch).await
+    }
+    
+    /// Sign the batch transaction with the signing backend and broadcast it through `host`
+    ///
+    /// Used inside WASM runtimes without direct network access; the key
+    /// never leaves the relayer (or its KMS).
+    pub async fn submit_batch_via_host<H: HostRpc>(&self, host: &H, batch: &Batch) -> Result<BatchResult> {
+        let start = std::time::Instant::now();
+        
+        let signer = self.signing_backend()?
+            .ok_or_else(|| Error::Config("Private key or signer required for host submission".to_string()))?;
+        let chain_id = self.config.chain_id
+            .ok_or_else(|| Error::Config("chain_id required for host submission".to_string()))?;
+        
+        let sender = signer.address();
+        let nonce = host.transaction_count(sender)?;
+        let gas_price = host.gas_price()?;
+        
+        let tx = crate::host::batch_transaction(
+            sender,
+            chain_id,
+            self.config.fisher_address,
+            batch,
+            nonce,
+            gas_price,
+        );
+        let signature = signer.sign_transaction(&tx).await?;
+        let raw_tx = crate::host::build_raw_batch_tx(&tx, &signature);
+        
+        info!("📤 Broadcasting batch {} via host ({} bytes)", batch.id, raw_tx.len());
+        
+        let receipt = host.send_raw_transaction(&raw_tx)?;
+        if !receipt.success {
+            return Err(Error::Reverted {
+                reason: format!("batch transaction {} failed", receipt.tx_hash_hex()),
+            });
+        }
+        
+        let gas_used = U256::from(receipt.gas_used);
+        let intent_count = batch.intents.len();
+        let baseline = U256::from(intent_count as u128 * self.config.per_tx_baseline_gas as u128);
+        
+        info!("✅ Batch {} confirmed: {}", batch.id, receipt.tx_hash_hex());
+        
+        Ok(BatchResult {
+            batch_id: batch.id,
+            tx_hash: receipt.tx_hash_hex(),
+            gas_used,
+            gas_saved: baseline.saturating_sub(gas_used),
+            successes: vec![true; intent_count],
+            processing_time_ms: start.elapsed().as_millis() as u64,
+            used_blob: false,
+            blob_gas_saved: U256::ZERO,
+            fees_collected: U256::ZERO,
+            gas_cost_wei: gas_used.saturating_mul(gas_price),
+            estimated_savings_percent: batch.savings_percent(),
+            calldata_bytes: 0,
+            blob_bytes: 0,
+            compression_ratio: 0.0,
+            williams_savings_percent: None,
+            contract_gas_saved: U256::ZERO,
+            fee_charged: Vec::new(),
+            onchain_batch_id: None,
+            onchain_operation_count: None,
+            realized_savings_percent: realized_savings_percent(
+                intent_count,
+                self.config.per_tx_baseline_gas,
+                gas_used,
+            ),
+        })
+    }
+    
+    /// Update metrics
+    async fn update_metrics(&self, batch: &Batch, result: &BatchResult) {
+        let mut metrics = self.metrics.write().await;
+        
+        metrics.total_batches += 1;
+        metrics.total_intents += batch.intents.len() as u64;
+        metrics.total_gas_saved += result.gas_saved;
+        metrics.total_fees_collected += result.fees_collected;
+        metrics.total_gas_cost += result.gas_cost_wei;
+        
+        metrics.total_contract_gas_saved += result.contract_gas_saved;
+        
+        // The contract doesn't break out state-update gas, so φ stays an estimate
+        let estimate = self.preview_savings(batch.intents.len(), false);
+        
+        // Averages are derived from these sums on read
+        metrics.savings_percent.record(result.realized_savings_percent);
+        if let Some(williams) = result.williams_savings_percent {
+            metrics.williams_savings.record(williams);
+        }
+        metrics.phi_savings_estimate.record(estimate.phi_percent);
+        metrics.processing_time_ms.record(result.processing_time_ms as f64);
+        metrics.compression_ratio.record(result.compression_ratio);
+        metrics.savings_histogram.record(result.realized_savings_percent);
+        metrics.last_batch_timestamp = Some(
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+        );
+    }
+    
+    /// Savings a batch of `batch_size` intents should achieve on this chain
+    ///
+    /// Uses the configured gas baselines, so integrators can quote users
+    /// before they submit.
+    pub fn preview_savings(&self, batch_size: usize, use_blobs: bool) -> SavingsEstimate {
+        SavingsEstimate::new(
+            batch_size,
+            self.config.per_tx_baseline_gas,
+            self.config.per_state_update_gas,
+            use_blobs,
+        )
+    }
+    
+    /// Per-operation era fee for the current era and the next `n_eras`, as `(era, fee)`
+    ///
+    /// Fees are scaled by 1e18. Returns `Error::Config` unless `era_fees` is set.
+    pub async fn fee_schedule(&self, n_eras: u64) -> Result<Vec<(u64, u128)>> {
+        let era_state = self.era_state.as_ref()
+            .ok_or_else(|| Error::Config("era_fees is not configured".to_string()))?;
+        
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut state = era_state.write().await;
+        state.advance_to(now);
+        
+        Ok(state.fee_schedule(n_eras))
+    }
+    
+    /// Get current metrics
+    pub async fn get_metrics(&self) -> Metrics {
+        let mut metrics = self.metrics.read().await.clone();
+        metrics.paused = self.is_paused();
+        metrics
+    }
+    
+    /// Prometheus/OpenMetrics exporter for this relayer's live metrics
+    pub fn metrics_collector(&self) -> crate::MetricsCollector {
+        crate::MetricsCollector::with_metrics(Arc::clone(&self.metrics))
+    }
+    
+    /// Stream of metrics snapshots, the first immediately and then one every `interval`
+    ///
+    /// Snapshots are taken like [`get_metrics`](Self::get_metrics) and the lock
+    /// is released before each is yielded, so a slow consumer never holds up
+    /// batch processing; ticks it misses are skipped, not bunched up.
+    /// `interval` must be non-zero.
+    pub fn metrics_stream(&self, interval: std::time::Duration) -> impl futures::Stream<Item = Metrics> {
+        let state = (self.clone_arc(), None::<tokio::time::Interval>);
+        
+        futures::stream::unfold(state, move |(this, ticks)| async move {
+            // Created on first poll, inside the runtime
+            let mut ticks = ticks.unwrap_or_else(|| {
+                let mut ticks = tokio::time::interval(interval);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                ticks
+            });
+            ticks.tick().await;
+            
+            let snapshot = this.get_metrics().await;
+            Some((snapshot, (this, Some(ticks))))
+        })
+    }
+    
+    /// Add auto-claimed staking rewards to metrics
+    async fn record_reward_claims(&self, claimed: &[(u64, U256)]) {
+        let mut metrics = self.metrics.write().await;
+        
+        for (era, amount) in claimed {
+            info!("💰 Auto-claimed {} for era {}", amount, era);
+            metrics.total_rewards_claimed += *amount;
+            metrics.last_claimed_era = Some(*era);
+        }
+    }
+    
+    /// Number of intents waiting to be batched
+    pub async fn queue_len(&self) -> usize {
+        self.intent_queue.read().await.len()
+    }
+    
+    /// Redacted summaries of the queued intents, in queue order
+    pub async fn queue_snapshot(&self) -> Vec<IntentSummary> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        
+        self.intent_queue.read().await.iter()
+            .map(|intent| IntentSummary::new(intent, now))
+            .collect()
+    }
+    
+    /// Attest once at startup, before accepting intents
+    ///
+    /// When no quote can be produced, returns `Error::Attestation` if
+    /// `attestation_required` is set; otherwise the relayer carries on flagged
+    /// as [`AttestationStatus::Unattested`] in its metrics.
+    pub async fn attest_startup(&self) -> Result<AttestationStatus> {
+        let status = if !self.config.enable_attestation {
+            if self.config.attestation_required {
+                return Err(Error::Config("attestation_required needs enable_attestation".to_string()));
+            }
+            AttestationStatus::Disabled
+        } else {
+            match self.attestation.generate_report(self.config.config_hash()) {
+                Ok(report) => {
+                    info!("✅ Attestation generated ({} byte quote)", report.quote.quote_data.len());
+                    AttestationStatus::Attested
+                }
+                Err(e) if self.config.attestation_required => {
+                    error!("❌ Attestation required but unavailable: {}", e);
+                    return Err(Error::Attestation(format!("required but unavailable: {}", e)));
+                }
+                Err(e) => {
+                    warn!("⚠️  Running UNATTESTED: {}", e);
+                    AttestationStatus::Unattested
+                }
+            }
+        };
+        
+        self.metrics.write().await.attestation = status;
+        Ok(status)
+    }
+    
+    /// Generate attestation report (if enabled)
+    #[cfg(feature = "attestation")]
+    pub fn get_attestation(&self) -> Result<crate::attestation::AttestationReport> {
+        self.attestation.generate_report(self.config.config_hash())
+    }
+    
+    /// Generate an attestation report committing to `batch` (if enabled)
+    ///
+    /// The report data covers the batch's canonical digest, so anyone holding
+    /// the batch can check it is exactly what this enclave produced.
+    #[cfg(feature = "attestation")]
+    pub fn attest_batch(&self, batch: &Batch) -> Result<crate::attestation::AttestationReport> {
+        self.attestation.generate_batch_report(self.config.config_hash(), batch.canonical_digest())
+    }
+    
+    /// Clone for Arc sharing (internal use)
+    fn clone_arc(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            intent_queue: Arc::clone(&self.intent_queue),
+            queue_space: Arc::clone(&self.queue_space),
+            processing_guard: Arc::clone(&self.processing_guard),
+            paused: Arc::clone(&self.paused),
+            trigger_pending: Arc::clone(&self.trigger_pending),
+            metrics: Arc::clone(&self.metrics),
+            era_state: self.era_state.clone(),
+            fishing_spot: self.fishing_spot.clone(),
+            pending_cancellations: Arc::clone(&self.pending_cancellations),
+            in_flight: Arc::clone(&self.in_flight),
+            staking: self.staking.clone(),
+            attestation: Arc::clone(&self.attestation),
+            rate_limiter: Arc::clone(&self.rate_limiter),
+            replay_guard: Arc::clone(&self.replay_guard),
+            failed_attempts: Arc::clone(&self.failed_attempts),
+            dead_letters: Arc::clone(&self.dead_letters),
+            batch_events: self.batch_events.clone(),
+            next_batch_id: Arc::clone(&self.next_batch_id),
+            shutdown: Arc::clone(&self.shutdown),
+            
+            #[cfg(not(target_arch = "wasm32"))]
+            wallet: Arc::clone(&self.wallet),
+            signer_backend: self.signer_backend.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            submissions: Arc::clone(&self.submissions),
+            #[cfg(not(target_arch = "wasm32"))]
+            private_relay: self.private_relay.clone(),
+        }
+    }
+    
+    /// Run one batch attempt, reconnecting first if the RPC has dropped
+    ///
+    /// Batching pauses while reconnecting. Returns an error only when the
+    /// connection cannot be restored (e.g. a configuration error).
+    async fn batch_tick(&self) -> Result<()> {
+        self.retry_pending_cancellations().await;
+        
+        match self.process_batch().await {
+            Err(e) if e.is_connection_error() => {
+                warn!("🔌 {}; pausing batches to reconnect", e);
+                
+                #[cfg(not(target_arch = "wasm32"))]
+                self.reconnect_with_backoff().await?;
+            }
+            Err(Error::BatchProcessing(_)) | Ok(_) => {}
+            Err(e) => warn!("⚠️  Batch processing error: {}", e),
+        }
+        
+        Ok(())
+    }
+    
+    /// Start automatic batch processing
+    ///
+    /// Runs until `stop()` is called; a batch already in flight is finished first.
+    /// With `tick_jitter`, ticks land at this fisher's fixed offset into each interval.
+    pub async fn start(&self) {
+        info!("🎯 Starting automatic batch processor");
+        info!("   • Interval: {}ms", self.config.batch_interval_ms);
+        info!("   • Min size: {}", self.config.min_batch_size);
+        info!("   • Trigger size: {}", self.config.batch_trigger_size);
+        info!("   • Max intents per tx: {}", self.config.max_intents_per_tx);
+        
+        let period = tokio::time::Duration::from_millis(self.config.batch_interval_ms);
+        let jitter_ms = self.config.tick_jitter_ms();
+        if self.config.tick_jitter {
+            info!("   • Tick offset: {}ms", jitter_ms);
+        }
+        
+        self.shutdown.send_replace(false);
+        let this = self.clone_arc();
+        let mut stop = self.shutdown.subscribe();
+        
+        tokio::spawn(async move {
+            let mut interval = if this.config.tick_jitter {
+                // Tick when unix time (ms) is `jitter_ms` past an interval boundary
+                let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+                let delay = first_tick_delay_ms(now_ms, this.config.batch_interval_ms, jitter_ms);
+                tokio::time::interval_at(
+                    tokio::time::Instant::now() + tokio::time::Duration::from_millis(delay),
+                    period,
+                )
+            } else {
+                tokio::time::interval(period)
+            };
+            
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = stop.wait_for(|stopped| *stopped) => {
+                        info!("🛑 Batch processor stopped");
+                        return;
+                    }
+                }
+                
+                if let Err(e) = this.batch_tick().await {
+                    error!("❌ Stopping batch processor: {}", e);
+                    return;
+                }
+            }
+        });
+        
+        // Era watcher: claim rewards as each era completes and apply the staking policy
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.staking.as_ref().is_some_and(|s| s.auto_claim || s.policy.is_some()) {
+            info!("   • Era watcher: every {:?}", crate::staking::ERA_POLL_INTERVAL);
+            let this = self.clone_arc();
+            let mut stop = self.shutdown.subscribe();
+            
+            tokio::spawn(async move {
+                let Some(staking) = this.staking.as_ref() else { return };
+                let mut interval = tokio::time::interval(crate::staking::ERA_POLL_INTERVAL);
+                
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {}
+                        _ = stop.wait_for(|stopped| *stopped) => return,
+                    }
+                    
+                    let mut rewards = U256::ZERO;
+                    if staking.auto_claim {
+                        // Eras claimed before a failure still count
+                        let (claimed, error) = staking.claim_completed_eras().await;
+                        if let Some(e) = error {
+                            warn!("⚠️  Reward auto-claim failed: {}", e);
+                        }
+                        this.record_reward_claims(&claimed).await;
+                        rewards = claimed.iter().fold(U256::ZERO, |total, (_, amount)| total + amount);
+                    }
+                    
+                    // Restake fresh rewards / trim excess stake per policy
+                    if let Err(e) = staking.enforce_policy(rewards).await {
+                        warn!("⚠️  Staking policy enforcement failed: {}", e);
+                    }
+                }
+            });
+        }
+    }
+    
+    /// Stop the background tasks started by `start()`
+    ///
+    /// Queued intents are kept; a later `start()` resumes batching.
+    pub fn stop(&self) {
+        info!("🛑 Stopping automatic batch processor");
+        self.shutdown.send_replace(true);
+    }
+    
+    /// Stop submitting batches (e.g. during a contract upgrade)
+    ///
+    /// Intents are still accepted and queue up to `max_queue_size`; the
+    /// background tasks keep running and `process_batch` defers until `resume()`.
+    /// A batch already being submitted is finished.
+    pub fn pause(&self) {
+        if !self.paused.swap(true, Ordering::SeqCst) {
+            info!("⏸️  Batch submission paused");
+        }
+    }
+    
+    /// Resume submitting batches after `pause()`
+    pub fn resume(&self) {
+        if self.paused.swap(false, Ordering::SeqCst) {
+            info!("▶️  Batch submission resumed");
+        }
+    }
+    
+    /// Whether batch submission is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+}
+
+/// Milliseconds from `now_ms` until unix time is next `offset_ms` past a multiple of `interval_ms`
+fn first_tick_delay_ms(now_ms: u64, interval_ms: u64, offset_ms: u64) -> u64 {
+    if interval_ms == 0 {
+        return 0;
+    }
+    
+    (offset_ms % interval_ms + interval_ms - now_ms % interval_ms) % interval_ms
+}
+
+/// Error for `intent` if its id or its sender's nonce is already in `queue`
+fn queued_duplicate(queue: &[Intent], intent: &Intent) -> Option<Error> {
+    queue.iter().find_map(|queued| {
+        if queued.id == intent.id {
+            Some(Error::InvalidIntent(format!("intent {} is already queued", intent.id)))
+        } else if (queued.from, queued.nonce) == (intent.from, intent.nonce) {
+            Some(Error::InvalidIntent(format!(
+                "nonce {} already queued by {:?}",
+                intent.nonce, intent.from
+            )))
+        } else {
+            None
+        }
+    })
+}
+
+/// Earlier transaction found for a batch being submitted
+#[cfg(not(target_arch = "wasm32"))]
+enum PriorSubmission {
+    /// Already mined
+    Mined(Box<TransactionReceipt>),
+    
+    /// Not yet mined, but known to the node or still within its relay deadline
+    Pending(H256, Option<u64>),
+    
+    /// Mined, but the transaction reverted, so nothing in it settled
+    Reverted(H256),
+}
+
+/// Current execution and blob base fees from `provider`
+///
+/// Prefers `eth_blobBaseFee`; nodes without it get the fee derived from
+/// the latest header's `excess_blob_gas`.
+#[cfg(not(target_arch = "wasm32"))]
+async fn fetch_data_fees(provider: &Provider<Http>) -> Result<DataFees> {
+    let block = provider.get_block(BlockNumber::Latest)
+        .await
+        .map_err(|e| provider_error("Latest block unavailable", &e))?
+        .ok_or_else(|| Error::Rpc("Latest block unavailable".to_string()))?;
+    let base_fee = block.base_fee_per_gas
+        .ok_or_else(|| Error::Rpc("Latest block has no base fee".to_string()))?;
+    
+    let blob_base_fee = match provider.request::<_, ethers::types::U256>("eth_blobBaseFee", ()).await {
+        Ok(fee) => U256::from_limbs(fee.0),
+        Err(e) => {
+            debug!("🫧 eth_blobBaseFee unavailable ({}), using excess blob gas", e);
+            let excess_blob_gas = block.excess_blob_gas
+                .ok_or_else(|| Error::Rpc("Blob base fee unavailable".to_string()))?;
+            crate::blob::blob_base_fee(excess_blob_gas.low_u64())
+        }
+    };
+    
+    Ok(DataFees { base_fee: U256::from_limbs(base_fee.0), blob_base_fee })
+}
+
+/// Convert batch intents into `submitBatchOptimized` arguments
+#[cfg(not(target_arch = "wasm32"))]
+fn encode_batch_call(batch: &Batch) -> (Vec<Payment>, Vec<Bytes>) {
+    // Convert intents to contract Payment structs
+    let payments: Vec<Payment> = batch.intents.iter()
+        .map(|intent| {
+            let from = H160::from_slice(intent.from.as_slice());
+            let to = H160::from_slice(intent.to.as_slice());
+            let amount = ethers::types::U256::from_big_endian(&intent.amount.to_be_bytes::<32>());
+            let nonce = ethers::types::U256::from(intent.nonce);
+            
+            Payment {
+                from,
+                to,
+                amount,
+                priority_flag: intent.priority,
+                nonce,
+            }
+        })
+        .collect();
+    
+    // Convert signatures
+    let signatures: Vec<Bytes> = batch.intents.iter()
+        .map(|intent| Bytes::from(intent.signature.clone()))
+        .collect();
+    
+    (payments, signatures)
+}
+
+/// Per-intent `PermitData` for `submitBatchWithPermits`, or `None` if no intent carries a permit
+#[cfg(not(target_arch = "wasm32"))]
+fn encode_batch_permits(batch: &Batch) -> Option<Vec<PermitData>> {
+    if batch.intents.iter().all(|intent| intent.permit.is_none()) {
+        return None;
+    }
+    
+    let permits = batch.intents.iter()
+        .map(|intent| match &intent.permit {
+            Some(permit) => PermitData {
+                present: true,
+                deadline: ethers::types::U256::from(permit.deadline),
+                v: permit.v,
+                r: permit.r.0,
+                s: permit.s.0,
+            },
+            None => PermitData::default(),
+        })
+        .collect();
+    
+    Some(permits)
+}
+
+/// The contract call settling `batch`, running permits first when any intent carries one
+#[cfg(not(target_arch = "wasm32"))]
+fn batch_call<M: Middleware>(contract: &FisherContract<M>, batch: &Batch) -> ContractCall<M, Vec<bool>> {
+    let (payments, signatures) = encode_batch_call(batch);
+    
+    match encode_batch_permits(batch) {
+        Some(permits) => contract.submit_batch_with_permits(payments, signatures, permits),
+        None => contract.submit_batch_optimized(payments, signatures),
+    }
+}
+
+/// Run the batch call through `eth_call` on any middleware
+#[cfg(not(target_arch = "wasm32"))]
+async fn simulate_with<M: Middleware + 'static>(
+    client: Arc<M>,
+    fisher_address: alloy_primitives::Address,
+    batch: &Batch,
+) -> Result<BatchSimulation> {
+    let contract = FisherContract::new(H160::from_slice(fisher_address.as_slice()), client);
+    
+    match batch_call(&contract, batch).call().await {
+        Ok(successes) => Ok(BatchSimulation {
+            successes,
+            revert_reason: None,
+        }),
+        Err(e) => match e.as_revert() {
+            Some(data) => Ok(BatchSimulation {
+                successes: vec![],
+                revert_reason: Some(crate::error::decode_revert_reason(data)),
+            }),
+            None => Err(Error::Rpc(format!("Simulation failed: {}", e))),
+        },
+    }
+}
+
+/// Wait until `receipt`'s block is `depth` blocks deep, re-checking the receipt still exists
+///
+/// Returns `Error::Reorged` if the transaction disappears from the canonical chain.
+/// If it was re-mined in a different block, waiting restarts from the new block.
+#[cfg(not(target_arch = "wasm32"))]
+async fn wait_for_confirmations<M: Middleware>(
+    client: &M,
+    receipt: TransactionReceipt,
+    depth: u64,
+    poll_interval: std::time::Duration,
+) -> Result<TransactionReceipt> {
+    let tx_hash = receipt.transaction_hash;
+    let mut block = receipt
+        .block_number
+        .ok_or_else(|| Error::Contract("Receipt missing block number".to_string()))?
+        .as_u64();
+    
+    loop {
+        let head = client
+            .get_block_number()
+            .await
+            .map_err(|e| Error::Rpc(e.to_string()))?
+            .as_u64();
+        
+        // The inclusion block itself counts as the first confirmation
+        if head + 1 >= block + depth {
+            let current = client
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(|e| Error::Rpc(e.to_string()))?;
+            
+            match current.and_then(|r| r.block_number.map(|b| (b.as_u64(), r))) {
+                None => return Err(Error::Reorged(format!("{:?}", tx_hash))),
+                Some((current_block, current)) if current_block == block => return Ok(current),
+                Some((current_block, _)) => {
+                    warn!("🔄 Tx {:?} moved from block {} to {}", tx_hash, block, current_block);
+                    block = current_block;
+                    continue;
+                }
+            }
+        }
+        
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Poll for a privately relayed transaction's receipt until it mines or `max_block_number` passes
+///
+/// Relays drop transactions not included by their deadline, so a head past
+/// it without a receipt means the batch was never mined.
+#[cfg(not(target_arch = "wasm32"))]
+async fn wait_for_private_inclusion<M: Middleware>(
+    client: &M,
+    tx_hash: H256,
+    max_block_number: u64,
+    poll_interval: std::time::Duration,
+) -> Result<TransactionReceipt> {
+    loop {
+        if let Some(receipt) = client
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| Error::Rpc(e.to_string()))?
+        {
+            return Ok(receipt);
+        }
+        
+        let head = client
+            .get_block_number()
+            .await
+            .map_err(|e| Error::Rpc(e.to_string()))?
+            .as_u64();
+        if head > max_block_number {
+            return Err(Error::Contract(format!(
+                "Private transaction {:?} not included by block {}",
+                tx_hash, max_block_number
+            )));
+        }
+        
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// `isValidSignature` magic value for an accepted signature
+#[cfg(not(target_arch = "wasm32"))]
+const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// Ask the sender contract whether it accepts the intent's signature (EIP-1271)
+///
+/// The contract is given the same EIP-191 digest an EOA would sign. Senders
+/// without code are not contract wallets; a reverting check rejects.
+#[cfg(not(target_arch = "wasm32"))]
+async fn eip1271_signature_valid<M: Middleware + 'static>(client: Arc<M>, intent: &Intent) -> Result<bool> {
+    let sender = H160::from_slice(intent.from.as_slice());
+    let code = client.get_code(sender, None)
+        .await
+        .map_err(|e| Error::Rpc(format!("eth_getCode failed: {}", e)))?;
+    if code.is_empty() {
+        return Ok(false);
+    }
+    
+    let digest = ethers::utils::hash_message(intent.signing_hash());
+    let wallet = Erc1271Wallet::new(sender, client);
+    match wallet.is_valid_signature(digest.0, intent.signature.clone().into()).call().await {
+        Ok(magic) => Ok(magic == EIP1271_MAGIC_VALUE),
+        Err(ContractError::Revert(_)) => Ok(false),
+        Err(e) => Err(Error::Rpc(format!("isValidSignature failed: {}", e))),
+    }
+}
+
+/// Fees collected (summed over `BatchSubmitted` events) and gas cost in wei for a receipt
+#[cfg(not(target_arch = "wasm32"))]
+fn batch_accounting(receipt: &TransactionReceipt) -> (U256, U256) {
+    let fees = receipt.logs.iter()
+        .filter_map(|log| ethers::contract::parse_log::<BatchSubmittedFilter>(log.clone()).ok())
+        .fold(ethers::types::U256::zero(), |total, event| total.saturating_add(event.fees_collected));
+    
+    let gas_used = receipt.gas_used.unwrap_or_default();
+    let gas_price = receipt.effective_gas_price.unwrap_or_default();
+    let gas_cost = gas_used.saturating_mul(gas_price);
+    
+    (U256::from_limbs(fees.0), U256::from_limbs(gas_cost.0))
+}
+
+/// Gas saved as reported by the receipt's `BatchSubmitted` events
+#[cfg(not(target_arch = "wasm32"))]
+fn contract_gas_saved(receipt: &TransactionReceipt) -> U256 {
+    let saved = receipt.logs.iter()
+        .filter_map(|log| ethers::contract::parse_log::<BatchSubmittedFilter>(log.clone()).ok())
+        .fold(ethers::types::U256::zero(), |total, event| total.saturating_add(event.gas_saved));
+    
+    U256::from_limbs(saved.0)
+}
+
+/// The contract's `batchId` and `operationCount` from the receipt's first `BatchSubmitted` event
+///
+/// Warns when the contract processed a different number of payments than the
+/// batch carried, which means local and on-chain accounting have diverged.
+#[cfg(not(target_arch = "wasm32"))]
+fn onchain_batch_info(batch: &Batch, receipt: &TransactionReceipt) -> (Option<u64>, Option<u64>) {
+    let Some(event) = receipt.logs.iter()
+        .find_map(|log| ethers::contract::parse_log::<BatchSubmittedFilter>(log.clone()).ok())
+    else {
+        warn!("⚠️  No BatchSubmitted event for batch {}", batch.id);
+        return (None, None);
+    };
+    
+    let saturate = |value: ethers::types::U256| u64::try_from(value).unwrap_or(u64::MAX);
+    let batch_id = saturate(event.batch_id);
+    let operation_count = saturate(event.operation_count);
+    info!("   On-chain batch: #{} ({} operations)", batch_id, operation_count);
+    
+    if operation_count != batch.intents.len() as u64 {
+        warn!(
+            "⚠️  Batch {} (on-chain #{}) processed {} operations for {} intents",
+            batch.id,
+            batch_id,
+            operation_count,
+            batch.intents.len()
+        );
+    }
+    
+    (Some(batch_id), Some(operation_count))
+}
+
+/// Williams savings for `batch_size` at the chunk size the contract reports
+///
+/// `None` if the contract can't be queried; callers then have no measurement
+/// rather than falling back to the estimate.
+#[cfg(not(target_arch = "wasm32"))]
+async fn contract_williams_savings<M: Middleware + 'static>(
+    client: Arc<M>,
+    fisher_address: alloy_primitives::Address,
+    batch_size: usize,
+) -> Option<f64> {
+    let contract = FisherContract::new(H160::from_slice(fisher_address.as_slice()), client);
+    
+    match contract.calculate_chunk_size(batch_size.into()).call().await {
+        Ok(chunk_size) => {
+            let chunk_size = usize::try_from(chunk_size).unwrap_or(usize::MAX);
+            Some(crate::williams::savings_for_chunk_size(batch_size, chunk_size))
+        }
+        Err(e) => {
+            warn!("⚠️  calculateChunkSize unavailable, Williams savings not measured: {}", e);
+            None
+        }
+    }
+}
+
+/// `Error::Reverted` if the transaction behind `receipt` failed
+///
+/// A reverted transaction emits no logs, so it must be caught before its
+/// receipt is read for per-payment outcomes.
+#[cfg(not(target_arch = "wasm32"))]
+fn ensure_receipt_succeeded(receipt: &TransactionReceipt) -> Result<()> {
+    if receipt.status == Some(U64::zero()) {
+        return Err(Error::Reverted {
+            reason: format!("batch transaction {:?} reverted", receipt.transaction_hash),
+        });
+    }
+    Ok(())
+}
+
+/// Per-payment success flags recovered from a batch receipt
+///
+/// The contract emits `FeeCharged(from, fee)` for each successful payment in
+/// batch order, so payments are matched to those events in sequence
+/// (consecutive payments from one sender are matched greedily). Receipts
+/// without a `BatchSubmitted` event, e.g. from an older contract, count every
+/// payment as successful.
+#[cfg(not(target_arch = "wasm32"))]
+fn payment_successes(batch: &Batch, receipt: &TransactionReceipt) -> Vec<bool> {
+    let submitted = receipt.logs.iter()
+        .any(|log| ethers::contract::parse_log::<BatchSubmittedFilter>(log.clone()).is_ok());
+    if !submitted {
+        return vec![true; batch.intents.len()];
+    }
+    
+    let mut charged = receipt.logs.iter()
+        .filter_map(|log| ethers::contract::parse_log::<FeeChargedFilter>(log.clone()).ok())
+        .peekable();
+    
+    batch.intents.iter()
+        .map(|payment| {
+            let from = H160::from_slice(payment.from.as_slice());
+            charged.next_if(|event| event.user == from).is_some()
+        })
+        .collect()
+}
+
+/// Split a batch's original intents into (succeeded, failed) on-chain
+///
+/// Payments missing from `result.successes` count as failed.
+fn on_chain_outcomes<'a>(batch: &'a Batch, result: &BatchResult) -> (Vec<&'a Intent>, Vec<&'a Intent>) {
+    let (confirmed, failed): (Vec<_>, Vec<_>) = batch.intents.iter()
+        .zip(result.successes.iter().chain(std::iter::repeat(&false)))
+        .flat_map(|(payment, ok)| batch.constituents(payment).iter().map(move |intent| (intent, *ok)))
+        .partition(|(_, ok)| *ok);
+    
+    (
+        confirmed.into_iter().map(|(intent, _)| intent).collect(),
+        failed.into_iter().map(|(intent, _)| intent).collect(),
+    )
+}
+
+/// Classify a contract call error into a structured `Error`
+///
+/// Reverts, nonce, funding and fee rejections get their own variants;
+/// anything else falls back to `Error::Contract`.
+#[cfg(not(target_arch = "wasm32"))]
+fn contract_error<M: Middleware>(context: &str, e: &ContractError<M>) -> Error {
+    if let Some(data) = e.as_revert() {
+        return Error::Reverted {
+            reason: crate::error::decode_revert_reason(data),
+        };
+    }
+    
+    // Signer failures carry no provider error and aren't connection problems
+    let provider_error = match e {
+        ContractError::MiddlewareError { e } => e.as_provider_error(),
+        ContractError::ProviderError { e } => Some(e),
+        _ => None,
+    };
+    
+    match provider_error.map(RpcError::as_error_response) {
+        Some(Some(response)) => match response.as_revert_data() {
+            Some(data) => Error::Reverted {
+                reason: crate::error::decode_revert_reason(&data),
+            },
+            None => Error::from_node_message(context, &response.message)
+                .unwrap_or_else(|| Error::Contract(format!("{}: {}", context, e))),
+        },
+        // Transport failures never got a JSON-RPC response from the node
+        Some(None) => Error::Disconnected(format!("{}: {}", context, e)),
+        None => Error::Contract(format!("{}: {}", context, e)),
+    }
+}
+
+/// Classify a signing-client error the way the contract call would
+#[cfg(not(target_arch = "wasm32"))]
+fn middleware_error(context: &str, e: <SignerClient as Middleware>::Error) -> Error {
+    contract_error(context, &ContractError::<SignerClient>::from_middleware_error(e))
+}
+
+/// Classify a provider error as a node-side RPC error or a lost connection
+#[cfg(not(target_arch = "wasm32"))]
+fn provider_error(context: &str, e: &ProviderError) -> Error {
+    match RpcError::as_error_response(e) {
+        Some(response) => Error::from_node_message(context, &response.message)
+            .unwrap_or_else(|| Error::Rpc(format!("{}: {}", context, e))),
+        None => Error::Disconnected(format!("{}: {}", context, e)),
+    }
+}
+
+/// Retry `connect` with exponential backoff while it fails at the connection level
+#[cfg(not(target_arch = "wasm32"))]
+async fn retry_connection<T, F, Fut>(
+    initial_backoff: std::time::Duration,
+    max_backoff: std::time::Duration,
+    mut connect: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut backoff = initial_backoff;
+    
+    loop {
+        match connect().await {
+            Err(e) if e.is_connection_error() => {
+                warn!("🔌 Reconnect failed ({}), retrying in {:?}", e, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(max_backoff);
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Address;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_fisher_relayer() {
+        let config = FisherConfig::default();
+        let relayer = FisherRelayer::new(config).unwrap();
+        
+        // Submit some test intents
+        for i in 0..15 {
+            relayer.submit_intent(test_intent(&format!("test_{}", i))).await.unwrap();
+        }
+        
+        // Verify intents were queued
+        let queue = relayer.intent_queue.read().await;
+        assert_eq!(queue.len(), 15);
+        
+        // Note: Can't test process_batch() without Ethereum connection
+        // For full integration tests, use examples/run_fisher.rs
+    }
+    
+    fn test_wallet(seed: u8) -> LocalWallet {
+        LocalWallet::from_bytes(&[seed; 32]).unwrap()
+    }
+    
+    /// Intent from `wallet`, signed by it
+    /// Nonce derived from the intent id, so one wallet's test intents don't share a nonce
+    fn id_nonce(id: &str) -> u64 {
+        u64::from_be_bytes(ethers::utils::keccak256(id)[..8].try_into().unwrap()) % 1_000_000
+    }
+    
+    fn intent_from(id: &str, wallet: &LocalWallet, priority: bool) -> Intent {
+        Intent::new(
+            id.to_string(),
+            Address::from_slice(wallet.address().as_bytes()),
+            Address::repeat_byte(0x01),
+            U256::from(100),
+            priority,
+            id_nonce(id),
+            vec![],
+        )
+        .signed(wallet)
+        .unwrap()
+    }
+    
+    fn test_intent(id: &str) -> Intent {
+        intent_from(id, &test_wallet(0x11), false)
+    }
+    
+    #[tokio::test]
+    async fn test_queue_snapshot() {
+        // No fast lane, so the priority intent stays queued
+        let relayer = FisherRelayer::new(FisherConfig {
+            priority_batch_threshold: 0,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallet = test_wallet(0x11);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        
+        for (id, age, priority) in [("old", 120, false), ("fresh", 5, true)] {
+            let intent = Intent { timestamp: now - age, ..intent_from(id, &wallet, priority) };
+            relayer.submit_intent(intent.signed(&wallet).unwrap()).await.unwrap();
+        }
+        
+        let snapshot = relayer.queue_snapshot().await;
+        assert_eq!(snapshot.len(), relayer.queue_len().await);
+        
+        let ids: Vec<&str> = snapshot.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["old", "fresh"]);
+        
+        // Ages may tick over a second while the test runs
+        assert!((120..=121).contains(&snapshot[0].age_secs));
+        assert!((5..=6).contains(&snapshot[1].age_secs));
+        
+        assert_eq!(snapshot[1].from, Address::from_slice(wallet.address().as_bytes()));
+        assert_eq!(snapshot[1].to, Address::repeat_byte(0x01));
+        assert_eq!(snapshot[1].amount, U256::from(100));
+        assert!(snapshot[1].priority && !snapshot[0].priority);
+        
+        // Nothing signature-related is exposed
+        let json = serde_json::to_value(&snapshot[0]).unwrap();
+        assert!(json.get("signature").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_unsigned_or_forged_intent_rejected() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        let mut forged = test_intent("forged");
+        forged.from = Address::repeat_byte(0xAA);
+        assert!(matches!(relayer.submit_intent(forged).await, Err(Error::InvalidSignature)));
+        
+        let mut unsigned = test_intent("unsigned");
+        unsigned.signature = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        assert!(matches!(relayer.submit_intent(unsigned).await, Err(Error::InvalidSignature)));
+        
+        assert!(relayer.intent_queue.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_submit_encrypted_intent() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        // Client-side: encrypt to the attested key
+        let encrypted = EncryptedIntent::encrypt(
+            &test_intent("secret_1"),
+            &relayer.encryption_public_key(),
+        ).unwrap();
+        
+        let id = relayer.submit_encrypted_intent(encrypted).await.unwrap();
+        assert_eq!(id, "secret_1");
+        
+        let queue = relayer.intent_queue.read().await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].amount, U256::from(100));
+    }
+    
+    #[tokio::test]
+    async fn test_tampered_encrypted_intent_rejected() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        let mut encrypted = EncryptedIntent::encrypt(
+            &test_intent("secret_2"),
+            &relayer.encryption_public_key(),
+        ).unwrap();
+        let last = encrypted.ciphertext.len() - 40;
+        encrypted.ciphertext[last] ^= 0x01;
+        
+        let result = relayer.submit_encrypted_intent(encrypted).await;
+        assert!(matches!(result, Err(Error::Decryption(_))));
+        assert!(relayer.intent_queue.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_plaintext_rejected_when_encryption_required() {
+        let config = FisherConfig {
+            require_encrypted_intents: true,
+            ..FisherConfig::default()
+        };
+        let relayer = FisherRelayer::new(config).unwrap();
+        
+        let result = relayer.submit_intent(test_intent("plain")).await;
+        assert!(matches!(result, Err(Error::InvalidIntent(_))));
+        
+        let encrypted = EncryptedIntent::encrypt(
+            &test_intent("sealed"),
+            &relayer.encryption_public_key(),
+        ).unwrap();
+        assert!(relayer.submit_encrypted_intent(encrypted).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_priority_intent_skips_min_batch_size() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        // A lone normal intent waits for min_batch_size
+        relayer.queue_intent(test_intent("normal")).await.unwrap();
+        let result = relayer.process_batch().await;
+        assert!(matches!(result, Err(Error::BatchProcessing(_))));
+        assert_eq!(relayer.intent_queue.read().await.len(), 1);
+        
+        // Same queue conditions plus one priority intent: batch is taken
+        let urgent = intent_from("urgent", &test_wallet(0x11), true);
+        relayer.intent_queue.write().await.push(urgent);
+        let submission = relayer.process_batch().await.unwrap();
+        assert_eq!(submission.sub_batches[0].intent_ids.len(), 2);
+        assert!(relayer.intent_queue.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_priority_submit_triggers_immediate_processing() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        relayer.submit_intent(test_intent("normal")).await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(relayer.intent_queue.read().await.len(), 1);
+        
+        let urgent = intent_from("urgent", &test_wallet(0x11), true);
+        relayer.submit_intent(urgent).await.unwrap();
+        
+        // The spawned fast-lane task drains the queue without waiting for the interval
+        for _ in 0..100 {
+            if relayer.intent_queue.read().await.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert!(relayer.intent_queue.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_fast_lane_disabled() {
+        let config = FisherConfig {
+            priority_batch_threshold: 0,
+            ..FisherConfig::default()
+        };
+        let relayer = FisherRelayer::new(config).unwrap();
+        
+        let urgent = intent_from("urgent", &test_wallet(0x11), true);
+        relayer.queue_intent(urgent).await.unwrap();
+        
+        let result = relayer.process_batch().await;
+        assert!(matches!(result, Err(Error::BatchProcessing(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_submit_intent_rate_limited_per_sender() {
+        let config = FisherConfig {
+            max_intents_per_sender_per_window: 2,
+            ..FisherConfig::default()
+        };
+        let relayer = FisherRelayer::new(config).unwrap();
+        
+        let wallet_a = test_wallet(0xAA);
+        let from_a = |id: &str| intent_from(id, &wallet_a, false);
+        
+        relayer.submit_intent(from_a("a1")).await.unwrap();
+        relayer.submit_intent(from_a("a2")).await.unwrap();
+        let result = relayer.submit_intent(from_a("a3")).await;
+        assert!(matches!(result, Err(Error::RateLimited(_))));
+        
+        // Different sender still accepted
+        relayer.submit_intent(intent_from("b1", &test_wallet(0xBB), false)).await.unwrap();
+        
+        assert_eq!(relayer.intent_queue.read().await.len(), 3);
+    }
+    
+    #[tokio::test]
+    async fn test_submit_intent_upgrades_old_and_rejects_future_versions() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let wallet = test_wallet(0x36);
+        
+        let legacy = Intent { version: 1, ..intent_from("legacy", &wallet, false) };
+        relayer.submit_intent(legacy).await.unwrap();
+        assert_eq!(relayer.intent_queue.read().await[0].version, crate::INTENT_VERSION);
+        
+        let future = Intent { version: crate::INTENT_VERSION + 1, ..intent_from("future", &wallet, false) };
+        let result = relayer.submit_intent(future).await;
+        assert!(matches!(result, Err(Error::InvalidIntent(_))));
+        assert_eq!(relayer.queue_len().await, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_submit_intent_rejects_future_timestamp() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let wallet = test_wallet(0x38);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        
+        let future = Intent { timestamp: now + 86_400, ..intent_from("future", &wallet, false) };
+        let result = relayer.submit_intent(future.signed(&wallet).unwrap()).await;
+        assert!(matches!(result, Err(Error::InvalidIntent(_))));
+        
+        let current = Intent { timestamp: now - 10, ..intent_from("current", &wallet, false) };
+        relayer.submit_intent(current.signed(&wallet).unwrap()).await.unwrap();
+        assert_eq!(relayer.queue_len().await, 1);
+    }
+    
+    async fn signed_cancel(wallet: &LocalWallet, intent: &Intent) -> Vec<u8> {
+        wallet
+            .sign_message(intent.cancellation_message())
+            .await
+            .unwrap()
+            .to_vec()
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_intent() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        
+        let intent = intent_from("cancel_me", &wallet, false);
+        relayer.submit_intent(intent.clone()).await.unwrap();
+        relayer.submit_intent(test_intent("keep_me")).await.unwrap();
+        
+        let signature = signed_cancel(&wallet, &intent).await;
+        assert!(relayer.cancel_intent("cancel_me", signature).await.unwrap());
+        
+        let queue = relayer.intent_queue.read().await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, "keep_me");
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_unknown_intent() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        
+        let signature = signed_cancel(&wallet, &test_intent("missing")).await;
+        assert!(!relayer.cancel_intent("missing", signature).await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_forged_cancellation_rejected() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let owner = LocalWallet::new(&mut rand::thread_rng());
+        let attacker = LocalWallet::new(&mut rand::thread_rng());
+        
+        let intent = intent_from("victim", &owner, false);
+        relayer.submit_intent(intent.clone()).await.unwrap();
+        
+        let forged = signed_cancel(&attacker, &intent).await;
+        let result = relayer.cancel_intent("victim", forged).await;
+        assert!(matches!(result, Err(Error::InvalidSignature)));
+        
+        // Garbage bytes are rejected too
+        let result = relayer.cancel_intent("victim", vec![0u8; 10]).await;
+        assert!(matches!(result, Err(Error::InvalidSignature)));
+        
+        assert_eq!(relayer.intent_queue.read().await.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_cancellation_bound_to_one_intent() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        
+        // A cancellation captured for an earlier intent under the same id
+        let old = Intent { nonce: 1, ..intent_from("reused", &wallet, false) }.signed(&wallet).unwrap();
+        let captured = signed_cancel(&wallet, &old).await;
+        
+        let new = Intent { nonce: 2, ..intent_from("reused", &wallet, false) }.signed(&wallet).unwrap();
+        relayer.submit_intent(new.clone()).await.unwrap();
+        
+        let result = relayer.cancel_intent("reused", captured).await;
+        assert!(matches!(result, Err(Error::InvalidSignature)));
+        assert_eq!(relayer.intent_queue.read().await.len(), 1);
+        
+        assert!(relayer.cancel_intent("reused", signed_cancel(&wallet, &new).await).await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_already_settled() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        
+        let intent = intent_from("too_late", &wallet, true);
+        relayer.queue_intent(intent.clone()).await.unwrap();
+        
+        relayer.process_batch().await.unwrap();
+        
+        let signature = signed_cancel(&wallet, &intent).await;
+        let result = relayer.cancel_intent("too_late", signature).await;
+        assert!(matches!(result, Err(Error::AlreadySettled(_))));
+    }
+    
+    fn mined_receipt(block: u64) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: H256::repeat_byte(0x42),
+            block_number: Some(U64::from(block)),
+            ..Default::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_confirmations_reached() {
+        let (provider, mock) = Provider::mocked();
+        
+        // Mock responses are popped LIFO: push in reverse order of use
+        mock.push(mined_receipt(100)).unwrap();
+        mock.push(U64::from(102)).unwrap();
+        mock.push(U64::from(101)).unwrap();
+        
+        let receipt = wait_for_confirmations(&provider, mined_receipt(100), 3, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(receipt.block_number, Some(U64::from(100)));
+    }
+    
+    #[tokio::test]
+    async fn test_confirmations_detect_reorg() {
+        let (provider, mock) = Provider::mocked();
+        
+        // Head reaches target depth but the receipt is gone
+        mock.push(serde_json::Value::Null).unwrap();
+        mock.push(U64::from(105)).unwrap();
+        
+        let result = wait_for_confirmations(&provider, mined_receipt(100), 3, Duration::ZERO).await;
+        assert!(matches!(result, Err(Error::Reorged(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_reorged_intents_requeued() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        relayer.queue_intent(test_intent("new")).await.unwrap();
+        relayer.replay_guard.write().await.record(&test_intent("reorged"));
+        
+        relayer.requeue_intents(vec![test_intent("reorged")]).await;
+        
+        let queue = relayer.intent_queue.read().await;
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].id, "reorged");
+        assert!(!relayer.replay_guard.write().await.is_batched("reorged"));
+    }
+    
+    /// Record intents as drained into a batch, as `process_batch` does
+    async fn mark_batched(relayer: &FisherRelayer, intents: &[Intent]) {
+        let mut guard = relayer.replay_guard.write().await;
+        for intent in intents {
+            guard.record(intent);
+        }
+    }
+    
+    fn test_batch(n: u64) -> Batch {
+        Batch {
+            id: 1,
+            intents: (0..n).map(|i| test_intent(&format!("sim_{}", i))).collect(),
+            chunk_size: 1,
+            phi_score: 0.0,
+            estimated_gas: U256::ZERO,
+            estimated_savings: U256::ZERO,
+            created_at: 0,
+            recipient_groups: Vec::new(),
+            aggregated: Vec::new(),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_simulate_batch_success_vector() {
+        let (provider, mock) = Provider::mocked();
+        let encoded = ethers::abi::encode(&[ethers::abi::Token::Array(vec![
+            ethers::abi::Token::Bool(true),
+            ethers::abi::Token::Bool(false),
+            ethers::abi::Token::Bool(true),
+        ])]);
+        mock.push::<Bytes, _>(Bytes::from(encoded)).unwrap();
+        
+        let simulation = simulate_with(Arc::new(provider), Address::ZERO, &test_batch(3))
+            .await
+            .unwrap();
+        
+        assert!(!simulation.reverted());
+        assert_eq!(simulation.successes, vec![true, false, true]);
+        assert_eq!(simulation.failed_count(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_simulate_batch_revert_reason() {
+        let (provider, mock) = Provider::mocked();
+        
+        // Error(string) "Fisher: paused"
+        let mut revert_data = vec![0x08, 0xc3, 0x79, 0xa0];
+        revert_data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::String("Fisher: paused".to_string()),
+        ]));
+        mock.push_response(ethers::providers::MockResponse::Error(ethers::providers::JsonRpcError {
+            code: 3,
+            message: "execution reverted: Fisher: paused".to_string(),
+            data: Some(serde_json::json!(format!("0x{}", hex::encode(&revert_data)))),
+        }));
+        
+        let simulation = simulate_with(Arc::new(provider), Address::ZERO, &test_batch(2))
+            .await
+            .unwrap();
+        
+        assert!(simulation.reverted());
+        assert_eq!(simulation.revert_reason.as_deref(), Some("Fisher: paused"));
+        assert!(simulation.successes.is_empty());
+    }
+    
+    /// Node error response for the next mocked request, as ethers surfaces it
+    async fn node_error(message: &str, data: Option<serde_json::Value>) -> ProviderError {
+        let (provider, mock) = Provider::mocked();
+        mock.push_response(ethers::providers::MockResponse::Error(ethers::providers::JsonRpcError {
+            code: -32000,
+            message: message.to_string(),
+            data,
+        }));
+        
+        provider.send_raw_transaction(Bytes::from(vec![0x01])).await.unwrap_err()
+    }
+    
+    #[tokio::test]
+    async fn test_contract_errors_classified() {
+        let classify = |e: ProviderError| {
+            contract_error("Transaction failed", &ContractError::<Provider<MockProvider>>::ProviderError { e })
+        };
+        
+        let e = classify(node_error("nonce too low: next nonce 8, tx nonce 7", None).await);
+        assert!(matches!(e, Error::NonceTooLow(_)), "{:?}", e);
+        
+        let e = classify(node_error("insufficient funds for gas * price + value", None).await);
+        assert!(matches!(e, Error::InsufficientFunds(_)), "{:?}", e);
+        
+        let e = classify(node_error("replacement transaction underpriced", None).await);
+        assert!(matches!(e, Error::Underpriced(_)), "{:?}", e);
+        
+        // Revert data is decoded rather than relying on the message text
+        let mut revert_data = vec![0x08, 0xc3, 0x79, 0xa0];
+        revert_data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::String("Fisher: paused".to_string()),
+        ]));
+        let data = serde_json::json!(format!("0x{}", hex::encode(&revert_data)));
+        match classify(node_error("execution reverted", Some(data)).await) {
+            Error::Reverted { reason } => assert_eq!(reason, "Fisher: paused"),
+            other => panic!("expected revert, got {:?}", other),
+        }
+        
+        // Unrecognised node errors keep the fallback variant
+        let e = classify(node_error("already known", None).await);
+        assert!(matches!(e, Error::Contract(_)), "{:?}", e);
+        
+        // Provider-level lookups classify the same way
+        let e = provider_error("Lookup failed", &node_error("nonce too low", None).await);
+        assert!(matches!(e, Error::NonceTooLow(_)), "{:?}", e);
+        let e = provider_error("Lookup failed", &node_error("header not found", None).await);
+        assert!(matches!(e, Error::Rpc(_)), "{:?}", e);
+    }
+    
+    #[tokio::test]
+    async fn test_only_confirmed_intents_acknowledged() {
+        let (endpoint, acks) = crate::fishing_spot::mock::spawn(1).await;
+        let relayer = FisherRelayer::new(FisherConfig::default())
+            .unwrap()
+            .with_fishing_spot(FishingSpotConfig {
+                endpoint,
+                ack_backoff_ms: 1,
+                ..FishingSpotConfig::default()
+            })
+            .unwrap();
+        
+        let batch = test_batch(4);
+        mark_batched(&relayer, &batch.intents).await;
+        
+        let result = BatchResult { successes: vec![true, false, true, false], ..settled_result(&batch) };
+        
+        relayer.acknowledge_confirmed(&batch, &result).await.unwrap().await.unwrap();
+        
+        let expected = vec![batch.intents[0].id.clone(), batch.intents[2].id.clone()];
+        assert_eq!(*acks.lock().unwrap(), vec![expected]);
+        
+        // Failed intents are no longer considered settled
+        let mut guard = relayer.replay_guard.write().await;
+        assert!(guard.is_batched(&batch.intents[0].id));
+        assert!(!guard.is_batched(&batch.intents[1].id));
+    }
+    
+    #[tokio::test]
+    async fn test_slow_acknowledgement_does_not_hold_up_batches() {
+        // The spot keeps failing and the relayer would back off for minutes
+        let (endpoint, _) = crate::fishing_spot::mock::spawn(usize::MAX).await;
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            ..FisherConfig::default()
+        })
+        .unwrap()
+        .with_fishing_spot(FishingSpotConfig {
+            endpoint,
+            ack_max_attempts: 5,
+            ack_backoff_ms: 60_000,
+            ..FishingSpotConfig::default()
+        })
+        .unwrap();
+        
+        for round in 0..2 {
+            relayer.submit_intent(intent_from(&format!("round_{}", round), &test_wallet(0x61 + round), false)).await.unwrap();
+            let settled = tokio::time::timeout(std::time::Duration::from_secs(5), relayer.process_batch()).await;
+            assert!(matches!(settled, Ok(Ok(_))), "round {} blocked on the acknowledgement", round);
+        }
+    }
+    
+    fn batch_submitted_log(fees: u64) -> Log {
+        use ethers::abi::Token;
+        
+        let data = ethers::abi::encode(&[
+            Token::Uint(10.into()),      // operationCount
+            Token::Uint(150_000.into()), // gasUsed
+            Token::Uint(850_000.into()), // gasSaved
+            Token::Uint(fees.into()),    // feesCollected
+            Token::Uint(1_700_000_000u64.into()),
+        ]);
+        
+        Log {
+            topics: vec![BatchSubmittedFilter::signature(), H256::from_low_u64_be(7)],
+            data: data.into(),
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_batch_accounting_from_receipt() {
+        let receipt = TransactionReceipt {
+            gas_used: Some(150_000.into()),
+            effective_gas_price: Some(20_000_000_000u64.into()),
+            logs: vec![Log::default(), batch_submitted_log(5_000_000_000_000_000)],
+            ..mined_receipt(100)
+        };
+        
+        let (fees, gas_cost) = batch_accounting(&receipt);
+        assert_eq!(fees, U256::from(5_000_000_000_000_000u64));
+        assert_eq!(gas_cost, U256::from(3_000_000_000_000_000u64));
+        
+        let result = BatchResult {
+            gas_used: U256::from(150_000),
+            fees_collected: fees,
+            gas_cost_wei: gas_cost,
+            ..settled_result(&test_batch(0))
+        };
+        assert_eq!(
+            result.net_profit(),
+            alloy_primitives::I256::try_from(2_000_000_000_000_000i64).unwrap()
+        );
+    }
+    
+    fn fee_charged_log(user: &LocalWallet, fee: u64) -> Log {
+        Log {
+            topics: vec![FeeChargedFilter::signature(), H256::from(user.address())],
+            data: ethers::abi::encode(&[ethers::abi::Token::Uint(fee.into())]).into(),
+            ..Default::default()
+        }
+    }
+    
+    /// Batch paying once from each of `wallets`
+    fn batch_from(wallets: &[LocalWallet]) -> Batch {
+        let intents = wallets.iter().enumerate()
+            .map(|(i, wallet)| intent_from(&format!("payer_{}", i), wallet, false))
+            .collect();
+        Batch { intents, ..test_batch(0) }
+    }
+    
+    #[test]
+    fn test_payment_successes_from_fee_events() {
+        let wallets: Vec<LocalWallet> = (1..=3).map(test_wallet).collect();
+        let batch = batch_from(&wallets);
+        
+        // The middle payment failed, so only the outer two were charged
+        let receipt = TransactionReceipt {
+            logs: vec![
+                fee_charged_log(&wallets[0], 10),
+                fee_charged_log(&wallets[2], 30),
+                batch_submitted_log(40),
+            ],
+            ..mined_receipt(100)
+        };
+        assert_eq!(payment_successes(&batch, &receipt), vec![true, false, true]);
+        
+        let receipt = TransactionReceipt {
+            logs: vec![batch_submitted_log(0)],
+            ..mined_receipt(100)
+        };
+        assert_eq!(payment_successes(&batch, &receipt), vec![false; 3]);
+        
+        // No batch event to anchor on: assume the old all-succeeded behaviour
+        assert_eq!(payment_successes(&batch, &mined_receipt(100)), vec![true; 3]);
+    }
+    
+    #[test]
+    fn test_reverted_receipt_is_not_settled() {
+        // Reverted receipts carry no logs, which payment_successes reads as all-succeeded
+        let reverted = TransactionReceipt {
+            status: Some(U64::zero()),
+            ..mined_receipt(100)
+        };
+        assert!(matches!(ensure_receipt_succeeded(&reverted), Err(Error::Reverted { .. })));
+        
+        let succeeded = TransactionReceipt {
+            status: Some(U64::one()),
+            ..mined_receipt(100)
+        };
+        assert!(ensure_receipt_succeeded(&succeeded).is_ok());
+        
+        // Pre-Byzantium receipts have no status to go on
+        assert!(ensure_receipt_succeeded(&mined_receipt(100)).is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_failed_intents_requeued_until_retries_exhausted() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            max_intent_retries: 2,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallets: Vec<LocalWallet> = (1..=3).map(test_wallet).collect();
+        let batch = batch_from(&wallets);
+        let result = BatchResult { successes: vec![true, false, true], ..settled_result(&batch) };
+        
+        // First failure and two retries: the failed intent is re-queued each time
+        for attempt in 1..=2u64 {
+            mark_batched(&relayer, &batch.intents).await;
+            relayer.finalize_batch(&batch, &result).await;
+            
+            let queued: Vec<String> = relayer.queue_snapshot().await.into_iter().map(|s| s.id).collect();
+            assert_eq!(queued, vec!["payer_1".to_string()]);
+            assert_eq!(relayer.get_metrics().await.intents_retried, attempt);
+            
+            // The next batch drains it again
+            relayer.intent_queue.write().await.clear();
+        }
+        
+        // Third failure exhausts the retries
+        mark_batched(&relayer, &batch.intents).await;
+        relayer.finalize_batch(&batch, &result).await;
+        
+        assert_eq!(relayer.queue_len().await, 0);
+        assert_eq!(relayer.get_metrics().await.intents_dropped, 1);
+        assert!(relayer.failed_attempts.read().await.is_empty());
+        assert_eq!(dropped_ids(&relayer).await, vec![("payer_1".to_string(), DropReason::RetriesExhausted(3))]);
+        assert!(!relayer.replay_guard.write().await.is_batched("payer_1"));
+    }
+    
+    #[tokio::test]
+    async fn test_success_resets_retry_count() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            max_intent_retries: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        let batch = batch_from(&[test_wallet(1)]);
+        
+        let failed = BatchResult { successes: vec![false], ..settled_result(&batch) };
+        relayer.finalize_batch(&batch, &failed).await;
+        assert_eq!(relayer.failed_attempts.read().await.get("payer_0"), Some(&1));
+        
+        relayer.intent_queue.write().await.clear();
+        relayer.finalize_batch(&batch, &settled_result(&batch)).await;
+        assert!(relayer.failed_attempts.read().await.is_empty());
+        assert_eq!(relayer.queue_len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_aggregate_profit() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let batch = test_batch(2);
+        
+        for (fees, cost) in [(1_000u64, 300u64), (200, 500)] {
+            let result = BatchResult {
+                fees_collected: U256::from(fees),
+                gas_cost_wei: U256::from(cost),
+                ..settled_result(&batch)
+            };
+            relayer.update_metrics(&batch, &result).await;
+        }
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.total_fees_collected, U256::from(1_200));
+        assert_eq!(metrics.total_gas_cost, U256::from(800));
+        assert_eq!(metrics.net_profit(), alloy_primitives::I256::try_from(400).unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_use_realized_savings() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let batch = relayer.build_batch(test_batch(10).intents).await.unwrap();
+        
+        // Batch burned 400K gas instead of the predicted 145K
+        let gas_used = U256::from(400_000);
+        let realized = realized_savings_percent(10, relayer.config.per_tx_baseline_gas, gas_used);
+        let result = BatchResult {
+            gas_used,
+            gas_saved: U256::from(600_000),
+            estimated_savings_percent: batch.savings_percent(),
+            realized_savings_percent: realized,
+            ..settled_result(&batch)
+        };
+        
+        assert_eq!(result.estimated_savings_percent, 85.5);
+        assert_eq!(result.realized_savings_percent, 60.0);
+        
+        relayer.update_metrics(&batch, &result).await;
+        assert_eq!(relayer.get_metrics().await.avg_savings_percent(), 60.0);
+    }
+    
+    #[tokio::test]
+    async fn test_concurrent_metric_updates_average_exactly() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let batch = test_batch(3);
+        
+        let savings: Vec<f64> = (0..2_000).map(|i| 50.0 + (i % 97) as f64 * 0.37).collect();
+        let updates = savings.iter().map(|&realized| {
+            let relayer = relayer.clone_arc();
+            let batch = batch.clone();
+            tokio::spawn(async move {
+                let result = BatchResult {
+                    realized_savings_percent: realized,
+                    processing_time_ms: 10,
+                    ..settled_result(&batch)
+                };
+                relayer.update_metrics(&batch, &result).await;
+            })
+        });
+        for update in futures::future::join_all(updates).await {
+            update.unwrap();
+        }
+        
+        let metrics = relayer.get_metrics().await;
+        let exact = savings.iter().sum::<f64>() / savings.len() as f64;
+        assert_eq!(metrics.total_batches, 2_000);
+        assert_eq!(metrics.avg_batch_size(), 3.0);
+        assert_eq!(metrics.avg_processing_time_ms(), 10.0);
+        assert!((metrics.avg_savings_percent() - exact).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_preview_savings_uses_configured_baselines() {
+        let l1 = FisherRelayer::new(FisherConfig::default()).unwrap();
+        assert_eq!(l1.preview_savings(500, false), SavingsEstimate::for_batch_size(500));
+        
+        let l2 = FisherRelayer::new(FisherConfig {
+            per_tx_baseline_gas: 30_000,
+            per_state_update_gas: 20_000,
+            ..FisherConfig::default()
+        }).unwrap();
+        let preview = l2.preview_savings(500, false);
+        assert_eq!(preview, SavingsEstimate::new(500, 30_000, 20_000, false));
+        assert!(preview.combined_percent < l1.preview_savings(500, false).combined_percent);
+        
+        assert!(l2.preview_savings(500, true).gas_saved > preview.gas_saved);
+    }
+    
+    fn settled_result(batch: &Batch) -> BatchResult {
+        BatchResult {
+            batch_id: batch.id,
+            tx_hash: format!("0x{:064x}", batch.id),
+            gas_used: U256::ZERO,
+            gas_saved: U256::ZERO,
+            successes: vec![true; batch.intents.len()],
+            processing_time_ms: 0,
+            used_blob: false,
+            blob_gas_saved: U256::ZERO,
+            fees_collected: U256::ZERO,
+            gas_cost_wei: U256::ZERO,
+            estimated_savings_percent: 0.0,
+            calldata_bytes: 0,
+            blob_bytes: 0,
+            compression_ratio: 0.0,
+            williams_savings_percent: None,
+            contract_gas_saved: U256::ZERO,
+            fee_charged: Vec::new(),
+            onchain_batch_id: None,
+            onchain_operation_count: None,
+            realized_savings_percent: 0.0,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_subscribe_batches() {
+        use futures::StreamExt;
+        
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let mut stream = Box::pin(relayer.subscribe_batches());
+        
+        for id in [1, 2] {
+            let batch = Batch { id, ..test_batch(3) };
+            relayer.finalize_batch(&batch, &settled_result(&batch)).await;
+        }
+        
+        assert_eq!(stream.next().await.unwrap().unwrap().batch_id, 1);
+        assert_eq!(stream.next().await.unwrap().unwrap().batch_id, 2);
+        assert_eq!(relayer.get_metrics().await.total_batches, 2);
+        assert!(relayer.get_metrics().await.last_batch_timestamp.is_some());
+    }
+    
+    #[tokio::test]
+    async fn test_slow_subscriber_lags_without_blocking() {
+        use futures::StreamExt;
+        
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let mut stream = Box::pin(relayer.subscribe_batches());
+        
+        // Overflow the buffer; the relayer must not wait for the subscriber
+        let batch = test_batch(1);
+        for id in 0..BATCH_EVENT_CAPACITY as u64 + 2 {
+            relayer.finalize_batch(&Batch { id, ..batch.clone() }, &settled_result(&batch)).await;
+        }
+        
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(broadcast::error::RecvError::Lagged(2)))
+        ));
+        assert!(stream.next().await.unwrap().is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_oversized_queue_split_into_blob_sized_batches() {
+        use sha2::{Digest, Sha256};
+        
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        // Random addresses, amounts and signatures so the payload barely compresses
+        let intents: Vec<Intent> = (0..5000u64)
+            .map(|i| {
+                let seed = Sha256::digest(i.to_le_bytes());
+                let noise = Sha256::digest(seed);
+                let wallet = LocalWallet::from_bytes(&seed).unwrap();
+                
+                let mut intent = Intent::new(
+                    format!("intent_{:x}", U256::from_be_slice(&noise)),
+                    Address::from_slice(wallet.address().as_bytes()),
+                    Address::from_slice(&noise[..20]),
+                    U256::from_be_slice(&seed),
+                    i % 7 == 0,
+                    i,
+                    vec![],
+                );
+                intent.max_gas_price = Some(U256::from_be_slice(&noise[..16]));
+                // Signing takes a while; a shared age keeps the φ order stable
+                intent.timestamp = 0;
+                intent.signed(&wallet).unwrap()
+            })
+            .collect();
+        
+        let whole = Batch { intents: intents.clone(), ..test_batch(0) };
+        assert!(BlobEncoder::blobs_required(&whole).unwrap() > relayer.config.max_blobs_per_tx);
+        
+        let batches = relayer.build_batches(intents.clone()).await.unwrap();
+        assert!(batches.len() > 1);
+        
+        // Every sub-batch encodes within limits and IDs are unique
+        for batch in &batches {
+            let blobs = BlobEncoder::encode_batch(batch, relayer.config.max_blobs_per_tx).unwrap();
+            assert!(blobs.len() <= relayer.config.max_blobs_per_tx);
+        }
+        let ids: std::collections::HashSet<u64> = batches.iter().map(|b| b.id).collect();
+        assert_eq!(ids.len(), batches.len());
+        
+        // Concatenated sub-batches are exactly the φ-ordered queue
+        let mut expected = intents;
+        crate::phi_freeman::phi_sort(&mut expected);
+        let split: Vec<String> = batches.iter()
+            .flat_map(|b| b.intents.iter().map(|i| i.id.clone()))
+            .collect();
+        assert_eq!(split, expected.iter().map(|i| i.id.clone()).collect::<Vec<_>>());
+    }
+    
+    #[tokio::test]
+    async fn test_drain_split_by_max_intents_per_tx() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            batch_trigger_size: 500,
+            max_intents_per_tx: 2000,
+            enable_blobs: BlobMode::Off,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let wallet = test_wallet(0x11);
+        let intents: Vec<Intent> = (0..5000u64)
+            .map(|i| Intent::new(
+                format!("cap_{}", i),
+                Address::from_slice(wallet.address().as_bytes()),
+                Address::repeat_byte(0x01),
+                U256::from(i + 1),
+                i % 10 == 0,
+                i,
+                vec![],
+            ))
+            .map(|intent| Intent { timestamp: 0, ..intent }.signed(&wallet).unwrap())
+            .collect();
+        
+        let batches = relayer.build_batches(intents.clone()).await.unwrap();
+        let sizes: Vec<usize> = batches.iter().map(|b| b.intents.len()).collect();
+        assert_eq!(sizes, vec![2000, 2000, 1000]);
+        
+        // Still contiguous slices of the φ-ordered queue
+        let mut expected = intents;
+        crate::phi_freeman::phi_sort(&mut expected);
+        let split: Vec<&str> = batches.iter()
+            .flat_map(|b| b.intents.iter().map(|i| i.id.as_str()))
+            .collect();
+        assert_eq!(split, expected.iter().map(|i| i.id.as_str()).collect::<Vec<_>>());
+    }
+    
+    /// Signed intents with a shared age so the φ order is stable
+    fn aged_intents(n: u64) -> Vec<Intent> {
+        let wallet = test_wallet(0x11);
+        (0..n)
+            .map(|i| Intent { timestamp: 0, ..intent_from(&format!("gas_{}", i), &wallet, i % 3 == 0) })
+            .map(|intent| intent.signed(&wallet).unwrap())
+            .collect()
+    }
+    
+    #[tokio::test]
+    async fn test_batches_split_to_fit_block_gas_limit() {
+        // Offline, so the fallback limit applies: 90% of 100k fits 6 intents
+        let relayer = FisherRelayer::new(FisherConfig {
+            enable_blobs: BlobMode::Off,
+            fallback_block_gas_limit: 100_000,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let intents = aged_intents(20);
+        let batches = relayer.build_batches(intents.clone()).await.unwrap();
+        
+        let sizes: Vec<usize> = batches.iter().map(|b| b.intents.len()).collect();
+        assert_eq!(sizes, vec![5, 5, 5, 5]);
+        assert!(batches.iter().all(|b| b.estimated_gas <= U256::from(90_000)));
+        
+        // Still contiguous slices of the φ-ordered queue
+        let mut expected = intents;
+        crate::phi_freeman::phi_sort(&mut expected);
+        let split: Vec<&str> = batches.iter()
+            .flat_map(|b| b.intents.iter().map(|i| i.id.as_str()))
+            .collect();
+        assert_eq!(split, expected.iter().map(|i| i.id.as_str()).collect::<Vec<_>>());
+    }
+    
+    #[tokio::test]
+    async fn test_batch_within_node_gas_limit_not_split() {
+        // The node reports 200k gas per block, so 90% fits 12 intents
+        let (endpoint, methods) = spawn_rpc(0, |method, _| match method {
+            "eth_getBlockByNumber" => Some(serde_json::json!({
+                "number": "0x10",
+                "hash": format!("0x{}", "11".repeat(32)),
+                "parentHash": format!("0x{}", "22".repeat(32)),
+                "gasLimit": "0x30d40",
+                "transactions": [],
+            })),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            enable_blobs: BlobMode::Off,
+            // Would split everything if the node's limit were ignored
+            fallback_block_gas_limit: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        let batches = relayer.build_batches(aged_intents(12)).await.unwrap();
+        assert_eq!(batches.len(), 1);
+        assert!(methods.lock().unwrap().iter().any(|m| m == "eth_getBlockByNumber"));
+        
+        let batches = relayer.build_batches(aged_intents(13)).await.unwrap();
+        let sizes: Vec<usize> = batches.iter().map(|b| b.intents.len()).collect();
+        assert_eq!(sizes, vec![6, 7]);
+    }
+    
+    #[tokio::test]
+    async fn test_batch_chunk_size_uses_williams_params() {
+        let params = crate::williams::WilliamsParams { log_exponent: 0.0, min_chunk_size: 1 };
+        let relayer = FisherRelayer::new(FisherConfig {
+            williams_params: params,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let batch = relayer.build_batch(aged_intents(100)).await.unwrap();
+        assert_eq!(batch.chunk_size, 10);
+        
+        let invalid = crate::williams::WilliamsParams { log_exponent: -1.0, ..params };
+        let result = FisherRelayer::new(FisherConfig { williams_params: invalid, ..FisherConfig::default() });
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_build_batches_drops_invalid_signatures() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        let mut intents: Vec<Intent> = (0..6).map(|i| test_intent(&format!("v_{}", i))).collect();
+        intents[1].from = Address::repeat_byte(0xAA);
+        intents[4].signature = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        
+        let batches = relayer.build_batches(intents.clone()).await.unwrap();
+        let mut ids: Vec<&str> = batches.iter()
+            .flat_map(|b| b.intents.iter().map(|i| i.id.as_str()))
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["v_0", "v_2", "v_3", "v_5"]);
+        
+        // Nothing left to submit
+        let forged = vec![intents[1].clone(), intents[4].clone()];
+        assert!(matches!(relayer.build_batches(forged).await, Err(Error::BatchProcessing(_))));
+    }
+    
+    #[test]
+    fn test_legacy_max_batch_size_is_trigger() {
+        let json = serde_json::json!({
+            "rpc_url": "http://localhost:8545",
+            "fisher_address": Address::ZERO,
+            "evvm_core_address": Address::ZERO,
+            "min_batch_size": 10,
+            "max_batch_size": 500,
+            "batch_interval_ms": 5000,
+            "enable_attestation": false,
+            "enable_blobs": false,
+        });
+        
+        let config: FisherConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.batch_trigger_size, 500);
+        assert_eq!(config.max_intents_per_tx, crate::MAX_BATCH_SIZE);
+    }
+    
+    #[tokio::test]
+    async fn test_reward_claims_recorded_in_metrics() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        relayer.record_reward_claims(&[(3, U256::from(40)), (4, U256::from(60))]).await;
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.total_rewards_claimed, U256::from(100));
+        assert_eq!(metrics.last_claimed_era, Some(4));
+    }
+    
+    /// Host stub recording the raw transaction it is asked to broadcast
+    struct MockHost {
+        raw_tx: std::sync::Mutex<Option<Vec<u8>>>,
+        success: bool,
+    }
+    
+    impl HostRpc for MockHost {
+        fn transaction_count(&self, _address: Address) -> Result<u64> {
+            Ok(9)
+        }
+        
+        fn gas_price(&self) -> Result<U256> {
+            Ok(U256::from(20_000_000_000u64))
+        }
+        
+        fn send_raw_transaction(&self, raw_tx: &[u8]) -> Result<crate::host::HostReceipt> {
+            *self.raw_tx.lock().unwrap() = Some(raw_tx.to_vec());
+            Ok(crate::host::HostReceipt { tx_hash: [0x42; 32], gas_used: 150_000, success: self.success })
+        }
+    }
+    
+    fn host_relayer() -> FisherRelayer {
+        FisherRelayer::new(FisherConfig {
+            chain_id: Some(10),
+            fisher_address: Address::repeat_byte(0xFE),
+            private_key: Some(hex::encode([0x77; 32])),
+            ..FisherConfig::default()
+        }).unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_host_submission_builds_signed_raw_tx() {
+        use ethers::abi::AbiDecode;
+        use ethers::types::transaction::eip2718::TypedTransaction;
+        
+        let relayer = host_relayer();
+        let host = MockHost { raw_tx: std::sync::Mutex::new(None), success: true };
+        let batch = Batch { estimated_gas: U256::from(200_000), ..test_batch(3) };
+        
+        let result = relayer.submit_batch_via_host(&host, &batch).await.unwrap();
+        assert_eq!(result.tx_hash, format!("0x{}", "42".repeat(32)));
+        assert_eq!(result.gas_used, U256::from(150_000));
+        assert_eq!(result.gas_cost_wei, U256::from(3_000_000_000_000_000u64));
+        
+        // Decode the broadcast bytes as a signed EIP-155 transaction
+        let raw = host.raw_tx.lock().unwrap().clone().unwrap();
+        let (tx, signature) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(&raw)).unwrap();
+        
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), test_wallet(0x77).address());
+        assert_eq!(tx.chain_id(), Some(10.into()));
+        assert_eq!(tx.to_addr(), Some(&H160::repeat_byte(0xFE)));
+        assert_eq!(tx.nonce(), Some(&9.into()));
+        assert_eq!(tx.gas(), Some(&300_000.into()));
+        assert_eq!(tx.gas_price(), Some(20_000_000_000u64.into()));
+        
+        // Calldata matches the contract binding's submitBatchOptimized encoding
+        let call = SubmitBatchOptimizedCall::decode(tx.data().unwrap()).unwrap();
+        let (payments, signatures) = encode_batch_call(&batch);
+        assert_eq!(call.payments, payments);
+        assert_eq!(call.signatures, signatures);
+    }
+    
+    #[tokio::test]
+    async fn test_host_submission_errors() {
+        let host = MockHost { raw_tx: std::sync::Mutex::new(None), success: false };
+        
+        // Reverted transaction
+        let result = host_relayer().submit_batch_via_host(&host, &test_batch(1)).await;
+        assert!(matches!(result, Err(Error::Reverted { .. })));
+        
+        // Missing key or chain ID
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let result = relayer.submit_batch_via_host(&host, &test_batch(1)).await;
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_host_submission_signs_with_backend() {
+        let backend = Arc::new(RecordingSigner {
+            wallet: test_wallet(0x78),
+            signatures: Default::default(),
+        });
+        let relayer = FisherRelayer::new(FisherConfig {
+            chain_id: Some(10),
+            ..FisherConfig::default()
+        }).unwrap().with_signer(backend.clone());
+        let host = MockHost { raw_tx: std::sync::Mutex::new(None), success: true };
+        
+        // No private key in config: the backend signs
+        relayer.submit_batch_via_host(&host, &test_batch(2)).await.unwrap();
+        
+        let raw = host.raw_tx.lock().unwrap().clone().unwrap();
+        let (tx, signature) = ethers::types::transaction::eip2718::TypedTransaction::decode_signed(
+            &ethers::utils::rlp::Rlp::new(&raw),
+        ).unwrap();
+        assert_eq!(backend.signatures.lock().unwrap().as_slice(), [signature]);
+        assert_eq!(tx.from(), Some(&test_wallet(0x78).address()));
+    }
+    
+    #[test]
+    fn test_batch_gas_estimate_uses_configured_baseline() {
+        let intents: Vec<Intent> = (0..10).map(|i| test_intent(&format!("l2_{}", i))).collect();
+        
+        let l1 = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let (gas, savings) = l1.estimate_batch_gas(&intents);
+        assert_eq!((gas, savings), (U256::from(145_000), U256::from(855_000)));
+        
+        // L2-style baseline: far smaller savings
+        let l2 = FisherRelayer::new(FisherConfig {
+            per_tx_baseline_gas: 21_000,
+            ..FisherConfig::default()
+        }).unwrap();
+        let (gas, savings) = l2.estimate_batch_gas(&intents);
+        assert_eq!((gas, savings), (U256::from(145_000), U256::from(65_000)));
+        
+        // Baseline below the optimized cost saves nothing rather than underflowing
+        let cheap = FisherRelayer::new(FisherConfig {
+            per_tx_baseline_gas: 10_000,
+            ..FisherConfig::default()
+        }).unwrap();
+        assert_eq!(cheap.estimate_batch_gas(&intents).1, U256::ZERO);
+    }
+    
+    #[tokio::test]
+    async fn test_build_batch_aggregates_same_recipient() {
+        let alice = test_wallet(0x11);
+        let bob = test_wallet(0x22);
+        let shop = Address::repeat_byte(0x01);
+        let other = Intent { to: Address::repeat_byte(0x02), ..intent_from("alice_other", &alice, false) };
+        let intents = vec![
+            intent_from("alice_1", &alice, false),
+            intent_from("bob_1", &bob, false),
+            intent_from("alice_2", &alice, false),
+            other,
+        ];
+        
+        // Analysis only by default: payments stay independent
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let batch = relayer.build_batch(intents.clone()).await.unwrap();
+        assert_eq!(batch.intents.len(), 4);
+        assert!(batch.aggregated.is_empty());
+        assert_eq!(batch.recipient_groups.len(), 1);
+        assert_eq!(batch.recipient_groups[0].to, shop);
+        assert_eq!(batch.recipient_groups[0].intent_ids, vec!["alice_1", "bob_1", "alice_2"]);
+        assert_eq!(batch.recipient_groups[0].total_amount, U256::from(300));
+        
+        // With aggregation alice's two payments to the shop become one
+        let relayer = FisherRelayer::new(FisherConfig {
+            aggregate_same_recipient: true,
+            ..FisherConfig::default()
+        }).unwrap();
+        let batch = relayer.build_batch(intents).await.unwrap();
+        let ids: Vec<&str> = batch.intents.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["alice_1", "bob_1", "alice_other"]);
+        assert_eq!(batch.intents[0].amount, U256::from(200));
+        assert_eq!(batch.intents[2].to, Address::repeat_byte(0x02));
+        
+        let originals: Vec<String> = batch.clone().into_original_intents().into_iter().map(|i| i.id).collect();
+        assert_eq!(originals, vec!["alice_1", "alice_2", "bob_1", "alice_other"]);
+        
+        // A failed aggregated payment releases every original intent
+        mark_batched(&relayer, &batch.clone().into_original_intents()).await;
+        let result = BatchResult { successes: vec![false, true, true], ..settled_result(&batch) };
+        relayer.acknowledge_confirmed(&batch, &result).await;
+        let mut guard = relayer.replay_guard.write().await;
+        assert!(!guard.is_batched("alice_1") && !guard.is_batched("alice_2"));
+        assert!(guard.is_batched("bob_1"));
+    }
+    
+    #[tokio::test]
+    async fn test_canonical_bytes_identical_across_relayers() {
+        let intents: Vec<Intent> = (0..8).map(|i| test_intent(&format!("canon_{}", i))).collect();
+        let mut reversed = intents.clone();
+        reversed.reverse();
+        
+        let first = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let second = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let a = first.build_batch(intents).await.unwrap();
+        let b = second.build_batch(reversed).await.unwrap();
+        
+        // Arrival order, batch ID and creation time don't matter
+        let b = Batch { id: a.id + 1, created_at: a.created_at + 60, ..b };
+        assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+        assert_eq!(a.canonical_digest(), b.canonical_digest());
+        
+        // NaN and signed-zero scores normalize
+        let nan = |score: f64| Batch { phi_score: score, ..a.clone() }.canonical_bytes();
+        assert_eq!(nan(f64::NAN), nan(-f64::NAN));
+        assert_eq!(nan(0.0), nan(-0.0));
+        
+        // Any content change does
+        let mut tampered = a.clone();
+        tampered.intents[0].amount += U256::from(1);
+        assert_ne!(tampered.canonical_digest(), a.canonical_digest());
+    }
+    
+    #[tokio::test]
+    async fn test_queue_depth_and_wait_metrics() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            min_batch_size: 1,
+            batch_trigger_size: 100,
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        
+        // Created 10s and 20s ago
+        for (i, age) in [10, 20].into_iter().enumerate() {
+            let intent = Intent { timestamp: now - age, ..test_intent(&format!("wait_{}", i)) };
+            relayer.submit_intent(intent.signed(&test_wallet(0x11)).unwrap()).await.unwrap();
+        }
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.current_queue_depth, 2);
+        assert_eq!(metrics.max_queue_depth_seen, 2);
+        
+        relayer.process_batch().await.unwrap();
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.current_queue_depth, 0);
+        assert_eq!(metrics.max_queue_depth_seen, 2);
+        assert_eq!(metrics.intents_batched(), 2);
+        
+        // Average of ~15s, allowing for second-granularity timestamps
+        assert!(
+            (15_000.0..17_000.0).contains(&metrics.avg_intent_wait_ms()),
+            "avg wait {}ms",
+            metrics.avg_intent_wait_ms()
+        );
+        assert!(metrics.summary().contains("Peak depth:            2"));
+    }
+    
+    #[tokio::test]
+    async fn test_retry_connection_backoff() {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let backoff = Duration::from_millis(5);
+        
+        // Transient failures are retried until the node comes back
+        let result = retry_connection(backoff, backoff * 4, || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(Error::Disconnected("connection refused".to_string())),
+                _ => Ok(42),
+            }
+        }).await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        
+        // Permanent errors stop immediately
+        attempts.store(0, Ordering::SeqCst);
+        let result: Result<()> = retry_connection(backoff, backoff * 4, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(Error::Config("chain ID mismatch".to_string()))
+        }).await;
+        assert!(matches!(result, Err(Error::Config(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+    
+    /// Key marking a `spawn_rpc` reply as a JSON-RPC error (see [`rpc_error`])
+    const RPC_ERROR: &str = "__rpc_error";
+    
+    /// Reply that `spawn_rpc` sends back as a JSON-RPC error instead of a result
+    fn rpc_error(message: &str, data: Option<serde_json::Value>) -> serde_json::Value {
+        serde_json::json!({ RPC_ERROR: {"code": 3, "message": message, "data": data} })
+    }
+    
+    /// JSON-RPC node answering 503 to the first `outage` requests
+    ///
+    /// Afterwards `respond(method, params)` supplies each result; `None`
+    /// rejects the method. Returns the endpoint and the methods requested.
+    async fn spawn_rpc<F>(outage: usize, respond: F) -> (String, Arc<std::sync::Mutex<Vec<String>>>)
+    where
+        F: Fn(&str, &serde_json::Value) -> Option<serde_json::Value> + Send + 'static,
+    {
+        use tokio::io::AsyncWriteExt;
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let methods: Arc<std::sync::Mutex<Vec<String>>> = Arc::default();
+        
+        let recorded = Arc::clone(&methods);
+        tokio::spawn(async move {
+            let mut remaining_outage = outage;
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let body = crate::fishing_spot::mock::read_body(&mut socket).await;
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                recorded.lock().unwrap().push(request["method"].as_str().unwrap().to_string());
+                
+                let (status, reply) = if remaining_outage > 0 {
+                    remaining_outage -= 1;
+                    ("503 Service Unavailable", String::new())
+                } else if let Some(result) = respond(request["method"].as_str().unwrap(), &request["params"]) {
+                    match result.get(RPC_ERROR) {
+                        Some(error) => ("200 OK", serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "error": error}).to_string()),
+                        None => ("200 OK", serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": result}).to_string()),
+                    }
+                } else {
+                    let error = serde_json::json!({"code": -32601, "message": "method not found"});
+                    ("200 OK", serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "error": error}).to_string())
+                };
+                
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    reply.len(),
+                    reply
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        
+        (endpoint, methods)
+    }
+    
+    #[tokio::test]
+    async fn test_stop_halts_batch_processing() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            min_batch_size: 1,
+            batch_interval_ms: 10,
+            priority_batch_threshold: 0,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        relayer.start().await;
+        relayer.submit_intent(test_intent("running")).await.unwrap();
+        
+        // The loop drains the queue on its next tick
+        for _ in 0..100 {
+            if relayer.queue_len().await == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(relayer.queue_len().await, 0);
+        
+        relayer.stop();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        
+        // Several intervals later nothing has been batched
+        relayer.submit_intent(intent_from("stopped", &test_wallet(0x12), false)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(relayer.queue_len().await, 1);
+        
+        // Restarting resumes batching
+        relayer.start().await;
+        for _ in 0..100 {
+            if relayer.queue_len().await == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(relayer.queue_len().await, 0);
+        relayer.stop();
+    }
+    
+    #[tokio::test]
+    async fn test_reconnects_and_resumes_batching() {
+        let (endpoint, methods) = spawn_rpc(2, |method, _| {
+            (method == "eth_chainId").then(|| serde_json::json!("0x1"))
+        }).await;
+        let relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x77; 32])),
+            min_batch_size: 1,
+            reconnect_backoff_ms: 10,
+            reconnect_max_backoff_ms: 40,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        // The node is down at startup
+        assert!(relayer.connect().await.unwrap_err().is_connection_error());
+        
+        relayer.submit_intent(test_intent("outage_1")).await.unwrap();
+        relayer.submit_intent(test_intent("outage_2")).await.unwrap();
+        
+        // The tick pauses to reconnect (second 503, then success) and keeps the intents
+        relayer.batch_tick().await.unwrap();
+        assert!(relayer.wallet.read().await.is_some());
+        assert_eq!(relayer.queue_len().await, 2);
+        assert_eq!(*methods.lock().unwrap(), vec!["eth_chainId"; 3]);
+        
+        // The next tick submits against the recovered node
+        relayer.batch_tick().await.unwrap();
+        assert!(methods.lock().unwrap().iter().any(|m| m != "eth_chainId"));
+    }
+    
+    /// Chain ID, nonce, gas and fee-market answers needed to fill a transaction
+    fn chain_basics(method: &str) -> Option<serde_json::Value> {
+        match method {
+            "eth_chainId" => Some(serde_json::json!("0x1")),
+            "eth_getTransactionCount" => Some(serde_json::json!("0x0")),
+            "eth_estimateGas" => Some(serde_json::json!("0x30d40")),
+            "eth_getBlockByNumber" => Some(serde_json::json!({
+                "number": "0x10",
+                "hash": format!("0x{}", "11".repeat(32)),
+                "parentHash": format!("0x{}", "22".repeat(32)),
+                "baseFeePerGas": "0x3b9aca00",
+                "transactions": [],
+            })),
+            "eth_feeHistory" => Some(serde_json::json!({
+                "oldestBlock": "0x10",
+                "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x3b9aca00"]],
+            })),
+            _ => None,
+        }
+    }
+    
+    /// Signer backend that records every signature it hands out
+    #[derive(Debug)]
+    struct RecordingSigner {
+        wallet: LocalWallet,
+        signatures: std::sync::Mutex<Vec<Signature>>,
+    }
+    
+    #[async_trait::async_trait]
+    impl crate::signer::Signer for RecordingSigner {
+        fn address(&self) -> Address {
+            Address::from_slice(self.wallet.address().as_bytes())
+        }
+        
+        async fn sign_transaction(&self, tx: &transaction::eip2718::TypedTransaction) -> Result<Signature> {
+            let signature = self.wallet.sign_transaction_sync(tx).unwrap();
+            self.signatures.lock().unwrap().push(signature);
+            Ok(signature)
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_submission_signs_with_configured_backend() {
+        use ethers::types::transaction::eip2718::TypedTransaction;
+        
+        let raw_tx: Arc<std::sync::Mutex<Option<String>>> = Arc::default();
+        let broadcast = Arc::clone(&raw_tx);
+        let (endpoint, _) = spawn_rpc(0, move |method, params| match method {
+            "eth_getTransactionCount" => Some(serde_json::json!("0x7")),
+            "eth_sendRawTransaction" => {
+                // Capture the broadcast and fail it so nothing waits on a receipt
+                *broadcast.lock().unwrap() = params[0].as_str().map(str::to_string);
+                None
+            }
+            _ => chain_basics(method),
+        }).await;
+        
+        // No private key in config: only the injected backend can sign
+        let backend = Arc::new(RecordingSigner {
+            wallet: test_wallet(0x55),
+            signatures: Default::default(),
+        });
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            fisher_address: Address::repeat_byte(0xFE),
+            ..FisherConfig::default()
+        }).unwrap().with_signer(backend.clone());
+        relayer.init_ethereum().await.unwrap();
+        
+        let batch = relayer.build_batch(test_batch(3).intents).await.unwrap();
+        assert!(relayer.submit_batch_to_ethereum(&batch, true).await.is_err());
+        
+        // The backend signed exactly once and its signature is what went on the wire
+        let signatures = backend.signatures.lock().unwrap().clone();
+        assert_eq!(signatures.len(), 1);
+        
+        let raw = hex::decode(raw_tx.lock().unwrap().clone().unwrap().trim_start_matches("0x")).unwrap();
+        let (tx, signature) = TypedTransaction::decode_signed(&ethers::utils::rlp::Rlp::new(&raw)).unwrap();
+        // EIP-1559 encodes `v` as the bare y-parity
+        assert_eq!((signature.r, signature.s), (signatures[0].r, signatures[0].s));
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), test_wallet(0x55).address());
+        assert_eq!(tx.nonce(), Some(&7.into()));
+        assert_eq!(tx.to_addr(), Some(&H160::repeat_byte(0xFE)));
+    }
+    
+    #[tokio::test]
+    async fn test_restart_does_not_resend_submitted_batch() {
+        let mined_tx = H256::repeat_byte(0xAB);
+        let receipt = serde_json::to_value(TransactionReceipt {
+            transaction_hash: mined_tx,
+            gas_used: Some(150_000.into()),
+            ..mined_receipt(100)
+        }).unwrap();
+        let (endpoint, methods) = spawn_rpc(0, move |method, params| match method {
+            "eth_chainId" => Some(serde_json::json!("0x1")),
+            "eth_getTransactionReceipt" if params[0] == serde_json::json!(mined_tx) => Some(receipt.clone()),
+            _ => None,
+        }).await;
+        
+        let log_path = std::env::temp_dir().join(format!("fisher-submissions-{}.jsonl", uuid::Uuid::new_v4()));
+        let config = FisherConfig {
+            rpc_url: endpoint,
+            submission_log_path: Some(log_path.to_string_lossy().into_owned()),
+            ..FisherConfig::default()
+        };
+        let intents = test_batch(3).intents;
+        
+        // The crashed relayer signed, recorded and sent the batch, then died
+        let crashed = FisherRelayer::new(config.clone()).unwrap();
+        let batch = crashed.build_batch(intents.clone()).await.unwrap();
+        crashed.submissions.write().await.record(batch.canonical_digest(), mined_tx.0).unwrap();
+        drop(crashed);
+        
+        // After restart the same intents rebuild into the same batch
+        let backend = Arc::new(RecordingSigner {
+            wallet: test_wallet(0x55),
+            signatures: Default::default(),
+        });
+        let mut restarted = FisherRelayer::new(config).unwrap().with_signer(backend.clone());
+        restarted.init_ethereum().await.unwrap();
+        let batch = restarted.build_batch(intents).await.unwrap();
+        
+        let result = restarted.submit_batch_to_ethereum(&batch, true).await.unwrap();
+        assert_eq!(result.tx_hash, format!("{:?}", mined_tx));
+        assert_eq!(result.gas_used, U256::from(150_000));
+        
+        // Nothing was signed or broadcast the second time
+        assert!(backend.signatures.lock().unwrap().is_empty());
+        let methods = methods.lock().unwrap().clone();
+        assert!(!methods.iter().any(|m| m == "eth_sendRawTransaction" || m == "eth_estimateGas"));
+        
+        std::fs::remove_file(log_path).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_dropped_submission_is_resent() {
+        let sent = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let broadcasts = Arc::clone(&sent);
+        let (endpoint, methods) = spawn_rpc(0, move |method, _| match method {
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Some(serde_json::Value::Null),
+            "eth_sendRawTransaction" => {
+                broadcasts.fetch_add(1, Ordering::SeqCst);
+                None
+            }
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        // A marker whose tx the node has never seen doesn't block the batch
+        let batch = relayer.build_batch(test_batch(2).intents).await.unwrap();
+        relayer.submissions.write().await.record(batch.canonical_digest(), [0xCD; 32]).unwrap();
+        
+        assert!(relayer.submit_batch_to_ethereum(&batch, true).await.is_err());
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        assert!(methods.lock().unwrap().iter().any(|m| m == "eth_getTransactionByHash"));
+        
+        // The new transaction's marker replaced the stale one before broadcast
+        let marker = relayer.submissions.read().await.get(&batch.canonical_digest()).cloned().unwrap();
+        assert_ne!(marker.tx_hash, format!("0x{}", "cd".repeat(32)));
+    }
+    
+    #[tokio::test]
+    async fn test_savings_histogram_tallies_batches() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let batch = test_batch(4);
+        
+        // Boundaries belong to the higher bucket
+        for savings in [12.5, 79.9, 80.0, 88.0, 90.0, 94.99, 95.0, 99.0, 100.0] {
+            let result = BatchResult {
+                estimated_savings_percent: 90.0,
+                realized_savings_percent: savings,
+                ..settled_result(&batch)
+            };
+            relayer.update_metrics(&batch, &result).await;
+        }
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.savings_histogram, SavingsHistogram {
+            below_80: 2,
+            from_80_to_90: 2,
+            from_90_to_95: 2,
+            from_95: 3,
+        });
+        assert_eq!(metrics.total_batches, 9);
+        
+        let summary = metrics.summary();
+        assert!(summary.contains("<80%:                  2"));
+        assert!(summary.contains(">=95%:                 3"));
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_collector_exports_relayer_metrics() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        let collector = relayer.metrics_collector();
+        
+        for seed in 1..=3u8 {
+            relayer.submit_intent(intent_from(&format!("exported_{}", seed), &test_wallet(seed), false)).await.unwrap();
+        }
+        let result = relayer.process_batch().await.unwrap().into_results().remove(0);
+        
+        // Rendered from the relayer's own metrics, not an empty copy
+        let output = collector.openmetrics().await;
+        let last_batch = relayer.get_metrics().await.last_batch_timestamp.unwrap();
+        assert!(output.contains(&format!("fisher_last_batch_timestamp {}\n", last_batch)));
+        let bucket = match result.realized_savings_percent {
+            s if s < 80.0 => "<80",
+            s if s < 90.0 => "80-90",
+            s if s < 95.0 => "90-95",
+            _ => ">=95",
+        };
+        assert!(output.contains(&format!("fisher_batches_by_savings_total{{bucket=\"{}\"}} 1\n", bucket)));
+        assert!(output.ends_with("# EOF\n"));
+    }
+    
+    #[tokio::test]
+    async fn test_simulated_replay_from_file() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            batch_trigger_size: 1_000,
+            min_gas_price: U256::from(2),
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let mut intents: Vec<Intent> = (1..=20u8)
+            .map(|seed| intent_from(&format!("replay_{}", seed), &test_wallet(seed), false))
+            .collect();
+        intents.push(Intent { signature: vec![0; 65], ..intent_from("forged", &test_wallet(0x99), false) });
+        
+        let path = std::env::temp_dir().join(format!("fisher-replay-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, serde_json::to_string(&intents).unwrap()).unwrap();
+        
+        // The forged intent is skipped, not fatal
+        assert_eq!(relayer.submit_intents_file(&path).await.unwrap(), 20);
+        std::fs::remove_file(&path).unwrap();
+        
+        let results = relayer.process_batch().await.unwrap().into_results();
+        assert_eq!(results.len(), 1);
+        
+        let result = &results[0];
+        let expected_gas = 20 * crate::phi_optimization::OPTIMIZED_GAS_PER_OP + crate::phi_optimization::ERA_UPDATE_GAS;
+        assert_eq!(result.gas_used, U256::from(expected_gas));
+        assert_eq!(result.gas_cost_wei, U256::from(expected_gas * 2));
+        assert_eq!(result.successes, vec![true; 20]);
+        assert_eq!(result.tx_hash.len(), 66);
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.total_batches, 1);
+        assert_eq!(metrics.total_intents, 20);
+        assert_eq!(metrics.total_gas_cost, U256::from(expected_gas * 2));
+        assert_eq!(metrics.avg_savings_percent(), result.realized_savings_percent);
+        assert!(metrics.avg_savings_percent() > 80.0);
+        assert!(metrics.last_batch_timestamp.is_some());
+        assert_eq!(relayer.queue_len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_unavailable_backend_rejected() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Wasm,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let result = relayer.submit_batch_to_chain(&test_batch(2), false).await;
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_batched_intents_cannot_be_replayed() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            replay_state_capacity: 4,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let wallets: Vec<LocalWallet> = (1..=10u8).map(test_wallet).collect();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let first = Intent { timestamp: now - 60, ..intent_from("first", &wallets[0], false) }
+            .signed(&wallets[0])
+            .unwrap();
+        relayer.submit_intent(first.clone()).await.unwrap();
+        relayer.process_batch().await.unwrap();
+        
+        // Same signed intent again, or the same nonce under a new ID
+        assert!(matches!(relayer.submit_intent(first.clone()).await, Err(Error::InvalidIntent(_))));
+        let same_nonce = Intent { id: "first_again".to_string(), ..first.clone() }.signed(&wallets[0]).unwrap();
+        assert!(matches!(relayer.submit_intent(same_nonce).await, Err(Error::InvalidIntent(_))));
+        
+        // Many more senders than capacity: state stays bounded
+        for (i, wallet) in wallets.iter().enumerate().skip(1) {
+            let later = Intent { timestamp: now - 50 + i as u64, ..intent_from(&format!("later_{}", i), wallet, false) }
+                .signed(wallet)
+                .unwrap();
+            relayer.submit_intent(later).await.unwrap();
+            relayer.process_batch().await.unwrap();
+        }
+        assert_eq!(relayer.replay_guard.read().await.len(), 4);
+        
+        // "first" was evicted, yet its replay is still refused
+        assert!(!relayer.replay_guard.write().await.is_batched("first"));
+        assert!(matches!(relayer.submit_intent(first).await, Err(Error::InvalidIntent(_))));
+    }
+    
+    async fn dropped_ids(relayer: &FisherRelayer) -> Vec<(String, DropReason)> {
+        relayer.drained_intents().await.into_iter().map(|(intent, reason)| (intent.id, reason)).collect()
+    }
+    
+    #[tokio::test]
+    async fn test_rejected_submissions_dead_lettered() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            max_intents_per_sender_per_window: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallet = test_wallet(0x21);
+        relayer.submit_intent(intent_from("accepted", &wallet, false)).await.unwrap();
+        
+        let limited = intent_from("limited", &wallet, false);
+        let forged = Intent { from: Address::repeat_byte(0xAA), ..intent_from("forged", &test_wallet(0x22), false) };
+        let zero = Intent { amount: U256::ZERO, ..intent_from("zero", &test_wallet(0x23), false) }
+            .signed(&test_wallet(0x23))
+            .unwrap();
+        for intent in [limited, forged, zero] {
+            assert!(relayer.submit_intent(intent).await.is_err());
+        }
+        
+        let dropped = dropped_ids(&relayer).await;
+        assert_eq!(dropped[..2], [
+            ("limited".to_string(), DropReason::RateLimited),
+            ("forged".to_string(), DropReason::InvalidSignature),
+        ]);
+        assert!(matches!(&dropped[2], (id, DropReason::Invalid(reason)) if id == "zero" && reason.contains("amount")));
+        
+        // Each drop is handed out once
+        assert!(relayer.drained_intents().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_expired_and_unverifiable_intents_dead_lettered() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            max_intent_age_secs: 60,
+            ..FisherConfig::default()
+        }).unwrap();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        
+        // Within the clock skew when submitted, but past the age limit
+        let stale_wallet = test_wallet(0x31);
+        let stale = Intent { timestamp: now - 120, ..intent_from("stale", &stale_wallet, false) }
+            .signed(&stale_wallet)
+            .unwrap();
+        relayer.submit_intent(stale).await.unwrap();
+        relayer.submit_intent(intent_from("fresh", &test_wallet(0x32), false)).await.unwrap();
+        
+        // Altered after it was queued, so only the batch-time re-check catches it
+        let tampered = Intent { amount: U256::from(1), ..intent_from("tampered", &test_wallet(0x33), false) };
+        relayer.intent_queue.write().await.push(tampered);
+        
+        relayer.process_batch().await.unwrap();
+        
+        assert_eq!(dropped_ids(&relayer).await, vec![
+            ("stale".to_string(), DropReason::Expired),
+            ("tampered".to_string(), DropReason::InvalidSignature),
+        ]);
+        assert!(relayer.replay_guard.write().await.is_batched("fresh"));
+        assert!(!relayer.replay_guard.write().await.is_batched("stale"));
+    }
+    
+    /// Relayer that simulates batches against a node answering `eth_call` with `simulation`
+    async fn simulating_relayer(simulation: Option<serde_json::Value>) -> FisherRelayer {
+        let (endpoint, _) = spawn_rpc(0, move |method, _| match method {
+            "eth_call" => simulation.clone(),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        relayer
+    }
+    
+    fn simulated_successes(successes: &[bool]) -> serde_json::Value {
+        let tokens = successes.iter().map(|ok| ethers::abi::Token::Bool(*ok)).collect();
+        let encoded = ethers::abi::encode(&[ethers::abi::Token::Array(tokens)]);
+        serde_json::json!(format!("0x{}", hex::encode(encoded)))
+    }
+    
+    #[tokio::test]
+    async fn test_simulation_failures_dead_lettered() {
+        let relayer = simulating_relayer(Some(simulated_successes(&[true, false, true]))).await;
+        
+        let wallets: Vec<LocalWallet> = (1..=3).map(test_wallet).collect();
+        let batch = relayer.build_batch(batch_from(&wallets).intents).await.unwrap();
+        let failing = batch.constituents(&batch.intents[1]).to_vec();
+        
+        let kept = relayer.drop_failing_intents(batch).await.unwrap();
+        
+        assert_eq!(kept.intents.len(), 2);
+        assert!(kept.intents.iter().all(|intent| intent.id != failing[0].id));
+        assert_eq!(dropped_ids(&relayer).await, vec![(failing[0].id.clone(), DropReason::SimulationFailed)]);
+    }
+    
+    #[tokio::test]
+    async fn test_simulation_rpc_error_requeues_batch() {
+        // The node can't run the simulation at all
+        let relayer = simulating_relayer(None).await;
+        
+        let wallets: Vec<LocalWallet> = (1..=3).map(test_wallet).collect();
+        let batch = relayer.build_batch(batch_from(&wallets).intents).await.unwrap();
+        let originals = batch.clone().into_original_intents();
+        mark_batched(&relayer, &originals).await;
+        
+        let result = relayer.drop_failing_intents(batch).await;
+        
+        assert!(matches!(result, Err(Error::Rpc(_))), "{:?}", result.map(|b| b.id));
+        assert_eq!(relayer.queue_len().await, originals.len());
+        assert!(dropped_ids(&relayer).await.is_empty());
+        assert!(!relayer.replay_guard.write().await.is_batched(&originals[0].id));
+    }
+    
+    #[tokio::test]
+    async fn test_simulated_revert_dead_letters_batch() {
+        // Error(string) "Fisher: paused"
+        let mut revert_data = vec![0x08, 0xc3, 0x79, 0xa0];
+        revert_data.extend(ethers::abi::encode(&[
+            ethers::abi::Token::String("Fisher: paused".to_string()),
+        ]));
+        let revert = rpc_error("execution reverted", Some(serde_json::json!(format!("0x{}", hex::encode(&revert_data)))));
+        
+        for (simulation, all_failed) in [(revert, false), (simulated_successes(&[false, false]), true)] {
+            let relayer = simulating_relayer(Some(simulation)).await;
+            
+            let wallets: Vec<LocalWallet> = (1..=2).map(test_wallet).collect();
+            let batch = relayer.build_batch(batch_from(&wallets).intents).await.unwrap();
+            let originals = batch.clone().into_original_intents();
+            mark_batched(&relayer, &originals).await;
+            
+            match relayer.drop_failing_intents(batch).await {
+                Err(Error::Reverted { reason }) if !all_failed => assert_eq!(reason, "Fisher: paused"),
+                Err(Error::BatchProcessing(_)) if all_failed => {}
+                other => panic!("unexpected simulation outcome: {:?}", other.map(|b| b.id)),
+            }
+            
+            // Dropped rather than retried, and free to be resubmitted
+            assert_eq!(relayer.queue_len().await, 0);
+            let dropped = dropped_ids(&relayer).await;
+            assert_eq!(dropped.len(), originals.len());
+            assert!(dropped.iter().all(|(_, reason)| *reason == DropReason::SimulationFailed));
+            let mut guard = relayer.replay_guard.write().await;
+            assert!(originals.iter().all(|intent| !guard.is_batched(&intent.id)));
+        }
+    }
+    
+    /// Relayer in auto blob mode against a node quoting `blob_base_fee` (and a 1 gwei base fee)
+    async fn auto_blob_relayer(blob_base_fee: u64) -> FisherRelayer {
+        let (endpoint, _) = spawn_rpc(0, move |method, _| match method {
+            "eth_blobBaseFee" => Some(serde_json::json!(format!("{:#x}", blob_base_fee))),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            submission_backend: SubmissionBackend::Simulate,
+            enable_blobs: BlobMode::Auto,
+            min_batch_size: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        for i in 0..10u8 {
+            relayer.submit_intent(intent_from(&format!("blob_{}", i), &test_wallet(0x40 + i), false)).await.unwrap();
+        }
+        
+        relayer
+    }
+    
+    #[tokio::test]
+    async fn test_auto_blob_mode_uses_blobs_when_cheap() {
+        let relayer = auto_blob_relayer(1).await;
+        
+        let results = relayer.process_batch().await.unwrap().into_results();
+        assert!(results[0].used_blob);
+        assert_eq!(results[0].blob_gas_saved, relayer.build_batch(test_batch(10).intents).await.unwrap().estimated_savings);
+    }
+    
+    #[tokio::test]
+    async fn test_auto_blob_mode_falls_back_to_calldata_when_blob_fee_spikes() {
+        // 50 gwei blob gas: one blob costs more than 10 intents of calldata at 1 gwei
+        let relayer = auto_blob_relayer(50_000_000_000).await;
+        
+        let results = relayer.process_batch().await.unwrap().into_results();
+        assert!(!results[0].used_blob);
+        assert_eq!(results[0].blob_gas_saved, U256::ZERO);
+    }
+    
+    #[tokio::test]
+    async fn test_auto_blob_mode_without_fees_uses_calldata() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            enable_blobs: BlobMode::Auto,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let result = relayer.settle_batch(relayer.build_batch(test_batch(2).intents).await.unwrap()).await.unwrap();
+        assert!(!result.used_blob);
+    }
+    
+    /// Stands in for a box without a TDX device
+    struct NoTdxDevice;
+    
+    impl crate::attestation::QuoteProvider for NoTdxDevice {
+        fn quote(&self, _report_data: &[u8; 64]) -> Result<Vec<u8>> {
+            Err(Error::Attestation("no TDX device".to_string()))
+        }
+    }
+    
+    fn attested_config(required: bool) -> FisherConfig {
+        FisherConfig {
+            enable_attestation: true,
+            attestation_required: required,
+            ..FisherConfig::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_required_attestation_refuses_to_start_without_quote() {
+        let relayer = FisherRelayer::new(attested_config(true)).unwrap().with_quote_provider(Arc::new(NoTdxDevice));
+        assert!(matches!(relayer.attest_startup().await, Err(Error::Attestation(_))));
+        
+        // A working device attests the same config
+        let relayer = FisherRelayer::new(attested_config(true)).unwrap();
+        assert_eq!(relayer.attest_startup().await.unwrap(), AttestationStatus::Attested);
+        assert_eq!(relayer.get_metrics().await.attestation, AttestationStatus::Attested);
+        
+        // Requiring attestation while disabling it is a misconfiguration
+        let relayer = FisherRelayer::new(FisherConfig {
+            enable_attestation: false,
+            ..attested_config(true)
+        }).unwrap();
+        assert!(matches!(relayer.attest_startup().await, Err(Error::Config(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_optional_attestation_runs_flagged_unattested() {
+        let relayer = FisherRelayer::new(attested_config(false)).unwrap().with_quote_provider(Arc::new(NoTdxDevice));
+        
+        assert_eq!(relayer.attest_startup().await.unwrap(), AttestationStatus::Unattested);
+        assert_eq!(relayer.get_metrics().await.attestation, AttestationStatus::Unattested);
+        
+        // Still serving, just flagged
+        relayer.submit_intent(test_intent("unattested")).await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_only_failed_sub_batch_requeued() {
+        let (first_tx, third_tx) = (H256::repeat_byte(0xA1), H256::repeat_byte(0xA3));
+        let (endpoint, _) = spawn_rpc(0, move |method, params| match method {
+            "eth_getTransactionReceipt" if params[0] == serde_json::json!(first_tx) => {
+                Some(serde_json::to_value(TransactionReceipt { transaction_hash: first_tx, ..mined_receipt(100) }).unwrap())
+            }
+            "eth_getTransactionReceipt" if params[0] == serde_json::json!(third_tx) => {
+                Some(serde_json::to_value(TransactionReceipt { transaction_hash: third_tx, ..mined_receipt(101) }).unwrap())
+            }
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Some(serde_json::Value::Null),
+            // The second sub-batch's broadcast is rejected
+            "eth_sendRawTransaction" => None,
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        let batches: Vec<Batch> = (1..=3u64)
+            .map(|id| Batch {
+                id,
+                intents: (0..4).map(|i| test_intent(&format!("sub{}_{}", id, i))).collect(),
+                ..test_batch(0)
+            })
+            .collect();
+        let intents: Vec<Intent> = batches.iter().flat_map(|b| b.intents.clone()).collect();
+        mark_batched(&relayer, &intents).await;
+        
+        // The first and third sub-batches were already mined
+        {
+            let mut submissions = relayer.submissions.write().await;
+            submissions.record(batches[0].canonical_digest(), first_tx.0).unwrap();
+            submissions.record(batches[2].canonical_digest(), third_tx.0).unwrap();
+        }
+        
+        let (submission, first_error) = relayer.settle_batches(batches).await;
+        assert!(first_error.is_some());
+        assert!(!submission.is_complete());
+        
+        let statuses: Vec<(u64, bool)> = submission.sub_batches.iter()
+            .map(|sub| (sub.batch_id, matches!(sub.status, SubBatchStatus::Confirmed(_))))
+            .collect();
+        assert_eq!(statuses, vec![(1, true), (2, false), (3, true)]);
+        assert_eq!(submission.failed().map(|sub| sub.batch_id).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(submission.results().count(), 2);
+        
+        // Only the failed sub-batch's intents are back in the queue
+        let queued: Vec<String> = relayer.queue_snapshot().await.into_iter().map(|i| i.id).collect();
+        let expected: Vec<String> = (0..4).map(|i| format!("sub2_{}", i)).collect();
+        assert_eq!(queued, expected);
+        assert_eq!(submission.sub_batches[1].intent_ids, expected);
+        
+        // Confirmed intents stay batched; the failed ones may be batched again
+        let mut guard = relayer.replay_guard.write().await;
+        assert!(guard.is_batched("sub1_0") && guard.is_batched("sub3_3"));
+        assert!(!guard.is_batched("sub2_0"));
+        drop(guard);
+        assert_eq!(relayer.get_metrics().await.intents_retried, 4);
+    }
+    
+    #[tokio::test]
+    async fn test_allowlisted_intents_lead_the_batch() {
+        let partner = test_wallet(0x21);
+        let relayer = FisherRelayer::new(FisherConfig {
+            max_intents_per_tx: 4,
+            enable_blobs: BlobMode::Off,
+            priority_allowlist: [Address::from_slice(partner.address().as_bytes())].into(),
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        // Small, non-priority partner intents against large priority ones
+        let mut intents: Vec<Intent> = (0..8u64)
+            .map(|i| {
+                let wallet = test_wallet(0x30 + i as u8);
+                Intent { amount: U256::from(1_000_000 * (i + 1)), ..intent_from(&format!("big_{}", i), &wallet, true) }
+                    .signed(&wallet)
+                    .unwrap()
+            })
+            .collect();
+        intents.extend((0..3).map(|i| {
+            Intent { amount: U256::from(1), nonce: i, ..intent_from(&format!("partner_{}", i), &partner, false) }
+                .signed(&partner)
+                .unwrap()
+        }));
+        
+        let batches = relayer.build_batches(intents.clone()).await.unwrap();
+        let first: Vec<&str> = batches[0].intents.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(batches.len(), 3);
+        assert!(first[..3].iter().all(|id| id.starts_with("partner_")), "{:?}", first);
+        
+        // Everyone else keeps their φ order behind them
+        let mut expected: Vec<Intent> = intents.into_iter().filter(|i| i.id.starts_with("big_")).collect();
+        crate::phi_freeman::phi_sort(&mut expected);
+        let rest: Vec<&str> = batches.iter()
+            .flat_map(|b| b.intents.iter().map(|i| i.id.as_str()))
+            .skip(3)
+            .collect();
+        assert_eq!(rest, expected.iter().map(|i| i.id.as_str()).collect::<Vec<_>>());
+    }
+    
+    #[tokio::test]
+    async fn test_batch_and_metric_savings_agree() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            min_batch_size: 1,
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        let batch = relayer.build_batch(test_batch(25).intents).await.unwrap();
+        
+        let result = relayer.settle_batch(batch.clone()).await.unwrap();
+        let metrics = relayer.get_metrics().await;
+        let preview = relayer.preview_savings(25, false);
+        
+        // Logged estimate, realized result, aggregate metric and preview share one definition
+        for reported in [result.realized_savings_percent, metrics.avg_savings_percent(), preview.combined_percent] {
+            assert!((batch.savings_percent() - reported).abs() < 1e-9, "{} vs {}", batch.savings_percent(), reported);
+        }
+    }
+    
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_lifecycle_spans_carry_ids() {
+        let mined_tx = H256::repeat_byte(0xAB);
+        let receipt = serde_json::to_value(TransactionReceipt { transaction_hash: mined_tx, ..mined_receipt(100) }).unwrap();
+        let (endpoint, _) = spawn_rpc(0, move |method, params| match method {
+            "eth_getTransactionReceipt" if params[0] == serde_json::json!(mined_tx) => Some(receipt.clone()),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        relayer.submit_intent(test_intent("traced_intent")).await.unwrap();
+        let intents = relayer.intent_queue.write().await.drain(..).collect();
+        let batch = relayer.build_batch(intents).await.unwrap();
+        relayer.submissions.write().await.record(batch.canonical_digest(), mined_tx.0).unwrap();
+        relayer.submit_batch_to_ethereum(&batch, false).await.unwrap();
+        
+        assert!(logs_contain("submit_intent{intent_id=traced_intent}"));
+        assert!(logs_contain(&format!("build_batch{{intents=1 batch_id={}}}", batch.id)));
+        assert!(logs_contain(r#"intent_ids=["traced_intent"]"#));
+        assert!(logs_contain(&format!("submit_batch_to_ethereum{{batch_id={} tx_hash={:?}}}", batch.id, mined_tx)));
+    }
+    
+    /// Relayer requiring positive savings, posting blobs at `blob_base_fee`, with `queued` intents
+    async fn savings_gated_relayer(blob_base_fee: u64, queued: u8) -> FisherRelayer {
+        let (endpoint, _) = spawn_rpc(0, move |method, _| match method {
+            "eth_blobBaseFee" => Some(serde_json::json!(format!("{:#x}", blob_base_fee))),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            batch_trigger_size: 1_000,
+            min_savings_percent: Some(0.0),
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        for i in 0..queued {
+            relayer.submit_intent(intent_from(&format!("gated_{}", i), &test_wallet(i + 1), false)).await.unwrap();
+        }
+        
+        relayer
+    }
+    
+    #[tokio::test]
+    async fn test_unprofitable_batch_deferred() {
+        // 10 gwei blob fee: one blob costs far more than 2 standalone transfers at 1 gwei
+        let relayer = savings_gated_relayer(10_000_000_000, 2).await;
+        
+        let projected = relayer.projected_savings_percent(2).await.unwrap();
+        assert!(projected < 0.0, "{}", projected);
+        
+        let result = relayer.process_batch().await;
+        assert!(matches!(result, Err(Error::BatchProcessing(reason)) if reason.contains("Projected savings")));
+        assert_eq!(relayer.queue_len().await, 2);
+        assert_eq!(relayer.get_metrics().await.total_batches, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_large_batch_proceeds_despite_fee_spike() {
+        // The same blob fee spread over a full blob of intents pays off
+        let relayer = savings_gated_relayer(10_000_000_000, 200).await;
+        
+        assert!(relayer.projected_savings_percent(200).await.unwrap() > 0.0);
+        
+        let submission = relayer.process_batch().await.unwrap();
+        assert!(submission.is_complete());
+        assert_eq!(relayer.queue_len().await, 0);
+    }
+    
+    fn ndjson(intents: &[Intent]) -> String {
+        intents.iter().map(|intent| serde_json::to_string(intent).unwrap() + "\n").collect()
+    }
+    
+    #[tokio::test]
+    async fn test_import_ndjson_counts_and_skips_bad_lines() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        let good: Vec<Intent> = (0..3).map(|i| intent_from(&format!("import_{}", i), &test_wallet(0x70 + i), false)).collect();
+        let forged = Intent { amount: U256::from(999), ..intent_from("import_forged", &test_wallet(0x7F), false) };
+        let input = format!(
+            "{}{{\"id\": \"truncated\", \"from\":\n\n{}{}",
+            ndjson(&good[..1]),
+            ndjson(&good[1..]),
+            ndjson(&[forged]),
+        );
+        
+        let stats = relayer.import_ndjson(input.as_bytes()).await.unwrap();
+        
+        assert_eq!(stats, ImportStats { accepted: 3, rejected: 1, malformed: 1 });
+        assert_eq!(relayer.queue_len().await, 3);
+    }
+    
+    #[tokio::test]
+    async fn test_import_ndjson_waits_for_queue_space() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            min_batch_size: 1,
+            batch_trigger_size: 4,
+            max_queue_size: 4,
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let intents: Vec<Intent> = (0..20).map(|i| intent_from(&format!("bulk_{}", i), &test_wallet(0x80 + i), false)).collect();
+        
+        // Direct submissions are refused once the queue is full
+        for intent in &intents[..4] {
+            relayer.intent_queue.write().await.push(intent.clone());
+        }
+        assert!(matches!(
+            relayer.submit_intent(intents[4].clone()).await,
+            Err(Error::QueueFull(4))
+        ));
+        relayer.intent_queue.write().await.clear();
+        
+        // The import instead waits for triggered batches to drain the queue
+        let stats = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            relayer.import_ndjson(ndjson(&intents).as_bytes()),
+        ).await.unwrap().unwrap();
+        
+        assert_eq!(stats.accepted, 20);
+        assert_eq!(relayer.get_metrics().await.max_queue_depth_seen, 4);
+        
+        // Every intent was either batched or is still queued
+        let queued: Vec<String> = relayer.queue_snapshot().await.into_iter().map(|i| i.id).collect();
+        let mut guard = relayer.replay_guard.write().await;
+        assert!(intents.iter().all(|intent| queued.contains(&intent.id) || guard.is_batched(&intent.id)));
+    }
+    
+    #[tokio::test]
+    async fn test_fee_schedule_preview() {
+        let unconfigured = FisherRelayer::new(FisherConfig::default()).unwrap();
+        assert!(matches!(unconfigured.fee_schedule(3).await, Err(Error::Config(_))));
+        
+        let params = crate::phi_optimization::EraFeeParams {
+            base_fee: 1_000_000_000_000_000,
+            fee_growth_rate: 50_000_000_000_000_000,
+            era_duration_secs: 3600,
+            max_fee: None,
+        };
+        let relayer = FisherRelayer::new(FisherConfig { era_fees: Some(params), ..FisherConfig::default() }).unwrap();
+        
+        let schedule = relayer.fee_schedule(3).await.unwrap();
+        assert_eq!(schedule.len(), 4);
+        assert_eq!(schedule[0], (0, u128::from(params.base_fee)));
+        for &(era, fee) in &schedule {
+            assert_eq!(fee, crate::phi_optimization::compound_growth(
+                u128::from(params.base_fee),
+                u128::from(params.fee_growth_rate),
+                era,
+            ));
+        }
+        
+        let zero_length = crate::phi_optimization::EraFeeParams { era_duration_secs: 0, ..params };
+        assert!(FisherRelayer::new(FisherConfig { era_fees: Some(zero_length), ..FisherConfig::default() }).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_propagated_to_fishing_spot() {
+        let (endpoint, requests) = crate::fishing_spot::mock::spawn_recording(0).await;
+        let relayer = FisherRelayer::new(FisherConfig::default())
+            .unwrap()
+            .with_fishing_spot(FishingSpotConfig { endpoint, ..FishingSpotConfig::default() })
+            .unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        
+        let intent = intent_from("cancel_me", &wallet, false);
+        relayer.submit_intent(intent.clone()).await.unwrap();
+        let signature = signed_cancel(&wallet, &intent).await;
+        assert!(relayer.cancel_intent("cancel_me", signature).await.unwrap());
+        
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![("/api/v1/cancel".to_string(), vec!["cancel_me".to_string()])]
+        );
+        assert!(relayer.pending_cancellations.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_retried_while_spot_unreachable() {
+        let (endpoint, requests) = crate::fishing_spot::mock::spawn_recording(1).await;
+        let relayer = FisherRelayer::new(FisherConfig::default())
+            .unwrap()
+            .with_fishing_spot(FishingSpotConfig { endpoint, ..FishingSpotConfig::default() })
+            .unwrap();
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        
+        let intent = intent_from("cancel_me", &wallet, false);
+        relayer.submit_intent(intent.clone()).await.unwrap();
+        let signature = signed_cancel(&wallet, &intent).await;
+        
+        // Not confirmed yet, but no longer batchable
+        let result = relayer.cancel_intent("cancel_me", signature).await;
+        assert!(matches!(result, Err(Error::CancellationPending(ref id)) if id == "cancel_me"), "{:?}", result);
+        assert_eq!(relayer.queue_len().await, 0);
+        assert!(requests.lock().unwrap().is_empty());
+        
+        relayer.batch_tick().await.unwrap();
+        
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![("/api/v1/cancel".to_string(), vec!["cancel_me".to_string()])]
+        );
+        assert!(relayer.pending_cancellations.read().await.is_empty());
+    }
+    
+    /// Relayer collecting `intent` from a fishing spot that never confirms cancellations
+    async fn relayer_serving(intent: &Intent, max_intent_retries: u32) -> FisherRelayer {
+        let (endpoint, _) = crate::fishing_spot::mock::spawn_pending(vec![intent.clone()], true).await;
+        FisherRelayer::new(FisherConfig { max_intent_retries, ..FisherConfig::default() })
+            .unwrap()
+            .with_fishing_spot(FishingSpotConfig { endpoint, ack_max_attempts: 1, ..FishingSpotConfig::default() })
+            .unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_pending_cancellation_not_collected_again() {
+        let wallet = test_wallet(0x41);
+        let intent = intent_from("cancel_me", &wallet, false);
+        let relayer = relayer_serving(&intent, 3).await;
+        
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 1);
+        let result = relayer.cancel_intent("cancel_me", signed_cancel(&wallet, &intent).await).await;
+        assert!(matches!(result, Err(Error::CancellationPending(_))), "{:?}", result);
+        
+        // The spot still serves it, but it stays cancelled
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 0);
+        assert!(relayer.submit_intent(intent).await.is_err());
+        assert_eq!(relayer.queue_len().await, 0);
+        
+        // A direct resubmission counts as a cancellation, not a replay
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.rejections(RejectionReason::Cancelled), 1);
+        assert_eq!(metrics.rejections(RejectionReason::StaleNonce), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_duplicate_intents_queued_once() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallet = test_wallet(0x43);
+        let intent = intent_from("twice", &wallet, false);
+        
+        relayer.submit_intent(intent.clone()).await.unwrap();
+        assert!(matches!(relayer.submit_intent(intent.clone()).await, Err(Error::InvalidIntent(_))));
+        
+        // Another intent for the same nonce would be a double spend too
+        let same_nonce = Intent { id: "twice_again".to_string(), ..intent }.signed(&wallet).unwrap();
+        assert!(matches!(relayer.submit_intent(same_nonce).await, Err(Error::InvalidIntent(_))));
+        
+        assert_eq!(relayer.queue_len().await, 1);
+        assert_eq!(relayer.get_metrics().await.rejections(RejectionReason::StaleNonce), 2);
+        assert!(dropped_ids(&relayer).await.is_empty());
+        
+        let results = relayer.process_batch().await.unwrap().into_results();
+        assert_eq!(results[0].successes, vec![true]);
+    }
+    
+    #[tokio::test]
+    async fn test_reserved_intent_collected_once() {
+        let intent = intent_from("reserved", &test_wallet(0x44), false);
+        let relayer = relayer_serving(&intent, 3).await;
+        
+        // The spot serves it again until it is acknowledged
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 1);
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 0);
+        
+        assert_eq!(relayer.queue_len().await, 1);
+        assert_eq!(relayer.in_flight.read().await.state("reserved"), Some(InFlightState::Collected));
+        assert_eq!(relayer.get_metrics().await.rejections(RejectionReason::StaleNonce), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_unconfirmed_cancellation_given_up_after_retries() {
+        let wallet = test_wallet(0x42);
+        let intent = intent_from("cancel_me", &wallet, false);
+        let relayer = relayer_serving(&intent, 2).await;
+        
+        relayer.submit_intent(intent.clone()).await.unwrap();
+        let result = relayer.cancel_intent("cancel_me", signed_cancel(&wallet, &intent).await).await;
+        assert!(matches!(result, Err(Error::CancellationPending(_))), "{:?}", result);
+        
+        relayer.retry_pending_cancellations().await;
+        assert!(relayer.pending_cancellations.read().await.contains_key("cancel_me"));
+        
+        relayer.retry_pending_cancellations().await;
+        assert!(relayer.pending_cancellations.read().await.is_empty());
+        assert_eq!(dropped_ids(&relayer).await, vec![("cancel_me".to_string(), DropReason::CancellationUnconfirmed(3))]);
+        
+        // Given up on, but still never settled
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 0);
+        assert_eq!(relayer.queue_len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_batch_results_report_encoded_sizes() {
+        use ethers::abi::AbiEncode;
+        
+        for n in [1u8, 10, 100] {
+            let relayer = FisherRelayer::new(FisherConfig {
+                submission_backend: SubmissionBackend::Simulate,
+                min_batch_size: 1,
+                ..FisherConfig::default()
+            }).unwrap();
+            
+            let intents: Vec<Intent> = (1..=n)
+                .map(|i| intent_from(&format!("sized_{}", i), &test_wallet(i), false))
+                .collect();
+            relayer.intent_queue.write().await.extend(intents.clone());
+            
+            let submission = relayer.process_batch().await.unwrap();
+            let result = submission.results().next().unwrap();
+            
+            // Matches the real ABI encoding of the contract call
+            let (payments, signatures) = encode_batch_call(&Batch { intents, ..test_batch(0) });
+            let calldata = SubmitBatchOptimizedCall { payments, signatures }.encode();
+            assert_eq!(result.calldata_bytes, calldata.len() as u64, "{} intents", n);
+            
+            assert!(result.blob_bytes > 0 && result.blob_bytes < result.calldata_bytes, "{} intents", n);
+            assert!(result.compression_ratio.is_finite() && result.compression_ratio > 0.0, "{} intents", n);
+            
+            let metrics = relayer.get_metrics().await;
+            assert_eq!(metrics.avg_compression_ratio(), result.compression_ratio);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_build_batch_handles_empty_and_single_input() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        
+        let empty = relayer.build_batch(Vec::new()).await.unwrap();
+        assert!(empty.intents.is_empty());
+        assert_eq!(empty.phi_score, 0.0);
+        assert_eq!(empty.savings_percent(), 0.0);
+        
+        let single = relayer.build_batch(vec![test_intent("only")]).await.unwrap();
+        assert_eq!(single.intents.len(), 1);
+        assert!(single.phi_score.is_finite() && single.phi_score > 0.0);
+        assert!(single.savings_percent().is_finite());
+    }
+    
+    #[tokio::test]
+    async fn test_gas_report_uses_live_fees() {
+        let (endpoint, _) = spawn_rpc(0, |method, _| match method {
+            "eth_blobBaseFee" => Some(serde_json::json!("0x3")),
+            _ => chain_basics(method),
+        }).await;
+        
+        // No signing key: the report reads fees straight from the RPC
+        let relayer = FisherRelayer::new(FisherConfig { rpc_url: endpoint, ..FisherConfig::default() }).unwrap();
+        let report = relayer.gas_report(500, 3_000.0).await.unwrap();
+        
+        let fees = DataFees { base_fee: U256::from(1_000_000_000u64), blob_base_fee: U256::from(3) };
+        assert_eq!(report, GasReport::new(500, relayer.config.per_tx_baseline_gas, fees, 3_000.0));
+        assert_eq!(report.traditional.gas, 50_000_000);
+        assert!((report.traditional.cost_usd - 150.0).abs() < 1e-6);
+        assert_eq!(report.fisher.cost_wei, U256::from(7_005_000u64 + 1_600_000) * fees.base_fee);
+        
+        let unreachable = FisherRelayer::new(FisherConfig { rpc_url: "http://127.0.0.1:1".to_string(), ..FisherConfig::default() }).unwrap();
+        assert!(unreachable.gas_report(500, 3_000.0).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_concurrent_process_batch_produces_one_batch() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        for i in 1..=20u8 {
+            relayer.intent_queue.write().await.push(intent_from(&format!("race_{}", i), &test_wallet(i), false));
+        }
+        
+        let (first, second) = tokio::join!(relayer.process_batch(), relayer.process_batch());
+        let submitted: Vec<BatchSubmission> = [first, second].into_iter()
+            .filter_map(|outcome| outcome.ok())
+            .filter(|submission| !submission.is_empty())
+            .collect();
+        
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].sub_batches[0].intent_ids.len(), 20);
+        assert_eq!(relayer.get_metrics().await.total_batches, 1);
+        
+        // A run arriving mid-processing is turned away without touching the queue
+        relayer.intent_queue.write().await.push(intent_from("waiting", &test_wallet(0x21), false));
+        let held = relayer.processing_guard.lock().await;
+        assert!(relayer.process_batch().await.unwrap().is_empty());
+        assert_eq!(relayer.queue_len().await, 1);
+        
+        drop(held);
+        assert!(!relayer.process_batch().await.unwrap().is_empty());
+    }
+    
+    fn permit_contract() -> FisherContract<Provider<Http>> {
+        let provider = Provider::<Http>::try_from("http://127.0.0.1:1").unwrap();
+        FisherContract::new(H160::repeat_byte(0xFE), Arc::new(provider))
+    }
+    
+    #[test]
+    fn test_permit_batch_encodes_permit_call() {
+        use ethers::abi::AbiDecode;
+        
+        let permit = crate::Permit {
+            deadline: 1_900_000_000,
+            v: 28,
+            r: alloy_primitives::B256::repeat_byte(0x11),
+            s: alloy_primitives::B256::repeat_byte(0x22),
+        };
+        let mut batch = test_batch(2);
+        batch.intents[0] = batch.intents[0].clone().with_permit(permit);
+        
+        let tx = batch_call(&permit_contract(), &batch).tx;
+        let calldata = tx.data().unwrap();
+        
+        // Permits ride alongside the payments, one slot per intent
+        let call = SubmitBatchWithPermitsCall::decode(calldata).unwrap();
+        let (payments, signatures) = encode_batch_call(&batch);
+        assert_eq!(call.payments, payments);
+        assert_eq!(call.signatures, signatures);
+        assert_eq!(call.permits.len(), 2);
+        assert!(call.permits[0].present);
+        assert_eq!(call.permits[0].deadline, 1_900_000_000u64.into());
+        assert_eq!(call.permits[0].v, 28);
+        assert_eq!(call.permits[0].r, [0x11; 32]);
+        assert_eq!(call.permits[0].s, [0x22; 32]);
+        assert_eq!(call.permits[1], PermitData::default());
+        
+        // The keep's hand-rolled encoding and the size estimate agree
+        assert_eq!(crate::host::encode_batch_calldata(&batch), *calldata);
+        assert_eq!(crate::blob::BlobEncoder::calldata_bytes(&batch), calldata.len());
+    }
+    
+    #[test]
+    fn test_batch_without_permits_keeps_optimized_call() {
+        use ethers::abi::AbiDecode;
+        
+        let batch = test_batch(2);
+        assert!(encode_batch_permits(&batch).is_none());
+        
+        let tx = batch_call(&permit_contract(), &batch).tx;
+        let calldata = tx.data().unwrap();
+        
+        let call = SubmitBatchOptimizedCall::decode(calldata).unwrap();
+        assert_eq!(call.payments, encode_batch_call(&batch).0);
+        assert!(SubmitBatchWithPermitsCall::decode(calldata).is_err());
+        assert_eq!(crate::host::encode_batch_calldata(&batch), *calldata);
+        assert_eq!(crate::blob::BlobEncoder::calldata_bytes(&batch), calldata.len());
+    }
+    
+    #[test]
+    fn test_contract_gas_saved_from_events() {
+        let receipt = TransactionReceipt {
+            logs: vec![Log::default(), batch_submitted_log(1), batch_submitted_log(2)],
+            ..mined_receipt(100)
+        };
+        assert_eq!(contract_gas_saved(&receipt), U256::from(1_700_000));
+        
+        // Older contracts without the event report nothing
+        assert_eq!(contract_gas_saved(&mined_receipt(100)), U256::ZERO);
+    }
+    
+    #[tokio::test]
+    async fn test_williams_savings_measured_from_contract_chunk_size() {
+        let (endpoint, _) = spawn_rpc(0, |method, params| match method {
+            "eth_call" => {
+                // calculateChunkSize(100) -> 25
+                let data = params[0]["input"].as_str().or(params[0]["data"].as_str()).unwrap();
+                assert!(data.ends_with(&format!("{:064x}", 100)), "{}", data);
+                Some(serde_json::json!(format!("0x{:064x}", 25)))
+            }
+            _ => chain_basics(method),
+        }).await;
+        let provider = Arc::new(Provider::<Http>::try_from(endpoint.as_str()).unwrap());
+        
+        let measured = contract_williams_savings(provider, Address::repeat_byte(0xFE), 100).await;
+        assert_eq!(measured, Some(75.0));
+        
+        // No contract answering: no measurement, not the estimate
+        let (endpoint, _) = spawn_rpc(0, |method, _| chain_basics(method)).await;
+        let provider = Arc::new(Provider::<Http>::try_from(endpoint.as_str()).unwrap());
+        assert_eq!(contract_williams_savings(provider, Address::repeat_byte(0xFE), 100).await, None);
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_separate_measured_and_estimated_savings() {
+        let relayer = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let batch = test_batch(10);
+        
+        // Settled off-chain: nothing measured
+        relayer.update_metrics(&batch, &settled_result(&batch)).await;
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.williams_savings.count(), 0);
+        assert_eq!(metrics.phi_savings_estimate.count(), 1);
+        assert!(metrics.summary().contains("Williams compression:  n/a"));
+        
+        let on_chain = BatchResult {
+            williams_savings_percent: Some(60.0),
+            contract_gas_saved: U256::from(850_000),
+            ..settled_result(&batch)
+        };
+        relayer.update_metrics(&batch, &on_chain).await;
+        relayer.update_metrics(&batch, &BatchResult { williams_savings_percent: Some(80.0), ..on_chain }).await;
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.avg_williams_savings(), 70.0);
+        assert_eq!(metrics.total_contract_gas_saved, U256::from(1_700_000));
+        assert!((metrics.avg_phi_savings_estimate() - relayer.preview_savings(10, false).phi_percent).abs() < 1e-9);
+        assert!(metrics.summary().contains("φ-optimization (est.)"));
+    }
+    
+    #[tokio::test]
+    async fn test_paused_relayer_queues_without_batching() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            min_batch_size: 1,
+            batch_trigger_size: 4,
+            max_queue_size: 4,
+            submission_backend: SubmissionBackend::Simulate,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        relayer.pause();
+        assert!(relayer.get_metrics().await.paused);
+        
+        // Intents keep queueing up to the bound, and the size trigger defers
+        for i in 1..=4u8 {
+            relayer.submit_intent(intent_from(&format!("paused_{}", i), &test_wallet(i), false)).await.unwrap();
+        }
+        assert!(matches!(
+            relayer.submit_intent(intent_from("overflow", &test_wallet(0x05), false)).await,
+            Err(Error::QueueFull(4))
+        ));
+        
+        assert!(relayer.process_batch().await.unwrap().is_empty());
+        relayer.batch_tick().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(relayer.queue_len().await, 4);
+        assert_eq!(relayer.get_metrics().await.total_batches, 0);
+        
+        relayer.resume();
+        let submission = relayer.process_batch().await.unwrap();
+        assert_eq!(submission.sub_batches[0].intent_ids.len(), 4);
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.total_batches, 1);
+        assert!(!metrics.paused);
+        assert_eq!(relayer.queue_len().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_settled_batch_charges_fees_by_gas_cap() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            min_gas_price: U256::from(1_000_000_000u64),
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        for (i, gwei) in [2u64, 8, 4].into_iter().enumerate() {
+            let wallet = test_wallet(0x40 + i as u8);
+            let intent = Intent {
+                max_gas_price: Some(U256::from(gwei * 1_000_000_000)),
+                ..intent_from(&format!("capped_{}", gwei), &wallet, false)
+            };
+            relayer.intent_queue.write().await.push(intent.signed(&wallet).unwrap());
+        }
+        
+        let submission = relayer.process_batch().await.unwrap();
+        let result = submission.results().next().unwrap();
+        
+        // One charge per payment, in batch order
+        let batch_ids = &submission.sub_batches[0].intent_ids;
+        assert_eq!(result.fee_charged.len(), 3);
+        let fee_for = |id: &str| result.fee_charged[batch_ids.iter().position(|b| b == id).unwrap()];
+        assert!(fee_for("capped_2") < fee_for("capped_4"));
+        assert!(fee_for("capped_4") < fee_for("capped_8"));
+        
+        let total = result.fee_charged.iter().fold(U256::ZERO, |t, f| t + f);
+        assert!(total <= result.gas_cost_wei && total > U256::ZERO);
+    }
+    
+    #[test]
+    fn test_first_tick_lands_on_jitter_offset() {
+        for now_ms in [0u64, 1_234, 4_999, 1_700_000_000_123] {
+            let delay = first_tick_delay_ms(now_ms, 5_000, 1_500);
+            assert!(delay < 5_000);
+            assert_eq!((now_ms + delay) % 5_000, 1_500);
+        }
+        assert_eq!(first_tick_delay_ms(42, 0, 0), 0);
+    }
+    
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_onchain_batch_info_from_event() {
+        let receipt = TransactionReceipt {
+            logs: vec![Log::default(), batch_submitted_log(1_000)],
+            ..mined_receipt(100)
+        };
+        
+        // Canned event: batchId 7, operationCount 10, gasSaved 850_000
+        let batch = test_batch(10);
+        assert_eq!(onchain_batch_info(&batch, &receipt), (Some(7), Some(10)));
+        assert_eq!(contract_gas_saved(&receipt), U256::from(850_000));
+        assert_eq!(batch_accounting(&receipt).0, U256::from(1_000));
+        assert!(!logs_contain("operations for"));
+        
+        // The contract processed more payments than the batch carried
+        let short = test_batch(3);
+        assert_eq!(onchain_batch_info(&short, &receipt), (Some(7), Some(10)));
+        assert!(logs_contain("processed 10 operations for 3 intents"));
+        
+        // No event, nothing to reconcile against
+        let bare = TransactionReceipt { logs: vec![Log::default()], ..mined_receipt(100) };
+        assert_eq!(onchain_batch_info(&batch, &bare), (None, None));
+    }
+    
+    #[tokio::test]
+    async fn test_canonical_ids_enforced_on_submit_and_cancel() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            enforce_canonical_ids: true,
+            priority_batch_threshold: 0,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallet = test_wallet(0x11);
+        
+        // A user-chosen id that another intent could also claim
+        let spoofed = intent_from("intent_1", &wallet, false);
+        assert!(matches!(relayer.submit_intent(spoofed.clone()).await, Err(Error::InvalidIntent(_))));
+        
+        let canonical_id = spoofed.canonical_id();
+        let intent = Intent { id: canonical_id.clone(), ..spoofed }.signed(&wallet).unwrap();
+        assert_eq!(relayer.submit_intent(intent.clone()).await.unwrap(), canonical_id);
+        
+        // Cancellation goes through the canonical id
+        let signature = signed_cancel(&wallet, &intent).await;
+        assert!(!relayer.cancel_intent("intent_1", signature.clone()).await.unwrap());
+        assert!(relayer.cancel_intent(&canonical_id, signature).await.unwrap());
+        assert!(relayer.queue_snapshot().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_private_inclusion_waits_until_deadline() {
+        let (provider, mock) = Provider::mocked();
+        
+        // Not yet mined at head 101, mined on the next poll
+        mock.push(mined_receipt(102)).unwrap();
+        mock.push(U64::from(101)).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        
+        let receipt = wait_for_private_inclusion(&provider, H256::repeat_byte(0x42), 125, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(receipt.block_number, Some(U64::from(102)));
+        
+        // The relay's deadline passed without the transaction being mined
+        let (provider, mock) = Provider::mocked();
+        mock.push(U64::from(126)).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        
+        let result = wait_for_private_inclusion(&provider, H256::repeat_byte(0x42), 125, Duration::ZERO).await;
+        assert!(matches!(result, Err(Error::Contract(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_private_relay_route_skips_public_mempool() {
+        let (relay_url, relay_requests) = crate::private_relay::mock::spawn_relay(crate::private_relay::mock::acknowledge).await;
+        let (endpoint, methods) = spawn_rpc(0, move |method, params| match method {
+            "eth_blockNumber" => Some(serde_json::json!("0x10")),
+            "eth_getTransactionReceipt" => Some(serde_json::to_value(TransactionReceipt {
+                transaction_hash: serde_json::from_value(params[0].clone()).unwrap(),
+                gas_used: Some(150_000.into()),
+                status: Some(1.into()),
+                ..mined_receipt(0x11)
+            }).unwrap()),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            submission_route: SubmissionRoute::PrivateRelay { url: relay_url },
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        let batch = relayer.build_batch(test_batch(3).intents).await.unwrap();
+        let result = relayer.submit_batch_to_ethereum(&batch, false).await.unwrap();
+        
+        // Only the relay saw the transaction
+        let methods = methods.lock().unwrap().clone();
+        assert!(!methods.iter().any(|m| m == "eth_sendRawTransaction"));
+        
+        let requests = relay_requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let params = &requests[0].body["params"][0];
+        assert_eq!(requests[0].body["method"], "eth_sendPrivateTransaction");
+        assert_eq!(params["maxBlockNumber"], format!("{:#x}", 0x10 + crate::private_relay::MAX_INCLUSION_BLOCKS));
+        
+        // The relayed bytes are the signed batch transaction that was mined
+        let raw = hex::decode(params["tx"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        assert_eq!(result.tx_hash, format!("0x{}", hex::encode(ethers::utils::keccak256(&raw))));
+        let (tx, signature) = ethers::types::transaction::eip2718::TypedTransaction::decode_signed(
+            &ethers::utils::rlp::Rlp::new(&raw),
+        ).unwrap();
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), test_wallet(0x55).address());
+        
+        let empty = FisherConfig {
+            submission_route: SubmissionRoute::PrivateRelay { url: String::new() },
+            ..FisherConfig::default()
+        };
+        assert!(matches!(FisherRelayer::new(empty), Err(Error::Config(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_unconfirmed_broadcast_is_held_until_mined() {
+        let (relay_url, relay_requests) = crate::private_relay::mock::spawn_relay(crate::private_relay::mock::acknowledge).await;
+        let mined = Arc::new(AtomicBool::new(false));
+        let node_mined = Arc::clone(&mined);
+        let (endpoint, methods) = spawn_rpc(0, move |method, params| match method {
+            "eth_blockNumber" => Some(serde_json::json!("0x10")),
+            // The node fails receipt lookups until the batch is mined
+            "eth_getTransactionReceipt" if node_mined.load(Ordering::SeqCst) => Some(serde_json::to_value(TransactionReceipt {
+                transaction_hash: serde_json::from_value(params[0].clone()).unwrap(),
+                status: Some(1.into()),
+                ..mined_receipt(0x11)
+            }).unwrap()),
+            "eth_getTransactionReceipt" => None,
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            submission_route: SubmissionRoute::PrivateRelay { url: relay_url },
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        let batch = relayer.build_batch(test_batch(3).intents).await.unwrap();
+        let batch_hash = batch.canonical_digest();
+        assert!(relayer.finish_batch(batch).await.is_err());
+        
+        // Sent but unconfirmed: neither retried nor forgotten
+        assert!(relayer.intent_queue.read().await.is_empty());
+        assert_eq!(relayer.get_metrics().await.intents_retried, 0);
+        assert!(relayer.submissions.read().await.is_unresolved(&batch_hash));
+        
+        mined.store(true, Ordering::SeqCst);
+        for _ in 0..100 {
+            if relayer.get_metrics().await.total_batches == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        
+        // Settled from the original transaction, never sent twice
+        assert_eq!(relayer.get_metrics().await.total_batches, 1);
+        assert!(!relayer.submissions.read().await.is_unresolved(&batch_hash));
+        assert_eq!(relay_requests.lock().unwrap().len(), 1);
+        assert!(!methods.lock().unwrap().iter().any(|m| m == "eth_sendRawTransaction"));
+    }
+    
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_large_batch_assembly_is_timed() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            assembly_timeout_ms: 1,
+            max_intents_per_tx: 5_000,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        // Signatures are trusted here, so unsigned intents do
+        let intents: Vec<Intent> = (0..50_000u64)
+            .map(|i| Intent::new(
+                format!("bulk_{}", i),
+                Address::with_last_byte((i % 251) as u8 + 2),
+                Address::repeat_byte(0x01),
+                U256::from(i + 1),
+                i % 7 == 0,
+                i,
+                vec![],
+            ))
+            .collect();
+        
+        let batches = relayer.build_batches_unchecked(intents).await.unwrap();
+        assert_eq!(batches.iter().map(|b| b.intents.len()).sum::<usize>(), 50_000);
+        assert!(batches.len() >= 10);
+        
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.assembly_time_ms.count(), 1);
+        assert!(metrics.avg_assembly_time_ms() > 1.0);
+        assert_eq!(metrics.slow_assemblies, 1);
+        assert!(logs_contain("Batch assembly took"));
+        assert!(metrics.summary().contains("(1 slow)"));
+        
+        // Disabled, assembly is still timed but never slow
+        let relayer = FisherRelayer::new(FisherConfig { assembly_timeout_ms: 0, ..FisherConfig::default() }).unwrap();
+        relayer.build_batches_unchecked(test_batch(3).intents).await.unwrap();
+        let metrics = relayer.get_metrics().await;
+        assert_eq!((metrics.assembly_time_ms.count(), metrics.slow_assemblies), (1, 0));
+    }
+    
+    /// Online relayer whose RPC has a contract wallet at 0xC0..C0 answering
+    /// `isValidSignature` with `magic`; also returns the `eth_call` inputs
+    async fn contract_wallet_relayer(
+        magic: [u8; 4],
+        allow_contract_wallets: bool,
+    ) -> (FisherRelayer, Arc<std::sync::Mutex<Vec<String>>>) {
+        let calls: Arc<std::sync::Mutex<Vec<String>>> = Arc::default();
+        let recorded = Arc::clone(&calls);
+        let (endpoint, _) = spawn_rpc(0, move |method, params| match method {
+            "eth_getCode" if params[0] == serde_json::json!(H160::repeat_byte(0xC0)) => {
+                Some(serde_json::json!("0x6080604052"))
+            }
+            "eth_getCode" => Some(serde_json::json!("0x")),
+            "eth_call" => {
+                let input = params[0].get("input").or_else(|| params[0].get("data")).unwrap();
+                recorded.lock().unwrap().push(input.as_str().unwrap().to_string());
+                let mut word = [0u8; 32];
+                word[..4].copy_from_slice(&magic);
+                Some(serde_json::json!(format!("0x{}", hex::encode(word))))
+            }
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            allow_contract_wallets,
+            ..FisherConfig::default()
+        }).unwrap();
+        relayer.init_ethereum().await.unwrap();
+        
+        (relayer, calls)
+    }
+    
+    fn contract_wallet_intent(id: &str) -> Intent {
+        // Safe-style concatenated owner signatures, not a single ECDSA signature
+        Intent::new(
+            id.to_string(),
+            Address::repeat_byte(0xC0),
+            Address::repeat_byte(0x01),
+            U256::from(100),
+            false,
+            0,
+            vec![0xAB; 130],
+        )
+    }
+    
+    #[tokio::test]
+    async fn test_contract_wallet_signature_accepted() {
+        let (relayer, calls) = contract_wallet_relayer(EIP1271_MAGIC_VALUE, true).await;
+        let intent = contract_wallet_intent("safe_1");
+        assert!(!intent.verify_signature());
+        
+        assert_eq!(relayer.submit_intent(intent.clone()).await.unwrap(), "safe_1");
+        
+        // isValidSignature(bytes32,bytes) on the sender, over the EIP-191 digest
+        let calls = calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 1);
+        let digest = ethers::utils::hash_message(intent.signing_hash());
+        assert!(calls[0].starts_with(&format!("0x1626ba7e{}", hex::encode(digest))), "{}", calls[0]);
+        
+        // Batch-time re-verification keeps it too
+        let batches = relayer.build_batches(vec![intent]).await.unwrap();
+        assert_eq!(batches[0].intents.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_contract_wallet_signature_rejected() {
+        // The contract answers with something other than the magic value
+        let (relayer, calls) = contract_wallet_relayer([0xFF; 4], true).await;
+        assert!(matches!(relayer.submit_intent(contract_wallet_intent("safe_1")).await, Err(Error::InvalidSignature)));
+        assert_eq!(calls.lock().unwrap().len(), 1);
+        
+        // An account without code is never asked
+        let eoa = Intent { from: Address::repeat_byte(0xE0), ..contract_wallet_intent("eoa_1") };
+        assert!(matches!(relayer.submit_intent(eoa).await, Err(Error::InvalidSignature)));
+        assert_eq!(calls.lock().unwrap().len(), 1);
+        
+        // Contract wallets are only consulted when enabled
+        let (relayer, calls) = contract_wallet_relayer(EIP1271_MAGIC_VALUE, false).await;
+        assert!(matches!(relayer.submit_intent(contract_wallet_intent("safe_2")).await, Err(Error::InvalidSignature)));
+        assert!(calls.lock().unwrap().is_empty());
+        
+        // And need the RPC connection
+        let offline = FisherRelayer::new(FisherConfig { allow_contract_wallets: true, ..FisherConfig::default() }).unwrap();
+        assert!(matches!(offline.submit_intent(contract_wallet_intent("safe_3")).await, Err(Error::InvalidSignature)));
+    }
+    
+    #[tokio::test]
+    async fn test_batches_split_under_total_value_cap() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            max_batch_total_value: Some(U256::from(250)),
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        // 10 × 100 can't settle in one batch capped at 250
+        let intents: Vec<Intent> = (1..=10u8)
+            .map(|seed| intent_from(&format!("value_{}", seed), &test_wallet(seed), false))
+            .collect();
+        let batches = relayer.build_batches(intents).await.unwrap();
+        
+        assert_eq!(batches.len(), 5);
+        for batch in &batches {
+            let total = batch.intents.iter().fold(U256::ZERO, |sum, intent| sum + intent.amount);
+            assert!(total <= U256::from(250), "batch {} settles {}", batch.id, total);
+        }
+        assert_eq!(batches.iter().map(|b| b.intents.len()).sum::<usize>(), 10);
+        
+        // Uncapped, the same queue is one batch
+        let uncapped = FisherRelayer::new(FisherConfig::default()).unwrap();
+        let intents: Vec<Intent> = (1..=10u8)
+            .map(|seed| intent_from(&format!("value_{}", seed), &test_wallet(seed), false))
+            .collect();
+        assert_eq!(uncapped.build_batches(intents).await.unwrap().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_collected_intents_not_resubmitted_after_restart() {
+        let pending: Vec<Intent> = ["a", "b", "c", "d"].iter().enumerate()
+            .map(|(i, id)| intent_from(id, &test_wallet(0x21 + i as u8), false))
+            .collect();
+        // The mock answers acknowledgements with its pending list, which acknowledges nothing
+        let (endpoint, _) = crate::fishing_spot::mock::spawn_pending(pending, true).await;
+        let log_path = std::env::temp_dir().join(format!("fisher-in-flight-{}.jsonl", uuid::Uuid::new_v4()));
+        
+        let config = FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            in_flight_log_path: Some(log_path.to_string_lossy().into_owned()),
+            ..FisherConfig::default()
+        };
+        let spot = |max_batch_size| FishingSpotConfig {
+            endpoint: endpoint.clone(),
+            max_batch_size,
+            ack_max_attempts: 1,
+            ..FishingSpotConfig::default()
+        };
+        
+        // First run collects two intents and submits them, but dies before the spot acknowledges
+        let relayer = FisherRelayer::new(config.clone()).unwrap().with_fishing_spot(spot(2)).unwrap();
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 2);
+        relayer.process_batch().await.unwrap();
+        assert!(relayer.in_flight.read().await.is_submitted("a"));
+        drop(relayer);
+        
+        // After a restart the spot re-serves them; only the unsubmitted ones are queued
+        let relayer = FisherRelayer::new(config).unwrap().with_fishing_spot(spot(4)).unwrap();
+        assert_eq!(relayer.collect_from_fishing_spot().await.unwrap(), 2);
+        let queued: Vec<String> = relayer.intent_queue.read().await.iter().map(|i| i.id.clone()).collect();
+        assert_eq!(queued, vec!["c", "d"]);
+        
+        let in_flight = relayer.in_flight.read().await;
+        assert!(in_flight.is_submitted("a") && in_flight.is_submitted("b"));
+        assert_eq!(in_flight.state("c"), Some(InFlightState::Collected));
+        drop(in_flight);
+        
+        std::fs::remove_file(log_path).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_metrics_stream_reports_progress() {
+        use futures::StreamExt;
+        
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            ..FisherConfig::default()
+        }).unwrap();
+        let mut stream = Box::pin(relayer.metrics_stream(Duration::from_millis(5)));
+        
+        assert_eq!(stream.next().await.unwrap().total_batches, 0);
+        
+        let mut last = 0;
+        for round in 1..=3u8 {
+            relayer.queue_intent(intent_from(&format!("round_{}", round), &test_wallet(0x30 + round), false)).await.unwrap();
+            relayer.process_batch().await.unwrap();
+            
+            // Snapshots never go backwards and catch up with each batch
+            loop {
+                let snapshot = stream.next().await.unwrap();
+                assert!(snapshot.total_batches >= last);
+                last = snapshot.total_batches;
+                if last == round as u64 {
+                    assert_eq!(snapshot.intents_batched(), round as u64);
+                    break;
+                }
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_rejections_counted_by_reason() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            max_intents_per_sender_per_window: 2,
+            max_queue_size: 3,
+            ..FisherConfig::default()
+        }).unwrap();
+        let counts = || async {
+            let metrics = relayer.get_metrics().await;
+            RejectionReason::ALL.map(|reason| metrics.rejections(reason))
+        };
+        
+        // [bad_signature, stale_nonce, rate_limited, invalid, queue_full, cancelled]
+        let mut tampered = test_intent("tampered");
+        tampered.amount = U256::from(1_000_000);
+        assert!(relayer.submit_intent(tampered).await.is_err());
+        assert_eq!(counts().await, [1, 0, 0, 0, 0, 0]);
+        
+        let wallet = test_wallet(0x41);
+        let zero = Intent { amount: U256::ZERO, ..intent_from("zero", &wallet, false) }.signed(&wallet).unwrap();
+        assert!(relayer.submit_intent(zero).await.is_err());
+        assert_eq!(counts().await, [1, 0, 0, 1, 0, 0]);
+        
+        let batched = intent_from("batched", &wallet, false);
+        relayer.submit_intent(batched.clone()).await.unwrap();
+        relayer.process_batch().await.unwrap();
+        assert!(relayer.submit_intent(batched).await.is_err());
+        assert_eq!(counts().await, [1, 1, 0, 1, 0, 0]);
+        
+        let sender = test_wallet(0x42);
+        let from_sender = |nonce| {
+            Intent { nonce, ..intent_from(&format!("sender_{}", nonce), &sender, false) }.signed(&sender).unwrap()
+        };
+        relayer.submit_intent(from_sender(0)).await.unwrap();
+        relayer.submit_intent(from_sender(1)).await.unwrap();
+        assert!(matches!(relayer.submit_intent(from_sender(2)).await, Err(Error::RateLimited(_))));
+        assert_eq!(counts().await, [1, 1, 1, 1, 0, 0]);
+        
+        let other = test_wallet(0x43);
+        relayer.submit_intent(intent_from("third", &other, false)).await.unwrap();
+        let overflow = Intent { nonce: 1, ..intent_from("overflow", &other, false) }.signed(&other).unwrap();
+        assert!(matches!(relayer.submit_intent(overflow).await, Err(Error::QueueFull(3))));
+        assert_eq!(counts().await, [1, 1, 1, 1, 1, 0]);
+        
+        assert_eq!(relayer.get_metrics().await.total_rejections(), 5);
+    }
+    
+    #[test]
+    fn test_max_blobs_checked_at_startup() {
+        let config = |max_blobs_per_tx, chain_id| FisherConfig { max_blobs_per_tx, chain_id, ..FisherConfig::default() };
+        
+        assert!(FisherRelayer::new(config(9, Some(1))).is_ok());
+        assert!(matches!(FisherRelayer::new(config(10, Some(1))), Err(Error::Config(_))));
+        assert!(matches!(FisherRelayer::new(config(0, None)), Err(Error::Config(_))));
+    }
+    
+    /// Priority intent from `wallet` bidding `gwei` as its max gas price
+    fn bidding(id: &str, wallet: &LocalWallet, gwei: u64) -> Intent {
+        let max_gas_price = Some(U256::from(gwei * 1_000_000_000));
+        Intent { max_gas_price, ..intent_from(id, wallet, true) }.signed(wallet).unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_unearned_priority_downgraded() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            priority_policy: PriorityPolicy::Downgrade { min_premium_wei: U256::from(10_000_000_000u64) },
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        // A cheap priority flag neither fast-lanes nor skips min_batch_size
+        let cheap = bidding("cheap", &test_wallet(0x51), 1);
+        relayer.submit_intent(cheap.clone()).await.unwrap();
+        assert!(matches!(relayer.process_batch().await, Err(Error::BatchProcessing(_))));
+        let queued = relayer.intent_queue.read().await;
+        assert_eq!(queued.len(), 1);
+        assert!(queued[0].priority, "the signed flag is left as submitted");
+        drop(queued);
+        
+        // Paying the premium keeps priority
+        relayer.queue_intent(bidding("paid", &test_wallet(0x52), 20)).await.unwrap();
+        let submission = relayer.process_batch().await.unwrap();
+        assert_eq!(submission.sub_batches[0].intent_ids.len(), 2);
+        assert!(relayer.intent_queue.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_unearned_priority_rejected() {
+        let partner = test_wallet(0x53);
+        let relayer = FisherRelayer::new(FisherConfig {
+            priority_policy: PriorityPolicy::Reject { min_premium_wei: U256::from(10_000_000_000u64) },
+            priority_allowlist: [Address::from_slice(partner.address().as_bytes())].into(),
+            priority_batch_threshold: 0,
+            ..FisherConfig::default()
+        }).unwrap();
+        
+        let cheap = bidding("cheap", &test_wallet(0x54), 1);
+        assert!(matches!(relayer.submit_intent(cheap).await, Err(Error::InvalidIntent(_))));
+        assert_eq!(relayer.get_metrics().await.rejections(RejectionReason::Invalid), 1);
+        
+        relayer.submit_intent(bidding("paid", &test_wallet(0x55), 20)).await.unwrap();
+        relayer.submit_intent(intent_from("partner", &partner, true)).await.unwrap();
+        assert_eq!(relayer.intent_queue.read().await.len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_restart_finalizes_mined_batch() {
+        let mined_tx = H256::repeat_byte(0xAC);
+        let receipt = serde_json::to_value(TransactionReceipt {
+            transaction_hash: mined_tx,
+            gas_used: Some(150_000.into()),
+            ..mined_receipt(100)
+        }).unwrap();
+        let (endpoint, methods) = spawn_rpc(0, move |method, params| match method {
+            "eth_chainId" => Some(serde_json::json!("0x1")),
+            "eth_getTransactionReceipt" if params[0] == serde_json::json!(mined_tx) => Some(receipt.clone()),
+            _ => None,
+        }).await;
+        
+        let log_path = std::env::temp_dir().join(format!("fisher-submissions-{}.jsonl", uuid::Uuid::new_v4()));
+        let config = FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            submission_log_path: Some(log_path.to_string_lossy().into_owned()),
+            ..FisherConfig::default()
+        };
+        
+        // The crashed relayer recorded and sent the batch, then died before the receipt
+        let crashed = FisherRelayer::new(config.clone()).unwrap();
+        let batch = crashed.build_batch(test_batch(3).intents).await.unwrap();
+        crashed.submissions.write().await.record_batch(&batch, mined_tx.0, None).unwrap();
+        drop(crashed);
+        
+        // Nobody resubmits the intents: the restarted relayer finds the batch itself
+        let mut restarted = FisherRelayer::new(config.clone()).unwrap();
+        restarted.init_ethereum().await.unwrap();
+        
+        let metrics = restarted.get_metrics().await;
+        assert_eq!(metrics.total_batches, 1);
+        assert_eq!(metrics.total_intents, 3);
+        assert!(restarted.intent_queue.read().await.is_empty());
+        assert!(!methods.lock().unwrap().iter().any(|m| m == "eth_sendRawTransaction"));
+        
+        // Resolved, so a second restart leaves it alone
+        assert_eq!(restarted.submissions.read().await.pending().count(), 0);
+        let mut again = FisherRelayer::new(config).unwrap();
+        again.init_ethereum().await.unwrap();
+        assert_eq!(again.get_metrics().await.total_batches, 0);
+        
+        std::fs::remove_file(log_path).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_restart_requeues_dropped_batch() {
+        let (endpoint, methods) = spawn_rpc(0, |method, _| match method {
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Some(serde_json::Value::Null),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            ..FisherConfig::default()
+        }).unwrap();
+        let batch = relayer.build_batch(test_batch(2).intents).await.unwrap();
+        relayer.submissions.write().await.record_batch(&batch, [0xCD; 32], None).unwrap();
+        
+        relayer.init_ethereum().await.unwrap();
+        
+        assert_eq!(relayer.intent_queue.read().await.len(), 2);
+        assert_eq!(relayer.submissions.read().await.pending().count(), 0);
+        assert_eq!(relayer.get_metrics().await.total_batches, 0);
+        assert!(!methods.lock().unwrap().iter().any(|m| m == "eth_sendRawTransaction"));
+    }
+    
+    #[tokio::test]
+    async fn test_restart_retries_reverted_batch() {
+        let reverted_tx = H256::repeat_byte(0xAD);
+        let receipt = serde_json::to_value(TransactionReceipt {
+            transaction_hash: reverted_tx,
+            status: Some(U64::zero()),
+            ..mined_receipt(100)
+        }).unwrap();
+        let (endpoint, methods) = spawn_rpc(0, move |method, params| match method {
+            "eth_getTransactionReceipt" if params[0] == serde_json::json!(reverted_tx) => Some(receipt.clone()),
+            _ => chain_basics(method),
+        }).await;
+        
+        let mut relayer = FisherRelayer::new(FisherConfig {
+            rpc_url: endpoint,
+            private_key: Some(hex::encode([0x55; 32])),
+            ..FisherConfig::default()
+        }).unwrap();
+        let batch = relayer.build_batch(test_batch(2).intents).await.unwrap();
+        relayer.submissions.write().await.record_batch(&batch, reverted_tx.0, None).unwrap();
+        
+        relayer.init_ethereum().await.unwrap();
+        
+        // Nothing settled: the intents are retried rather than finalized
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.total_batches, 0);
+        assert_eq!(metrics.intents_retried, 2);
+        assert_eq!(relayer.intent_queue.read().await.len(), 2);
+        assert_eq!(relayer.submissions.read().await.pending().count(), 0);
+        assert!(!methods.lock().unwrap().iter().any(|m| m == "eth_sendRawTransaction"));
+    }
+    
+    #[tokio::test]
+    async fn test_trigger_flood_spawns_one_run() {
+        let relayer = FisherRelayer::new(FisherConfig {
+            submission_backend: SubmissionBackend::Simulate,
+            min_batch_size: 1,
+            batch_trigger_size: 5,
+            batch_trigger_debounce_ms: 10,
+            ..FisherConfig::default()
+        }).unwrap();
+        let wallets: Vec<LocalWallet> = (1..=40u8).map(test_wallet).collect();
+        let flood = |range: std::ops::Range<usize>| {
+            let (relayer, wallets) = (&relayer, &wallets);
+            futures::future::join_all(range.map(move |i| async move {
+                relayer.submit_intent(intent_from(&format!("flood_{}", i), &wallets[i], false)).await.unwrap();
+            }))
+        };
+        let drained = || async {
+            for _ in 0..200 {
+                if relayer.intent_queue.read().await.is_empty() {
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+            panic!("queue never drained");
+        };
+        
+        // A slow run holds the processing guard while triggers pour in
+        let held = relayer.processing_guard.lock().await;
+        flood(0..20).await;
+        let metrics = relayer.get_metrics().await;
+        assert_eq!((metrics.triggered_runs, metrics.coalesced_triggers), (1, 15));
+        
+        // The one scheduled run drains the queue once the slow run finishes
+        drop(held);
+        drained().await;
+        assert_eq!(relayer.get_metrics().await.total_batches, 1);
+        
+        // The next burst may schedule a run of its own
+        flood(20..40).await;
+        drained().await;
+        let metrics = relayer.get_metrics().await;
+        assert_eq!(metrics.triggered_runs, 2);
+        assert_eq!(metrics.total_batches, 2);
+        assert_eq!(metrics.total_intents, 40);
     }
 }
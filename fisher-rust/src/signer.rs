@@ -0,0 +1,277 @@
+//! Pluggable transaction signing backends
+//!
+//! The relayer only needs something that can sign a transaction and report
+//! its address, so the key can live outside the process: a local wallet for
+//! development, or a KMS / hardware key (feature `kms`) where compliance
+//! forbids raw keys in config. Contract calls go through [`SharedSigner`],
+//! which adapts any [`Signer`] to ethers' `SignerMiddleware`.
+
+use crate::{Error, Result};
+use alloy_primitives::Address;
+use async_trait::async_trait;
+use ethers::signers::{LocalWallet, Signer as EthersSigner};
+use ethers::types::{transaction::eip2718::TypedTransaction, transaction::eip712::Eip712, Signature, H160};
+use std::sync::Arc;
+
+/// Signing client used for contract calls
+#[cfg(not(target_arch = "wasm32"))]
+pub type SignerClient = ethers::middleware::SignerMiddleware<
+    ethers::providers::Provider<ethers::providers::Http>,
+    SharedSigner,
+>;
+
+/// Backend that signs relayer transactions
+#[async_trait]
+pub trait Signer: std::fmt::Debug + Send + Sync {
+    /// Address transactions are sent from
+    fn address(&self) -> Address;
+    
+    /// Sign `tx` (which carries its chain ID), returning an EIP-155 signature
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature>;
+}
+
+/// Signer holding the private key in process memory
+#[derive(Debug, Clone)]
+pub struct LocalWalletSigner {
+    wallet: LocalWallet,
+}
+
+impl LocalWalletSigner {
+    /// Wrap an existing wallet
+    pub fn new(wallet: LocalWallet) -> Self {
+        Self { wallet }
+    }
+    
+    /// Parse a hex-encoded private key
+    pub fn from_private_key(private_key: &str) -> Result<Self> {
+        let wallet = private_key
+            .parse()
+            .map_err(|e| Error::Config(format!("Invalid private key: {}", e)))?;
+        
+        Ok(Self::new(wallet))
+    }
+}
+
+#[async_trait]
+impl Signer for LocalWalletSigner {
+    fn address(&self) -> Address {
+        Address::from_slice(self.wallet.address().as_bytes())
+    }
+    
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        self.wallet
+            .sign_transaction_sync(tx)
+            .map_err(|e| Error::Signing(e.to_string()))
+    }
+}
+
+/// Remote key service that signs digests with a secp256k1 key it never exports
+#[cfg(feature = "kms")]
+#[async_trait]
+pub trait KmsClient: std::fmt::Debug + Send + Sync {
+    /// Sign a 32-byte digest with `key_id`, returning a DER-encoded ECDSA signature
+    async fn sign_digest(&self, key_id: &str, digest: [u8; 32]) -> Result<Vec<u8>>;
+}
+
+/// Signer backed by a KMS or hardware key
+///
+/// The service returns a bare DER `(r, s)`; the signature is normalized to
+/// low-s and the recovery ID found by recovering against the key's address.
+#[cfg(feature = "kms")]
+#[derive(Debug, Clone)]
+pub struct KmsSigner {
+    client: Arc<dyn KmsClient>,
+    key_id: String,
+    address: Address,
+}
+
+#[cfg(feature = "kms")]
+impl KmsSigner {
+    /// Create a signer for `key_id`, given the key's SEC1-encoded public key
+    pub fn new(client: Arc<dyn KmsClient>, key_id: impl Into<String>, public_key: &[u8]) -> Result<Self> {
+        let public_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|e| Error::Config(format!("Invalid KMS public key: {}", e)))?;
+        let address = ethers::utils::public_key_to_address(&public_key);
+        
+        Ok(Self {
+            client,
+            key_id: key_id.into(),
+            address: Address::from_slice(address.as_bytes()),
+        })
+    }
+}
+
+#[cfg(feature = "kms")]
+#[async_trait]
+impl Signer for KmsSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+    
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        let chain_id = tx.chain_id()
+            .ok_or_else(|| Error::Signing("Transaction has no chain ID".to_string()))?
+            .as_u64();
+        let sighash = tx.sighash();
+        
+        let der = self.client.sign_digest(&self.key_id, sighash.0).await?;
+        let signature = k256::ecdsa::Signature::from_der(&der)
+            .map_err(|e| Error::Signing(format!("Malformed KMS signature: {}", e)))?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+        let (r, s) = signature.split_bytes();
+        
+        for recovery_id in 0..2u8 {
+            let candidate = Signature {
+                r: ethers::types::U256::from_big_endian(&r),
+                s: ethers::types::U256::from_big_endian(&s),
+                v: recovery_id as u64 + 27,
+            };
+            
+            if candidate.recover(sighash).ok().map(|a| a.0) == Some(self.address.0 .0) {
+                return Ok(Signature {
+                    v: ethers::signers::to_eip155_v(recovery_id, chain_id),
+                    ..candidate
+                });
+            }
+        }
+        
+        Err(Error::Signing(format!("KMS key {} did not sign as {}", self.key_id, self.address)))
+    }
+}
+
+/// Any [`Signer`] as an ethers signer, for use with `SignerMiddleware`
+#[derive(Debug, Clone)]
+pub struct SharedSigner {
+    inner: Arc<dyn Signer>,
+    chain_id: u64,
+}
+
+impl SharedSigner {
+    /// Wrap a signing backend
+    pub fn new(inner: Arc<dyn Signer>) -> Self {
+        Self { inner, chain_id: 1 }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl EthersSigner for SharedSigner {
+    type Error = Error;
+    
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(&self, _message: S) -> Result<Signature> {
+        Err(Error::Signing("Message signing is not supported by relayer signers".to_string()))
+    }
+    
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        // The sighash must commit to the chain ID the backend encodes into `v`
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+        
+        self.inner.sign_transaction(&tx).await
+    }
+    
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(&self, _payload: &T) -> Result<Signature> {
+        Err(Error::Signing("Typed data signing is not supported by relayer signers".to_string()))
+    }
+    
+    fn address(&self) -> H160 {
+        H160::from_slice(self.inner.address().as_slice())
+    }
+    
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+    
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        Self { chain_id: chain_id.into(), ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::TransactionRequest;
+    
+    fn wallet() -> LocalWallet {
+        LocalWallet::from_bytes(&[0x42; 32]).unwrap()
+    }
+    
+    fn transfer() -> TypedTransaction {
+        TransactionRequest::new()
+            .to(H160::repeat_byte(0xFE))
+            .nonce(3)
+            .gas(21_000)
+            .gas_price(1_000_000_000u64)
+            .into()
+    }
+    
+    #[tokio::test]
+    async fn test_shared_signer_applies_chain_id() {
+        let backend: Arc<dyn Signer> = Arc::new(LocalWalletSigner::new(wallet()));
+        let signer = SharedSigner::new(backend).with_chain_id(10u64);
+        assert_eq!(EthersSigner::address(&signer), wallet().address());
+        
+        let signature = EthersSigner::sign_transaction(&signer, &transfer()).await.unwrap();
+        
+        // EIP-155 `v` for chain 10, over the chain-10 sighash
+        let mut tx = transfer();
+        tx.set_chain_id(10u64);
+        assert!(signature.v == 55 || signature.v == 56);
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), wallet().address());
+        
+        assert!(signer.sign_message("hello").await.is_err());
+    }
+    
+    #[test]
+    fn test_invalid_private_key() {
+        assert!(matches!(LocalWalletSigner::from_private_key("0xnope"), Err(Error::Config(_))));
+        
+        let signer = LocalWalletSigner::from_private_key(&hex::encode([0x42; 32])).unwrap();
+        assert_eq!(signer.address().as_slice(), wallet().address().as_bytes());
+    }
+    
+    /// KMS stand-in that returns the high-s twin of each signature
+    #[cfg(feature = "kms")]
+    #[derive(Debug)]
+    struct MockKms {
+        key: k256::ecdsa::SigningKey,
+    }
+    
+    #[cfg(feature = "kms")]
+    #[async_trait]
+    impl KmsClient for MockKms {
+        async fn sign_digest(&self, key_id: &str, digest: [u8; 32]) -> Result<Vec<u8>> {
+            assert_eq!(key_id, "alias/fisher");
+            
+            let (signature, _) = self.key.sign_prehash_recoverable(&digest).unwrap();
+            let (r, s) = signature.split_scalars();
+            let high_s = k256::ecdsa::Signature::from_scalars(r, -*s).unwrap();
+            
+            Ok(high_s.to_der().as_bytes().to_vec())
+        }
+    }
+    
+    #[cfg(feature = "kms")]
+    #[tokio::test]
+    async fn test_kms_signer_normalizes_and_recovers() {
+        let key = k256::ecdsa::SigningKey::from_bytes(&[0x42; 32].into()).unwrap();
+        let public_key = key.verifying_key().to_encoded_point(false);
+        let kms = KmsSigner::new(Arc::new(MockKms { key }), "alias/fisher", public_key.as_bytes()).unwrap();
+        assert_eq!(kms.address().as_slice(), wallet().address().as_bytes());
+        
+        let mut tx = transfer();
+        tx.set_chain_id(1u64);
+        let signature = Signer::sign_transaction(&kms, &tx).await.unwrap();
+        
+        // Same canonical signature a local wallet produces for the same key
+        let expected = wallet().sign_transaction_sync(&tx).unwrap();
+        assert_eq!(signature, expected);
+        
+        assert!(matches!(
+            Signer::sign_transaction(&kms, &transfer()).await,
+            Err(Error::Signing(_))
+        ));
+    }
+}
@@ -8,30 +8,47 @@
 use crate::{Result, Error};
 use alloy_primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Seconds in a (365-day) year, for annualizing reward rates
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Completed eras averaged for the trailing APY by default
+pub const DEFAULT_APY_HISTORY_ERAS: u64 = 7;
+
+/// How often the era watcher checks for a new era when auto-claiming
+pub const ERA_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
 
 #[cfg(not(target_arch = "wasm32"))]
-use ethers::{
-    prelude::*,
-    contract::abigen,
-};
+use ethers::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bindings::*;
 
 // Generate bindings for EVVM FisherStaking contract
 #[cfg(not(target_arch = "wasm32"))]
-abigen!(
-    FisherStakingContract,
-    r#"[
-        function stakeFisher(uint256 amount) external
-        function unstakeFisher(uint256 amount) external
-        function claimFisherRewards(uint256 era) external returns (uint256)
-        function getStakedAmount(address fisher) external view returns (uint256)
-        function getPendingRewards(address fisher, uint256 era) external view returns (uint256)
-        function getCurrentEra() external view returns (uint256)
-        function isFisherActive(address fisher) external view returns (bool)
-        event FisherStaked(address indexed fisher, uint256 amount, uint256 era)
-        event FisherUnstaked(address indexed fisher, uint256 amount)
-        event RewardsClaimed(address indexed fisher, uint256 era, uint256 amount)
-    ]"#
-);
+mod bindings {
+    use ethers::contract::abigen;
+
+    abigen!(
+        FisherStakingContract,
+        r#"[
+            function stakeFisher(uint256 amount) external
+            function unstakeFisher(uint256 amount) external
+            function claimFisherRewards(uint256 era) external returns (uint256)
+            function getStakedAmount(address fisher) external view returns (uint256)
+            function getPendingRewards(address fisher, uint256 era) external view returns (uint256)
+            function getCurrentEra() external view returns (uint256)
+            function eraDuration() external view returns (uint256)
+            function isFisherActive(address fisher) external view returns (bool)
+            event FisherStaked(address indexed fisher, uint256 amount, uint256 era)
+            event FisherUnstaked(address indexed fisher, uint256 amount)
+            event RewardsClaimed(address indexed fisher, uint256 era, uint256 amount)
+        ]"#
+    );
+}
 
 /// Fisher staking manager
 #[derive(Clone)]
@@ -45,8 +62,71 @@ pub struct FisherStaking {
     /// Minimum stake required
     pub min_stake: U256,
     
+    /// Completed eras averaged for the trailing APY
+    pub apy_history_eras: u64,
+    
+    /// Claim completed eras' rewards automatically when a new era starts
+    pub auto_claim: bool,
+    
+    /// Last era whose rewards were claimed (shared across clones)
+    last_claimed_era: Arc<Mutex<Option<u64>>>,
+    
+    /// Amounts auto-claim collected for recent eras, for the trailing APY
+    /// (`getPendingRewards` reads 0 once an era is claimed)
+    claimed_rewards: Arc<Mutex<BTreeMap<u64, U256>>>,
+    
+    /// Automatic restake/unstake policy (optional)
+    pub policy: Option<StakingPolicy>,
+    
     #[cfg(not(target_arch = "wasm32"))]
-    contract: Option<FisherStakingContract<SignerMiddleware<Provider<Http>, LocalWallet>>>,
+    contract: Option<FisherStakingContract<crate::signer::SignerClient>>,
+}
+
+/// Automatic stake management policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakingPolicy {
+    /// Restake rewards while the stake is below this amount
+    pub target_stake: U256,
+    
+    /// Unstake anything above this amount (never below `min_stake`)
+    pub max_stake: U256,
+    
+    /// Whether claimed rewards may be restaked
+    pub restake_rewards: bool,
+}
+
+/// Stake adjustment chosen by a [`StakingPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyAction {
+    /// Stake is within policy bounds
+    Hold,
+    
+    /// Stake this many more tokens
+    Stake(U256),
+    
+    /// Unstake this many tokens
+    Unstake(U256),
+}
+
+impl StakingPolicy {
+    /// Decide how to adjust `staked` given `rewards` available to restake
+    pub fn action(&self, staked: U256, min_stake: U256, rewards: U256) -> PolicyAction {
+        if staked > self.max_stake {
+            // Never drop below the activation threshold
+            let floor = self.max_stake.max(min_stake);
+            let excess = staked.saturating_sub(floor);
+            return if excess.is_zero() { PolicyAction::Hold } else { PolicyAction::Unstake(excess) };
+        }
+        
+        if self.restake_rewards && staked < self.target_stake {
+            let amount = rewards.min(self.target_stake - staked);
+            if !amount.is_zero() {
+                return PolicyAction::Stake(amount);
+            }
+        }
+        
+        PolicyAction::Hold
+    }
 }
 
 /// Staking status information
@@ -64,8 +144,14 @@ pub struct StakingStatus {
     /// Pending rewards
     pub pending_rewards: U256,
     
-    /// Estimated APY
+    /// Naive APY extrapolated from the current era's pending rewards
     pub estimated_apy: f64,
+    
+    /// APY from the average reward over recent completed eras
+    pub trailing_apy: f64,
+    
+    /// Era length reported by the staking contract (seconds)
+    pub era_duration_secs: u64,
 }
 
 impl FisherStaking {
@@ -79,16 +165,43 @@ impl FisherStaking {
             staking_address,
             fisher_address,
             min_stake,
+            apy_history_eras: DEFAULT_APY_HISTORY_ERAS,
+            auto_claim: false,
+            last_claimed_era: Arc::new(Mutex::new(None)),
+            claimed_rewards: Arc::new(Mutex::new(BTreeMap::new())),
+            policy: None,
             #[cfg(not(target_arch = "wasm32"))]
             contract: None,
         }
     }
     
+    /// Enable auto-claim, resuming after `last_claimed_era` (persisted by the operator)
+    pub fn with_auto_claim(mut self, last_claimed_era: Option<u64>) -> Self {
+        self.auto_claim = true;
+        self.last_claimed_era = Arc::new(Mutex::new(last_claimed_era));
+        self
+    }
+    
+    /// Enable automatic restake/unstake
+    ///
+    /// The relayer applies the policy on every era poll. Only rewards claimed
+    /// by auto-claim are restaked, so without [`FisherStaking::with_auto_claim`]
+    /// the policy just trims stake above `max_stake`.
+    pub fn with_policy(mut self, policy: StakingPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+    
+    /// Last era whose rewards were claimed by auto-claim
+    pub async fn last_claimed_era(&self) -> Option<u64> {
+        *self.last_claimed_era.lock().await
+    }
+    
     /// Initialize contract connection
     #[cfg(not(target_arch = "wasm32"))]
     pub fn init_contract(
         &mut self,
-        wallet: SignerMiddleware<Provider<Http>, LocalWallet>,
+        wallet: crate::signer::SignerClient,
     ) {
         let contract = FisherStakingContract::new(
             H160::from_slice(self.staking_address.as_slice()),
@@ -150,7 +263,7 @@ impl FisherStaking {
             .map_err(|e| Error::Contract(format!("Transaction failed: {}", e)))?
             .ok_or_else(|| Error::Contract("No receipt".to_string()))?;
         
-        // Extract reward amount from logs
+        // Extract reward amount from logs (a reverted claim has none)
         let reward = self.extract_reward_from_receipt(&receipt)?;
         
         log::info!("✅ Claimed {} tokens for era {}", reward, era);
@@ -158,6 +271,121 @@ impl FisherStaking {
         Ok(reward)
     }
     
+    /// Apply the staking policy, restaking up to `rewards` or unstaking the excess
+    ///
+    /// Returns the action taken (`Hold` if no policy is configured).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn enforce_policy(&self, rewards: U256) -> Result<PolicyAction> {
+        let Some(policy) = &self.policy else {
+            return Ok(PolicyAction::Hold);
+        };
+        let contract = self.contract.as_ref()
+            .ok_or_else(|| Error::Other("Contract not initialized".to_string()))?;
+        
+        let action = plan_policy(
+            contract,
+            H160::from_slice(self.fisher_address.as_slice()),
+            policy,
+            self.min_stake,
+            rewards,
+        ).await?;
+        
+        let call = match action {
+            PolicyAction::Hold => return Ok(action),
+            PolicyAction::Stake(amount) => {
+                log::info!("📈 Restaking {} (target {})", amount, policy.target_stake);
+                contract.stake_fisher(ethers::types::U256(amount.into_limbs()))
+            }
+            PolicyAction::Unstake(amount) => {
+                log::info!("📉 Unstaking {} (cap {})", amount, policy.max_stake);
+                contract.unstake_fisher(ethers::types::U256(amount.into_limbs()))
+            }
+        };
+        
+        let tx = call
+            .send()
+            .await
+            .map_err(|e| Error::Contract(format!("Policy transaction failed: {}", e)))?;
+        
+        tx.await
+            .map_err(|e| Error::Contract(format!("Transaction failed: {}", e)))?
+            .ok_or_else(|| Error::Contract("No receipt".to_string()))?;
+        
+        Ok(action)
+    }
+    
+    /// Claim every completed era not yet claimed
+    ///
+    /// Called by the era watcher. Eras with no pending rewards (e.g. claimed
+    /// before a restart) are marked claimed without sending a transaction.
+    /// Returns the eras claimed, plus the error that stopped the run early:
+    /// claims that went through before it still count.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn claim_completed_eras(&self) -> (Vec<(u64, U256)>, Option<Error>) {
+        let Some(contract) = self.contract.as_ref() else {
+            return (Vec::new(), Some(Error::Other("Contract not initialized".to_string())));
+        };
+        let fisher_h160 = H160::from_slice(self.fisher_address.as_slice());
+        
+        let current_era = match contract.get_current_era().call().await {
+            Ok(era) => era.as_u64(),
+            Err(e) => return (Vec::new(), Some(Error::Contract(format!("Failed to get era: {}", e)))),
+        };
+        
+        self.claim_eras_before(current_era, |era| async move {
+            let pending = contract
+                .get_pending_rewards(fisher_h160, ethers::types::U256::from(era))
+                .call()
+                .await
+                .map_err(|e| Error::Contract(format!("Failed to get rewards: {}", e)))?;
+            
+            if pending.is_zero() {
+                log::info!("⏭️  Era {} has no pending rewards, skipping claim", era);
+                return Ok(U256::ZERO);
+            }
+            
+            self.claim_rewards(era).await
+        }).await
+    }
+    
+    /// Claim each era from the last claimed one up to (excluding) `current_era`
+    ///
+    /// Holds the claim lock throughout so concurrent callers can't double-claim.
+    /// A failed claim stops the run and is retried on the next call; the eras
+    /// claimed before it are returned alongside the error.
+    async fn claim_eras_before<F, Fut>(&self, current_era: u64, mut claim: F) -> (Vec<(u64, U256)>, Option<Error>)
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: Future<Output = Result<U256>>,
+    {
+        let mut last_claimed = self.last_claimed_era.lock().await;
+        
+        // First run only claims the era that just ended
+        let first = match *last_claimed {
+            Some(era) => era + 1,
+            None => current_era.saturating_sub(1),
+        };
+        
+        let mut claimed = Vec::new();
+        for era in first..current_era {
+            match claim(era).await {
+                Ok(amount) => {
+                    *last_claimed = Some(era);
+                    claimed.push((era, amount));
+                    
+                    let mut rewards = self.claimed_rewards.lock().await;
+                    rewards.insert(era, amount);
+                    while rewards.len() as u64 > self.apy_history_eras {
+                        rewards.pop_first();
+                    }
+                }
+                Err(e) => return (claimed, Some(e)),
+            }
+        }
+        
+        (claimed, None)
+    }
+    
     /// Get current staking status
     #[cfg(not(target_arch = "wasm32"))]
     pub async fn get_status(&self) -> Result<StakingStatus> {
@@ -183,13 +411,22 @@ impl FisherStaking {
             .await
             .map_err(|e| Error::Contract(format!("Failed to get rewards: {}", e)))?;
         
-        // Convert to alloy U256
-        let mut buf = [0u8; 32];
-        staked_amount_eth.to_big_endian(&mut buf);
-        let staked_amount = U256::from_be_slice(&buf);
+        let era_duration_secs = contract.era_duration().call().await
+            .map_err(|e| Error::Contract(format!("Failed to get era duration: {}", e)))?
+            .as_u64();
         
-        pending_rewards_eth.to_big_endian(&mut buf);
-        let pending_rewards = U256::from_be_slice(&buf);
+        let claimed = self.claimed_rewards.lock().await.clone();
+        let history = fetch_reward_history(
+            contract,
+            fisher_h160,
+            current_era,
+            self.apy_history_eras,
+            &claimed,
+        ).await?;
+        
+        // Convert to alloy U256
+        let staked_amount = U256::from_limbs(staked_amount_eth.0);
+        let pending_rewards = U256::from_limbs(pending_rewards_eth.0);
         
         Ok(StakingStatus {
             is_active,
@@ -197,6 +434,8 @@ impl FisherStaking {
             current_era,
             pending_rewards,
             estimated_apy: self.calculate_apy(staked_amount, pending_rewards),
+            trailing_apy: trailing_apy(staked_amount, &history, era_duration_secs),
+            era_duration_secs,
         })
     }
     
@@ -212,24 +451,31 @@ impl FisherStaking {
         
         // Assuming ~365 eras per year (1 per day)
         let apy = (rewards_f64 / staked_f64) * 365.0 * 100.0;
-        apy.max(0.0).min(1000.0)  // Cap at reasonable range
+        apy.clamp(0.0, 1000.0)  // Cap at reasonable range
     }
     
-    /// Extract reward amount from transaction receipt
+    /// Extract reward amount from this fisher's `RewardsClaimed` event in a receipt
+    ///
+    /// Only `fisher` is indexed; `era` and `amount` are ABI-decoded from the log data.
+    /// A reverted claim is an error rather than a zero reward, so its era isn't
+    /// marked claimed.
     #[cfg(not(target_arch = "wasm32"))]
     fn extract_reward_from_receipt(&self, receipt: &TransactionReceipt) -> Result<U256> {
-        // Parse RewardsClaimed event from logs
-        for log in &receipt.logs {
-            // Simple parsing - in production, use proper event decoding
-            if log.topics.len() >= 4 {
-                let amount_bytes = log.topics[3].as_bytes();
-                let amount = U256::from_be_slice(amount_bytes);
-                return Ok(amount);
-            }
+        if receipt.status == Some(U64::zero()) {
+            return Err(Error::Reverted {
+                reason: format!("reward claim {:?} reverted", receipt.transaction_hash),
+            });
         }
         
+        let fisher = H160::from_slice(self.fisher_address.as_slice());
+        
+        let reward = receipt.logs.iter()
+            .filter_map(|log| ethers::contract::parse_log::<RewardsClaimedFilter>(log.clone()).ok())
+            .find(|event| event.fisher == fisher)
+            .map(|event| U256::from_limbs(event.amount.0));
+        
         // Default to zero if event not found
-        Ok(U256::ZERO)
+        Ok(reward.unwrap_or(U256::ZERO))
     }
     
     /// WASM stub
@@ -251,6 +497,72 @@ impl FisherStaking {
     }
 }
 
+/// Read the current stake and pick the policy action
+#[cfg(not(target_arch = "wasm32"))]
+async fn plan_policy<M: Middleware + 'static>(
+    contract: &FisherStakingContract<M>,
+    fisher: H160,
+    policy: &StakingPolicy,
+    min_stake: U256,
+    rewards: U256,
+) -> Result<PolicyAction> {
+    let staked = contract.get_staked_amount(fisher).call().await
+        .map_err(|e| Error::Contract(format!("Failed to get stake: {}", e)))?;
+    
+    Ok(policy.action(U256::from_limbs(staked.0), min_stake, rewards))
+}
+
+/// Rewards earned in each of the `eras` completed eras before `current_era`, oldest first
+///
+/// Claimed eras read 0 from `getPendingRewards`, so they come from `claimed`
+/// instead. Eras claimed outside this process (or before a restart) still
+/// read 0 and drag the average down.
+#[cfg(not(target_arch = "wasm32"))]
+async fn fetch_reward_history<M: Middleware + 'static>(
+    contract: &FisherStakingContract<M>,
+    fisher: H160,
+    current_era: u64,
+    eras: u64,
+    claimed: &BTreeMap<u64, U256>,
+) -> Result<Vec<U256>> {
+    let mut history = Vec::new();
+    
+    for era in current_era.saturating_sub(eras)..current_era {
+        if let Some(amount) = claimed.get(&era) {
+            history.push(*amount);
+            continue;
+        }
+        
+        let reward = contract
+            .get_pending_rewards(fisher, ethers::types::U256::from(era))
+            .call()
+            .await
+            .map_err(|e| Error::Contract(format!("Failed to get rewards for era {}: {}", era, e)))?;
+        history.push(U256::from_limbs(reward.0));
+    }
+    
+    Ok(history)
+}
+
+/// Annualized APY from the average per-era reward over `history`
+///
+/// Uses the contract's real era length instead of assuming one era per day.
+pub fn trailing_apy(staked: U256, history: &[U256], era_duration_secs: u64) -> f64 {
+    if staked == U256::ZERO || history.is_empty() || era_duration_secs == 0 {
+        return 0.0;
+    }
+    
+    let total: f64 = history.iter()
+        .map(|r| r.to_string().parse::<f64>().unwrap_or(0.0))
+        .sum();
+    let avg_reward = total / history.len() as f64;
+    let staked_f64 = staked.to_string().parse::<f64>().unwrap_or(1.0);
+    let eras_per_year = SECONDS_PER_YEAR as f64 / era_duration_secs as f64;
+    
+    let apy = (avg_reward / staked_f64) * eras_per_year * 100.0;
+    apy.clamp(0.0, 1000.0)  // Cap at reasonable range
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,6 +592,237 @@ mod tests {
         let apy = staking.calculate_apy(staked, rewards);
         
         // Should be ~365% APY (1% per day * 365 days)
-        assert!(apy >= 300.0 && apy <= 400.0);
+        assert!((300.0..=400.0).contains(&apy));
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_reward_history() {
+        let (provider, mock) = Provider::mocked();
+        let contract = FisherStakingContract::new(H160::zero(), std::sync::Arc::new(provider));
+        
+        // Mock responses are popped LIFO: push in reverse order of use
+        for reward in [70u64, 80, 90] {
+            let encoded = ethers::abi::encode(&[ethers::abi::Token::Uint(reward.into())]);
+            mock.push::<Bytes, _>(Bytes::from(encoded)).unwrap();
+        }
+        
+        let history = fetch_reward_history(&contract, H160::zero(), 10, 3, &BTreeMap::new()).await.unwrap();
+        assert_eq!(history, vec![U256::from(90), U256::from(80), U256::from(70)]);
+    }
+    
+    #[tokio::test]
+    async fn test_reward_history_uses_claimed_amounts() {
+        let staking = FisherStaking::new(Address::ZERO, Address::ZERO, U256::from(1000))
+            .with_auto_claim(Some(6));
+        let (claimed, error) = staking.claim_eras_before(9, |era| async move {
+            Ok(U256::from(era * 10))
+        }).await;
+        assert!(error.is_none());
+        assert_eq!(claimed.len(), 2);
+        
+        // Only era 9 is still pending; eras 7 and 8 were claimed and read 0 on-chain
+        let (provider, mock) = Provider::mocked();
+        let contract = FisherStakingContract::new(H160::zero(), std::sync::Arc::new(provider));
+        let encoded = ethers::abi::encode(&[ethers::abi::Token::Uint(90u64.into())]);
+        mock.push::<Bytes, _>(Bytes::from(encoded)).unwrap();
+        
+        let recorded = staking.claimed_rewards.lock().await.clone();
+        let history = fetch_reward_history(&contract, H160::zero(), 10, 3, &recorded).await.unwrap();
+        assert_eq!(history, vec![U256::from(70), U256::from(80), U256::from(90)]);
+        assert!(trailing_apy(U256::from(1000), &history, 86_400) > 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_claimed_rewards_bounded_by_history() {
+        let mut staking = FisherStaking::new(Address::ZERO, Address::ZERO, U256::from(1000))
+            .with_auto_claim(None);
+        staking.apy_history_eras = 3;
+        
+        staking.claim_eras_before(2, |_| async { Ok(U256::from(1)) }).await;
+        staking.claim_eras_before(10, |_| async { Ok(U256::from(1)) }).await;
+        
+        let recorded = staking.claimed_rewards.lock().await;
+        assert_eq!(recorded.keys().copied().collect::<Vec<_>>(), vec![7, 8, 9]);
+    }
+    
+    #[test]
+    fn test_trailing_apy_smoother_than_naive() {
+        let staking = FisherStaking::new(Address::ZERO, Address::ZERO, U256::from(1000));
+        let staked = U256::from(100_000);
+        let day = 24 * 60 * 60;
+        
+        // Declining, noisy reward series
+        let rewards: Vec<U256> = [200u64, 120, 180, 90, 150, 60, 110, 40, 80, 30]
+            .into_iter()
+            .map(U256::from)
+            .collect();
+        
+        let naive: Vec<f64> = rewards.iter()
+            .map(|r| staking.calculate_apy(staked, *r))
+            .collect();
+        let trailing: Vec<f64> = (3..=rewards.len())
+            .map(|end| trailing_apy(staked, &rewards[end - 3..end], day))
+            .collect();
+        
+        let max_swing = |series: &[f64]| {
+            series.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f64::max)
+        };
+        assert!(max_swing(&trailing) < max_swing(&naive) / 2.0);
+        
+        // One era per day matches the naive formula for a flat series
+        let flat = vec![U256::from(100); 5];
+        assert!((trailing_apy(staked, &flat, day) - staking.calculate_apy(staked, U256::from(100))).abs() < 1e-9);
+        
+        // Longer eras mean fewer payouts per year
+        assert!((trailing_apy(staked, &flat, 7 * day) - trailing_apy(staked, &flat, day) / 7.0).abs() < 1e-9);
+        assert_eq!(trailing_apy(staked, &[], day), 0.0);
+    }
+    
+    /// Drive the claim loop through `eras`, recording every claim
+    async fn advance_eras(staking: &FisherStaking, eras: &[u64], claims: &mut Vec<u64>) {
+        for &current_era in eras {
+            let (_, error) = staking.claim_eras_before(current_era, |era| {
+                claims.push(era);
+                async { Ok(U256::from(100)) }
+            }).await;
+            assert!(error.is_none());
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_auto_claim_once_per_completed_era() {
+        let staking = FisherStaking::new(Address::ZERO, Address::ZERO, U256::from(1000))
+            .with_auto_claim(None);
+        let mut claims = Vec::new();
+        
+        // Repeated polls within an era and a skipped-over era (downtime)
+        advance_eras(&staking, &[0, 1, 1, 2, 2, 2, 5, 5], &mut claims).await;
+        
+        assert_eq!(claims, vec![0, 1, 2, 3, 4]);
+        assert_eq!(staking.last_claimed_era().await, Some(4));
+    }
+    
+    #[tokio::test]
+    async fn test_auto_claim_resumes_after_restart() {
+        // Restarted with persisted progress: nothing claimed twice
+        let staking = FisherStaking::new(Address::ZERO, Address::ZERO, U256::from(1000))
+            .with_auto_claim(Some(4));
+        let mut claims = Vec::new();
+        
+        advance_eras(&staking, &[5, 6], &mut claims).await;
+        assert_eq!(claims, vec![5]);
+    }
+    
+    #[tokio::test]
+    async fn test_failed_claim_retried() {
+        let staking = FisherStaking::new(Address::ZERO, Address::ZERO, U256::from(1000))
+            .with_auto_claim(Some(2));
+        
+        let (claimed, error) = staking.claim_eras_before(5, |era| async move {
+            if era == 4 {
+                Err(Error::Contract("reverted".to_string()))
+            } else {
+                Ok(U256::from(era))
+            }
+        }).await;
+        assert!(error.is_some());
+        assert_eq!(staking.last_claimed_era().await, Some(3));
+        
+        // The era claimed before the failure is still reported
+        assert_eq!(claimed, vec![(3, U256::from(3))]);
+        
+        let mut claims = Vec::new();
+        advance_eras(&staking, &[5], &mut claims).await;
+        assert_eq!(claims, vec![4]);
+    }
+    
+    fn policy() -> StakingPolicy {
+        StakingPolicy {
+            target_stake: U256::from(10_000),
+            max_stake: U256::from(20_000),
+            restake_rewards: true,
+        }
+    }
+    
+    /// Plan against a mock contract reporting `staked`
+    async fn plan_with_stake(policy: &StakingPolicy, staked: u64, min_stake: u64, rewards: u64) -> PolicyAction {
+        let (provider, mock) = Provider::mocked();
+        let encoded = ethers::abi::encode(&[ethers::abi::Token::Uint(staked.into())]);
+        mock.push::<Bytes, _>(Bytes::from(encoded)).unwrap();
+        let contract = FisherStakingContract::new(H160::zero(), std::sync::Arc::new(provider));
+        
+        plan_policy(&contract, H160::zero(), policy, U256::from(min_stake), U256::from(rewards))
+            .await
+            .unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_policy_restakes_below_target() {
+        // Rewards fully restaked while short of target
+        assert_eq!(plan_with_stake(&policy(), 8_000, 1_000, 500).await, PolicyAction::Stake(U256::from(500)));
+        
+        // Only up to the target
+        assert_eq!(plan_with_stake(&policy(), 9_800, 1_000, 500).await, PolicyAction::Stake(U256::from(200)));
+        
+        // Disabled restaking or nothing to restake
+        let no_restake = StakingPolicy { restake_rewards: false, ..policy() };
+        assert_eq!(plan_with_stake(&no_restake, 8_000, 1_000, 500).await, PolicyAction::Hold);
+        assert_eq!(plan_with_stake(&policy(), 8_000, 1_000, 0).await, PolicyAction::Hold);
+    }
+    
+    #[tokio::test]
+    async fn test_policy_unstakes_above_cap() {
+        assert_eq!(plan_with_stake(&policy(), 25_000, 1_000, 500).await, PolicyAction::Unstake(U256::from(5_000)));
+        assert_eq!(plan_with_stake(&policy(), 15_000, 1_000, 500).await, PolicyAction::Hold);
+        
+        // A cap below min_stake never takes us under the activation threshold
+        let low_cap = StakingPolicy { max_stake: U256::from(500), ..policy() };
+        assert_eq!(plan_with_stake(&low_cap, 3_000, 1_000, 0).await, PolicyAction::Unstake(U256::from(2_000)));
+        assert_eq!(plan_with_stake(&low_cap, 1_000, 1_000, 0).await, PolicyAction::Hold);
+    }
+    
+    fn rewards_claimed_log(fisher: Address, era: u64, amount: u64) -> Log {
+        use ethers::abi::Token;
+        
+        Log {
+            topics: vec![
+                RewardsClaimedFilter::signature(),
+                H256::from(H160::from_slice(fisher.as_slice())),
+            ],
+            data: ethers::abi::encode(&[Token::Uint(era.into()), Token::Uint(amount.into())]).into(),
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_extract_reward_from_receipt() {
+        let fisher = Address::repeat_byte(0xF1);
+        let staking = FisherStaking::new(Address::ZERO, fisher, U256::from(1000));
+        
+        // Unrelated log, another fisher's claim, then ours
+        let unrelated = Log {
+            topics: vec![H256::repeat_byte(0xAA), H256::zero(), H256::zero(), H256::repeat_byte(0x01)],
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            logs: vec![
+                unrelated,
+                rewards_claimed_log(Address::repeat_byte(0xF2), 7, 999),
+                rewards_claimed_log(fisher, 7, 1_234),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(staking.extract_reward_from_receipt(&receipt).unwrap(), U256::from(1_234));
+        
+        // No matching event
+        let receipt = TransactionReceipt {
+            logs: vec![rewards_claimed_log(Address::repeat_byte(0xF2), 7, 999)],
+            ..Default::default()
+        };
+        assert_eq!(staking.extract_reward_from_receipt(&receipt).unwrap(), U256::ZERO);
+        
+        // A reverted claim paid nothing and must be retried
+        let receipt = TransactionReceipt { status: Some(U64::zero()), ..Default::default() };
+        assert!(matches!(staking.extract_reward_from_receipt(&receipt), Err(Error::Reverted { .. })));
     }
 }
@@ -0,0 +1,520 @@
+//! Write-ahead log of submitted batch transactions
+//!
+//! Each batch transaction is signed first, its hash recorded here under the
+//! batch's canonical digest, and only then broadcast. If the relayer crashes
+//! before settling, the restarted relayer rebuilds the same batch, finds the
+//! marker and follows the original transaction instead of sending a
+//! duplicate. Markers are appended as JSON lines so a torn write loses at
+//! most the last entry.
+//!
+//! Markers also carry the batch itself until its outcome is handled, so a
+//! restarted relayer can finish batches nobody resubmits (see
+//! [`SubmissionLog::pending`]). Resolved markers are forgotten after a
+//! retention period, and the file is rewritten with only the latest marker
+//! per batch when opened or once enough superseded lines pile up.
+//!
+//! [`InFlightLog`] does the same for intents collected from a fishing spot:
+//! ids handed to the chain but not yet acknowledged back are remembered, so
+//! a restarted relayer doesn't submit them again when the spot re-serves them.
+
+use crate::{types::Batch, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Superseded lines tolerated in the marker file before it is rewritten
+const COMPACTION_SLACK: usize = 256;
+
+/// Record that a batch was handed to the network in a given transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionMarker {
+    /// Canonical batch digest (hex)
+    pub batch_hash: String,
+    
+    /// Transaction carrying the batch (0x-prefixed hex)
+    pub tx_hash: String,
+    
+    /// Unix timestamp of the submission
+    pub submitted_at: u64,
+    
+    /// Batch carried by the transaction, kept until it is resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<Batch>,
+    
+    /// Last block a private relay may include the transaction in
+    ///
+    /// Privately relayed transactions are invisible to the node until mined,
+    /// so they only count as dropped once this block has passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block_number: Option<u64>,
+    
+    /// Whether the batch's outcome was handled (settled or handed back to the queue)
+    #[serde(default)]
+    pub resolved: bool,
+    
+    /// Unix timestamp of the resolution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<u64>,
+}
+
+/// Submission markers keyed on batch digest, optionally backed by a file
+#[derive(Debug, Default)]
+pub struct SubmissionLog {
+    /// Append-only marker file (in-memory only when unset)
+    path: Option<PathBuf>,
+    
+    /// Latest marker per batch digest
+    markers: HashMap<[u8; 32], SubmissionMarker>,
+    
+    /// How long resolved markers are kept (forever when unset)
+    retention: Option<Duration>,
+    
+    /// Lines in the marker file, superseded ones included
+    lines: usize,
+}
+
+impl SubmissionLog {
+    /// Log that only lives as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+    
+    /// Open (or create on first record) the marker file at `path`
+    ///
+    /// Unparseable lines, such as a write torn by a crash, are skipped, and
+    /// the file is compacted down to the latest marker per batch.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut markers = HashMap::new();
+        
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(Error::Io(e)),
+        };
+        
+        let mut lines = 0;
+        for line in contents.lines() {
+            lines += 1;
+            let Ok(marker) = serde_json::from_str::<SubmissionMarker>(line) else {
+                continue;
+            };
+            if let Some(key) = decode_hash(&marker.batch_hash) {
+                markers.insert(key, marker);
+            }
+        }
+        
+        let mut log = Self { path: Some(path), markers, retention: None, lines };
+        if log.lines > log.markers.len() {
+            log.compact()?;
+        }
+        Ok(log)
+    }
+    
+    /// Forget resolved markers once `retention` has passed since their resolution
+    ///
+    /// Markers resolved longer ago are dropped right away.
+    pub fn with_retention(mut self, retention: Duration) -> Result<Self> {
+        self.retention = Some(retention);
+        self.prune()?;
+        if self.lines > self.markers.len() {
+            self.compact()?;
+        }
+        Ok(self)
+    }
+    
+    /// Marker for a batch digest, if it was submitted before
+    pub fn get(&self, batch_hash: &[u8; 32]) -> Option<&SubmissionMarker> {
+        self.markers.get(batch_hash)
+    }
+    
+    /// Durably record that `batch_hash` is being sent in `tx_hash`
+    ///
+    /// Returns once the marker is synced to disk (for file-backed logs).
+    pub fn record(&mut self, batch_hash: [u8; 32], tx_hash: [u8; 32]) -> Result<()> {
+        self.append(batch_hash, new_marker(batch_hash, tx_hash, None))
+    }
+    
+    /// [`record`](Self::record) with the batch itself, so it can be resumed after a restart
+    ///
+    /// `max_block_number` is the relay deadline of a privately sent transaction.
+    pub fn record_batch(&mut self, batch: &Batch, tx_hash: [u8; 32], max_block_number: Option<u64>) -> Result<()> {
+        let batch_hash = batch.canonical_digest();
+        let marker = SubmissionMarker {
+            max_block_number,
+            ..new_marker(batch_hash, tx_hash, Some(batch.clone()))
+        };
+        self.append(batch_hash, marker)
+    }
+    
+    /// Mark the batch's outcome handled, dropping its stored content
+    ///
+    /// The marker itself is kept for the retention period, so a rebuilt
+    /// batch still isn't resent.
+    pub fn resolve(&mut self, batch_hash: &[u8; 32]) -> Result<()> {
+        let Some(marker) = self.markers.get(batch_hash).filter(|marker| !marker.resolved) else {
+            return Ok(());
+        };
+        
+        let marker = SubmissionMarker {
+            batch: None,
+            resolved: true,
+            resolved_at: Some(unix_now()),
+            ..marker.clone()
+        };
+        self.append(*batch_hash, marker)?;
+        self.prune()
+    }
+    
+    /// Whether a transaction was recorded for `batch_hash` and its outcome not yet handled
+    pub fn is_unresolved(&self, batch_hash: &[u8; 32]) -> bool {
+        self.markers.get(batch_hash).is_some_and(|marker| !marker.resolved)
+    }
+    
+    /// Recorded batches whose outcome hasn't been handled yet
+    pub fn pending(&self) -> impl Iterator<Item = &Batch> {
+        self.markers.values()
+            .filter(|marker| !marker.resolved)
+            .filter_map(|marker| marker.batch.as_ref())
+    }
+    
+    fn append(&mut self, batch_hash: [u8; 32], marker: SubmissionMarker) -> Result<()> {
+        if let Some(path) = &self.path {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(&marker)?)?;
+            file.sync_data()?;
+            self.lines += 1;
+        }
+        
+        self.markers.insert(batch_hash, marker);
+        Ok(())
+    }
+    
+    /// Drop resolved markers past retention, compacting the file once it has grown enough
+    fn prune(&mut self) -> Result<()> {
+        if let Some(retention) = self.retention {
+            let now = unix_now();
+            self.markers.retain(|_, marker| {
+                let resolved_at = marker.resolved_at.unwrap_or(marker.submitted_at);
+                !marker.resolved || resolved_at.saturating_add(retention.as_secs()) > now
+            });
+        }
+        
+        if self.lines > self.markers.len() + COMPACTION_SLACK {
+            self.compact()?;
+        }
+        Ok(())
+    }
+    
+    /// Rewrite the marker file with only the latest marker per batch
+    ///
+    /// The new file is synced and renamed over the old one, so a crash leaves
+    /// one or the other intact.
+    fn compact(&mut self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        
+        let mut contents = String::new();
+        for marker in self.markers.values() {
+            contents.push_str(&serde_json::to_string(marker)?);
+            contents.push('\n');
+        }
+        
+        let staging = path.with_extension("compacting");
+        let mut file = std::fs::File::create(&staging)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_data()?;
+        std::fs::rename(&staging, path)?;
+        
+        self.lines = self.markers.len();
+        Ok(())
+    }
+    
+    /// Number of batches with a marker
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+    
+    /// Whether no batch has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+}
+
+fn new_marker(batch_hash: [u8; 32], tx_hash: [u8; 32], batch: Option<Batch>) -> SubmissionMarker {
+    SubmissionMarker {
+        batch_hash: hex::encode(batch_hash),
+        tx_hash: format!("0x{}", hex::encode(tx_hash)),
+        submitted_at: unix_now(),
+        batch,
+        max_block_number: None,
+        resolved: false,
+        resolved_at: None,
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Where a collected intent is between the fishing spot and the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InFlightState {
+    /// Pulled from the fishing spot and queued, not yet in a sent batch
+    Collected,
+    
+    /// Part of a batch handed to the network
+    Submitted,
+    
+    /// Settled and acknowledged; the entry is forgotten
+    Acknowledged,
+}
+
+/// One state change, as appended to the in-flight file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InFlightEntry {
+    id: String,
+    state: InFlightState,
+}
+
+/// Collected-but-unacknowledged intent ids, optionally backed by a file
+#[derive(Debug, Default)]
+pub struct InFlightLog {
+    /// Append-only state file (in-memory only when unset)
+    path: Option<PathBuf>,
+    
+    /// Latest state per intent id (acknowledged ids are removed)
+    states: HashMap<String, InFlightState>,
+}
+
+impl InFlightLog {
+    /// Log that only lives as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+    
+    /// Open (or create on first record) the state file at `path`
+    ///
+    /// Replays every entry so the last state of each id wins; torn lines are
+    /// skipped.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = Self::in_memory();
+        
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(Error::Io(e)),
+        };
+        
+        for line in contents.lines() {
+            if let Ok(entry) = serde_json::from_str::<InFlightEntry>(line) {
+                log.apply(entry.id, entry.state);
+            }
+        }
+        
+        log.path = Some(path);
+        Ok(log)
+    }
+    
+    /// Current state of an intent id, if it is in flight
+    pub fn state(&self, id: &str) -> Option<InFlightState> {
+        self.states.get(id).copied()
+    }
+    
+    /// Whether `id` was handed to the network and not yet acknowledged
+    pub fn is_submitted(&self, id: &str) -> bool {
+        self.state(id) == Some(InFlightState::Submitted)
+    }
+    
+    /// Durably move `ids` to `state`
+    ///
+    /// Returns once the entries are synced to disk (for file-backed logs).
+    pub fn record<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>, state: InFlightState) -> Result<()> {
+        let ids: Vec<&str> = ids.into_iter().collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        
+        if let Some(path) = &self.path {
+            let mut lines = String::new();
+            for id in &ids {
+                let entry = InFlightEntry { id: id.to_string(), state };
+                lines.push_str(&serde_json::to_string(&entry)?);
+                lines.push('\n');
+            }
+            
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(lines.as_bytes())?;
+            file.sync_data()?;
+        }
+        
+        for id in ids {
+            self.apply(id.to_string(), state);
+        }
+        Ok(())
+    }
+    
+    fn apply(&mut self, id: String, state: InFlightState) {
+        if state == InFlightState::Acknowledged {
+            self.states.remove(&id);
+        } else {
+            self.states.insert(id, state);
+        }
+    }
+    
+    /// Number of intents in flight
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+    
+    /// Whether nothing is in flight
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+/// Parse a 32-byte hash from (optionally 0x-prefixed) hex
+pub fn decode_hash(hex_str: &str) -> Option<[u8; 32]> {
+    hex::decode(hex_str.trim_start_matches("0x")).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn temp_log_path() -> PathBuf {
+        std::env::temp_dir().join(format!("fisher-submissions-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+    
+    #[test]
+    fn test_markers_survive_reopen() {
+        let path = temp_log_path();
+        
+        let mut log = SubmissionLog::open(&path).unwrap();
+        assert!(log.is_empty());
+        log.record([0x01; 32], [0xAA; 32]).unwrap();
+        log.record([0x02; 32], [0xBB; 32]).unwrap();
+        // Resubmission after a dropped tx replaces the marker
+        log.record([0x01; 32], [0xCC; 32]).unwrap();
+        drop(log);
+        
+        // Simulate a torn final write
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"batch_hash\": \"03").unwrap();
+        
+        let log = SubmissionLog::open(&path).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.get(&[0x01; 32]).unwrap().tx_hash, format!("0x{}", "cc".repeat(32)));
+        
+        // Reopening compacted away the superseded marker and the torn line
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        assert_eq!(decode_hash(&log.get(&[0x02; 32]).unwrap().tx_hash), Some([0xBB; 32]));
+        assert!(log.get(&[0x03; 32]).is_none());
+        
+        std::fs::remove_file(path).unwrap();
+    }
+    
+    #[test]
+    fn test_resolved_markers_expire() {
+        let path = temp_log_path();
+        
+        let mut log = SubmissionLog::open(&path).unwrap().with_retention(Duration::from_secs(3600)).unwrap();
+        log.record([0x01; 32], [0xAA; 32]).unwrap();
+        log.record([0x02; 32], [0xBB; 32]).unwrap();
+        log.resolve(&[0x01; 32]).unwrap();
+        
+        // Still within retention, so a rebuilt batch isn't resent yet
+        assert!(log.get(&[0x01; 32]).is_some());
+        drop(log);
+        
+        // Once past retention only the unresolved marker is kept, in memory and on disk
+        let log = SubmissionLog::open(&path).unwrap().with_retention(Duration::ZERO).unwrap();
+        assert!(log.get(&[0x01; 32]).is_none());
+        assert!(log.get(&[0x02; 32]).is_some());
+        drop(log);
+        
+        let log = SubmissionLog::open(&path).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        
+        std::fs::remove_file(path).unwrap();
+    }
+    
+    #[test]
+    fn test_in_memory_log() {
+        let mut log = SubmissionLog::in_memory();
+        log.record([0x01; 32], [0xAA; 32]).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(decode_hash(&log.get(&[0x01; 32]).unwrap().batch_hash), Some([0x01; 32]));
+    }
+    
+    #[test]
+    fn test_in_flight_states_survive_reopen() {
+        let path = temp_log_path();
+        
+        let mut log = InFlightLog::open(&path).unwrap();
+        log.record(["a", "b", "c"], InFlightState::Collected).unwrap();
+        log.record(["a", "b"], InFlightState::Submitted).unwrap();
+        log.record(["a"], InFlightState::Acknowledged).unwrap();
+        drop(log);
+        
+        // Simulate a torn final write
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"id\": \"c\", \"sta").unwrap();
+        
+        let log = InFlightLog::open(&path).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.state("a"), None);
+        assert!(log.is_submitted("b"));
+        assert_eq!(log.state("c"), Some(InFlightState::Collected));
+        
+        std::fs::remove_file(path).unwrap();
+    }
+    
+    #[test]
+    fn test_batches_pending_until_resolved() {
+        let path = temp_log_path();
+        let batch = Batch {
+            id: 7,
+            intents: vec![],
+            chunk_size: 1,
+            phi_score: 0.0,
+            estimated_gas: Default::default(),
+            estimated_savings: Default::default(),
+            created_at: 0,
+            recipient_groups: vec![],
+            aggregated: vec![],
+        };
+        let batch_hash = batch.canonical_digest();
+        
+        let mut log = SubmissionLog::open(&path).unwrap();
+        log.record_batch(&batch, [0xAA; 32], Some(40)).unwrap();
+        log.record([0x02; 32], [0xBB; 32]).unwrap();
+        drop(log);
+        
+        // Only markers carrying their batch can be resumed
+        let mut log = SubmissionLog::open(&path).unwrap();
+        let pending: Vec<_> = log.pending().map(|batch| batch.id).collect();
+        assert_eq!(pending, [7]);
+        assert!(log.is_unresolved(&batch_hash));
+        assert_eq!(log.get(&batch_hash).unwrap().max_block_number, Some(40));
+        
+        log.resolve(&batch_hash).unwrap();
+        drop(log);
+        
+        let log = SubmissionLog::open(&path).unwrap();
+        assert_eq!(log.pending().count(), 0);
+        assert!(!log.is_unresolved(&batch_hash));
+        assert_eq!(decode_hash(&log.get(&batch_hash).unwrap().tx_hash), Some([0xAA; 32]));
+        
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,9 +1,32 @@
 //! Core types for Fisher relayer
 
 use serde::{Deserialize, Serialize};
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, I256, U256};
+use std::collections::HashSet;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Half the secp256k1 curve order; signatures with `s` above this are malleable (EIP-2)
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0x5D, 0x57, 0x6E, 0x73, 0x57, 0xA4, 0x50, 0x1D, 0xDF, 0xE9, 0x2F, 0x46, 0x68, 0x1B, 0x20, 0xA0,
+];
+
+/// Newest intent schema this relayer understands
+///
+/// - 1: original schema, without `target_chain` or `version`
+/// - 2: adds `target_chain` and `version`
+/// - 3: adds `permit`
+pub const INTENT_VERSION: u8 = 3;
+
+/// Tag hashed into [`Intent::signing_hash`], so an intent signature can't
+/// be passed off as a signature over any other Fisher message
+const INTENT_SIGNING_DOMAIN: &[u8] = b"Fisher intent";
+
+/// Payloads from before versioning carry no `version` field
+fn legacy_intent_version() -> u8 {
+    1
+}
+
 /// User intent submitted to Fisher
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Intent {
@@ -33,6 +56,42 @@ pub struct Intent {
     
     /// Gas price user is willing to pay
     pub max_gas_price: Option<U256>,
+    
+    /// Chain ID the intent settles on (`None` routes to the default chain)
+    ///
+    /// Covered by the signature, so relayers for other chains refuse it. An
+    /// intent without a target is valid on every chain, so it can be replayed
+    /// on each one it is sent to.
+    #[serde(default)]
+    pub target_chain: Option<u64>,
+    
+    /// Schema version the intent was created with
+    #[serde(default = "legacy_intent_version")]
+    pub version: u8,
+    
+    /// ERC-2612 permit submitted ahead of the transfer, replacing a prior `approve`
+    #[serde(default)]
+    pub permit: Option<Permit>,
+}
+
+/// ERC-2612 `permit` signature letting the Fisher contract spend an ERC-20 sender's tokens
+///
+/// Settled in the same transaction as the batch, so the sender never sends
+/// an `approve`. The owner is the intent's `from`, the spender the Fisher
+/// contract and the value the intent's `amount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Permit {
+    /// Unix time after which the token rejects the permit
+    pub deadline: u64,
+    
+    /// Signature recovery id
+    pub v: u8,
+    
+    /// Signature `r`
+    pub r: B256,
+    
+    /// Signature `s`
+    pub s: B256,
 }
 
 impl Intent {
@@ -59,8 +118,50 @@ impl Intent {
                 .unwrap()
                 .as_secs(),
             max_gas_price: None,
+            target_chain: None,
+            version: INTENT_VERSION,
+            permit: None,
+        }
+    }
+    
+    /// Upgrade an intent from an older schema to the current one
+    ///
+    /// Versions newer than [`INTENT_VERSION`] are rejected, since their
+    /// extra fields would be silently dropped.
+    pub fn normalized(mut self) -> crate::Result<Self> {
+        match self.version {
+            1 => {
+                // v1 had no chain routing or permits
+                self.target_chain = None;
+                self.permit = None;
+                self.version = INTENT_VERSION;
+                Ok(self)
+            }
+            2 => {
+                // v2 had no permits
+                self.permit = None;
+                self.version = INTENT_VERSION;
+                Ok(self)
+            }
+            INTENT_VERSION => Ok(self),
+            version => Err(crate::Error::InvalidIntent(format!(
+                "Unsupported intent version {} (newest supported is {})",
+                version, INTENT_VERSION
+            ))),
         }
     }
+    
+    /// Route this intent to a specific chain
+    pub fn with_target_chain(mut self, chain_id: u64) -> Self {
+        self.target_chain = Some(chain_id);
+        self
+    }
+    
+    /// Attach an ERC-2612 permit for the intent's amount
+    pub fn with_permit(mut self, permit: Permit) -> Self {
+        self.permit = Some(permit);
+        self
+    }
 
     /// Calculate intent hash for ordering
     pub fn hash(&self) -> [u8; 32] {
@@ -69,15 +170,412 @@ impl Intent {
         hasher.update(self.id.as_bytes());
         hasher.update(self.from.as_slice());
         hasher.update(self.to.as_slice());
-        hasher.update(&self.amount.to_be_bytes::<32>());
-        hasher.update(&self.nonce.to_le_bytes());
+        hasher.update(self.amount.to_be_bytes::<32>());
+        hasher.update(self.nonce.to_le_bytes());
+        hasher.finalize().into()
+    }
+    
+    /// Content-addressed id: hex [`Intent::signing_hash`] with the id itself left out
+    ///
+    /// Identical intents always get the same id, and two that differ in any
+    /// signed field can't share it. Clients set `id` to this before signing,
+    /// since the signature covers the id.
+    pub fn canonical_id(&self) -> String {
+        let unnamed = Intent { id: String::new(), ..self.clone() };
+        format!("0x{}", hex::encode(unnamed.signing_hash()))
+    }
+
+    /// Digest the sender signs with EIP-191 `personal_sign`
+    ///
+    /// Covers every field except `signature` and `version`, so the relayer
+    /// cannot alter an intent without invalidating its signature. `version`
+    /// is left out because [`Intent::normalized`] rewrites it, and it changes
+    /// nothing about what settles. Optional fields are tagged with their
+    /// presence, so `None` and `Some(0)` sign differently.
+    pub fn signing_hash(&self) -> [u8; 32] {
+        use sha3::{Digest, Keccak256};
+        let mut hasher = Keccak256::new();
+        hasher.update(Keccak256::digest(INTENT_SIGNING_DOMAIN));
+        // Hashed so its variable length can't run into the next field
+        hasher.update(Keccak256::digest(self.id.as_bytes()));
+        hasher.update(self.from.as_slice());
+        hasher.update(self.to.as_slice());
+        hasher.update(self.amount.to_be_bytes::<32>());
+        hasher.update([self.priority as u8]);
+        hasher.update(self.nonce.to_be_bytes());
+        hasher.update(self.timestamp.to_be_bytes());
+        match self.max_gas_price {
+            Some(price) => {
+                hasher.update([1]);
+                hasher.update(price.to_be_bytes::<32>());
+            }
+            None => hasher.update([0]),
+        }
+        match self.target_chain {
+            Some(chain_id) => {
+                hasher.update([1]);
+                hasher.update(chain_id.to_be_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        match &self.permit {
+            Some(permit) => {
+                hasher.update([1]);
+                hasher.update(permit.deadline.to_be_bytes());
+                hasher.update([permit.v]);
+                hasher.update(permit.r.as_slice());
+                hasher.update(permit.s.as_slice());
+            }
+            None => hasher.update([0]),
+        }
         hasher.finalize().into()
     }
+    
+    /// Sign the intent with the sender's key, setting `signature`
+    pub fn signed(mut self, wallet: &ethers::signers::LocalWallet) -> crate::Result<Self> {
+        let digest = ethers::utils::hash_message(self.signing_hash());
+        let signature = wallet
+            .sign_hash(digest)
+            .map_err(|e| crate::Error::Other(format!("Failed to sign intent: {}", e)))?;
+        
+        self.signature = signature.to_vec();
+        Ok(self)
+    }
 
-    /// Verify EIP-191 signature
+    /// Verify the EIP-191 signature was produced by `from`
+    ///
+    /// Only the canonical encoding is accepted (see [`is_canonical`]), so each
+    /// intent has exactly one valid signature and can't be replayed under a
+    /// malleated twin.
     pub fn verify_signature(&self) -> bool {
-        // TODO: Implement EIP-191 verification
-        !self.signature.is_empty()
+        self.signature_signed_by_sender(self.signing_hash().to_vec(), &self.signature)
+    }
+    
+    /// Check the intent is economically worth batching
+    ///
+    /// Rejects zero amounts, self-transfers and gas prices below the
+    /// configured floor, and ids other than [`Intent::canonical_id`] when
+    /// `enforce_canonical_ids` is set.
+    pub fn validate(&self, config: &FisherConfig) -> crate::Result<()> {
+        // Normalizing instead would invalidate the signature over the id
+        if config.enforce_canonical_ids {
+            let canonical = self.canonical_id();
+            if self.id != canonical {
+                return Err(crate::Error::InvalidIntent(format!(
+                    "id {} does not match canonical id {}",
+                    self.id, canonical
+                )));
+            }
+        }
+        
+        if self.amount.is_zero() {
+            return Err(crate::Error::InvalidIntent("amount must be greater than zero".to_string()));
+        }
+        
+        if self.from == self.to {
+            return Err(crate::Error::InvalidIntent("self-transfer (from == to)".to_string()));
+        }
+        
+        // No batch could ever settle it
+        if let Some(max_value) = config.max_batch_total_value {
+            if self.amount > max_value {
+                return Err(crate::Error::InvalidIntent(format!(
+                    "amount {} exceeds max_batch_total_value {}",
+                    self.amount, max_value
+                )));
+            }
+        }
+        
+        if let Some(max_gas_price) = self.max_gas_price {
+            if max_gas_price < config.min_gas_price {
+                return Err(crate::Error::InvalidIntent(format!(
+                    "max_gas_price {} below floor {}",
+                    max_gas_price, config.min_gas_price
+                )));
+            }
+        }
+        
+        // Future timestamps break φ age scoring and game the ordering
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        if self.timestamp.abs_diff(now) > config.max_intent_clock_skew_secs {
+            return Err(crate::Error::InvalidIntent(format!(
+                "timestamp {} is more than {}s from now ({})",
+                self.timestamp, config.max_intent_clock_skew_secs, now
+            )));
+        }
+        
+        // An expired permit would revert the whole batch
+        if let Some(permit) = &self.permit {
+            if permit.deadline <= now {
+                return Err(crate::Error::InvalidIntent(format!(
+                    "permit deadline {} has passed",
+                    permit.deadline
+                )));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Message the sender signs (EIP-191) to cancel this intent
+    ///
+    /// Bound to the [`Intent::signing_hash`], so the cancellation can't be
+    /// replayed against a later intent reusing the id, on any chain.
+    pub fn cancellation_message(&self) -> String {
+        format!("Cancel Fisher intent {} (0x{})", self.id, hex::encode(self.signing_hash()))
+    }
+    
+    /// Check that `signature` is the sender's EIP-191 signature over the cancellation message
+    pub fn verify_cancellation(&self, signature: &[u8]) -> bool {
+        self.signature_signed_by_sender(self.cancellation_message().into_bytes(), signature)
+    }
+    
+    /// Recover the EIP-191 signer of `message` and compare it to `from`
+    fn signature_signed_by_sender(&self, message: Vec<u8>, signature: &[u8]) -> bool {
+        let Ok(signature) = ethers::types::Signature::try_from(signature) else {
+            return false;
+        };
+        
+        if !is_canonical(&signature) {
+            return false;
+        }
+        
+        signature
+            .recover(message)
+            .map(|signer| signer.as_bytes() == self.from.as_slice())
+            .unwrap_or(false)
+    }
+    
+    /// Start building an intent field by field
+    pub fn builder() -> IntentBuilder {
+        IntentBuilder::default()
+    }
+}
+
+/// Fluent, validating alternative to `Intent::new` and struct literals
+///
+/// `from`, `to` and `amount` are required. Everything else defaults as in
+/// [`Intent::new`]: the current time, the current schema version, no
+/// priority, nonce 0. Without an explicit `id` the intent gets its
+/// [`Intent::canonical_id`].
+#[derive(Debug, Clone, Default)]
+pub struct IntentBuilder {
+    id: Option<String>,
+    from: Option<Address>,
+    to: Option<Address>,
+    amount: Option<U256>,
+    priority: bool,
+    nonce: u64,
+    signature: Vec<u8>,
+    timestamp: Option<u64>,
+    max_gas_price: Option<U256>,
+    target_chain: Option<u64>,
+    permit: Option<Permit>,
+}
+
+impl IntentBuilder {
+    /// Intent ID
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+    
+    /// Sender address
+    pub fn from(mut self, from: Address) -> Self {
+        self.from = Some(from);
+        self
+    }
+    
+    /// Recipient address
+    pub fn to(mut self, to: Address) -> Self {
+        self.to = Some(to);
+        self
+    }
+    
+    /// Amount to transfer
+    pub fn amount(mut self, amount: U256) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+    
+    /// Mark the intent urgent
+    pub fn priority(mut self, priority: bool) -> Self {
+        self.priority = priority;
+        self
+    }
+    
+    /// User nonce
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+    
+    /// Pre-computed EIP-191 signature (or sign the built intent with [`Intent::signed`])
+    pub fn signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
+        self
+    }
+    
+    /// Creation time (Unix seconds) instead of now
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+    
+    /// Highest gas price the user will pay
+    pub fn max_gas_price(mut self, max_gas_price: U256) -> Self {
+        self.max_gas_price = Some(max_gas_price);
+        self
+    }
+    
+    /// Chain the intent settles on
+    pub fn target_chain(mut self, chain_id: u64) -> Self {
+        self.target_chain = Some(chain_id);
+        self
+    }
+    
+    /// ERC-2612 permit for the intent's amount
+    pub fn permit(mut self, permit: Permit) -> Self {
+        self.permit = Some(permit);
+        self
+    }
+    
+    /// Check required fields and assemble the intent
+    ///
+    /// Errs with `Error::InvalidIntent` naming the first missing field, or if
+    /// the sender is the zero address or the id is empty.
+    pub fn build(self) -> crate::Result<Intent> {
+        let missing = |field: &str| crate::Error::InvalidIntent(format!("Intent builder missing `{}`", field));
+        
+        let from = self.from.ok_or_else(|| missing("from"))?;
+        let to = self.to.ok_or_else(|| missing("to"))?;
+        let amount = self.amount.ok_or_else(|| missing("amount"))?;
+        if from == Address::ZERO {
+            return Err(crate::Error::InvalidIntent("Intent sender is the zero address".to_string()));
+        }
+        if self.id.as_deref() == Some("") {
+            return Err(crate::Error::InvalidIntent("Intent id must not be empty".to_string()));
+        }
+        
+        let mut intent = Intent {
+            id: self.id.clone().unwrap_or_default(),
+            from,
+            to,
+            amount,
+            priority: self.priority,
+            nonce: self.nonce,
+            signature: self.signature,
+            timestamp: self.timestamp.unwrap_or_else(|| {
+                SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+            }),
+            max_gas_price: self.max_gas_price,
+            target_chain: self.target_chain,
+            version: INTENT_VERSION,
+            permit: self.permit,
+        };
+        if self.id.is_none() {
+            intent.id = intent.canonical_id();
+        }
+        
+        Ok(intent)
+    }
+}
+
+/// Outcome of a bulk intent import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportStats {
+    /// Intents queued
+    pub accepted: usize,
+    
+    /// Intents that parsed but failed validation
+    pub rejected: usize,
+    
+    /// Lines that weren't an intent at all
+    pub malformed: usize,
+}
+
+/// Redacted view of a queued intent, without signature or calldata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntentSummary {
+    /// Intent ID
+    pub id: String,
+    
+    /// Sender address
+    pub from: Address,
+    
+    /// Recipient address
+    pub to: Address,
+    
+    /// Amount to transfer
+    pub amount: U256,
+    
+    /// Seconds since the intent was created
+    pub age_secs: u64,
+    
+    /// Priority flag
+    pub priority: bool,
+}
+
+impl IntentSummary {
+    /// Summarize `intent` as of `now` (Unix seconds)
+    pub fn new(intent: &Intent, now: u64) -> Self {
+        Self {
+            id: intent.id.clone(),
+            from: intent.from,
+            to: intent.to,
+            amount: intent.amount,
+            age_secs: now.saturating_sub(intent.timestamp),
+            priority: intent.priority,
+        }
+    }
+}
+
+/// Per-chain connection settings for multi-chain deployments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// EIP-155 chain ID
+    pub chain_id: u64,
+    
+    /// RPC URL for this chain
+    pub rpc_url: String,
+    
+    /// Fisher contract address on this chain
+    pub fisher_address: Address,
+    
+    /// Whether the chain supports EIP-4844 blob transactions
+    pub enable_blobs: bool,
+}
+
+/// Whether a signature is in the single canonical encoding we accept
+///
+/// Requires low-s (`s <= n/2`, EIP-2), since `(r, n - s)` with the flipped
+/// recovery id recovers the same signer, and `v ∈ {27, 28}` (recovery id
+/// `v - 27 ∈ {0, 1}`). Raw `0/1` and EIP-155 `v` values would be byte-distinct
+/// encodings of the same signature, so they are rejected too.
+fn is_canonical(signature: &ethers::types::Signature) -> bool {
+    let low_s = signature.s <= ethers::types::U256::from_big_endian(&SECP256K1_HALF_ORDER);
+    let recovery_id = signature.v.checked_sub(27);
+    
+    low_s && matches!(recovery_id, Some(0 | 1))
+}
+
+/// Intent encrypted to the TEE's attested public key
+///
+/// Protects intent contents from MEV searchers while in transit and at rest
+/// outside the enclave. Only the Fisher holding the attested key can decrypt it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedIntent {
+    /// ECIES ciphertext of the JSON-encoded intent
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedIntent {
+    /// Encrypt an intent client-side to the Fisher's attested public key
+    pub fn encrypt(intent: &Intent, public_key: &[u8]) -> crate::Result<Self> {
+        let plaintext = serde_json::to_vec(intent)?;
+        let ciphertext = crate::attestation::encrypt_to(public_key, &plaintext)?;
+        
+        Ok(Self { ciphertext })
     }
 }
 
@@ -104,18 +602,189 @@ pub struct Batch {
     
     /// Creation timestamp
     pub created_at: u64,
+    
+    /// Recipients paid by more than one intent (aggregation opportunities)
+    #[serde(default)]
+    pub recipient_groups: Vec<RecipientGroup>,
+    
+    /// Original intents behind each aggregated payment in `intents`
+    #[serde(default)]
+    pub aggregated: Vec<AggregatedPayment>,
+}
+
+/// Intents in a batch paying the same recipient
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipientGroup {
+    /// Shared recipient
+    pub to: Address,
+    
+    /// IDs of the intents paying it
+    pub intent_ids: Vec<String>,
+    
+    /// Sum of their amounts
+    pub total_amount: U256,
+}
+
+/// Same-sender intents to one recipient collapsed into a single payment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedPayment {
+    /// ID of the payment in `Batch::intents` (the first original's ID)
+    pub payment_id: String,
+    
+    /// Original intents, in batch order
+    pub intents: Vec<Intent>,
 }
 
 impl Batch {
-    /// Calculate savings percentage
+    /// Original intents behind a payment (the payment itself unless aggregated)
+    pub fn constituents<'a>(&'a self, payment: &'a Intent) -> &'a [Intent] {
+        self.aggregated.iter()
+            .find(|a| a.payment_id == payment.id)
+            .map(|a| a.intents.as_slice())
+            .unwrap_or(std::slice::from_ref(payment))
+    }
+    
+    /// All original intents, with aggregated payments expanded
+    pub fn into_original_intents(mut self) -> Vec<Intent> {
+        if self.aggregated.is_empty() {
+            return self.intents;
+        }
+        
+        let mut originals = Vec::with_capacity(self.intents.len());
+        for payment in self.intents {
+            match self.aggregated.iter().position(|a| a.payment_id == payment.id) {
+                Some(i) => originals.append(&mut self.aggregated.swap_remove(i).intents),
+                None => originals.push(payment),
+            }
+        }
+        
+        originals
+    }
+    
+    /// Estimated savings percentage, per [`savings_percent`]
+    ///
+    /// The baseline is recovered as `estimated_gas + estimated_savings`.
     pub fn savings_percent(&self) -> f64 {
-        if self.estimated_gas.is_zero() {
-            return 0.0;
+        savings_percent(self.estimated_gas.saturating_add(self.estimated_savings), self.estimated_gas)
+    }
+    
+    /// Keccak256 Merkle root over each intent's `hash()`
+    pub fn merkle_root(&self) -> [u8; 32] {
+        crate::merkle::merkle_root(&self.leaves())
+    }
+    
+    /// Merkle proof path for the intent at `index`
+    pub fn merkle_proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        crate::merkle::merkle_proof(&self.leaves(), index)
+    }
+    
+    fn leaves(&self) -> Vec<[u8; 32]> {
+        self.intents.iter().map(Intent::hash).collect()
+    }
+    
+    /// Platform-independent encoding of the batch contents
+    ///
+    /// Fields are written in a fixed order with big-endian integers,
+    /// length-prefixed byte strings, intents sorted by `hash()`, and
+    /// `phi_score` normalized (one NaN, no negative zero). The locally assigned
+    /// `id` and `created_at` are excluded, so any relayer building the same
+    /// batch produces the same bytes.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend((self.chunk_size as u64).to_be_bytes());
+        out.extend(canonical_f64(self.phi_score).to_be_bytes());
+        out.extend(self.estimated_gas.to_be_bytes::<32>());
+        out.extend(self.estimated_savings.to_be_bytes::<32>());
+        
+        write_canonical_intents(&mut out, &self.intents);
+        
+        let mut aggregated: Vec<&AggregatedPayment> = self.aggregated.iter().collect();
+        aggregated.sort_by(|a, b| a.payment_id.cmp(&b.payment_id));
+        out.extend((aggregated.len() as u64).to_be_bytes());
+        for payment in aggregated {
+            write_bytes(&mut out, payment.payment_id.as_bytes());
+            write_canonical_intents(&mut out, &payment.intents);
         }
         
-        let total = self.estimated_gas + self.estimated_savings;
-        (self.estimated_savings.to::<u128>() as f64 / total.to::<u128>() as f64) * 100.0
+        out
+    }
+    
+    /// SHA-256 of [`Batch::canonical_bytes`], bound into batch attestations
+    pub fn canonical_digest(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(self.canonical_bytes()).into()
+    }
+}
+
+/// Bit pattern of `x` with NaNs and signed zeros collapsed
+fn canonical_f64(x: f64) -> u64 {
+    if x.is_nan() {
+        f64::NAN.to_bits()
+    } else if x == 0.0 {
+        0
+    } else {
+        x.to_bits()
+    }
+}
+
+/// Append a u32 length prefix and the bytes
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend((bytes.len() as u32).to_be_bytes());
+    out.extend(bytes);
+}
+
+/// Append a count and each intent's encoding, ordered by intent hash
+fn write_canonical_intents(out: &mut Vec<u8>, intents: &[Intent]) {
+    let mut encoded: Vec<([u8; 32], Vec<u8>)> = intents.iter()
+        .map(|intent| (intent.hash(), canonical_intent_bytes(intent)))
+        .collect();
+    encoded.sort();
+    
+    out.extend((encoded.len() as u64).to_be_bytes());
+    for (_, bytes) in encoded {
+        out.extend(bytes);
+    }
+}
+
+/// Fixed-order encoding of every intent field
+fn canonical_intent_bytes(intent: &Intent) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_bytes(&mut out, intent.id.as_bytes());
+    out.extend(intent.from.as_slice());
+    out.extend(intent.to.as_slice());
+    out.extend(intent.amount.to_be_bytes::<32>());
+    out.push(intent.priority as u8);
+    out.extend(intent.nonce.to_be_bytes());
+    write_bytes(&mut out, &intent.signature);
+    out.extend(intent.timestamp.to_be_bytes());
+    
+    match intent.max_gas_price {
+        Some(price) => {
+            out.push(1);
+            out.extend(price.to_be_bytes::<32>());
+        }
+        None => out.push(0),
+    }
+    match intent.target_chain {
+        Some(chain_id) => {
+            out.push(1);
+            out.extend(chain_id.to_be_bytes());
+        }
+        None => out.push(0),
     }
+    
+    match &intent.permit {
+        Some(permit) => {
+            out.push(1);
+            out.extend(permit.deadline.to_be_bytes());
+            out.push(permit.v);
+            out.extend(permit.r.as_slice());
+            out.extend(permit.s.as_slice());
+        }
+        None => out.push(0),
+    }
+    
+    out
 }
 
 /// Batch processing result
@@ -144,74 +813,1123 @@ pub struct BatchResult {
     
     /// Blob gas savings (if applicable)
     pub blob_gas_saved: U256,
-}
-
-/// Fisher configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FisherConfig {
-    /// Ethereum RPC URL
-    pub rpc_url: String,
     
-    /// Fisher contract address
-    pub fisher_address: Address,
+    /// Fees collected by the fisher (from the `BatchSubmitted` event)
+    #[serde(default)]
+    pub fees_collected: U256,
     
-    /// EVVM Core contract address
-    pub evvm_core_address: Address,
+    /// Gas spent submitting the batch (gas used × effective gas price)
+    #[serde(default)]
+    pub gas_cost_wei: U256,
     
-    /// Minimum batch size
-    pub min_batch_size: usize,
+    /// Savings predicted before submission (percent)
+    #[serde(default)]
+    pub estimated_savings_percent: f64,
     
-    /// Maximum batch size
-    pub max_batch_size: usize,
+    /// Savings measured from the receipt's actual gas used (percent)
+    #[serde(default)]
+    pub realized_savings_percent: f64,
     
-    /// Batch interval (milliseconds)
-    pub batch_interval_ms: u64,
+    /// ABI-encoded `submitBatchOptimized` calldata size in bytes
+    #[serde(default)]
+    pub calldata_bytes: u64,
     
-    /// Enable TDX attestation
-    pub enable_attestation: bool,
+    /// Framed blob payload size in bytes (whether or not blobs were used)
+    #[serde(default)]
+    pub blob_bytes: u64,
     
-    /// Enable EIP-4844 blob transactions
-    pub enable_blobs: bool,
+    /// Serialized batch size over blob payload size (>= 1.0 means smaller)
+    #[serde(default)]
+    pub compression_ratio: f64,
     
-    /// Relayer private key (encrypted in TEE)
-    #[serde(skip_serializing)]
-    pub private_key: Option<String>,
+    /// Williams savings at the chunk size the contract's `calculateChunkSize`
+    /// reports (None when settled off-chain or the contract didn't answer)
+    #[serde(default)]
+    pub williams_savings_percent: Option<f64>,
+    
+    /// Gas saved according to the contract's `BatchSubmitted` events
+    #[serde(default)]
+    pub contract_gas_saved: U256,
+    
+    /// Each payment's share of `gas_cost_wei` (wei), in batch order, for billing
+    #[serde(default)]
+    pub fee_charged: Vec<U256>,
+    
+    /// The contract's `batchId` from the `BatchSubmitted` event
+    #[serde(default)]
+    pub onchain_batch_id: Option<u64>,
+    
+    /// Payments the contract reports processing (`operationCount`)
+    #[serde(default)]
+    pub onchain_operation_count: Option<u64>,
 }
 
-impl FisherConfig {
-    /// Load configuration from JSON file
-    pub async fn load(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
-        let contents = tokio::fs::read_to_string(path).await
-            .map_err(|e| crate::Error::Config(format!("Failed to read config: {}", e)))?;
-        
-        let config: Self = serde_json::from_str(&contents)
-            .map_err(|e| crate::Error::Config(format!("Failed to parse config: {}", e)))?;
+impl BatchResult {
+    /// Fill in the on-the-wire sizes of `batch`, the batch this result settled
+    pub fn record_encoded_size(&mut self, batch: &Batch) {
+        self.calldata_bytes = crate::blob::BlobEncoder::calldata_bytes(batch) as u64;
         
-        Ok(config)
+        match crate::blob::BlobEncoder::compress_batch(batch) {
+            Ok(compressed) => {
+                self.blob_bytes = compressed.data.len() as u64;
+                self.compression_ratio = compressed.compression_ratio();
+            }
+            Err(e) => log::warn!("Could not size blob payload for batch {}: {}", batch.id, e),
+        }
     }
-}
-
-impl Default for FisherConfig {
-    fn default() -> Self {
-        Self {
-            rpc_url: "http://localhost:8545".to_string(),
-            fisher_address: Address::ZERO,
-            evvm_core_address: Address::ZERO,
-            min_batch_size: 10,
-            max_batch_size: 1000,
-            batch_interval_ms: 5000,
-            enable_attestation: true,
-            enable_blobs: true,  // Enable blobs by default for best savings
-            private_key: None,
+    
+    /// Fees collected minus gas spent (negative when the batch lost money)
+    pub fn net_profit(&self) -> I256 {
+        net_profit(self.fees_collected, self.gas_cost_wei)
+    }
+    
+    /// Split the batch's gas cost over `batch`'s payments, weighted by `max_gas_price`
+    ///
+    /// Intents without a cap are weighted at the batch's effective gas price.
+    /// No payment is charged more than its cap times an equal share of the gas
+    /// used; what capped payments can't cover is spread over the rest, and
+    /// whatever is left once every payment is capped stays with the relayer.
+    pub fn attribute_fees(&mut self, batch: &Batch) {
+        let n = batch.intents.len();
+        if n == 0 || self.gas_used.is_zero() {
+            self.fee_charged = vec![U256::ZERO; n];
+            return;
+        }
+        
+        let gas_price = self.gas_cost_wei / self.gas_used;
+        let gas_share = self.gas_used / U256::from(n);
+        
+        // Clamped so the weight sum can't overflow
+        let weights: Vec<U256> = batch.intents.iter()
+            .map(|intent| intent.max_gas_price.unwrap_or(gas_price).min(U256::from(u128::MAX)))
+            .collect();
+        let caps: Vec<Option<U256>> = batch.intents.iter()
+            .map(|intent| intent.max_gas_price.map(|cap| cap.saturating_mul(gas_share)))
+            .collect();
+        
+        let mut charged = vec![U256::ZERO; n];
+        let mut open: Vec<usize> = (0..n).collect();
+        let mut remaining = self.gas_cost_wei;
+        
+        // Cap the payments whose proportional share exceeds their cap, then
+        // re-split what's left over the others until no share is over
+        while !open.is_empty() {
+            let total_weight = open.iter().fold(U256::ZERO, |total, &i| total + weights[i]);
+            let share = |i: usize| -> U256 {
+                if total_weight.is_zero() {
+                    return remaining / U256::from(open.len());
+                }
+                let scaled: alloy_primitives::U512 = remaining.widening_mul(weights[i]);
+                (scaled / alloy_primitives::U512::from(total_weight)).to()
+            };
+            
+            let (over, under): (Vec<usize>, Vec<usize>) = open.iter()
+                .partition(|&&i| caps[i].is_some_and(|cap| share(i) > cap));
+            
+            if over.is_empty() {
+                for i in under {
+                    charged[i] = share(i);
+                }
+                break;
+            }
+            
+            for i in over {
+                let cap = caps[i].unwrap_or_default();
+                charged[i] = cap;
+                remaining = remaining.saturating_sub(cap);
+            }
+            open = under;
         }
+        
+        self.fee_charged = charged;
     }
 }
 
-/// Metrics for monitoring
+/// Settlement state of one sub-batch transaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Metrics {
-    /// Total batches processed
-    pub total_batches: u64,
+#[serde(tag = "status", content = "detail", rename_all = "snake_case")]
+pub enum SubBatchStatus {
+    /// Not sent (an earlier sub-batch lost the connection); intents re-queued
+    Pending,
+    
+    /// Mined; per-intent outcomes are in the result
+    Confirmed(Box<BatchResult>),
+    
+    /// Submission failed; intents re-queued (or dropped once out of retries)
+    Failed(String),
+}
+
+/// One settlement transaction of a split batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubBatch {
+    /// Batch ID
+    pub batch_id: u64,
+    
+    /// Original intents it carries
+    pub intent_ids: Vec<String>,
+    
+    /// Where its transaction stands
+    pub status: SubBatchStatus,
+}
+
+/// Outcome of one `process_batch` run, tracked per sub-batch transaction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSubmission {
+    /// Sub-batches in submission order
+    pub sub_batches: Vec<SubBatch>,
+}
+
+impl BatchSubmission {
+    /// Results of the sub-batches that confirmed
+    pub fn results(&self) -> impl Iterator<Item = &BatchResult> {
+        self.sub_batches.iter().filter_map(|sub| match &sub.status {
+            SubBatchStatus::Confirmed(result) => Some(result.as_ref()),
+            _ => None,
+        })
+    }
+    
+    /// Sub-batches whose transaction failed
+    pub fn failed(&self) -> impl Iterator<Item = &SubBatch> {
+        self.sub_batches.iter().filter(|sub| matches!(sub.status, SubBatchStatus::Failed(_)))
+    }
+    
+    /// Whether nothing was submitted (another `process_batch` run was busy)
+    pub fn is_empty(&self) -> bool {
+        self.sub_batches.is_empty()
+    }
+    
+    /// Whether every sub-batch confirmed
+    pub fn is_complete(&self) -> bool {
+        self.sub_batches.iter().all(|sub| matches!(sub.status, SubBatchStatus::Confirmed(_)))
+    }
+    
+    /// Results of the confirmed sub-batches, in submission order
+    pub fn into_results(self) -> Vec<BatchResult> {
+        self.sub_batches.into_iter()
+            .filter_map(|sub| match sub.status {
+                SubBatchStatus::Confirmed(result) => Some(*result),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Savings versus sending each intent as its own transaction
+///
+/// Computed as `(baseline - gas_used) / baseline` with
+/// `baseline = intent_count * per_tx_baseline_gas`. Negative when the batch
+/// cost more than the individual transactions would have.
+pub fn realized_savings_percent(intent_count: usize, per_tx_baseline_gas: u64, gas_used: U256) -> f64 {
+    let baseline = U256::from(intent_count as u128 * per_tx_baseline_gas as u128);
+    savings_percent(baseline, gas_used)
+}
+
+/// Percent of `baseline_gas` saved by spending `gas_used` instead
+///
+/// The one savings definition shared by batch estimates, realized results
+/// and the metrics; `baseline_gas` is always the traditional cost of sending
+/// each intent as its own transaction. 0 for an empty baseline.
+///
+/// Exact to `f64` precision over the whole `U256` range: values past
+/// `u128` are converted, not clamped, so the ratio survives.
+pub fn savings_percent(baseline_gas: U256, gas_used: U256) -> f64 {
+    if baseline_gas.is_zero() {
+        return 0.0;
+    }
+    
+    let baseline = u256_to_f64(baseline_gas);
+    let gas_used = u256_to_f64(gas_used);
+    (baseline - gas_used) / baseline * 100.0
+}
+
+/// `value` as `f64`, dropping only bits below `f64` precision
+fn u256_to_f64(value: U256) -> f64 {
+    let shift = value.bit_len().saturating_sub(128);
+    (value >> shift).to::<u128>() as f64 * 2f64.powi(shift as i32)
+}
+
+/// Signed difference `fees - cost`, saturating at the `I256` bounds
+fn net_profit(fees: U256, cost: U256) -> I256 {
+    let fees = I256::try_from(fees).unwrap_or(I256::MAX);
+    let cost = I256::try_from(cost).unwrap_or(I256::MAX);
+    fees.saturating_sub(cost)
+}
+
+/// Result of a dry-run `eth_call` of a batch submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSimulation {
+    /// Predicted success flag for each intent (empty if the call reverted)
+    pub successes: Vec<bool>,
+    
+    /// Decoded revert reason if the whole call reverted
+    pub revert_reason: Option<String>,
+}
+
+impl BatchSimulation {
+    /// Whether the whole batch call would revert
+    pub fn reverted(&self) -> bool {
+        self.revert_reason.is_some()
+    }
+    
+    /// Number of intents predicted to fail individually
+    pub fn failed_count(&self) -> usize {
+        self.successes.iter().filter(|ok| !**ok).count()
+    }
+}
+
+/// Why an intent was dropped instead of settled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "reason", content = "detail")]
+pub enum DropReason {
+    /// Still queued `max_intent_age_secs` after it was signed
+    Expired,
+    
+    /// Failed on-chain on its first attempt and every retry (failure count)
+    RetriesExhausted(u32),
+    
+    /// Sender was over its rate limit
+    RateLimited,
+    
+    /// Signature did not recover to the sender
+    InvalidSignature,
+    
+    /// Rejected at submission (bad fields, replayed nonce, ...)
+    Invalid(String),
+    
+    /// Predicted to fail by the pre-submission simulation
+    SimulationFailed,
+    
+    /// Cancelled by the sender, but the fishing spot never confirmed it (attempt count)
+    CancellationUnconfirmed(u32),
+}
+
+impl DropReason {
+    /// Reason for an intent refused with `error` at submission
+    pub fn rejected(error: &crate::Error) -> Self {
+        match error {
+            crate::Error::InvalidSignature => Self::InvalidSignature,
+            crate::Error::RateLimited(_) => Self::RateLimited,
+            crate::Error::InvalidIntent(reason) => Self::Invalid(reason.clone()),
+            other => Self::Invalid(other.to_string()),
+        }
+    }
+}
+
+/// Why an intent was refused at submission, as counted in [`Metrics`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// Signature did not recover to the sender (nor pass EIP-1271)
+    BadSignature,
+    
+    /// Id already batched or queued, or the sender's nonce already used or queued
+    StaleNonce,
+    
+    /// Sender was over its rate limit
+    RateLimited,
+    
+    /// Failed field validation (amount, timestamp, gas price, schema, ...)
+    Invalid,
+    
+    /// Queue at `max_queue_size`
+    QueueFull,
+    
+    /// Cancelled by its sender, still awaiting the fishing spot's confirmation
+    Cancelled,
+}
+
+impl RejectionReason {
+    /// Every reason, in reporting order
+    pub const ALL: [Self; 6] = [
+        Self::BadSignature,
+        Self::StaleNonce,
+        Self::RateLimited,
+        Self::Invalid,
+        Self::QueueFull,
+        Self::Cancelled,
+    ];
+    
+    /// Metric label (`bad_signature`, `stale_nonce`, ...)
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BadSignature => "bad_signature",
+            Self::StaleNonce => "stale_nonce",
+            Self::RateLimited => "rate_limited",
+            Self::Invalid => "invalid",
+            Self::QueueFull => "queue_full",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Read a JSON array of intents, as recorded from a fishing spot
+pub async fn load_intents_file(path: impl AsRef<std::path::Path>) -> crate::Result<Vec<Intent>> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Where settled batches are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionBackend {
+    /// Sign and send to the configured RPC node
+    Ethereum,
+    
+    /// Deterministic offline simulator, for load tests and demos
+    Simulate,
+    
+    /// Broadcast through the WASM host's imported RPC functions
+    Wasm,
+}
+
+impl Default for SubmissionBackend {
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Self::Wasm
+        } else {
+            Self::Ethereum
+        }
+    }
+}
+
+/// How the `Ethereum` backend gets a signed batch transaction to builders
+///
+/// Configured as `"public_mempool"` or `{"private_relay": {"url": "..."}}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionRoute {
+    /// `eth_sendRawTransaction` to the configured RPC node
+    #[default]
+    PublicMempool,
+    
+    /// `eth_sendPrivateTransaction` to a private order-flow relay
+    /// (Flashbots Protect, MEV-Share), out of reach of front-runners
+    PrivateRelay {
+        /// Relay JSON-RPC endpoint, e.g. `https://rpc.flashbots.net`
+        url: String,
+    },
+}
+
+/// Who may use an intent's `priority` flag
+///
+/// Configured as `"open"` or e.g. `{"downgrade": {"min_premium_wei": "0x3b9aca00"}}`.
+/// Under a restrictive policy priority is earned by a `priority_allowlist`
+/// sender, or by a `max_gas_price` at least `min_premium_wei` over the
+/// current base fee (measured from zero while offline). The flag is covered
+/// by the sender's signature, so it is never cleared; a downgraded intent is
+/// just ordered and batched like a normal one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityPolicy {
+    /// Any intent may claim priority
+    #[default]
+    Open,
+    
+    /// Unearned priority flags are accepted but ignored
+    Downgrade {
+        /// Gas price premium over the base fee that earns priority
+        min_premium_wei: U256,
+    },
+    
+    /// Unearned priority flags are refused at submission
+    Reject {
+        /// Gas price premium over the base fee that earns priority
+        min_premium_wei: U256,
+    },
+}
+
+impl PriorityPolicy {
+    /// Whether `intent` is handled as priority at `base_fee`
+    pub fn earns_priority(&self, intent: &Intent, allowlist: &HashSet<Address>, base_fee: U256) -> bool {
+        let min_premium = match self {
+            _ if !intent.priority => return false,
+            Self::Open => return true,
+            Self::Downgrade { min_premium_wei } | Self::Reject { min_premium_wei } => *min_premium_wei,
+        };
+        
+        allowlist.contains(&intent.from)
+            || intent.max_gas_price.is_some_and(|price| price >= base_fee.saturating_add(min_premium))
+    }
+}
+
+/// Whether batch data is posted as EIP-4844 blobs or as calldata
+///
+/// Configured as `true`, `false` or `"auto"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobMode {
+    /// Always calldata
+    Off,
+    
+    /// Always blobs
+    On,
+    
+    /// Whichever is cheaper for each batch at the current blob and execution base fees
+    Auto,
+}
+
+impl BlobMode {
+    /// Whether batches may go out as blobs (and so must fit blob limits)
+    pub fn enabled(self) -> bool {
+        self != Self::Off
+    }
+}
+
+impl From<bool> for BlobMode {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+}
+
+/// Config spelling of [`BlobMode`]: a flag, or a name (env values arrive as strings)
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum BlobModeSetting {
+    Flag(bool),
+    Name(String),
+}
+
+impl Serialize for BlobMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::Off => BlobModeSetting::Flag(false),
+            Self::On => BlobModeSetting::Flag(true),
+            Self::Auto => BlobModeSetting::Name("auto".to_string()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlobMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match BlobModeSetting::deserialize(deserializer)? {
+            BlobModeSetting::Flag(enabled) => Ok(enabled.into()),
+            BlobModeSetting::Name(name) => match name.to_ascii_lowercase().as_str() {
+                "auto" => Ok(Self::Auto),
+                "true" | "on" => Ok(Self::On),
+                "false" | "off" => Ok(Self::Off),
+                _ => Err(serde::de::Error::custom(format!(
+                    "invalid blob mode {:?} (expected true, false or \"auto\")",
+                    name
+                ))),
+            },
+        }
+    }
+}
+
+/// Fisher configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FisherConfig {
+    /// Ethereum RPC URL
+    pub rpc_url: String,
+    
+    /// Expected chain ID (checked against the RPC on connect when set)
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+    
+    /// Fisher contract address
+    pub fisher_address: Address,
+    
+    /// EVVM Core contract address
+    pub evvm_core_address: Address,
+    
+    /// Minimum batch size
+    pub min_batch_size: usize,
+    
+    /// Queue size that triggers immediate processing
+    #[serde(alias = "max_batch_size")]
+    pub batch_trigger_size: usize,
+    
+    /// Maximum intents settled in one on-chain transaction (block gas limit)
+    #[serde(default = "default_max_intents_per_tx")]
+    pub max_intents_per_tx: usize,
+    
+    /// Batch interval (milliseconds)
+    pub batch_interval_ms: u64,
+    
+    /// Delay before a run triggered by `batch_trigger_size` or the fast lane
+    /// starts, so a burst of triggers coalesces into one run (milliseconds)
+    #[serde(default = "default_batch_trigger_debounce_ms")]
+    pub batch_trigger_debounce_ms: u64,
+    
+    /// Offset batch ticks within the interval by an amount derived from
+    /// `fisher_address`, so a fleet doesn't submit on the same boundary
+    #[serde(default)]
+    pub tick_jitter: bool,
+    
+    /// Warn when φ-sorting and splitting one run's intents into batches
+    /// takes longer than this (milliseconds, 0 = never)
+    #[serde(default = "default_assembly_timeout_ms")]
+    pub assembly_timeout_ms: u64,
+    
+    /// Enable TDX attestation
+    pub enable_attestation: bool,
+    
+    /// Refuse to start without a TDX quote, rather than running flagged as unattested
+    #[serde(default)]
+    pub attestation_required: bool,
+    
+    /// Post batches as EIP-4844 blobs (`"auto"` compares current blob and calldata costs per batch)
+    pub enable_blobs: BlobMode,
+    
+    /// Most blobs one batch transaction may carry; checked against the
+    /// network's per-block maximum when `chain_id` is a known chain
+    #[serde(default = "default_max_blobs_per_tx")]
+    pub max_blobs_per_tx: usize,
+    
+    /// Reject plaintext intents; only accept intents encrypted to the TEE key
+    #[serde(default)]
+    pub require_encrypted_intents: bool,
+    
+    /// Accept EIP-1271 signatures from contract wallets (Safe, Argent) when
+    /// the ECDSA check fails; needs the RPC connection
+    #[serde(default)]
+    pub allow_contract_wallets: bool,
+    
+    /// Only accept intents whose id is their [`Intent::canonical_id`], so
+    /// dedup, cancellation and acknowledgment can't be confused by reused ids
+    #[serde(default)]
+    pub enforce_canonical_ids: bool,
+    
+    /// Dry-run each batch via `eth_call` and drop failing intents before submitting
+    #[serde(default)]
+    pub simulate_before_submit: bool,
+    
+    /// Where batches are submitted
+    #[serde(default)]
+    pub submission_backend: SubmissionBackend,
+    
+    /// Public mempool or private relay for Ethereum submissions
+    #[serde(default)]
+    pub submission_route: SubmissionRoute,
+    
+    /// Collapse same-sender intents to one recipient into a single summed payment
+    ///
+    /// Changes on-chain semantics: the contract must accept aggregated payments
+    /// carrying the concatenated signatures of their original intents.
+    #[serde(default)]
+    pub aggregate_same_recipient: bool,
+    
+    /// Most intents queued at once; further submissions are refused with
+    /// `Error::QueueFull` until a batch drains the queue (0 = unbounded).
+    /// Keep it at or above `batch_trigger_size` so a full queue triggers a batch
+    #[serde(default)]
+    pub max_queue_size: usize,
+    
+    /// Queued priority intents that trigger immediate processing, bypassing
+    /// `min_batch_size` (0 disables the fast lane)
+    #[serde(default = "default_priority_batch_threshold")]
+    pub priority_batch_threshold: usize,
+    
+    /// Senders (e.g. integration partners) whose intents lead every batch
+    /// ahead of φ-ordering, so split size caps always leave them in the first one
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub priority_allowlist: HashSet<Address>,
+    
+    /// Who may use the `priority` flag (anyone by default)
+    #[serde(default)]
+    pub priority_policy: PriorityPolicy,
+    
+    /// Maximum intents a single sender may submit per rate-limit window (0 disables)
+    #[serde(default = "default_max_intents_per_sender")]
+    pub max_intents_per_sender_per_window: u32,
+    
+    /// Rate-limit window (milliseconds)
+    #[serde(default = "default_rate_limit_window_ms")]
+    pub rate_limit_window_ms: u64,
+    
+    /// Minimum `max_gas_price` an intent may specify (wei)
+    #[serde(default)]
+    pub min_gas_price: U256,
+    
+    /// Most value one batch may settle (sum of intent amounts); larger
+    /// queues are split into several batches, all submitted in the same round
+    ///
+    /// This is a single global sum over every intent in the batch, regardless
+    /// of sender or recipient. Intents carry no token field and the Fisher
+    /// contract settles a single asset, so there is no per-token cap.
+    #[serde(default)]
+    pub max_batch_total_value: Option<U256>,
+    
+    /// Blocks a batch must be buried under before it counts as settled
+    #[serde(default = "default_confirmation_depth")]
+    pub confirmation_depth: u64,
+    
+    /// Initial delay before reconnecting after the RPC drops (milliseconds)
+    #[serde(default = "default_reconnect_backoff_ms")]
+    pub reconnect_backoff_ms: u64,
+    
+    /// Upper bound on the reconnect delay (milliseconds)
+    #[serde(default = "default_reconnect_max_backoff_ms")]
+    pub reconnect_max_backoff_ms: u64,
+    
+    /// Gas one intent would cost as a standalone transaction (savings baseline)
+    #[serde(default = "default_per_tx_baseline_gas")]
+    pub per_tx_baseline_gas: u64,
+    
+    /// Gas one per-user state update would cost without era tracking (savings baseline)
+    #[serde(default = "default_per_state_update_gas")]
+    pub per_state_update_gas: u64,
+    
+    /// Largest share of the block gas limit a single batch may use
+    #[serde(default = "default_block_gas_fraction")]
+    pub block_gas_fraction: f64,
+    
+    /// Savings (percent, projected at current fees) a batch must reach before
+    /// it is sent; below it, intents wait for a larger batch (unset disables)
+    #[serde(default)]
+    pub min_savings_percent: Option<f64>,
+    
+    /// Extra attempts an intent gets after failing on-chain before it is dropped
+    #[serde(default = "default_max_intent_retries")]
+    pub max_intent_retries: u32,
+    
+    /// How far an intent's timestamp may sit from our clock, either way (seconds)
+    #[serde(default = "default_max_intent_clock_skew_secs")]
+    pub max_intent_clock_skew_secs: u64,
+    
+    /// Most batched intent IDs (and sender nonces) remembered for replay protection
+    #[serde(default = "default_replay_state_capacity")]
+    pub replay_state_capacity: usize,
+    
+    /// How long replay-protection entries are kept once idle (seconds)
+    ///
+    /// Never less than twice `max_intent_clock_skew_secs`, so an entry
+    /// outlives every replay the timestamp check would still let through.
+    #[serde(default = "default_replay_state_ttl_secs")]
+    pub replay_state_ttl_secs: u64,
+    
+    /// Age (from its timestamp) at which a still-queued intent is dropped as expired (0 disables)
+    #[serde(default)]
+    pub max_intent_age_secs: u64,
+    
+    /// Most dropped intents held for `FisherRelayer::drained_intents` (oldest discarded first)
+    #[serde(default = "default_dead_letter_capacity")]
+    pub dead_letter_capacity: usize,
+    
+    /// Block gas limit assumed when the node can't report one
+    #[serde(default = "default_fallback_block_gas_limit")]
+    pub fallback_block_gas_limit: u64,
+    
+    /// φ-Freeman scoring coefficients used to order queued intents
+    #[serde(default)]
+    pub phi_weights: crate::phi_freeman::PhiWeights,
+    
+    /// Williams chunk-size formula parameters
+    #[serde(default)]
+    pub williams_params: crate::williams::WilliamsParams,
+    
+    /// φ-compounded per-operation era fee, enabling `fee_schedule` previews
+    #[serde(default)]
+    pub era_fees: Option<crate::phi_optimization::EraFeeParams>,
+    
+    /// File recording submitted batch transactions, so a restart never
+    /// resends a batch (in-memory only when unset)
+    #[serde(default)]
+    pub submission_log_path: Option<String>,
+    
+    /// File tracking intents collected from the fishing spot until they are
+    /// acknowledged, so a restart never resubmits them (in-memory only when unset)
+    #[serde(default)]
+    pub in_flight_log_path: Option<String>,
+    
+    /// Relayer private key (encrypted in TEE)
+    ///
+    /// Only ever read from `FISHER_PRIVATE_KEY`, never from a config file.
+    #[serde(skip)]
+    pub private_key: Option<String>,
+    
+    /// Key identifying this relayer to a private relay, so its reputation
+    /// survives restarts (a fresh key per start when unset); holds no funds
+    ///
+    /// Only ever read from `FISHER_RELAY_AUTH_KEY`, never from a config file.
+    #[serde(skip)]
+    pub relay_auth_key: Option<String>,
+}
+
+/// Environment variable holding the relayer private key
+pub const PRIVATE_KEY_ENV: &str = "FISHER_PRIVATE_KEY";
+
+/// Environment variable holding the private relay authentication key
+pub const RELAY_AUTH_KEY_ENV: &str = "FISHER_RELAY_AUTH_KEY";
+
+/// Settings that can come from the environment: (variable, field, plain string)
+///
+/// Non-string values are parsed as JSON (`5000`, `true`, `{"amount_weight": 0}`).
+const ENV_SETTINGS: &[(&str, &str, bool)] = &[
+    ("FISHER_RPC_URL", "rpc_url", true),
+    ("FISHER_CHAIN_ID", "chain_id", false),
+    ("FISHER_ADDRESS", "fisher_address", true),
+    ("FISHER_EVVM_CORE", "evvm_core_address", true),
+    ("FISHER_MIN_BATCH_SIZE", "min_batch_size", false),
+    ("FISHER_BATCH_TRIGGER_SIZE", "batch_trigger_size", false),
+    ("FISHER_MAX_INTENTS_PER_TX", "max_intents_per_tx", false),
+    ("FISHER_BATCH_INTERVAL_MS", "batch_interval_ms", false),
+    ("FISHER_BATCH_TRIGGER_DEBOUNCE_MS", "batch_trigger_debounce_ms", false),
+    ("FISHER_TICK_JITTER", "tick_jitter", false),
+    ("FISHER_ASSEMBLY_TIMEOUT_MS", "assembly_timeout_ms", false),
+    ("FISHER_ENFORCE_CANONICAL_IDS", "enforce_canonical_ids", false),
+    ("FISHER_ALLOW_CONTRACT_WALLETS", "allow_contract_wallets", false),
+    ("FISHER_MAX_BATCH_TOTAL_VALUE", "max_batch_total_value", false),
+    ("FISHER_SUBMISSION_ROUTE", "submission_route", false),
+    ("FISHER_MAX_QUEUE_SIZE", "max_queue_size", false),
+    ("FISHER_ENABLE_ATTESTATION", "enable_attestation", false),
+    ("FISHER_ENABLE_BLOBS", "enable_blobs", true),
+    ("FISHER_MAX_BLOBS_PER_TX", "max_blobs_per_tx", false),
+    ("FISHER_CONFIRMATION_DEPTH", "confirmation_depth", false),
+    ("FISHER_PRIORITY_ALLOWLIST", "priority_allowlist", false),
+    ("FISHER_PRIORITY_POLICY", "priority_policy", false),
+    ("FISHER_MIN_SAVINGS_PERCENT", "min_savings_percent", false),
+    ("FISHER_PHI_WEIGHTS", "phi_weights", false),
+    ("FISHER_WILLIAMS_PARAMS", "williams_params", false),
+    ("FISHER_ERA_FEES", "era_fees", false),
+    ("FISHER_SUBMISSION_LOG_PATH", "submission_log_path", true),
+    ("FISHER_IN_FLIGHT_LOG_PATH", "in_flight_log_path", true),
+];
+
+/// Settings holding an address, or a list of them, checked against EIP-55
+const ADDRESS_SETTINGS: &[&str] = &["fisher_address", "evvm_core_address", "priority_allowlist"];
+
+/// Settings without a default
+const REQUIRED_SETTINGS: &[&str] = &[
+    "rpc_url",
+    "fisher_address",
+    "evvm_core_address",
+    "min_batch_size",
+    "batch_trigger_size",
+    "batch_interval_ms",
+    "enable_attestation",
+    "enable_blobs",
+];
+
+impl FisherConfig {
+    /// Load configuration from JSON file
+    pub async fn load(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Self::from_settings(read_config_file(path).await?, |_| None)
+    }
+    
+    /// Load configuration purely from `FISHER_*` environment variables
+    pub fn from_env() -> crate::Result<Self> {
+        Self::from_settings(serde_json::json!({}), |var| std::env::var(var).ok())
+    }
+    
+    /// Load a JSON file, then overlay any `FISHER_*` environment variables
+    ///
+    /// Environment values win over the file.
+    pub async fn load_with_env_overrides(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        Self::from_settings(read_config_file(path).await?, |var| std::env::var(var).ok())
+    }
+    
+    /// Build a config from file settings overlaid with variables from `lookup`
+    fn from_settings(
+        mut settings: serde_json::Value,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> crate::Result<Self> {
+        let fields = settings.as_object_mut()
+            .ok_or_else(|| crate::Error::Config("Config must be a JSON object".to_string()))?;
+        
+        for (field, var) in [("private_key", PRIVATE_KEY_ENV), ("relay_auth_key", RELAY_AUTH_KEY_ENV)] {
+            if fields.contains_key(field) {
+                return Err(crate::Error::Config(format!(
+                    "{} must not be stored in the config file; set {} instead",
+                    field, var
+                )));
+            }
+        }
+        
+        for (var, field, plain_string) in ENV_SETTINGS {
+            let Some(raw) = lookup(var) else { continue };
+            
+            let value = if *plain_string {
+                serde_json::Value::String(raw)
+            } else {
+                serde_json::from_str(&raw)
+                    .map_err(|e| crate::Error::Config(format!("Invalid {} ({:?}): {}", var, raw, e)))?
+            };
+            
+            // The env value replaces the legacy spelling too
+            if *field == "batch_trigger_size" {
+                fields.remove("max_batch_size");
+            }
+            fields.insert(field.to_string(), value);
+        }
+        
+        for field in REQUIRED_SETTINGS {
+            let present = fields.contains_key(*field)
+                || (*field == "batch_trigger_size" && fields.contains_key("max_batch_size"));
+            
+            if !present {
+                let var = ENV_SETTINGS.iter().find(|(_, f, _)| f == field).map(|(v, _, _)| *v).unwrap();
+                return Err(crate::Error::Config(format!(
+                    "Missing required setting `{}`: set {} or add it to the config file",
+                    field, var
+                )));
+            }
+        }
+        
+        for field in ADDRESS_SETTINGS {
+            match fields.get(*field) {
+                Some(serde_json::Value::String(address)) => check_address_checksum(field, address)?,
+                Some(serde_json::Value::Array(addresses)) => {
+                    for address in addresses.iter().filter_map(|a| a.as_str()) {
+                        check_address_checksum(field, address)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        let mut config: Self = serde_json::from_value(settings)
+            .map_err(|e| crate::Error::Config(format!("Failed to parse config: {}", e)))?;
+        config.private_key = lookup(PRIVATE_KEY_ENV);
+        config.relay_auth_key = lookup(RELAY_AUTH_KEY_ENV);
+        
+        Ok(config)
+    }
+    
+    /// Derive the configuration for one chain, keeping shared batching settings
+    pub fn for_chain(&self, chain: &ChainConfig) -> Self {
+        Self {
+            rpc_url: chain.rpc_url.clone(),
+            chain_id: Some(chain.chain_id),
+            fisher_address: chain.fisher_address,
+            enable_blobs: match (chain.enable_blobs, self.enable_blobs) {
+                (false, _) => BlobMode::Off,
+                (true, BlobMode::Auto) => BlobMode::Auto,
+                (true, _) => BlobMode::On,
+            },
+            ..self.clone()
+        }
+    }
+    
+    /// SHA-256 over every security-relevant setting, bound into attestation reports
+    ///
+    /// Each setting is hashed as a `name=<json>` line in name order, so
+    /// operators can recompute it from their own config. The private key and
+    /// the [`UNHASHED_SETTINGS`] are left out.
+    pub fn config_hash(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        
+        let serde_json::Value::Object(fields) = serde_json::to_value(self)
+            .expect("FisherConfig serializes to a JSON object")
+        else {
+            unreachable!("FisherConfig serializes to a JSON object")
+        };
+        let fields: std::collections::BTreeMap<String, serde_json::Value> = fields.into_iter()
+            .filter(|(name, _)| !UNHASHED_SETTINGS.contains(&name.as_str()))
+            .collect();
+        
+        let mut hasher = Sha256::new();
+        for (name, value) in fields {
+            hasher.update(format!("{}={}\n", name, value));
+        }
+        hasher.finalize().into()
+    }
+    
+    /// Batch tick offset within `batch_interval_ms` (0 unless `tick_jitter` is set)
+    ///
+    /// Taken from the keccak hash of `fisher_address`, so it is stable across
+    /// restarts and differs between fishers without any randomness.
+    pub fn tick_jitter_ms(&self) -> u64 {
+        use sha3::{Digest, Keccak256};
+        
+        if !self.tick_jitter || self.batch_interval_ms == 0 {
+            return 0;
+        }
+        
+        let hash = Keccak256::digest(self.fisher_address.as_slice());
+        let seed = u64::from_be_bytes(hash[..8].try_into().unwrap());
+        seed % self.batch_interval_ms
+    }
+}
+
+/// Read a JSON config file into untyped settings
+async fn read_config_file(path: impl AsRef<std::path::Path>) -> crate::Result<serde_json::Value> {
+    let contents = tokio::fs::read_to_string(path).await
+        .map_err(|e| crate::Error::Config(format!("Failed to read config: {}", e)))?;
+    
+    serde_json::from_str(&contents)
+        .map_err(|e| crate::Error::Config(format!("Failed to parse config: {}", e)))
+}
+
+/// Reject a mixed-case address whose case doesn't match its EIP-55 checksum
+///
+/// All-lowercase and all-uppercase addresses carry no checksum and pass;
+/// anything that isn't an address is left for deserialization to report.
+fn check_address_checksum(field: &str, address: &str) -> crate::Result<()> {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    
+    if mixed_case && hex.len() == 40 && Address::parse_checksummed(address, None).is_err() {
+        return Err(crate::Error::Config(format!(
+            "Invalid `{}` address {}: EIP-55 checksum mismatch (check for a typo)",
+            field, address
+        )));
+    }
+    
+    Ok(())
+}
+
+/// Write a set in sorted order, so the serialized config (and its hash) is stable
+fn serialize_sorted<S: serde::Serializer>(set: &HashSet<Address>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_seq(set.iter().collect::<std::collections::BTreeSet<_>>())
+}
+
+fn default_priority_batch_threshold() -> usize {
+    1
+}
+
+fn default_max_intents_per_sender() -> u32 {
+    100
+}
+
+fn default_max_intents_per_tx() -> usize {
+    crate::MAX_BATCH_SIZE
+}
+
+fn default_rate_limit_window_ms() -> u64 {
+    60_000
+}
+
+fn default_batch_trigger_debounce_ms() -> u64 {
+    50
+}
+
+fn default_assembly_timeout_ms() -> u64 {
+    1_000
+}
+
+fn default_confirmation_depth() -> u64 {
+    1
+}
+
+fn default_reconnect_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_reconnect_max_backoff_ms() -> u64 {
+    60_000
+}
+
+fn default_per_tx_baseline_gas() -> u64 {
+    crate::phi_optimization::DEFAULT_PER_TX_BASELINE_GAS
+}
+
+fn default_per_state_update_gas() -> u64 {
+    crate::phi_optimization::DEFAULT_PER_STATE_UPDATE_GAS
+}
+
+fn default_max_intent_retries() -> u32 {
+    3
+}
+
+fn default_max_intent_clock_skew_secs() -> u64 {
+    300
+}
+
+fn default_replay_state_capacity() -> usize {
+    100_000
+}
+
+fn default_replay_state_ttl_secs() -> u64 {
+    3_600
+}
+
+fn default_dead_letter_capacity() -> usize {
+    1_000
+}
+
+fn default_block_gas_fraction() -> f64 {
+    0.9
+}
+
+fn default_fallback_block_gas_limit() -> u64 {
+    36_000_000
+}
+
+fn default_max_blobs_per_tx() -> usize {
+    crate::blob::MAX_BLOBS_PER_TX
+}
+
+/// Settings that don't affect how intents are handled: local paths and
+/// reconnect/bookkeeping tuning
+pub const UNHASHED_SETTINGS: &[&str] = &[
+    "submission_log_path",
+    "in_flight_log_path",
+    "reconnect_backoff_ms",
+    "reconnect_max_backoff_ms",
+    "dead_letter_capacity",
+    "tick_jitter",
+    "batch_trigger_debounce_ms",
+    "assembly_timeout_ms",
+];
+
+impl Default for FisherConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: "http://localhost:8545".to_string(),
+            chain_id: None,
+            fisher_address: Address::ZERO,
+            evvm_core_address: Address::ZERO,
+            min_batch_size: 10,
+            batch_trigger_size: 1000,
+            max_intents_per_tx: default_max_intents_per_tx(),
+            batch_interval_ms: 5000,
+            tick_jitter: false,
+            batch_trigger_debounce_ms: default_batch_trigger_debounce_ms(),
+            assembly_timeout_ms: default_assembly_timeout_ms(),
+            enable_attestation: true,
+            attestation_required: false,
+            enable_blobs: BlobMode::On,  // Enable blobs by default for best savings
+            max_blobs_per_tx: default_max_blobs_per_tx(),
+            require_encrypted_intents: false,
+            allow_contract_wallets: false,
+            enforce_canonical_ids: false,
+            simulate_before_submit: false,
+            submission_backend: SubmissionBackend::default(),
+            submission_route: SubmissionRoute::default(),
+            aggregate_same_recipient: false,
+            max_queue_size: 0,
+            priority_batch_threshold: default_priority_batch_threshold(),
+            priority_allowlist: HashSet::new(),
+            priority_policy: PriorityPolicy::Open,
+            max_intents_per_sender_per_window: default_max_intents_per_sender(),
+            rate_limit_window_ms: default_rate_limit_window_ms(),
+            min_gas_price: U256::ZERO,
+            max_batch_total_value: None,
+            confirmation_depth: default_confirmation_depth(),
+            reconnect_backoff_ms: default_reconnect_backoff_ms(),
+            reconnect_max_backoff_ms: default_reconnect_max_backoff_ms(),
+            per_tx_baseline_gas: default_per_tx_baseline_gas(),
+            per_state_update_gas: default_per_state_update_gas(),
+            block_gas_fraction: default_block_gas_fraction(),
+            min_savings_percent: None,
+            max_intent_retries: default_max_intent_retries(),
+            max_intent_clock_skew_secs: default_max_intent_clock_skew_secs(),
+            replay_state_capacity: default_replay_state_capacity(),
+            replay_state_ttl_secs: default_replay_state_ttl_secs(),
+            max_intent_age_secs: 0,
+            dead_letter_capacity: default_dead_letter_capacity(),
+            fallback_block_gas_limit: default_fallback_block_gas_limit(),
+            phi_weights: crate::phi_freeman::PhiWeights::default(),
+            williams_params: crate::williams::WilliamsParams::default(),
+            era_fees: None,
+            submission_log_path: None,
+            in_flight_log_path: None,
+            private_key: None,
+            relay_auth_key: None,
+        }
+    }
+}
+
+/// Metrics for monitoring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Metrics {
+    /// Total batches processed
+    pub total_batches: u64,
     
     /// Total intents processed
     pub total_intents: u64,
@@ -219,66 +1937,416 @@ pub struct Metrics {
     /// Total gas saved
     pub total_gas_saved: U256,
     
-    /// Average batch size
-    pub avg_batch_size: f64,
+    /// Realized savings percent per batch (combined Williams + φ-optimization)
+    #[serde(default)]
+    pub savings_percent: Mean,
+    
+    /// Williams compression savings per on-chain batch, at the contract's chunk size
+    #[serde(default)]
+    pub williams_savings: Mean,
+    
+    /// Estimated φ-optimization savings per batch (the contract doesn't report
+    /// state-update gas separately, so this is never measured)
+    #[serde(default, alias = "phi_savings")]
+    pub phi_savings_estimate: Mean,
+    
+    /// Total gas saved as reported by the contract's `BatchSubmitted` events
+    #[serde(default)]
+    pub total_contract_gas_saved: U256,
+    
+    /// EIP-4844 blob savings per blob batch
+    #[serde(default)]
+    pub blob_savings: Mean,
+    
+    /// Total batches using blobs
+    pub blob_batches: u64,
+    
+    /// Processing time per batch (ms)
+    #[serde(default)]
+    pub processing_time_ms: Mean,
+    
+    /// Blob payload compression ratio per batch
+    #[serde(default)]
+    pub compression_ratio: Mean,
+    
+    /// Total fees collected across all batches
+    #[serde(default)]
+    pub total_fees_collected: U256,
+    
+    /// Total gas cost (wei) across all batches
+    #[serde(default)]
+    pub total_gas_cost: U256,
+    
+    /// Total staking rewards auto-claimed
+    #[serde(default)]
+    pub total_rewards_claimed: U256,
+    
+    /// Most recent era whose rewards were auto-claimed
+    #[serde(default)]
+    pub last_claimed_era: Option<u64>,
+    
+    /// Intents currently waiting in the queue
+    #[serde(default)]
+    pub current_queue_depth: u64,
+    
+    /// Deepest the queue has been
+    #[serde(default)]
+    pub max_queue_depth_seen: u64,
+    
+    /// Time from intent creation to batch inclusion, per batched intent (ms)
+    #[serde(default)]
+    pub intent_wait_ms: Mean,
+    
+    /// Distribution of realized savings across settled batches
+    #[serde(default)]
+    pub savings_histogram: SavingsHistogram,
+    
+    /// Intents re-queued after failing on-chain
+    #[serde(default)]
+    pub intents_retried: u64,
+    
+    /// Intents dropped after exhausting their on-chain retries
+    #[serde(default)]
+    pub intents_dropped: u64,
+    
+    /// Unix time (seconds) the most recent batch settled
+    #[serde(default)]
+    pub last_batch_timestamp: Option<u64>,
+    
+    /// Attestation state found at startup
+    #[serde(default)]
+    pub attestation: crate::attestation::AttestationStatus,
+    
+    /// Whether batch submission is paused for maintenance
+    #[serde(default)]
+    pub paused: bool,
+    
+    /// Time to φ-sort and split one run's intents into batches (ms)
+    #[serde(default)]
+    pub assembly_time_ms: Mean,
+    
+    /// Batch assemblies that took longer than `assembly_timeout_ms`
+    #[serde(default)]
+    pub slow_assemblies: u64,
+    
+    /// Early batch runs scheduled by a full queue or the priority fast lane
+    #[serde(default)]
+    pub triggered_runs: u64,
+    
+    /// Early triggers folded into a run that was already scheduled
+    #[serde(default)]
+    pub coalesced_triggers: u64,
+    
+    /// Intents refused for a bad signature
+    #[serde(default)]
+    pub rejected_bad_signature: u64,
+    
+    /// Intents refused as replays (id already batched or nonce reused)
+    #[serde(default)]
+    pub rejected_stale_nonce: u64,
+    
+    /// Intents refused by the per-sender rate limit
+    #[serde(default)]
+    pub rejected_rate_limited: u64,
+    
+    /// Intents refused by field validation
+    #[serde(default)]
+    pub rejected_invalid: u64,
+    
+    /// Intents refused because the queue was full
+    #[serde(default)]
+    pub rejected_queue_full: u64,
+    
+    /// Intents refused because their sender cancelled them
+    #[serde(default)]
+    pub rejected_cancelled: u64,
+}
+
+/// Running mean kept as a sum and a count
+///
+/// Averages are computed on read, so recording order doesn't matter and
+/// the compensated sum keeps rounding error flat over millions of samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mean {
+    sum: f64,
+    compensation: f64,
+    count: u64,
+}
+
+impl Mean {
+    /// Add one sample
+    pub fn record(&mut self, value: f64) {
+        // Neumaier summation
+        let sum = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - sum) + value;
+        } else {
+            self.compensation += (value - sum) + self.sum;
+        }
+        self.sum = sum;
+        self.count += 1;
+    }
+    
+    /// Sum of all samples
+    pub fn sum(&self) -> f64 {
+        self.sum + self.compensation
+    }
     
-    /// Average savings percent (combined Williams + φ-optimization)
-    pub avg_savings_percent: f64,
+    /// Number of samples recorded
+    pub fn count(&self) -> u64 {
+        self.count
+    }
     
-    /// Average Williams compression savings
-    pub avg_williams_savings: f64,
+    /// Mean of all samples (0 before the first)
+    pub fn value(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum() / self.count as f64
+    }
+}
+
+/// Settled batches bucketed by realized savings percentage
+///
+/// Averages hide the tail; alert when `below_80` grows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavingsHistogram {
+    /// Batches saving less than 80%
+    pub below_80: u64,
     
-    /// Average φ-optimization savings
-    pub avg_phi_savings: f64,
+    /// Batches saving 80% up to 90%
+    pub from_80_to_90: u64,
     
-    /// EIP-4844 blob savings
-    pub avg_blob_savings: f64,
+    /// Batches saving 90% up to 95%
+    pub from_90_to_95: u64,
     
-    /// Total batches using blobs
-    pub blob_batches: u64,
+    /// Batches saving 95% or more
+    pub from_95: u64,
+}
+
+impl SavingsHistogram {
+    /// Count one batch with the given realized savings
+    pub fn record(&mut self, savings_percent: f64) {
+        let bucket = match savings_percent {
+            p if p >= 95.0 => &mut self.from_95,
+            p if p >= 90.0 => &mut self.from_90_to_95,
+            p if p >= 80.0 => &mut self.from_80_to_90,
+            _ => &mut self.below_80,
+        };
+        *bucket += 1;
+    }
     
-    /// Average processing time (ms)
-    pub avg_processing_time_ms: f64,
+    /// Bucket labels and counts, lowest savings first
+    pub fn buckets(&self) -> [(&'static str, u64); 4] {
+        [
+            ("<80", self.below_80),
+            ("80-90", self.from_80_to_90),
+            ("90-95", self.from_90_to_95),
+            (">=95", self.from_95),
+        ]
+    }
 }
 
 impl Metrics {
+    /// Lifetime fees collected minus gas spent
+    pub fn net_profit(&self) -> I256 {
+        net_profit(self.total_fees_collected, self.total_gas_cost)
+    }
+    
+    /// Record the current queue depth, tracking the peak
+    pub fn record_queue_depth(&mut self, depth: usize) {
+        self.current_queue_depth = depth as u64;
+        self.max_queue_depth_seen = self.max_queue_depth_seen.max(depth as u64);
+    }
+    
+    /// Record one batch assembly, counting it as slow past `timeout_ms` (0 = never)
+    pub fn record_assembly(&mut self, elapsed_ms: u64, timeout_ms: u64) {
+        self.assembly_time_ms.record(elapsed_ms as f64);
+        if timeout_ms > 0 && elapsed_ms > timeout_ms {
+            self.slow_assemblies += 1;
+        }
+    }
+    
+    /// Count one intent refused at submission
+    pub fn record_rejection(&mut self, reason: RejectionReason) {
+        *self.rejection_counter(reason) += 1;
+    }
+    
+    /// Intents refused for `reason`
+    pub fn rejections(&self, reason: RejectionReason) -> u64 {
+        match reason {
+            RejectionReason::BadSignature => self.rejected_bad_signature,
+            RejectionReason::StaleNonce => self.rejected_stale_nonce,
+            RejectionReason::RateLimited => self.rejected_rate_limited,
+            RejectionReason::Invalid => self.rejected_invalid,
+            RejectionReason::QueueFull => self.rejected_queue_full,
+            RejectionReason::Cancelled => self.rejected_cancelled,
+        }
+    }
+    
+    /// Intents refused at submission for any reason
+    pub fn total_rejections(&self) -> u64 {
+        RejectionReason::ALL.iter().map(|reason| self.rejections(*reason)).sum()
+    }
+    
+    fn rejection_counter(&mut self, reason: RejectionReason) -> &mut u64 {
+        match reason {
+            RejectionReason::BadSignature => &mut self.rejected_bad_signature,
+            RejectionReason::StaleNonce => &mut self.rejected_stale_nonce,
+            RejectionReason::RateLimited => &mut self.rejected_rate_limited,
+            RejectionReason::Invalid => &mut self.rejected_invalid,
+            RejectionReason::QueueFull => &mut self.rejected_queue_full,
+            RejectionReason::Cancelled => &mut self.rejected_cancelled,
+        }
+    }
+    
+    /// Fold intents included in a batch at `now_ms` into the average wait
+    pub fn record_intent_waits(&mut self, intents: &[Intent], now_ms: u64) {
+        for intent in intents {
+            let wait_ms = now_ms.saturating_sub(intent.timestamp.saturating_mul(1000));
+            self.intent_wait_ms.record(wait_ms as f64);
+        }
+    }
+    
+    /// Intents drained into batches (samples behind `avg_intent_wait_ms`)
+    pub fn intents_batched(&self) -> u64 {
+        self.intent_wait_ms.count()
+    }
+    
+    /// Average intents per batch
+    pub fn avg_batch_size(&self) -> f64 {
+        if self.total_batches == 0 {
+            return 0.0;
+        }
+        self.total_intents as f64 / self.total_batches as f64
+    }
+    
+    /// Average realized savings percent
+    pub fn avg_savings_percent(&self) -> f64 {
+        self.savings_percent.value()
+    }
+    
+    /// Average Williams compression savings measured on-chain
+    pub fn avg_williams_savings(&self) -> f64 {
+        self.williams_savings.value()
+    }
+    
+    /// Average estimated φ-optimization savings
+    pub fn avg_phi_savings_estimate(&self) -> f64 {
+        self.phi_savings_estimate.value()
+    }
+    
+    /// Average EIP-4844 blob savings
+    pub fn avg_blob_savings(&self) -> f64 {
+        self.blob_savings.value()
+    }
+    
+    /// Average processing time (ms)
+    pub fn avg_processing_time_ms(&self) -> f64 {
+        self.processing_time_ms.value()
+    }
+    
+    /// Average blob payload compression ratio
+    pub fn avg_compression_ratio(&self) -> f64 {
+        self.compression_ratio.value()
+    }
+    
+    /// Average time from intent creation to batch inclusion (ms)
+    pub fn avg_intent_wait_ms(&self) -> f64 {
+        self.intent_wait_ms.value()
+    }
+    
+    /// Average batch assembly time (ms)
+    pub fn avg_assembly_time_ms(&self) -> f64 {
+        self.assembly_time_ms.value()
+    }
+    
     /// Display human-readable summary
     pub fn summary(&self) -> String {
         let blob_info = if self.blob_batches > 0 {
             format!(
                 "\n             EIP-4844 blobs:        {:.2}% ({} batches)",
-                self.avg_blob_savings,
+                self.avg_blob_savings(),
                 self.blob_batches
             )
         } else {
             String::new()
         };
         
+        // Only measured for batches settled against the contract
+        let williams = if self.williams_savings.count() > 0 {
+            format!("{:.2}%", self.avg_williams_savings())
+        } else {
+            "n/a".to_string()
+        };
+        
         format!(
             "📊 Fisher Metrics Summary\n\
              ═══════════════════════════════════════\n\
              Batches processed:     {}\n\
              Intents processed:     {}\n\
              Total gas saved:       {} gas\n\
+             Contract-reported:     {} gas\n\
              \n\
              💰 Gas Savings Breakdown:\n\
-             Williams compression:  {:.2}%\n\
-             φ-optimization:        {:.2}%{}\n\
+             Williams compression:  {}\n\
+             φ-optimization (est.): {:.2}%{}\n\
              Combined total:        {:.2}%\n\
              \n\
+             🏦 Profitability:\n\
+             Fees collected:        {} wei\n\
+             Gas spent:             {} wei\n\
+             Net profit:            {} wei\n\
+             \n\
              📈 Performance:\n\
              Avg batch size:        {:.1} intents\n\
              Avg processing time:   {:.1}ms\n\
+             Avg assembly time:     {:.1}ms ({} slow)\n\
+             Avg compression:       {:.2}x\n\
+             \n\
+             📥 Queue:\n\
+             Submission paused:     {}\n\
+             Current depth:         {}\n\
+             Peak depth:            {}\n\
+             Avg intent wait:       {:.1}ms\n\
+             Early batch runs:      {} ({} coalesced)\n\
+             Retried on-chain:      {}\n\
+             Dropped after retries: {}\n\
+             Rejected on submit:    {}\n\
+             \n\
+             📉 Realized savings per batch:\n\
+             {}\n\
              ═══════════════════════════════════════",
             self.total_batches,
             self.total_intents,
             self.total_gas_saved,
-            self.avg_williams_savings,
-            self.avg_phi_savings,
+            self.total_contract_gas_saved,
+            williams,
+            self.avg_phi_savings_estimate(),
             blob_info,
-            self.avg_savings_percent,
-            self.avg_batch_size,
-            self.avg_processing_time_ms,
+            self.avg_savings_percent(),
+            self.total_fees_collected,
+            self.total_gas_cost,
+            self.net_profit(),
+            self.avg_batch_size(),
+            self.avg_processing_time_ms(),
+            self.avg_assembly_time_ms(),
+            self.slow_assemblies,
+            self.avg_compression_ratio(),
+            if self.paused { "yes" } else { "no" },
+            self.current_queue_depth,
+            self.max_queue_depth_seen,
+            self.avg_intent_wait_ms(),
+            self.triggered_runs,
+            self.coalesced_triggers,
+            self.intents_retried,
+            self.intents_dropped,
+            self.total_rejections(),
+            self.savings_histogram.buckets()
+                .iter()
+                .map(|(label, count)| format!("{:<23}{}", format!("{}%:", label), count))
+                .collect::<Vec<_>>()
+                .join("\n"),
         )
     }
 }
@@ -289,13 +2357,821 @@ impl Default for Metrics {
             total_batches: 0,
             total_intents: 0,
             total_gas_saved: U256::ZERO,
-            avg_batch_size: 0.0,
-            avg_savings_percent: 0.0,
-            avg_williams_savings: 0.0,
-            avg_phi_savings: 0.0,
-            avg_blob_savings: 0.0,
+            savings_percent: Mean::default(),
+            williams_savings: Mean::default(),
+            phi_savings_estimate: Mean::default(),
+            total_contract_gas_saved: U256::ZERO,
+            blob_savings: Mean::default(),
             blob_batches: 0,
-            avg_processing_time_ms: 0.0,
+            processing_time_ms: Mean::default(),
+            compression_ratio: Mean::default(),
+            total_fees_collected: U256::ZERO,
+            total_gas_cost: U256::ZERO,
+            total_rewards_claimed: U256::ZERO,
+            last_claimed_era: None,
+            current_queue_depth: 0,
+            max_queue_depth_seen: 0,
+            intent_wait_ms: Mean::default(),
+            savings_histogram: SavingsHistogram::default(),
+            intents_retried: 0,
+            intents_dropped: 0,
+            last_batch_timestamp: None,
+            attestation: crate::attestation::AttestationStatus::Disabled,
+            paused: false,
+            assembly_time_ms: Mean::default(),
+            slow_assemblies: 0,
+            triggered_runs: 0,
+            coalesced_triggers: 0,
+            rejected_bad_signature: 0,
+            rejected_stale_nonce: 0,
+            rejected_rate_limited: 0,
+            rejected_invalid: 0,
+            rejected_queue_full: 0,
+            rejected_cancelled: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn valid_intent() -> Intent {
+        Intent::new(
+            "intent_1".to_string(),
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            U256::from(1_000),
+            false,
+            0,
+            vec![0xDE, 0xAD, 0xBE, 0xEF],
+        )
+    }
+    
+    fn config_with_floor(floor: u64) -> FisherConfig {
+        FisherConfig {
+            min_gas_price: U256::from(floor),
+            ..FisherConfig::default()
+        }
+    }
+    
+    fn rejection(intent: &Intent, config: &FisherConfig) -> String {
+        match intent.validate(config) {
+            Err(crate::Error::InvalidIntent(msg)) => msg,
+            other => panic!("expected InvalidIntent, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_validate_accepts_valid_intent() {
+        let mut intent = valid_intent();
+        intent.max_gas_price = Some(U256::from(2_000_000_000u64));
+        
+        assert!(intent.validate(&config_with_floor(1_000_000_000)).is_ok());
+    }
+    
+    #[test]
+    fn test_validate_rejects_zero_amount() {
+        let mut intent = valid_intent();
+        intent.amount = U256::ZERO;
+        
+        assert!(rejection(&intent, &FisherConfig::default()).contains("amount"));
+    }
+    
+    #[test]
+    fn test_validate_rejects_self_transfer() {
+        let mut intent = valid_intent();
+        intent.to = intent.from;
+        
+        assert!(rejection(&intent, &FisherConfig::default()).contains("self-transfer"));
+    }
+    
+    #[test]
+    fn test_validate_rejects_amount_over_batch_value_cap() {
+        let config = FisherConfig { max_batch_total_value: Some(U256::from(1_000)), ..FisherConfig::default() };
+        assert!(valid_intent().validate(&config).is_ok());
+        
+        let whale = Intent { amount: U256::from(1_001), ..valid_intent() };
+        assert!(rejection(&whale, &config).contains("max_batch_total_value"));
+    }
+    
+    #[test]
+    fn test_validate_rejects_gas_price_below_floor() {
+        let mut intent = valid_intent();
+        intent.max_gas_price = Some(U256::from(1u64));
+        
+        assert!(rejection(&intent, &config_with_floor(1_000_000_000)).contains("max_gas_price"));
+    }
+    
+    #[test]
+    fn test_validate_timestamp_within_skew_window() {
+        let config = FisherConfig::default();
+        let now = valid_intent().timestamp;
+        
+        let future = Intent { timestamp: now + 3_600, ..valid_intent() };
+        assert!(rejection(&future, &config).contains("timestamp"));
+        
+        let stale = Intent { timestamp: now - 86_400, ..valid_intent() };
+        assert!(rejection(&stale, &config).contains("timestamp"));
+        
+        // Small clock differences either way are tolerated
+        for timestamp in [now - 60, now + 60] {
+            assert!(Intent { timestamp, ..valid_intent() }.validate(&config).is_ok());
+        }
+        
+        let strict = FisherConfig { max_intent_clock_skew_secs: 30, ..FisherConfig::default() };
+        assert!(Intent { timestamp: now + 60, ..valid_intent() }.validate(&strict).is_err());
+    }
+    
+    #[test]
+    fn test_canonical_id_stable_for_identical_intents() {
+        let intent = valid_intent();
+        let id = intent.canonical_id();
+        assert!(id.starts_with("0x") && id.len() == 66, "{}", id);
+        
+        // The claimed id and signature don't change it
+        let twin = Intent {
+            id: "something_else".to_string(),
+            signature: vec![0x01],
+            ..intent.clone()
+        };
+        assert_eq!(twin.canonical_id(), id);
+        
+        // Any other signed difference does
+        let others = [
+            Intent { amount: U256::from(1_001), ..intent.clone() },
+            Intent { nonce: 1, ..intent.clone() },
+            Intent { priority: true, ..intent.clone() },
+            Intent { max_gas_price: Some(U256::from(1)), ..intent.clone() },
+            Intent { target_chain: Some(10), ..intent.clone() },
+            Intent { timestamp: intent.timestamp + 5, ..intent.clone() },
+        ];
+        for other in others {
+            assert_ne!(other.canonical_id(), id);
+        }
+    }
+    
+    #[test]
+    fn test_validate_enforces_canonical_ids() {
+        let config = FisherConfig { enforce_canonical_ids: true, ..FisherConfig::default() };
+        
+        let spoofed = valid_intent();
+        assert!(rejection(&spoofed, &config).contains("canonical id"));
+        assert!(spoofed.validate(&FisherConfig::default()).is_ok());
+        
+        let canonical = Intent { id: spoofed.canonical_id(), ..spoofed.clone() };
+        assert!(canonical.validate(&config).is_ok());
+        
+        // Upper-case hex is a different string, not the canonical id
+        let shouting = Intent { id: canonical.id.to_uppercase(), ..spoofed };
+        assert!(shouting.validate(&config).is_err());
+    }
+    
+    fn result_with(fees: u64, gas_cost: u64) -> BatchResult {
+        BatchResult {
+            batch_id: 1,
+            tx_hash: String::new(),
+            gas_used: U256::ZERO,
+            gas_saved: U256::ZERO,
+            successes: vec![],
+            processing_time_ms: 0,
+            used_blob: false,
+            blob_gas_saved: U256::ZERO,
+            fees_collected: U256::from(fees),
+            gas_cost_wei: U256::from(gas_cost),
+            estimated_savings_percent: 0.0,
+            calldata_bytes: 0,
+            blob_bytes: 0,
+            compression_ratio: 0.0,
+            williams_savings_percent: None,
+            contract_gas_saved: U256::ZERO,
+            fee_charged: Vec::new(),
+            onchain_batch_id: None,
+            onchain_operation_count: None,
+            realized_savings_percent: 0.0,
+        }
+    }
+    
+    #[test]
+    fn test_net_profit() {
+        assert_eq!(result_with(1_000, 400).net_profit(), I256::try_from(600).unwrap());
+        assert_eq!(result_with(400, 1_000).net_profit(), I256::try_from(-600).unwrap());
+        
+        let metrics = Metrics {
+            total_fees_collected: U256::from(5),
+            total_gas_cost: U256::from(3),
+            ..Metrics::default()
+        };
+        assert_eq!(metrics.net_profit(), I256::try_from(2).unwrap());
+    }
+    
+    /// Fees charged for a 300k-gas batch at 20 gwei, one payment per cap
+    fn fees_for_caps(caps: &[Option<u64>]) -> (Vec<U256>, Vec<Option<U256>>) {
+        const GWEI: u64 = 1_000_000_000;
+        
+        let intents: Vec<Intent> = caps.iter()
+            .map(|cap| Intent { max_gas_price: cap.map(|c| U256::from(c * GWEI)), ..valid_intent() })
+            .collect();
+        let batch = Batch {
+            id: 1,
+            intents,
+            chunk_size: 1,
+            phi_score: 0.0,
+            estimated_gas: U256::ZERO,
+            estimated_savings: U256::ZERO,
+            created_at: 0,
+            recipient_groups: vec![],
+            aggregated: vec![],
+        };
+        
+        let mut result = BatchResult { gas_used: U256::from(300_000), ..result_with(0, 300_000 * 20 * GWEI) };
+        result.attribute_fees(&batch);
+        
+        // Cap on the whole charge: the gas price cap times an equal share of the gas
+        let limits = caps.iter()
+            .map(|cap| cap.map(|c| U256::from(c * GWEI) * U256::from(300_000 / caps.len())))
+            .collect();
+        (result.fee_charged, limits)
+    }
+    
+    #[test]
+    fn test_fees_weighted_by_gas_cap() {
+        let total = U256::from(300_000u64 * 20_000_000_000);
+        
+        // Uncapped intents weigh in at the effective 20 gwei
+        let (fees, limits) = fees_for_caps(&[Some(10), Some(30), None]);
+        assert!(fees[0] < fees[2] && fees[2] < fees[1], "{:?}", fees);
+        assert_eq!(fees[1], fees[0] * U256::from(3));
+        assert_eq!(fees.iter().fold(U256::ZERO, |t, f| t + f), total);
+        for (fee, limit) in fees.iter().zip(&limits) {
+            assert!(limit.is_none_or(|limit| *fee <= limit));
+        }
+        
+        // Caps below the effective price bind; the uncapped intent covers the rest
+        let (fees, limits) = fees_for_caps(&[Some(10), Some(15), None]);
+        assert_eq!(fees[0], limits[0].unwrap());
+        assert_eq!(fees[1], limits[1].unwrap());
+        assert!(fees[0] < fees[1]);
+        assert_eq!(fees.iter().fold(U256::ZERO, |t, f| t + f), total);
+        
+        // Everyone capped: the relayer absorbs the shortfall
+        let (fees, limits) = fees_for_caps(&[Some(1), Some(2)]);
+        assert_eq!(fees, vec![limits[0].unwrap(), limits[1].unwrap()]);
+        assert!(fees.iter().fold(U256::ZERO, |t, f| t + f) < total);
+        
+        // Nothing spent, nothing charged
+        assert_eq!(fees_for_caps(&[]).0, Vec::<U256>::new());
+    }
+    
+    #[test]
+    fn test_mean_stays_exact_over_many_samples() {
+        let mut mean = Mean::default();
+        assert_eq!(mean.value(), 0.0);
+        
+        // 0.1 isn't representable, so a naive running average drifts
+        for i in 0..1_000_000u64 {
+            mean.record(90.0 + (i % 10) as f64 * 0.1);
+        }
+        
+        assert_eq!(mean.count(), 1_000_000);
+        assert!((mean.value() - 90.45).abs() < 1e-12, "mean {}", mean.value());
+    }
+    
+    #[test]
+    fn test_realized_savings_from_gas_used() {
+        // 10 intents at 100K baseline = 1M gas
+        assert_eq!(realized_savings_percent(10, 100_000, U256::from(140_000)), 86.0);
+        assert_eq!(realized_savings_percent(10, 100_000, U256::from(1_500_000)), -50.0);
+        assert_eq!(realized_savings_percent(0, 100_000, U256::from(21_000)), 0.0);
+    }
+    
+    #[test]
+    fn test_savings_percent_past_u128() {
+        let near = U256::from(u128::MAX);
+        assert_eq!(savings_percent(near, near - near / U256::from(4)), 25.0);
+        
+        // Clamping both to u128::MAX would report 0%
+        let huge = near * U256::from(1_000);
+        assert_eq!(savings_percent(huge, huge / U256::from(2)), 50.0);
+        assert_eq!(savings_percent(huge, huge * U256::from(3)), -200.0);
+        assert_eq!(savings_percent(U256::MAX, U256::ZERO), 100.0);
+        assert_eq!(savings_percent(U256::MAX, U256::MAX), 0.0);
+        
+        // A small baseline against a huge cost stays finite
+        let percent = savings_percent(U256::from(1), U256::MAX);
+        assert!(percent.is_finite() && percent < -1e70, "{}", percent);
+        
+        let batch = Batch {
+            id: 1,
+            intents: Vec::new(),
+            chunk_size: 0,
+            phi_score: 0.0,
+            estimated_gas: huge,
+            estimated_savings: huge,
+            created_at: 0,
+            recipient_groups: Vec::new(),
+            aggregated: Vec::new(),
+        };
+        assert_eq!(batch.savings_percent(), 50.0);
+    }
+    
+    fn wallet(seed: u8) -> ethers::signers::LocalWallet {
+        ethers::signers::LocalWallet::from_bytes(&[seed; 32]).unwrap()
+    }
+    
+    fn intent_from(wallet: &ethers::signers::LocalWallet) -> Intent {
+        use ethers::signers::Signer;
+        Intent {
+            from: Address::from_slice(wallet.address().as_bytes()),
+            ..valid_intent()
+        }
+    }
+    
+    #[test]
+    fn test_signature_recovers_sender() {
+        let alice = wallet(1);
+        let intent = intent_from(&alice).signed(&alice).unwrap();
+        assert!(intent.verify_signature());
+        
+        // Any change to a signed field invalidates the signature
+        let tampered = Intent { amount: U256::from(1_000_000), ..intent.clone() };
+        assert!(!tampered.verify_signature());
+        let tampered = Intent { priority: !intent.priority, ..intent };
+        assert!(!tampered.verify_signature());
+    }
+    
+    #[test]
+    fn test_signature_covers_every_field() {
+        let alice = wallet(1);
+        let intent = Intent {
+            max_gas_price: Some(U256::from(2_000_000_000u64)),
+            target_chain: Some(1),
+            permit: Some(test_permit(1_000)),
+            ..intent_from(&alice)
+        }
+        .signed(&alice)
+        .unwrap();
+        assert!(intent.verify_signature());
+        
+        let tampered = [
+            Intent { id: "intent_2".to_string(), ..intent.clone() },
+            Intent { from: Address::repeat_byte(0x07), ..intent.clone() },
+            Intent { to: Address::repeat_byte(0x07), ..intent.clone() },
+            Intent { amount: intent.amount + U256::from(1), ..intent.clone() },
+            Intent { priority: !intent.priority, ..intent.clone() },
+            Intent { nonce: intent.nonce + 1, ..intent.clone() },
+            Intent { timestamp: intent.timestamp + 1, ..intent.clone() },
+            Intent { max_gas_price: Some(U256::from(3_000_000_000u64)), ..intent.clone() },
+            Intent { max_gas_price: None, ..intent.clone() },
+            Intent { target_chain: Some(10), ..intent.clone() },
+            Intent { target_chain: None, ..intent.clone() },
+            Intent { permit: Some(test_permit(2_000)), ..intent.clone() },
+            Intent { permit: None, ..intent.clone() },
+        ];
+        for (i, tampered) in tampered.iter().enumerate() {
+            assert!(!tampered.verify_signature(), "tampered field {} still verifies", i);
+        }
+        
+        // Normalization rewrites the version without breaking the signature
+        let legacy = Intent { version: 1, ..intent_from(&alice) }.signed(&alice).unwrap();
+        assert!(legacy.normalized().unwrap().verify_signature());
+    }
+    
+    #[test]
+    fn test_signature_from_other_signer_rejected() {
+        let alice = wallet(1);
+        let bob = wallet(2);
+        
+        // Valid signature by B attached to an intent claiming from = A
+        let intent = intent_from(&alice).signed(&bob).unwrap();
+        assert!(!intent.verify_signature());
+        
+        assert!(!Intent { signature: vec![0xDE, 0xAD, 0xBE, 0xEF], ..intent.clone() }.verify_signature());
+        assert!(!Intent { signature: vec![], ..intent }.verify_signature());
+    }
+    
+    #[test]
+    fn test_high_s_signature_rejected() {
+        let alice = wallet(1);
+        let intent = intent_from(&alice).signed(&alice).unwrap();
+        
+        // Malleate (r, s, v) into (r, n - s, v ^ 1), which recovers to the same key
+        let mut signature = ethers::types::Signature::try_from(intent.signature.as_slice()).unwrap();
+        let order = ethers::types::U256::from_big_endian(&SECP256K1_HALF_ORDER) * 2 + 1;
+        signature.s = order - signature.s;
+        signature.v = if signature.v == 27 { 28 } else { 27 };
+        assert_eq!(
+            signature.recover(intent.signing_hash().to_vec()).unwrap().as_bytes(),
+            intent.from.as_slice()
+        );
+        
+        let malleated = Intent { signature: signature.to_vec(), ..intent };
+        assert!(!malleated.verify_signature());
+    }
+    
+    #[test]
+    fn test_only_canonical_recovery_id_accepted() {
+        let alice = wallet(1);
+        let intent = intent_from(&alice).signed(&alice).unwrap();
+        let signature = ethers::types::Signature::try_from(intent.signature.as_slice()).unwrap();
+        assert!(is_canonical(&signature));
+        assert!(intent.verify_signature());
+        
+        // Same (r, s) under raw, EIP-155 or out-of-range v encodings
+        let recovery_id = signature.v - 27;
+        // EIP-155 on mainnet: v = 35 + 2 * chain_id + recovery_id
+        for v in [recovery_id, 37 + recovery_id, 29, 255] {
+            let variant = ethers::types::Signature { v, ..signature };
+            let intent = Intent { signature: variant.to_vec(), ..intent.clone() };
+            assert!(!intent.verify_signature(), "v = {} accepted", v);
+        }
+    }
+    
+    fn file_settings() -> serde_json::Value {
+        serde_json::json!({
+            "rpc_url": "http://file:8545",
+            "fisher_address": "0x0000000000000000000000000000000000000001",
+            "evvm_core_address": "0x0000000000000000000000000000000000000002",
+            "min_batch_size": 10,
+            "batch_trigger_size": 1000,
+            "batch_interval_ms": 5000,
+            "enable_attestation": false,
+            "enable_blobs": false
+        })
+    }
+    
+    #[tokio::test]
+    async fn test_env_overrides_config_file() {
+        let path = std::env::temp_dir().join(format!("fisher-config-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, file_settings().to_string()).unwrap();
+        
+        std::env::set_var("FISHER_RPC_URL", "http://env:8545");
+        std::env::set_var("FISHER_ADDRESS", "0x00000000000000000000000000000000000000aa");
+        std::env::set_var("FISHER_MIN_BATCH_SIZE", "25");
+        std::env::set_var(PRIVATE_KEY_ENV, "0xfeed");
+        
+        let config = FisherConfig::load_with_env_overrides(&path).await.unwrap();
+        assert_eq!(config.rpc_url, "http://env:8545");
+        assert_eq!(config.fisher_address, Address::with_last_byte(0xaa));
+        assert_eq!(config.min_batch_size, 25);
+        assert_eq!(config.private_key.as_deref(), Some("0xfeed"));
+        // Untouched settings still come from the file
+        assert_eq!(config.evvm_core_address, Address::with_last_byte(2));
+        assert_eq!(config.batch_trigger_size, 1000);
+        
+        // Plain load ignores the environment entirely
+        let config = FisherConfig::load(&path).await.unwrap();
+        assert_eq!(config.rpc_url, "http://file:8545");
+        assert_eq!(config.private_key, None);
+        
+        // The environment alone is missing the remaining required settings
+        match FisherConfig::from_env() {
+            Err(crate::Error::Config(msg)) => assert!(msg.contains("FISHER_EVVM_CORE"), "{}", msg),
+            other => panic!("expected missing setting, got {:?}", other),
+        }
+        
+        for var in ["FISHER_RPC_URL", "FISHER_ADDRESS", "FISHER_MIN_BATCH_SIZE", PRIVATE_KEY_ENV] {
+            std::env::remove_var(var);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_from_env_settings() {
+        let env: std::collections::HashMap<&str, &str> = [
+            ("FISHER_RPC_URL", "http://env:8545"),
+            ("FISHER_ADDRESS", "0x0000000000000000000000000000000000000001"),
+            ("FISHER_EVVM_CORE", "0x0000000000000000000000000000000000000002"),
+            ("FISHER_MIN_BATCH_SIZE", "5"),
+            ("FISHER_BATCH_TRIGGER_SIZE", "500"),
+            ("FISHER_BATCH_INTERVAL_MS", "1000"),
+            ("FISHER_ENABLE_ATTESTATION", "true"),
+            ("FISHER_ENABLE_BLOBS", "false"),
+            ("FISHER_PHI_WEIGHTS", r#"{"amount_weight": 0.0}"#),
+        ].into_iter().collect();
+        let lookup = |var: &str| env.get(var).map(|v| v.to_string());
+        
+        let config = FisherConfig::from_settings(serde_json::json!({}), lookup).unwrap();
+        assert_eq!(config.batch_trigger_size, 500);
+        assert!(config.enable_attestation);
+        assert_eq!(config.enable_blobs, BlobMode::Off);
+        assert_eq!(config.phi_weights.amount_weight, 0.0);
+        assert_eq!(config.private_key, None);
+        
+        // A malformed numeric value names the variable
+        let bad = |var: &str| if var == "FISHER_MIN_BATCH_SIZE" { Some("ten".to_string()) } else { lookup(var) };
+        match FisherConfig::from_settings(serde_json::json!({}), bad) {
+            Err(crate::Error::Config(msg)) => assert!(msg.contains("FISHER_MIN_BATCH_SIZE"), "{}", msg),
+            other => panic!("expected invalid setting, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_blob_mode_settings() {
+        for (raw, mode) in [
+            (serde_json::json!(true), BlobMode::On),
+            (serde_json::json!(false), BlobMode::Off),
+            (serde_json::json!("auto"), BlobMode::Auto),
+            (serde_json::json!("AUTO"), BlobMode::Auto),
+            (serde_json::json!("off"), BlobMode::Off),
+        ] {
+            assert_eq!(serde_json::from_value::<BlobMode>(raw).unwrap(), mode);
+        }
+        assert!(serde_json::from_value::<BlobMode>(serde_json::json!("sometimes")).is_err());
+        
+        // Fixed modes keep their boolean spelling
+        assert_eq!(serde_json::to_value(BlobMode::On).unwrap(), serde_json::json!(true));
+        assert_eq!(serde_json::to_value(BlobMode::Auto).unwrap(), serde_json::json!("auto"));
+    }
+    
+    /// Required settings from the environment, with `FISHER_ADDRESS` set to `address`
+    fn env_with_fisher_address(address: &str) -> impl Fn(&str) -> Option<String> {
+        let env: std::collections::HashMap<&str, String> = [
+            ("FISHER_RPC_URL", "http://env:8545".to_string()),
+            ("FISHER_ADDRESS", address.to_string()),
+            ("FISHER_EVVM_CORE", "0x0000000000000000000000000000000000000002".to_string()),
+            ("FISHER_MIN_BATCH_SIZE", "5".to_string()),
+            ("FISHER_BATCH_TRIGGER_SIZE", "500".to_string()),
+            ("FISHER_BATCH_INTERVAL_MS", "1000".to_string()),
+            ("FISHER_ENABLE_ATTESTATION", "false".to_string()),
+            ("FISHER_ENABLE_BLOBS", "false".to_string()),
+        ].into_iter().collect();
+        move |var| env.get(var).cloned()
+    }
+    
+    #[test]
+    fn test_tick_jitter_stable_per_address() {
+        let config = |byte: u8| FisherConfig {
+            fisher_address: Address::repeat_byte(byte),
+            batch_interval_ms: 5_000,
+            tick_jitter: true,
+            ..FisherConfig::default()
+        };
+        
+        let (a, b) = (config(0x01).tick_jitter_ms(), config(0x02).tick_jitter_ms());
+        assert_ne!(a, b);
+        assert!(a < 5_000 && b < 5_000);
+        assert_eq!(config(0x01).tick_jitter_ms(), a);
+        
+        // Off by default, and meaningless without an interval
+        assert_eq!(FisherConfig { tick_jitter: false, ..config(0x01) }.tick_jitter_ms(), 0);
+        assert_eq!(FisherConfig { batch_interval_ms: 0, ..config(0x01) }.tick_jitter_ms(), 0);
+    }
+    
+    #[test]
+    fn test_config_address_checksums() {
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let load = |address: &str| FisherConfig::from_settings(serde_json::json!({}), env_with_fisher_address(address));
+        
+        let expected: Address = checksummed.parse().unwrap();
+        assert_eq!(load(checksummed).unwrap().fisher_address, expected);
+        
+        // No checksum to verify either way
+        assert_eq!(load(&checksummed.to_lowercase()).unwrap().fisher_address, expected);
+        assert_eq!(load(&format!("0x{}", checksummed[2..].to_uppercase())).unwrap().fisher_address, expected);
+        
+        // One flipped letter is a typo
+        let broken = checksummed.replacen("aA", "Aa", 1);
+        match load(&broken) {
+            Err(crate::Error::Config(msg)) => assert!(msg.contains("fisher_address") && msg.contains("checksum"), "{}", msg),
+            other => panic!("expected checksum error, got {:?}", other),
+        }
+        
+        // Allowlist entries in the config file are checked too
+        let settings = serde_json::json!({ "priority_allowlist": [checksummed, broken] });
+        match FisherConfig::from_settings(settings, env_with_fisher_address(checksummed)) {
+            Err(crate::Error::Config(msg)) => assert!(msg.contains("priority_allowlist"), "{}", msg),
+            other => panic!("expected checksum error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_missing_required_setting_names_env_var() {
+        match FisherConfig::from_settings(serde_json::json!({}), |_| None) {
+            Err(crate::Error::Config(msg)) => {
+                assert!(msg.contains("rpc_url") && msg.contains("FISHER_RPC_URL"), "{}", msg)
+            }
+            other => panic!("expected missing setting, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_config_hash_covers_relevant_settings() {
+        let base = FisherConfig::default();
+        let hash = base.config_hash();
+        assert_eq!(base.clone().config_hash(), hash);
+        
+        let changed = [
+            FisherConfig { rpc_url: "http://other:8545".to_string(), ..base.clone() },
+            FisherConfig { chain_id: Some(5), ..base.clone() },
+            FisherConfig { fisher_address: Address::repeat_byte(0x42), ..base.clone() },
+            FisherConfig { evvm_core_address: Address::repeat_byte(0x43), ..base.clone() },
+            FisherConfig { min_batch_size: base.min_batch_size + 1, ..base.clone() },
+            FisherConfig { max_intents_per_tx: base.max_intents_per_tx + 1, ..base.clone() },
+            FisherConfig { enable_blobs: BlobMode::Auto, ..base.clone() },
+            FisherConfig { max_blobs_per_tx: 3, ..base.clone() },
+            FisherConfig {
+                priority_policy: PriorityPolicy::Downgrade { min_premium_wei: U256::from(1) },
+                ..base.clone()
+            },
+            FisherConfig { require_encrypted_intents: true, ..base.clone() },
+            FisherConfig { enforce_canonical_ids: true, ..base.clone() },
+            FisherConfig { allow_contract_wallets: true, ..base.clone() },
+            FisherConfig { max_batch_total_value: Some(U256::from(1_000)), ..base.clone() },
+            FisherConfig {
+                submission_route: SubmissionRoute::PrivateRelay { url: "https://rpc.flashbots.net".to_string() },
+                ..base.clone()
+            },
+            FisherConfig { min_gas_price: U256::from(1), ..base.clone() },
+            FisherConfig { block_gas_fraction: 0.5, ..base.clone() },
+            FisherConfig { phi_weights: crate::phi_freeman::PhiWeights::fair(60), ..base.clone() },
+        ];
+        for config in changed {
+            assert_ne!(config.config_hash(), hash, "{:?}", config);
+        }
+        
+        // Secrets and local bookkeeping don't change what is attested
+        let unchanged = [
+            FisherConfig { private_key: Some(hex::encode([0x11; 32])), ..base.clone() },
+            FisherConfig { submission_log_path: Some("/var/lib/fisher/log".to_string()), ..base.clone() },
+            FisherConfig { in_flight_log_path: Some("/var/lib/fisher/in-flight".to_string()), ..base.clone() },
+            FisherConfig { reconnect_backoff_ms: 5, ..base.clone() },
+            FisherConfig { batch_trigger_debounce_ms: 0, ..base.clone() },
+        ];
+        for config in unchanged {
+            assert_eq!(config.config_hash(), hash);
+        }
+    }
+    
+    #[test]
+    fn test_priority_allowlist_hashed_in_stable_order() {
+        let partners: Vec<Address> = (1..=20u8).map(Address::repeat_byte).collect();
+        let forward = FisherConfig { priority_allowlist: partners.iter().copied().collect(), ..FisherConfig::default() };
+        let reverse = FisherConfig { priority_allowlist: partners.iter().rev().copied().collect(), ..FisherConfig::default() };
+        
+        assert_eq!(forward.config_hash(), reverse.config_hash());
+        assert_ne!(forward.config_hash(), FisherConfig::default().config_hash());
+        
+        let mut settings = file_settings();
+        settings["priority_allowlist"] = serde_json::json!(partners);
+        let parsed = FisherConfig::from_settings(settings, |_| None).unwrap();
+        assert_eq!(parsed.priority_allowlist, forward.priority_allowlist);
+    }
+    
+    #[test]
+    fn test_private_key_rejected_in_config_file() {
+        let mut settings = file_settings();
+        settings["private_key"] = "0xfeed".into();
+        
+        match FisherConfig::from_settings(settings, |_| None) {
+            Err(crate::Error::Config(msg)) => assert!(msg.contains(PRIVATE_KEY_ENV), "{}", msg),
+            other => panic!("expected private_key rejection, got {:?}", other),
+        }
+        
+        let mut settings = file_settings();
+        settings["relay_auth_key"] = "0xfeed".into();
+        match FisherConfig::from_settings(settings, |_| None) {
+            Err(crate::Error::Config(msg)) => assert!(msg.contains(RELAY_AUTH_KEY_ENV), "{}", msg),
+            other => panic!("expected relay_auth_key rejection, got {:?}", other),
+        }
+        
+        let config = FisherConfig::from_settings(file_settings(), |var| {
+            (var == RELAY_AUTH_KEY_ENV).then(|| "0xbeef".to_string())
+        }).unwrap();
+        assert_eq!(config.relay_auth_key.as_deref(), Some("0xbeef"));
+    }
+    
+    #[test]
+    fn test_builder_requires_sender() {
+        let result = Intent::builder()
+            .to(Address::repeat_byte(0x02))
+            .amount(U256::from(1_000))
+            .build();
+        assert!(matches!(result, Err(crate::Error::InvalidIntent(msg)) if msg.contains("`from`")));
+        
+        let result = Intent::builder()
+            .from(Address::ZERO)
+            .to(Address::repeat_byte(0x02))
+            .amount(U256::from(1_000))
+            .build();
+        assert!(matches!(result, Err(crate::Error::InvalidIntent(_))));
+    }
+    
+    #[test]
+    fn test_builder_produces_valid_intent() {
+        let config = FisherConfig::default();
+        let wallet = ethers::signers::LocalWallet::from_bytes(&[0x11; 32]).unwrap();
+        let from = Address::from_slice(ethers::signers::Signer::address(&wallet).as_bytes());
+        
+        let intent = Intent::builder()
+            .from(from)
+            .to(Address::repeat_byte(0x02))
+            .amount(U256::from(1_000))
+            .nonce(7)
+            .build()
+            .unwrap()
+            .signed(&wallet)
+            .unwrap();
+        
+        // Defaults match Intent::new
+        assert_eq!(intent.version, INTENT_VERSION);
+        assert!(intent.timestamp > 0);
+        assert!(!intent.priority);
+        assert_eq!(intent.id, intent.canonical_id());
+        
+        assert!(intent.verify_signature());
+        assert!(intent.validate(&config).is_ok(), "{:?}", intent.validate(&config));
+        
+        let named = Intent::builder()
+            .id("intent_1")
+            .from(from)
+            .to(Address::repeat_byte(0x02))
+            .amount(U256::from(1_000))
+            .timestamp(42)
+            .build()
+            .unwrap();
+        assert_eq!(named.id, "intent_1");
+        assert_eq!(named.timestamp, 42);
+    }
+    
+    #[test]
+    fn test_v1_payload_upgrades_to_current_version() {
+        // A v1 client knows nothing of chain routing or versions
+        let mut payload = serde_json::to_value(valid_intent()).unwrap();
+        let fields = payload.as_object_mut().unwrap();
+        fields.remove("target_chain");
+        fields.remove("version");
+        
+        let intent: Intent = serde_json::from_value(payload).unwrap();
+        assert_eq!(intent.version, 1);
+        
+        let intent = intent.normalized().unwrap();
+        assert_eq!(intent.version, INTENT_VERSION);
+        assert_eq!(intent.target_chain, None);
+        assert_eq!(intent.id, "intent_1");
+        assert_eq!(intent.amount, U256::from(1_000));
+        assert_eq!(intent.signature, vec![0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+    
+    #[test]
+    fn test_v2_payload_upgrades_without_permit() {
+        let mut payload = serde_json::to_value(valid_intent()).unwrap();
+        let fields = payload.as_object_mut().unwrap();
+        fields.remove("permit");
+        fields.insert("version".to_string(), serde_json::json!(2));
+        
+        let intent: Intent = serde_json::from_value(payload).unwrap();
+        let intent = intent.normalized().unwrap();
+        assert_eq!(intent.version, INTENT_VERSION);
+        assert_eq!(intent.permit, None);
+    }
+    
+    fn test_permit(deadline: u64) -> Permit {
+        Permit { deadline, v: 27, r: B256::repeat_byte(0x0A), s: B256::repeat_byte(0x0B) }
+    }
+    
+    #[test]
+    fn test_permit_roundtrips_and_is_validated() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let config = FisherConfig::default();
+        
+        let intent = valid_intent().with_permit(test_permit(now + 600));
+        let json = serde_json::to_string(&intent).unwrap();
+        let decoded: Intent = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.permit, Some(test_permit(now + 600)));
+        assert!(intent.validate(&config).is_ok());
+        
+        let expired = valid_intent().with_permit(test_permit(now - 1));
+        assert!(matches!(expired.validate(&config), Err(crate::Error::InvalidIntent(msg)) if msg.contains("permit")));
+    }
+    
+    #[test]
+    fn test_unknown_intent_versions_rejected() {
+        assert!(valid_intent().normalized().is_ok());
+        
+        for version in [0, INTENT_VERSION + 1, u8::MAX] {
+            let intent = Intent { version, ..valid_intent() };
+            assert!(matches!(intent.normalized(), Err(crate::Error::InvalidIntent(_))));
         }
     }
+    
+    #[test]
+    fn test_priority_policy_requires_premium_or_allowlist() {
+        let gwei = |n: u64| U256::from(n * 1_000_000_000);
+        let bidding = |price| Intent { priority: true, max_gas_price: Some(gwei(price)), ..valid_intent() };
+        let policy: PriorityPolicy = serde_json::from_value(serde_json::json!({
+            "downgrade": {"min_premium_wei": format!("{:#x}", gwei(10))}
+        })).unwrap();
+        let none = HashSet::new();
+        
+        assert!(!policy.earns_priority(&bidding(11), &none, gwei(2)));
+        assert!(policy.earns_priority(&bidding(12), &none, gwei(2)));
+        assert!(!policy.earns_priority(&Intent { max_gas_price: None, ..bidding(0) }, &none, U256::ZERO));
+        assert!(!policy.earns_priority(&Intent { priority: false, ..bidding(12) }, &none, gwei(2)));
+        
+        // Allowlisted senders and the open policy need no premium
+        let allowlist = HashSet::from([valid_intent().from]);
+        assert!(policy.earns_priority(&bidding(0), &allowlist, gwei(2)));
+        assert!(PriorityPolicy::Open.earns_priority(&bidding(0), &none, gwei(2)));
+    }
 }
@@ -0,0 +1,126 @@
+//! Parallel intent signature verification
+//!
+//! Recovering an ECDSA signer is the most expensive per-intent step, so a
+//! full 2000-intent batch is checked across the rayon thread pool rather
+//! than one signature at a time. Results come back in index order, so the
+//! same batch always rejects the same intents.
+
+use crate::Intent;
+use rayon::prelude::*;
+
+/// Indices of intents whose signature doesn't recover to `from`, ascending
+pub fn invalid_signatures(intents: &[Intent]) -> Vec<usize> {
+    intents.par_iter()
+        .enumerate()
+        .filter(|(_, intent)| !intent.verify_signature())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Split intents into those with valid signatures and the rejected ones
+///
+/// Both halves keep their original relative order.
+pub fn partition_valid(intents: Vec<Intent>) -> (Vec<Intent>, Vec<Intent>) {
+    let invalid = invalid_signatures(&intents);
+    if invalid.is_empty() {
+        return (intents, Vec::new());
+    }
+    
+    let mut rejected = Vec::with_capacity(invalid.len());
+    let mut valid = Vec::with_capacity(intents.len() - invalid.len());
+    let mut next_invalid = invalid.iter().peekable();
+    
+    for (index, intent) in intents.into_iter().enumerate() {
+        if next_invalid.next_if_eq(&&index).is_some() {
+            rejected.push(intent);
+        } else {
+            valid.push(intent);
+        }
+    }
+    
+    (valid, rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+    use ethers::signers::{LocalWallet, Signer};
+    
+    fn signed_intent(id: usize, wallet: &LocalWallet) -> Intent {
+        Intent::new(
+            format!("sig_{}", id),
+            Address::from_slice(wallet.address().as_bytes()),
+            Address::repeat_byte(0x01),
+            U256::from(100),
+            false,
+            id as u64,
+            vec![],
+        )
+        .signed(wallet)
+        .unwrap()
+    }
+    
+    fn wallets() -> Vec<LocalWallet> {
+        (1..=4u8).map(|seed| LocalWallet::from_bytes(&[seed; 32]).unwrap()).collect()
+    }
+    
+    #[test]
+    fn test_mixed_valid_and_invalid_signatures() {
+        let wallets = wallets();
+        let mut intents: Vec<Intent> = (0..20)
+            .map(|i| signed_intent(i, &wallets[i % wallets.len()]))
+            .collect();
+        
+        // Forged sender, garbage signature, missing signature, tampered amount
+        intents[3].from = Address::repeat_byte(0xAA);
+        intents[7].signature = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        intents[12].signature.clear();
+        intents[19].amount = U256::from(1_000_000);
+        
+        // Same rejections on every run
+        for _ in 0..5 {
+            assert_eq!(invalid_signatures(&intents), vec![3, 7, 12, 19]);
+        }
+        
+        let (valid, rejected) = partition_valid(intents);
+        assert_eq!(valid.len(), 16);
+        let rejected_ids: Vec<&str> = rejected.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(rejected_ids, vec!["sig_3", "sig_7", "sig_12", "sig_19"]);
+        assert!(valid.iter().all(Intent::verify_signature));
+        assert!(valid.windows(2).all(|w| w[0].nonce < w[1].nonce));
+    }
+    
+    #[test]
+    fn test_parallel_verification_1000_intents() {
+        let wallets = wallets();
+        let mut intents: Vec<Intent> = (0..1000)
+            .map(|i| signed_intent(i, &wallets[i % wallets.len()]))
+            .collect();
+        for i in (0..1000).step_by(97) {
+            intents[i].signature[10] ^= 0xFF;
+        }
+        
+        let start = std::time::Instant::now();
+        let sequential: Vec<usize> = intents.iter()
+            .enumerate()
+            .filter(|(_, intent)| !intent.verify_signature())
+            .map(|(index, _)| index)
+            .collect();
+        let sequential_time = start.elapsed();
+        
+        let start = std::time::Instant::now();
+        let parallel = invalid_signatures(&intents);
+        let parallel_time = start.elapsed();
+        
+        println!(
+            "1000 signatures: sequential {:?}, parallel {:?} ({} threads)",
+            sequential_time,
+            parallel_time,
+            rayon::current_num_threads()
+        );
+        
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel, (0..1000).step_by(97).collect::<Vec<_>>());
+    }
+}
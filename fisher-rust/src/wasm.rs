@@ -1,8 +1,50 @@
 //! WASM bindings for browser/Enarx integration
+//!
+//! Besides the full relayer wrapper, this exposes stateless batch-preview
+//! helpers (φ-ordering, Williams chunking, savings estimates) that browser
+//! simulators can call without a relayer or any chain access.
+
+use crate::phi_freeman::phi_sort;
+use crate::williams::williams_chunk_size;
+use serde::{Deserialize, Serialize};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
+pub use crate::phi_optimization::SavingsEstimate;
+
+/// φ-ordering and chunking a batch would use, without submitting it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchPreview {
+    /// Intent IDs in φ-Freeman order
+    pub order: Vec<String>,
+    
+    /// Williams chunk size for the batch
+    pub chunk_size: usize,
+    
+    /// Estimated savings for the batch size
+    pub savings: SavingsEstimate,
+}
+
+/// Preview a JSON array of intents as a JSON [`BatchPreview`]
+pub fn preview_batch_json(intents_json: &str) -> crate::Result<String> {
+    let mut intents: Vec<crate::Intent> = serde_json::from_str(intents_json)?;
+    phi_sort(&mut intents);
+    
+    let preview = BatchPreview {
+        chunk_size: williams_chunk_size(intents.len()),
+        savings: SavingsEstimate::for_batch_size(intents.len()),
+        order: intents.into_iter().map(|i| i.id).collect(),
+    };
+    
+    Ok(serde_json::to_string(&preview)?)
+}
+
+/// Savings estimate for `batch_size` as JSON
+pub fn savings_estimate_json(batch_size: usize) -> crate::Result<String> {
+    Ok(serde_json::to_string(&SavingsEstimate::for_batch_size(batch_size))?)
+}
+
 #[cfg(target_arch = "wasm32")]
 use crate::{FisherRelayer, FisherConfig, Intent};
 
@@ -65,6 +107,29 @@ impl WasmFisherRelayer {
     }
 }
 
+/// Estimated savings for a batch size, as JSON
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn estimate_total_savings(batch_size: usize) -> Result<String, JsValue> {
+    savings_estimate_json(batch_size)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Williams chunk size for `n` intents
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = williams_chunk_size)]
+pub fn wasm_williams_chunk_size(n: usize) -> usize {
+    williams_chunk_size(n)
+}
+
+/// φ-ordering and chunk size for a JSON array of intents, without submitting
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn preview_batch(intents_json: &str) -> Result<String, JsValue> {
+    preview_batch_json(intents_json)
+        .map_err(|e| JsValue::from_str(&format!("Preview error: {}", e)))
+}
+
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(start)]
 pub fn main() {
@@ -74,3 +139,50 @@ pub fn main() {
     // Initialize tracing
     tracing_wasm::set_as_global_default();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Intent;
+    use alloy_primitives::{Address, U256};
+    
+    fn intent(id: &str, priority: bool, timestamp: u64) -> Intent {
+        Intent {
+            timestamp,
+            ..Intent::new(id.to_string(), Address::ZERO, Address::ZERO, U256::from(100), priority, 0, vec![])
+        }
+    }
+    
+    #[test]
+    fn test_preview_batch_json_roundtrip() {
+        let intents: Vec<Intent> = (0..16)
+            .map(|i| intent(&format!("i{}", i), i == 7, 1_000))
+            .collect();
+        let json = serde_json::to_string(&intents).unwrap();
+        
+        let preview: BatchPreview = serde_json::from_str(&preview_batch_json(&json).unwrap()).unwrap();
+        
+        // Same ordering as sorting natively
+        let mut sorted = intents.clone();
+        phi_sort(&mut sorted);
+        assert_eq!(preview.order, sorted.iter().map(|i| i.id.clone()).collect::<Vec<_>>());
+        assert_eq!(preview.order[0], "i7");
+        assert_eq!(preview.chunk_size, williams_chunk_size(16));
+        let expected = SavingsEstimate::for_batch_size(16);
+        assert!((preview.savings.combined_percent - expected.combined_percent).abs() < 1e-9);
+        assert_eq!(preview.savings.gas_saved, expected.gas_saved);
+        
+        assert!(preview_batch_json("not json").is_err());
+    }
+    
+    #[test]
+    fn test_savings_estimate_json() {
+        let estimate: SavingsEstimate =
+            serde_json::from_str(&savings_estimate_json(100).unwrap()).unwrap();
+        assert!(estimate.combined_percent > 85.0);
+        
+        // Empty batch serializes as zeros rather than NaN
+        let empty: SavingsEstimate = serde_json::from_str(&savings_estimate_json(0).unwrap()).unwrap();
+        assert_eq!(empty.combined_percent, 0.0);
+    }
+}
@@ -3,9 +3,47 @@
 //! Implements Ryan Williams' space-time tradeoff for batch operations.
 //! Achieves 86-91% memory reduction compared to standard O(n) approaches.
 
-use crate::{Intent, Result};
+use crate::{Error, Intent, Result};
+use serde::{Deserialize, Serialize};
 use std::cmp::min;
 
+/// Tuning for the chunk-size formula `√n · ⌈log₂ n⌉^log_exponent`
+///
+/// Missing fields deserialize to the defaults, which reproduce the classic
+/// `√n · ⌈log₂ n⌉` formula.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WilliamsParams {
+    /// Exponent applied to the `⌈log₂ n⌉` term (below 1 chunks small batches finer)
+    pub log_exponent: f64,
+    
+    /// Smallest chunk size produced (still capped at `n`)
+    pub min_chunk_size: usize,
+}
+
+impl WilliamsParams {
+    /// Reject exponents that would produce meaningless chunk sizes
+    pub fn validate(&self) -> Result<()> {
+        if !self.log_exponent.is_finite() || self.log_exponent < 0.0 {
+            return Err(Error::Config(format!(
+                "Williams log_exponent must be finite and >= 0, got {}",
+                self.log_exponent
+            )));
+        }
+        
+        Ok(())
+    }
+}
+
+impl Default for WilliamsParams {
+    fn default() -> Self {
+        Self {
+            log_exponent: 1.0,
+            min_chunk_size: 1,
+        }
+    }
+}
+
 /// Calculate Williams optimal chunk size: √n * log₂(n)
 ///
 /// # Arguments
@@ -25,17 +63,18 @@ use std::cmp::min;
 /// // Reduction: 86.7%
 /// ```
 pub fn williams_chunk_size(n: usize) -> usize {
-    if n <= 1 {
-        return n;
-    }
-    
-    let sqrt_n = (n as f64).sqrt() as usize;
-    let log_n = (n as f64).log2().ceil() as usize;
-    
-    sqrt_n * log_n
+    williams_chunk_size_with(n, &WilliamsParams::default())
+}
+
+/// Calculate the Williams chunk size using custom `params`
+///
+/// The result is always within `1..=n` (and `0` for an empty batch).
+pub fn williams_chunk_size_with(n: usize, params: &WilliamsParams) -> usize {
+    fisher_core::williams::chunk_size(n, params.log_exponent, params.min_chunk_size)
 }
 
 /// Fast integer square root using Newton's method
+#[allow(dead_code)]
 #[inline]
 fn isqrt(n: usize) -> usize {
     if n == 0 {
@@ -43,7 +82,7 @@ fn isqrt(n: usize) -> usize {
     }
     
     let mut x = n;
-    let mut y = (x + 1) / 2;
+    let mut y = x.div_ceil(2);
     
     while y < x {
         x = y;
@@ -54,6 +93,7 @@ fn isqrt(n: usize) -> usize {
 }
 
 /// Fast integer log₂
+#[allow(dead_code)]
 #[inline]
 fn ilog2(n: usize) -> usize {
     if n == 0 {
@@ -74,19 +114,33 @@ fn ilog2(n: usize) -> usize {
 /// Vector of results from processing each chunk
 pub fn process_in_chunks<T, F>(
     intents: &[Intent],
+    process_fn: F,
+) -> Result<Vec<T>>
+where
+    F: FnMut(&[Intent]) -> Result<T>,
+{
+    process_in_chunks_with(intents, &WilliamsParams::default(), process_fn)
+}
+
+/// Process intents in chunks sized by custom Williams `params`
+pub fn process_in_chunks_with<T, F>(
+    intents: &[Intent],
+    params: &WilliamsParams,
     mut process_fn: F,
 ) -> Result<Vec<T>>
 where
     F: FnMut(&[Intent]) -> Result<T>,
 {
     let n = intents.len();
-    let chunk_size = williams_chunk_size(n);
+    let chunk_size = williams_chunk_size_with(n, params).max(1);
     
-    let mut results = Vec::with_capacity((n + chunk_size - 1) / chunk_size);
+    let mut results = Vec::with_capacity(n.div_ceil(chunk_size));
     
     for chunk_start in (0..n).step_by(chunk_size) {
         let chunk_end = min(chunk_start + chunk_size, n);
         let chunk = &intents[chunk_start..chunk_end];
+        #[cfg(test)]
+        probe::record(std::mem::size_of_val(chunk));
         
         results.push(process_fn(chunk)?);
     }
@@ -94,6 +148,45 @@ where
     Ok(results)
 }
 
+/// Process intents pulled lazily from a source in Williams-optimized chunks
+///
+/// Unlike [`process_in_chunks`], the full intent set never needs to be in
+/// memory: one chunk is pulled from `source`, processed and discarded before
+/// the next is read, so peak residency is a single chunk buffer.
+///
+/// # Arguments
+/// * `source` - Intent source (queue drain, fishing-spot feed, ...)
+/// * `expected_len` - Expected total intents, used to size chunks
+/// * `process_fn` - Function to apply to each chunk
+pub fn process_stream<I, T, F>(
+    source: I,
+    expected_len: usize,
+    mut process_fn: F,
+) -> Result<Vec<T>>
+where
+    I: IntoIterator<Item = Intent>,
+    F: FnMut(&[Intent]) -> Result<T>,
+{
+    let chunk_size = williams_chunk_size(expected_len).max(1);
+    let mut source = source.into_iter();
+    let mut buffer = Vec::with_capacity(chunk_size);
+    let mut results = Vec::new();
+    
+    loop {
+        buffer.extend(source.by_ref().take(chunk_size));
+        if buffer.is_empty() {
+            break;
+        }
+        #[cfg(test)]
+        probe::record(buffer.capacity() * std::mem::size_of::<Intent>());
+        
+        results.push(process_fn(&buffer)?);
+        buffer.clear();
+    }
+    
+    Ok(results)
+}
+
 /// Williams tree evaluation for combining results
 ///
 /// Combines chunk results using a tree structure with bounded memory.
@@ -108,7 +201,7 @@ where
     let mut current_level = items;
     
     while current_level.len() > 1 {
-        let mut next_level = Vec::with_capacity((current_level.len() + 1) / 2);
+        let mut next_level = Vec::with_capacity(current_level.len().div_ceil(2));
         
         for i in (0..current_level.len()).step_by(2) {
             if i + 1 < current_level.len() {
@@ -130,14 +223,60 @@ where
 
 /// Calculate memory savings from Williams compression
 pub fn calculate_savings(n: usize) -> f64 {
-    if n == 0 {
-        return 0.0;
+    savings_for_chunk_size(n, williams_chunk_size(n))
+}
+
+pub use fisher_core::williams::savings_for_chunk_size;
+
+/// Predicted peak working memory for processing `n` intents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// One Williams chunk resident at a time (O(√n log n))
+    pub chunked_bytes: usize,
+    
+    /// Every intent resident at once (O(n))
+    pub naive_bytes: usize,
+}
+
+impl MemoryEstimate {
+    /// Chunked peak as a fraction of the naive one (0 for an empty batch)
+    pub fn ratio(&self) -> f64 {
+        if self.naive_bytes == 0 {
+            return 0.0;
+        }
+        self.chunked_bytes as f64 / self.naive_bytes as f64
     }
+}
+
+/// Peak bytes held by chunked versus naive processing of `n` intents
+///
+/// `intent_size_bytes` is the cost of one resident intent; pass
+/// `size_of::<Intent>()` for the chunk buffer alone, or add the average
+/// heap (id, signature) for a fuller capacity plan.
+pub fn peak_memory_estimate(n: usize, intent_size_bytes: usize) -> MemoryEstimate {
+    MemoryEstimate {
+        chunked_bytes: williams_chunk_size(n).saturating_mul(intent_size_bytes),
+        naive_bytes: n.saturating_mul(intent_size_bytes),
+    }
+}
+
+/// Largest chunk allocation seen by the processing functions on this thread
+#[cfg(test)]
+mod probe {
+    use std::cell::Cell;
     
-    let standard_space = n;
-    let williams_space = williams_chunk_size(n);
+    thread_local! {
+        static PEAK_CHUNK_BYTES: Cell<usize> = const { Cell::new(0) };
+    }
     
-    ((standard_space - williams_space) as f64 / standard_space as f64) * 100.0
+    pub(super) fn record(bytes: usize) {
+        PEAK_CHUNK_BYTES.with(|peak| peak.set(peak.get().max(bytes)));
+    }
+    
+    /// Peak since the last call, resetting it
+    pub(super) fn take() -> usize {
+        PEAK_CHUNK_BYTES.with(|peak| peak.replace(0))
+    }
 }
 
 #[cfg(test)]
@@ -163,14 +302,148 @@ mod tests {
     fn test_calculate_savings() {
         // For n=100: sqrt(100)=10, log2(100)≈7, chunk=70, savings=30%
         let savings_100 = calculate_savings(100);
-        assert!(savings_100 >= 25.0 && savings_100 <= 35.0);
+        assert!((25.0..=35.0).contains(&savings_100));
         
         // For n=1000: sqrt(1000)≈32, log2(1000)≈10, chunk=320, savings=68%
         let savings_1000 = calculate_savings(1_000);
-        assert!(savings_1000 >= 60.0 && savings_1000 <= 75.0);
+        assert!((60.0..=75.0).contains(&savings_1000));
         
         // For n=10000: sqrt(10000)=100, log2(10000)≈14, chunk=1400, savings=86%
         let savings_10k = calculate_savings(10_000);
-        assert!(savings_10k >= 80.0 && savings_10k <= 90.0);
+        assert!((80.0..=90.0).contains(&savings_10k));
+    }
+
+    fn intent(i: u64) -> Intent {
+        use alloy_primitives::{Address, U256};
+        Intent::new(format!("intent_{}", i), Address::ZERO, Address::ZERO, U256::from(i), false, i, vec![])
+    }
+
+    #[test]
+    fn test_process_stream_bounded_memory() {
+        use std::cell::Cell;
+        
+        let n = 100_000;
+        let chunk_size = williams_chunk_size(n);
+        
+        // Instrumented source: counts intents pulled so far
+        let pulled = Cell::new(0usize);
+        let source = (0..n as u64).map(|i| {
+            pulled.set(pulled.get() + 1);
+            intent(i)
+        });
+        
+        let mut processed = 0;
+        let sums = process_stream(source, n, |chunk| {
+            assert!(chunk.len() <= chunk_size);
+            
+            // Nothing is read ahead of the chunk being processed
+            assert_eq!(pulled.get(), processed + chunk.len());
+            processed += chunk.len();
+            
+            Ok(chunk.iter().map(|i| i.nonce).sum::<u64>())
+        }).unwrap();
+        
+        assert_eq!(processed, n);
+        assert_eq!(sums.len(), n.div_ceil(chunk_size));
+        assert_eq!(sums.iter().sum::<u64>(), (0..n as u64).sum::<u64>());
+    }
+
+    #[test]
+    fn test_process_stream_matches_slice_version() {
+        let intents: Vec<Intent> = (0..1_000).map(intent).collect();
+        let count = |chunk: &[Intent]| Ok(chunk.len());
+        
+        let from_slice = process_in_chunks(&intents, count).unwrap();
+        let from_stream = process_stream(intents.clone(), intents.len(), count).unwrap();
+        assert_eq!(from_slice, from_stream);
+        
+        assert!(process_stream(Vec::new(), 0, count).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_default_params_match_classic_formula() {
+        let params = WilliamsParams::default();
+        
+        for n in (0..5_000).chain([10_000, 100_000, 1_000_000]) {
+            let classic = if n <= 1 {
+                n
+            } else {
+                (n as f64).sqrt() as usize * (n as f64).log2().ceil() as usize
+            };
+            
+            assert_eq!(williams_chunk_size_with(n, &params), classic.min(n), "n = {}", n);
+            assert_eq!(williams_chunk_size(n), williams_chunk_size_with(n, &params));
+        }
+    }
+    
+    #[test]
+    fn test_chunk_size_bounds_and_floor() {
+        let params = WilliamsParams { log_exponent: 0.5, min_chunk_size: 64 };
+        
+        for n in 1..3_000 {
+            let chunk_size = williams_chunk_size_with(n, &params);
+            assert!((1..=n).contains(&chunk_size), "n = {}: {}", n, chunk_size);
+            assert!(chunk_size >= 64.min(n), "n = {}: {}", n, chunk_size);
+        }
+        
+        // A smaller exponent chunks large batches finer than the default
+        assert!(williams_chunk_size_with(10_000, &params) < williams_chunk_size(10_000));
+        
+        // Exponent 0 leaves just √n
+        let sqrt_only = WilliamsParams { log_exponent: 0.0, min_chunk_size: 1 };
+        assert_eq!(williams_chunk_size_with(10_000, &sqrt_only), 100);
+    }
+    
+    #[test]
+    fn test_process_in_chunks_with_params() {
+        let intents: Vec<Intent> = (0..100).map(intent).collect();
+        let params = WilliamsParams { log_exponent: 0.0, min_chunk_size: 30 };
+        
+        let sizes = process_in_chunks_with(&intents, &params, |chunk| Ok(chunk.len())).unwrap();
+        assert_eq!(sizes, vec![30, 30, 30, 10]);
+    }
+    
+    #[test]
+    fn test_params_validation() {
+        assert!(WilliamsParams::default().validate().is_ok());
+        
+        for log_exponent in [-1.0, f64::NAN, f64::INFINITY] {
+            let params = WilliamsParams { log_exponent, ..WilliamsParams::default() };
+            assert!(matches!(params.validate(), Err(Error::Config(_))));
+        }
+        
+        let params: WilliamsParams = serde_json::from_str(r#"{"min_chunk_size": 8}"#).unwrap();
+        assert_eq!(params, WilliamsParams { min_chunk_size: 8, ..WilliamsParams::default() });
+    }
+    
+    #[test]
+    fn test_peak_memory_tracks_sqrt_n_log_n() {
+        let intent_size = std::mem::size_of::<Intent>();
+        
+        for n in [100, 1_000, 10_000, 100_000] {
+            let intents: Vec<Intent> = (0..n as u64).map(intent).collect();
+            let estimate = peak_memory_estimate(n, intent_size);
+            
+            // The recorded peak chunk is exactly the prediction
+            probe::take();
+            process_in_chunks(&intents, |chunk| Ok(chunk.len())).unwrap();
+            assert_eq!(probe::take(), estimate.chunked_bytes, "n = {}", n);
+            
+            process_stream(intents, n, |chunk| Ok(chunk.len())).unwrap();
+            let streamed = probe::take();
+            assert!(streamed >= estimate.chunked_bytes && streamed <= 2 * estimate.chunked_bytes, "n = {}", n);
+            
+            // Chunked / naive follows √n·log₂n / n within a small factor
+            let nf = n as f64;
+            let predicted = nf.sqrt() * nf.log2() / nf;
+            let factor = estimate.ratio() / predicted;
+            assert!((0.5..=2.0).contains(&factor), "n = {}: {:.3} vs {:.3}", n, estimate.ratio(), predicted);
+        }
+        
+        // The advantage grows with n
+        let small = peak_memory_estimate(1_000, intent_size);
+        let large = peak_memory_estimate(1_000_000, intent_size);
+        assert!(large.ratio() < small.ratio() / 5.0);
+        assert_eq!(peak_memory_estimate(0, intent_size).ratio(), 0.0);
     }
 }
@@ -2,14 +2,12 @@
 //!
 //! Confirms 91-95% (or 95-98% with blobs) gas savings
 
-use fisher_relayer::*;
-use alloy_primitives::{Address, U256};
-
 /// Standard gas costs for Ethereum operations
 const GAS_PER_TRANSFER: u64 = 21_000;  // Base transaction cost
 const STORAGE_WRITE: u64 = 20_000;      // SSTORE cold
 const STORAGE_UPDATE: u64 = 5_000;      // SSTORE warm
 const CALLDATA_BYTE: u64 = 16;          // Non-zero byte
+#[allow(dead_code)]
 const CALLDATA_ZERO_BYTE: u64 = 4;      // Zero byte
 
 #[test]
@@ -221,47 +219,3 @@ fn estimate_batch_calldata(num_users: usize) -> u64 {
     // Effective: ~30 bytes per intent after compression
     (num_users as u64) * 30
 }
-
-#[test]
-fn test_real_world_comparison() {
-    println!("\n💰 Real-World Gas Cost Comparison (at 20 gwei, $2500 ETH):");
-    println!("╔════════════════════════════════════════════════════════════╗");
-    println!("║  Scenario: 1000 Users Making Transfers                    ║");
-    println!("╚════════════════════════════════════════════════════════════╝");
-    
-    let num_users = 1000;
-    let gas_price_gwei = 20u64;
-    let eth_price_usd = 2500.0;
-    
-    let traditional = calculate_traditional_gas(num_users);
-    let fisher = calculate_fisher_gas(num_users);
-    let fisher_blob = calculate_fisher_with_blobs(num_users);
-    
-    // Calculate USD costs
-    let traditional_eth = (traditional as f64) * (gas_price_gwei as f64) / 1e9;
-    let fisher_eth = (fisher as f64) * (gas_price_gwei as f64) / 1e9;
-    let fisher_blob_eth = (fisher_blob as f64) * (gas_price_gwei as f64) / 1e9;
-    
-    let traditional_usd = traditional_eth * eth_price_usd;
-    let fisher_usd = fisher_eth * eth_price_usd;
-    let fisher_blob_usd = fisher_blob_eth * eth_price_usd;
-    
-    println!("\n📊 Gas Usage:");
-    println!("   Traditional:      {:>12} gas", traditional);
-    println!("   Fisher:           {:>12} gas ({:.1}% savings)", 
-             fisher, ((traditional-fisher) as f64 / traditional as f64)*100.0);
-    println!("   Fisher + Blobs:   {:>12} gas ({:.1}% savings)", 
-             fisher_blob, ((traditional-fisher_blob) as f64 / traditional as f64)*100.0);
-    
-    println!("\n💵 Cost in USD:");
-    println!("   Traditional:      ${:>8.2}", traditional_usd);
-    println!("   Fisher:           ${:>8.2} (save ${:.2})", fisher_usd, traditional_usd - fisher_usd);
-    println!("   Fisher + Blobs:   ${:>8.2} (save ${:.2})", fisher_blob_usd, traditional_usd - fisher_blob_usd);
-    
-    println!("\n💰 Per User Cost:");
-    println!("   Traditional:      ${:.2}/user", traditional_usd / num_users as f64);
-    println!("   Fisher:           ${:.2}/user", fisher_usd / num_users as f64);
-    println!("   Fisher + Blobs:   ${:.3}/user", fisher_blob_usd / num_users as f64);
-    
-    println!("\n✅ Verification complete!");
-}